@@ -1,16 +1,43 @@
+pub mod queue;
+
 use crate::analysis::RepoAnalysis;
+use crate::db::{self, DbPool};
+use crate::fs_utils::FileRole;
+use crate::redact::redact_secrets;
 use crate::storage::{ProjectSummary, Settings};
+use crate::freshness;
+use crate::ideas;
+use crate::usage::{self, UsageEvent, UsageKind};
+use queue::{RequestQueue, Submission};
 use regex::Regex;
 use reqwest::header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use once_cell::sync::Lazy;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+// Ideas shorter than this are fragments ("Add more tests."); longer ones are
+// cut so one runaway item can't dominate the list. Both in characters
+const MIN_IDEA_LENGTH: usize = 50;
+const MAX_IDEA_LENGTH: usize = 2000;
+
+// Stops the model once it starts an 11th idea, in either list style
+const IDEA_STOP_SEQUENCES: [&str; 2] = ["\n11.", "\n11)"];
+
+// Lockfiles, declarations and generated output: often the largest files in a
+// repo but say nothing about it. "lock" covers package-lock.json, yarn.lock,
+// Cargo.lock and friends; patterns with `*` are globs on the file name
+const NOISE_FILE_PATTERNS: [&str; 7] =
+    ["lock", ".d.ts", ".min.js", ".min.css", "*.pb.go", "*_pb2.py", "*.generated.*"];
 
 // Cached regex patterns
 static THINKING_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?is)<think>(.*?)</think>(.*)").unwrap());
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelInfo {
     pub id: String,
     pub name: Option<String>,
@@ -27,6 +54,8 @@ pub struct IdeaRequest {
     pub analysis: RepoAnalysis,
     pub settings: Settings,
     pub focus_area: Option<String>,
+    #[serde(default)]
+    pub project_path: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -184,7 +213,18 @@ fn parse_structured_response(content: &str) -> Vec<String> {
         ideas.push(current_idea.trim().to_string());
     }
     
-    ideas.into_iter().filter(|idea| idea.len() > 20).collect()
+    ideas
+        .into_iter()
+        .filter(|idea| idea.chars().count() >= MIN_IDEA_LENGTH)
+        // The ellipsis counts toward the cap
+        .map(|idea| match idea.char_indices().nth(MAX_IDEA_LENGTH) {
+            Some(_) => {
+                let cut = idea.char_indices().nth(MAX_IDEA_LENGTH - 1).map_or(idea.len(), |(i, _)| i);
+                format!("{}…", idea[..cut].trim_end())
+            }
+            None => idea,
+        })
+        .collect()
 }
 
 fn extract_key_features(summary: &str) -> Vec<String> {
@@ -207,48 +247,34 @@ fn extract_key_features(summary: &str) -> Vec<String> {
     features
 }
 
-// Smart suggestion engine structures with pre-allocated capacity
-#[derive(Debug, Clone)]
+// Smart suggestion engine structures; sets keep every distinct path once,
+// in sorted order
+#[derive(Debug, Clone, Default)]
 struct ProjectKeywords {
-    api_related: Vec<String>,
-    auth_related: Vec<String>,
-    database_related: Vec<String>,
-    testing_related: Vec<String>,
-    cicd_related: Vec<String>,
-    ui_related: Vec<String>,
-    performance_related: Vec<String>,
-    security_related: Vec<String>,
-}
-
-impl ProjectKeywords {
-    fn new() -> Self {
-        ProjectKeywords {
-            api_related: Vec::with_capacity(20),
-            auth_related: Vec::with_capacity(20),
-            database_related: Vec::with_capacity(20),
-            testing_related: Vec::with_capacity(20),
-            cicd_related: Vec::with_capacity(20),
-            ui_related: Vec::with_capacity(20),
-            performance_related: Vec::with_capacity(20),
-            security_related: Vec::with_capacity(20),
-        }
-    }
+    api_related: BTreeSet<String>,
+    auth_related: BTreeSet<String>,
+    database_related: BTreeSet<String>,
+    testing_related: BTreeSet<String>,
+    cicd_related: BTreeSet<String>,
+    ui_related: BTreeSet<String>,
+    performance_related: BTreeSet<String>,
+    security_related: BTreeSet<String>,
 }
 
-#[derive(Debug, Clone)]
-struct TechnologyProfile {
-    frameworks: Vec<String>,
-    has_api: bool,
-    has_auth: bool,
-    has_database: bool,
-    has_testing: bool,
-    has_cicd: bool,
-    has_ui: bool,
-    project_type: String,
+#[derive(Debug, Clone, Default)]
+pub struct TechnologyProfile {
+    pub frameworks: Vec<String>,
+    pub has_api: bool,
+    pub has_auth: bool,
+    pub has_database: bool,
+    pub has_testing: bool,
+    pub has_cicd: bool,
+    pub has_ui: bool,
+    pub project_type: String,
 }
 
 fn extract_project_keywords(analysis: &RepoAnalysis) -> ProjectKeywords {
-    let mut keywords = ProjectKeywords::new();
+    let mut keywords = ProjectKeywords::default();
 
     // Analyze all file contents for keywords
     for file in &analysis.files {
@@ -258,14 +284,14 @@ fn extract_project_keywords(analysis: &RepoAnalysis) -> ProjectKeywords {
         if content_lower.contains("api") || content_lower.contains("endpoint") || 
            content_lower.contains("rest") || content_lower.contains("graphql") ||
            content_lower.contains("swagger") || content_lower.contains("openapi") {
-            keywords.api_related.push(file.path.clone());
+            keywords.api_related.insert(file.path.clone());
         }
         
         // Authentication keywords
         if content_lower.contains("auth") || content_lower.contains("login") ||
            content_lower.contains("jwt") || content_lower.contains("oauth") ||
            content_lower.contains("session") || content_lower.contains("password") {
-            keywords.auth_related.push(file.path.clone());
+            keywords.auth_related.insert(file.path.clone());
         }
         
         // Database keywords
@@ -273,66 +299,66 @@ fn extract_project_keywords(analysis: &RepoAnalysis) -> ProjectKeywords {
            content_lower.contains("mongo") || content_lower.contains("redis") ||
            content_lower.contains("postgres") || content_lower.contains("mysql") ||
            content_lower.contains("migration") || content_lower.contains("schema") {
-            keywords.database_related.push(file.path.clone());
+            keywords.database_related.insert(file.path.clone());
         }
         
         // Testing keywords
         if content_lower.contains("test") || content_lower.contains("spec") ||
            content_lower.contains("jest") || content_lower.contains("mocha") ||
            content_lower.contains("vitest") || content_lower.contains("cypress") {
-            keywords.testing_related.push(file.path.clone());
+            keywords.testing_related.insert(file.path.clone());
         }
         
         // CI/CD keywords
         if file.path.contains(".github/workflows") || file.path.contains("gitlab-ci") ||
            content_lower.contains("pipeline") || content_lower.contains("deploy") ||
            content_lower.contains("docker") || content_lower.contains("kubernetes") {
-            keywords.cicd_related.push(file.path.clone());
+            keywords.cicd_related.insert(file.path.clone());
         }
         
         // UI keywords
         if content_lower.contains("component") || content_lower.contains("react") ||
            content_lower.contains("vue") || content_lower.contains("angular") ||
            content_lower.contains("tailwind") || content_lower.contains("css") {
-            keywords.ui_related.push(file.path.clone());
+            keywords.ui_related.insert(file.path.clone());
         }
         
         // Performance keywords
         if content_lower.contains("cache") || content_lower.contains("optimize") ||
            content_lower.contains("performance") || content_lower.contains("lazy") ||
            content_lower.contains("memoize") || content_lower.contains("throttle") {
-            keywords.performance_related.push(file.path.clone());
+            keywords.performance_related.insert(file.path.clone());
         }
         
         // Security keywords
         if content_lower.contains("security") || content_lower.contains("encrypt") ||
            content_lower.contains("cors") || content_lower.contains("xss") ||
            content_lower.contains("csrf") || content_lower.contains("sanitize") {
-            keywords.security_related.push(file.path.clone());
+            keywords.security_related.insert(file.path.clone());
         }
     }
     
-    // Deduplicate
-    keywords.api_related.sort();
-    keywords.api_related.dedup();
-    keywords.auth_related.sort();
-    keywords.auth_related.dedup();
-    keywords.database_related.sort();
-    keywords.database_related.dedup();
-    keywords.testing_related.sort();
-    keywords.testing_related.dedup();
-    keywords.cicd_related.sort();
-    keywords.cicd_related.dedup();
-    keywords.ui_related.sort();
-    keywords.ui_related.dedup();
-    keywords.performance_related.sort();
-    keywords.performance_related.dedup();
-    keywords.security_related.sort();
-    keywords.security_related.dedup();
-    
     keywords
 }
 
+const META_FRAMEWORK_CONFIGS: [(&str, &str); 3] = [
+    ("astro.config.", "Astro"),
+    ("nuxt.config.", "Nuxt"),
+    ("remix.config.", "Remix"),
+];
+
+// `from 'module'` / `from "module"`, including subpath imports like 'svelte/store'
+fn imports_module(content: &str, module: &str) -> bool {
+    ['\'', '"'].iter().any(|q| {
+        content.contains(&format!("from {q}{module}{q}")) || content.contains(&format!("from {q}{module}/"))
+    })
+}
+
+// The profile the idea prompt is built from, for callers outside this module
+pub fn technology_profile(analysis: &RepoAnalysis) -> TechnologyProfile {
+    analyze_technology_profile(analysis, &extract_project_keywords(analysis))
+}
+
 fn analyze_technology_profile(analysis: &RepoAnalysis, keywords: &ProjectKeywords) -> TechnologyProfile {
     let mut profile = TechnologyProfile {
         frameworks: Vec::with_capacity(10),
@@ -384,6 +410,35 @@ fn analyze_technology_profile(analysis: &RepoAnalysis, keywords: &ProjectKeyword
         if content.contains("tauri::") || file.path.contains("tauri") {
             profile.frameworks.push("Tauri".to_string());
         }
+        
+        // Svelte/SolidJS/Qwik
+        if imports_module(content, "svelte") || file.path.ends_with(".svelte") {
+            profile.frameworks.push("Svelte".to_string());
+        }
+        if imports_module(content, "solid-js") {
+            profile.frameworks.push("SolidJS".to_string());
+        }
+        if imports_module(content, "@builder.io/qwik") {
+            profile.frameworks.push("Qwik".to_string());
+        }
+        
+        // Meta-frameworks are identified by their config files
+        let file_name = file.path.rsplit(['/', '\\']).next().unwrap_or(&file.path);
+        for (prefix, framework) in META_FRAMEWORK_CONFIGS {
+            if file_name.starts_with(prefix) {
+                profile.frameworks.push(framework.to_string());
+            }
+        }
+
+        // A compose stack running a database image means there is a database,
+        // whatever the application code mentions
+        if crate::containers::is_compose_file(file_name)
+            && crate::containers::parse_compose(content)
+                .iter()
+                .any(|s| s.image.as_deref().is_some_and(crate::containers::is_database_image))
+        {
+            profile.has_database = true;
+        }
     }
     
     profile.frameworks.sort();
@@ -392,6 +447,8 @@ fn analyze_technology_profile(analysis: &RepoAnalysis, keywords: &ProjectKeyword
     // Determine project type
     profile.project_type = if profile.frameworks.contains(&"Tauri".to_string()) {
         "Desktop Application".to_string()
+    } else if META_FRAMEWORK_CONFIGS.iter().any(|(_, f)| profile.frameworks.iter().any(|p| p == f)) {
+        "Meta-Framework Application".to_string()
     } else if profile.has_ui && profile.has_api {
         "Full-Stack Web Application".to_string()
     } else if profile.has_ui {
@@ -407,7 +464,13 @@ fn analyze_technology_profile(analysis: &RepoAnalysis, keywords: &ProjectKeyword
     profile
 }
 
-fn generate_smart_suggestions(profile: &TechnologyProfile, keywords: &ProjectKeywords) -> Vec<String> {
+// `workflows` are the project's parsed CI files; whether they scan is read from
+// the jobs themselves rather than guessed from file names
+fn generate_smart_suggestions(
+    profile: &TechnologyProfile,
+    keywords: &ProjectKeywords,
+    workflows: &[crate::ci::CIWorkflow],
+) -> Vec<String> {
     let mut suggestions = Vec::with_capacity(20); // Pre-allocate
     
     // API-specific suggestions
@@ -467,7 +530,7 @@ fn generate_smart_suggestions(profile: &TechnologyProfile, keywords: &ProjectKey
         if !keywords.cicd_related.iter().any(|p| p.contains("semantic")) {
             suggestions.push("Semantic versioning automation".to_string());
         }
-        if !keywords.cicd_related.iter().any(|p| p.contains("security") || p.contains("scan")) {
+        if !workflows.iter().any(|w| w.parsed && w.runs_security_scan) {
             suggestions.push("Security scanning in CI pipeline".to_string());
         }
     } else {
@@ -508,8 +571,241 @@ fn generate_smart_suggestions(profile: &TechnologyProfile, keywords: &ProjectKey
     suggestions
 }
 
-// Optimized: Pre-allocate string capacity and use write! macro
-fn build_comprehensive_context(analysis: &RepoAnalysis) -> String {
+// Errors can echo request URLs or response bodies; never hand secrets back to the UI
+fn ai_error(prefix: &str, error: impl std::fmt::Display) -> String {
+    redact_secrets(&format!("{}: {}", prefix, error)).into_owned()
+}
+
+// Outcome of the most recent AI request, for diagnostics
+#[derive(Debug, Serialize, Clone)]
+pub struct EndpointStatus {
+    pub host: String,
+    pub kind: String,
+    // None when no response arrived (DNS, refused, timeout)
+    pub status: Option<u16>,
+    pub ok: bool,
+    pub at: String,
+}
+
+static LAST_ENDPOINT_STATUS: Lazy<Mutex<Option<EndpointStatus>>> = Lazy::new(|| Mutex::new(None));
+
+pub fn last_endpoint_status() -> Option<EndpointStatus> {
+    LAST_ENDPOINT_STATUS.lock().ok().and_then(|last| last.clone())
+}
+
+// Send a completion or model-list request, logging how long it took and the
+// status. Only the endpoint's host is logged; keys can ride in the URL.
+// Called by the request queue, never directly
+async fn send_logged(
+    client: &reqwest::Client,
+    kind: &str,
+    model: &str,
+    request: reqwest::Request,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let host = request.url().host_str().map(str::to_string).unwrap_or_default();
+    let started = std::time::Instant::now();
+    let result = client.execute(request).await;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    let status = result.as_ref().ok().map(|response| response.status().as_u16());
+    if let Ok(mut last) = LAST_ENDPOINT_STATUS.lock() {
+        *last = Some(EndpointStatus {
+            host: host.clone(),
+            kind: kind.to_string(),
+            status,
+            ok: result.as_ref().is_ok_and(|response| response.status().is_success()),
+            at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+    match &result {
+        Ok(response) => {
+            tracing::info!(kind, model, host = %host, status = response.status().as_u16(), elapsed_ms, "AI request finished")
+        }
+        Err(e) => {
+            tracing::warn!(kind, model, host = %host, elapsed_ms, error = %redact_secrets(&e.to_string()), "AI request failed")
+        }
+    }
+    result
+}
+
+fn is_noise_file(path: &str) -> bool {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path).to_lowercase();
+    NOISE_FILE_PATTERNS.iter().any(|pattern| match *pattern {
+        "lock" => {
+            let (stem, ext) = name.split_once('.').unwrap_or((&name, ""));
+            stem == "lock" || stem.ends_with("-lock") || ext.starts_with("lock")
+        }
+        p if p.contains('*') => glob::Pattern::new(p).is_ok_and(|g| g.matches(&name)),
+        p => name.ends_with(p),
+    })
+}
+
+fn file_preview(content: &str, redact: bool) -> String {
+    // Redact before cutting, so a secret crossing the cut still matches
+    let content = if redact { redact_secrets(content) } else { std::borrow::Cow::Borrowed(content) };
+    // Cut on a char boundary; a byte index can land inside a multibyte character
+    match content.char_indices().nth(300) {
+        Some((cut, _)) => format!("{}...", &content[..cut]),
+        None => content.into_owned(),
+    }
+}
+
+// "Actively maintained by N people" from the last 90 days of history, with
+// the project's contributor aliases applied so one person isn't counted twice
+fn maintainer_observation(project_path: &str, aliases: &HashMap<String, String>) -> Option<String> {
+    let recent = crate::insights::contributor_stats(
+        std::path::Path::new(project_path),
+        Some("90 days ago"),
+        aliases,
+    )
+    .ok()?;
+    match recent.len() {
+        0 => Some("\nMaintenance: no commits in the last 90 days\n".to_string()),
+        1 => Some("\nMaintenance: actively maintained by 1 person (last 90 days)\n".to_string()),
+        n => Some(format!("\nMaintenance: actively maintained by {} people (last 90 days)\n", n)),
+    }
+}
+
+// README gaps for documentation-focused ideas, e.g. "missing sections: usage, license"
+fn readme_observation(project_path: &str) -> Option<String> {
+    let quality = crate::insights::readme_quality(std::path::Path::new(project_path))?;
+    if quality.reasons.is_empty() {
+        return Some(format!("\nREADME quality: {}/100, no obvious gaps\n", quality.score));
+    }
+    Some(format!(
+        "\nREADME quality: {}/100; gaps: {}\n",
+        quality.score,
+        quality.reasons.join(", ")
+    ))
+}
+
+// Community health files for documentation-focused ideas, so only genuinely
+// missing ones get suggested
+fn community_observation(project_path: &str) -> Option<String> {
+    let info = crate::insights::get_community_info(std::path::Path::new(project_path));
+    let present: Vec<&str> = [
+        info.contributing.as_deref(),
+        info.code_of_conduct.as_deref(),
+        info.security_policy.as_deref(),
+        info.pull_request_template.as_deref(),
+        info.codeowners.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let mut note = String::from("\n");
+    if !present.is_empty() || !info.issue_templates.is_empty() {
+        let templates = if info.issue_templates.is_empty() { "" } else { ", issue templates" };
+        note.push_str(&format!("Community files present: {}{}\n", present.join(", "), templates));
+    }
+    if info.missing_files.is_empty() {
+        note.push_str("Community files missing: none\n");
+    } else {
+        note.push_str(&format!("Community files missing: {}\n", info.missing_files.join(", ")));
+    }
+    Some(note)
+}
+
+// "CI workflows: build, test, release (on tags), security scanning", so ideas
+// don't propose CI steps that already exist
+fn ci_observation(project_path: &str) -> Option<String> {
+    let workflows = crate::ci::read_workflows(std::path::Path::new(project_path));
+    let parsed: Vec<_> = workflows.iter().filter(|w| w.parsed).collect();
+    if parsed.is_empty() {
+        return None;
+    }
+    let mut purposes = Vec::new();
+    let checks = [
+        ("build", parsed.iter().any(|w| w.runs_build)),
+        ("test", parsed.iter().any(|w| w.runs_tests)),
+        ("lint", parsed.iter().any(|w| w.runs_lint)),
+        ("security scanning", parsed.iter().any(|w| w.runs_security_scan)),
+    ];
+    purposes.extend(checks.iter().filter(|(_, present)| *present).map(|(label, _)| label.to_string()));
+    if parsed.iter().any(|w| w.builds_release) {
+        let on_tags = parsed.iter().any(|w| w.builds_release && w.triggers.iter().any(|t| t == "tags"));
+        purposes.push(if on_tags { "release (on tags)".to_string() } else { "release".to_string() });
+    }
+    if purposes.is_empty() {
+        return None;
+    }
+    let mut note = format!("\nCI workflows: {}\n", purposes.join(", "));
+    match crate::ci::merged_matrix(&workflows) {
+        Some(matrix) => {
+            if !matrix.os_targets.is_empty() {
+                note.push_str(&format!("CI matrix platforms: {}\n", matrix.os_targets.join(", ")));
+            }
+            if !matrix.language_versions.is_empty() {
+                note.push_str(&format!("CI matrix versions: {}\n", matrix.language_versions.join(", ")));
+            }
+        }
+        None => note.push_str("CI runs on a single platform and toolchain version (no build matrix)\n"),
+    }
+    Some(note)
+}
+
+// "Developer experience: hooks via husky, lint-staged; 82% conventional commits (feat, fix)"
+fn developer_experience_observation(project_path: &str) -> Option<String> {
+    let hooks = crate::insights::get_hooks_info(std::path::Path::new(project_path));
+    let mut parts = Vec::new();
+    if !hooks.tools.is_empty() {
+        parts.push(format!("hooks via {}", hooks.tools.join(", ")));
+    } else if !hooks.native_hooks.is_empty() {
+        parts.push(format!("local git hooks ({})", hooks.native_hooks.join(", ")));
+    } else {
+        parts.push("no commit hooks configured".to_string());
+    }
+    if let Some(convention) = &hooks.commit_convention {
+        parts.push(format!(
+            "{}% of the last {} commits follow conventional commits{}",
+            convention.conventional_percentage,
+            convention.sampled,
+            if convention.dominant_types.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", convention.dominant_types.join(", "))
+            }
+        ));
+    }
+    Some(format!("\nDeveloper experience: {}\n", parts.join("; ")))
+}
+
+// "Formatting gaps: no ESLint config, no .editorconfig"
+fn formatter_observation(project_path: &str) -> Option<String> {
+    let path = std::path::Path::new(project_path);
+    let gaps = crate::insights::formatter_gaps(path, &crate::insights::get_formatter_info(path));
+    if gaps.is_empty() {
+        return None;
+    }
+    Some(format!("\nFormatting gaps: {}\n", gaps.join(", ")))
+}
+
+// "Dependencies: 3 major versions behind (react ^17.0.2 -> 18.3.1, ...)",
+// from registry versions already cached by check_outdated_dependencies
+fn outdated_observation(conn: &rusqlite::Connection, project_path: &str) -> Option<String> {
+    let major: Vec<_> = crate::outdated::cached_outdated(conn, std::path::Path::new(project_path))
+        .into_iter()
+        .filter(|d| d.severity == crate::outdated::Severity::Major)
+        .collect();
+    if major.is_empty() {
+        return None;
+    }
+    let examples: Vec<String> = major
+        .iter()
+        .take(5)
+        .map(|d| format!("{} {} -> {}", d.name, d.current_req, d.latest))
+        .collect();
+    Some(format!(
+        "\nDependencies: {} major versions behind ({})\n",
+        major.len(),
+        examples.join(", ")
+    ))
+}
+
+fn is_documentation_focus(focus: Option<&str>) -> bool {
+    focus.is_some_and(|f| f.to_lowercase().contains("doc"))
+}
+
+fn build_comprehensive_context(analysis: &RepoAnalysis, redact: bool) -> String {
     // Pre-allocate with reasonable capacity
     let mut context = String::with_capacity(50_000);
     
@@ -570,11 +866,7 @@ fn build_comprehensive_context(analysis: &RepoAnalysis) -> String {
     let mut test_file_count = 0usize;
     let mut testing_frameworks: Vec<&'static str> = Vec::new();
     for file in &analysis.files {
-        let name = file.path.to_lowercase();
-        let is_test_name = name.ends_with(".test.js") || name.ends_with(".test.ts") || name.ends_with(".test.jsx") || name.ends_with(".test.tsx")
-            || name.ends_with(".spec.js") || name.ends_with(".spec.ts") || name.ends_with(".spec.jsx") || name.ends_with(".spec.tsx")
-            || name.ends_with("_test.py") || name.contains("/test/") || name.contains("/tests/") || name.contains("/__tests__/") || name.contains("/spec/");
-        if is_test_name { test_file_count += 1; }
+        if file.role == FileRole::Test { test_file_count += 1; }
         let lower = file.content.to_lowercase();
         if lower.contains("jest") && !testing_frameworks.contains(&"Jest") { testing_frameworks.push("Jest"); }
         if lower.contains("vitest") && !testing_frameworks.contains(&"Vitest") { testing_frameworks.push("Vitest"); }
@@ -617,23 +909,19 @@ fn build_comprehensive_context(analysis: &RepoAnalysis) -> String {
     let mut test_files = Vec::new();
     
     for file in &analysis.files {
-        let path_lower = file.path.to_lowercase();
-        
-        if path_lower.contains("test") || path_lower.contains("spec") {
-            test_files.push(file);
-        } else if path_lower.contains("config") || file.path.ends_with(".json") || 
-                  file.path.ends_with(".yml") || file.path.ends_with(".yaml") ||
-                  file.path.ends_with(".toml") {
-            config_files.push(file);
-        } else {
-            source_files.push(file);
+        match file.role {
+            FileRole::Test => test_files.push(file),
+            FileRole::Config | FileRole::Manifest | FileRole::Lockfile | FileRole::Ci => {
+                config_files.push(file)
+            }
+            FileRole::Source | FileRole::Docs => source_files.push(file),
         }
     }
     
     // Provide context about notable files (prefer roles over long previews)
     if !source_files.is_empty() {
         // Select top by size as a simple proxy for centrality
-        let mut sorted_sources = source_files.clone();
+        let mut sorted_sources: Vec<_> = source_files.iter().copied().filter(|f| !is_noise_file(&f.path)).collect();
         sorted_sources.sort_by_key(|f| std::cmp::Reverse(f.size));
         let _ = write!(&mut context, "\nNotable Files (by size):\n");
         for file in sorted_sources.iter().take(5) {
@@ -642,11 +930,7 @@ fn build_comprehensive_context(analysis: &RepoAnalysis) -> String {
         // Include short previews for the top 2 only
         let _ = write!(&mut context, "\nContent Previews (top 2):\n");
         for file in sorted_sources.iter().take(2) {
-            let preview = if file.content.len() > 300 {
-                format!("{}...", &file.content[..300])
-            } else {
-                file.content.clone()
-            };
+            let preview = file_preview(&file.content, redact);
             let _ = write!(&mut context, "\n{} ({}):\n{}\n", file.path, file.language, preview);
         }
     }
@@ -663,9 +947,84 @@ fn build_comprehensive_context(analysis: &RepoAnalysis) -> String {
     context
 }
 
+// Parse an OpenAI-style `data` list or an Ollama-style `models` list
+pub(crate) fn parse_models_response(response_text: &str) -> Option<Vec<ModelInfo>> {
+    if let Ok(models_response) = serde_json::from_str::<ModelsResponse>(response_text) {
+        return Some(models_response.data);
+    }
+    let models_json = serde_json::from_str::<serde_json::Value>(response_text).ok()?;
+    let models: Vec<ModelInfo> = models_json["models"]
+        .as_array()?
+        .iter()
+        .filter_map(|model| {
+            model["name"].as_str().map(|name| ModelInfo {
+                id: name.to_string(),
+                name: Some(name.to_string()),
+                description: model["details"]["parameter_size"].as_str().map(|s| s.to_string()),
+            })
+        })
+        .collect();
+    if models.is_empty() { None } else { Some(models) }
+}
+
+// Model lists by API URL with the unix time they were fetched; only
+// successful loads are cached
+type ModelCache = HashMap<String, (Vec<ModelInfo>, u64)>;
+static MODEL_CACHE: Lazy<Mutex<ModelCache>> = Lazy::new(|| Mutex::new(HashMap::new()));
+const MODEL_CACHE_TTL_SECS: u64 = 60;
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn cached_models(api_url: &str, now: u64) -> Option<Vec<ModelInfo>> {
+    let cache = MODEL_CACHE.lock().ok()?;
+    let (models, cached_at) = cache.get(api_url)?;
+    (now.saturating_sub(*cached_at) < MODEL_CACHE_TTL_SECS).then(|| models.clone())
+}
+
+fn store_models(api_url: &str, models: &[ModelInfo], now: u64) {
+    if let Ok(mut cache) = MODEL_CACHE.lock() {
+        cache.insert(api_url.to_string(), (models.to_vec(), now));
+    }
+}
+
+// Served from MODEL_CACHE when the list for this URL is under a minute old
+#[tauri::command]
+pub async fn load_models(
+    queue: State<'_, RequestQueue>,
+    api_url: String,
+    api_key: String,
+) -> Result<Vec<ModelInfo>, String> {
+    if let Some(models) = cached_models(&api_url, unix_now()) {
+        return Ok(models);
+    }
+    fetch_and_store_models(&queue, &api_url, &api_key).await
+}
+
+// Always asks the API, then updates the cache
+#[tauri::command]
+pub async fn refresh_models(
+    queue: State<'_, RequestQueue>,
+    api_url: String,
+    api_key: String,
+) -> Result<Vec<ModelInfo>, String> {
+    fetch_and_store_models(&queue, &api_url, &api_key).await
+}
+
+async fn fetch_and_store_models(queue: &RequestQueue, api_url: &str, api_key: &str) -> Result<Vec<ModelInfo>, String> {
+    let models = fetch_models(queue, api_url, api_key).await?;
+    store_models(api_url, &models, unix_now());
+    Ok(models)
+}
+
 #[tauri::command]
-pub async fn load_models(api_url: String, api_key: String) -> Result<Vec<ModelInfo>, String> {
-    let client = reqwest::Client::new();
+pub async fn clear_model_cache() -> Result<(), String> {
+    MODEL_CACHE.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
+
+async fn fetch_models(queue: &RequestQueue, api_url: &str, api_key: &str) -> Result<Vec<ModelInfo>, String> {
     let model_endpoints = vec![
         format!("{}/models", api_url.replace("/chat/completions", "")),
         format!(
@@ -681,32 +1040,15 @@ pub async fn load_models(api_url: String, api_key: String) -> Result<Vec<ModelIn
         if !api_key.is_empty() {
             headers.insert(AUTHORIZATION, format!("Bearer {}", api_key).parse().unwrap());
         }
-        match client.get(&endpoint).headers(headers).send().await {
+        let Ok(request) = queue.client().get(&endpoint).headers(headers).build() else { continue };
+        let submission = Submission { kind: "models", model: String::new(), project_path: None, request };
+        match queue.submit(submission).await {
             Ok(response) => {
                 let status = response.status();
                 let response_text = response.text().await.unwrap_or_default();
                 if status.is_success() {
-                    if let Ok(models_response) = serde_json::from_str::<ModelsResponse>(&response_text) {
-                        return Ok(models_response.data);
-                    }
-                    if let Ok(models_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-                        if let Some(models_array) = models_json["models"].as_array() {
-                            let models: Vec<ModelInfo> = models_array
-                                .iter()
-                                .filter_map(|model| {
-                                    if let Some(name) = model["name"].as_str() {
-                                        Some(ModelInfo {
-                                            id: name.to_string(),
-                                            name: Some(name.to_string()),
-                                            description: model["details"]["parameter_size"].as_str().map(|s| s.to_string()),
-                                        })
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            if !models.is_empty() { return Ok(models); }
-                        }
+                    if let Some(models) = parse_models_response(&response_text) {
+                        return Ok(models);
                     }
                 }
             }
@@ -717,16 +1059,50 @@ pub async fn load_models(api_url: String, api_key: String) -> Result<Vec<ModelIn
 }
 
 #[tauri::command]
-pub async fn generate_ideas(request: IdeaRequest) -> Result<Vec<String>, String> {
-    let client = reqwest::Client::new();
-    let comprehensive_context = build_comprehensive_context(&request.analysis);
+pub async fn generate_ideas(
+    db_pool: State<'_, Arc<DbPool>>,
+    queue: State<'_, RequestQueue>,
+    request: IdeaRequest,
+) -> Result<Vec<String>, String> {
+    let requested = std::time::Instant::now();
+    let mut comprehensive_context = build_comprehensive_context(
+        &request.analysis,
+        request.settings.should_redact_previews(),
+    );
+    if let Some(path) = request.project_path.clone() {
+        let documentation = is_documentation_focus(request.focus_area.as_deref());
+        let pool = db_pool.inner().clone();
+        let notes = tokio::task::spawn_blocking(move || {
+            let conn = pool.get().ok();
+            let aliases = conn
+                .as_ref()
+                .map(|conn| crate::insights::load_contributor_aliases(conn, &path))
+                .unwrap_or_default();
+            let mut notes = maintainer_observation(&path, &aliases).unwrap_or_default();
+            notes.push_str(&ci_observation(&path).unwrap_or_default());
+            notes.push_str(&formatter_observation(&path).unwrap_or_default());
+            notes.push_str(&developer_experience_observation(&path).unwrap_or_default());
+            if let Some(conn) = &conn {
+                notes.push_str(&outdated_observation(conn, &path).unwrap_or_default());
+                notes.push_str(&ideas::exclusion_note(conn, &path).unwrap_or_default());
+            }
+            if documentation {
+                notes.push_str(&readme_observation(&path).unwrap_or_default());
+                notes.push_str(&community_observation(&path).unwrap_or_default());
+            }
+            notes
+        })
+        .await
+        .unwrap_or_default();
+        comprehensive_context.push_str(&notes);
+    }
     
     // Build focus-specific instructions
     let focus_instructions = if let Some(ref focus) = request.focus_area {
         format!(
             "\n\nIMPORTANT FOCUS AREA: The user specifically wants ideas focused on '{}'.\n\
             Please generate ALL ideas with a strong emphasis on this area. For example:\n\
-            - If the focus is 'documentation': suggest README improvements, API docs, code comments, documentation generators, etc.\n\
+            - If the focus is 'documentation': suggest README improvements (name the specific missing sections from 'README quality' when given), community files (only those listed under 'Community files missing'), API docs, code comments, documentation generators, etc.\n\
             - If the focus is 'testing': suggest unit tests, integration tests, test coverage, testing frameworks, etc.\n\
             - If the focus is 'performance': suggest optimization opportunities, caching, lazy loading, database indexing, etc.\n\
             - If the focus is 'security': suggest authentication, authorization, input validation, encryption, security audits, etc.\n\
@@ -798,28 +1174,48 @@ Start directly with '1.' and end after '10.'."#,
     });
     if request.settings.use_stop_ideas {
         if let Some(obj) = body.as_object_mut() {
-            obj.insert("stop".to_string(), serde_json::json!(["\n11."]));
+            obj.insert("stop".to_string(), serde_json::json!(IDEA_STOP_SEQUENCES));
         }
     }
 
-    let response = client
+    let http_request = queue
+        .client()
         .post(&request.settings.api_url)
         .headers(headers)
         .json(&body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .build()
+        .map_err(|e| ai_error("Request failed", e))?;
+    let response = queue
+        .submit(Submission {
+            kind: "ideas",
+            model: request.settings.model.clone(),
+            project_path: request.project_path.clone(),
+            request: http_request,
+        })
+        .await?;
 
     let response_json: serde_json::Value = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+        .map_err(|e| ai_error("Failed to parse response", e))?;
 
     if let Some(choices) = response_json["choices"].as_array() {
         if let Some(choice) = choices.first() {
             let (_thinking, content) = extract_choice_texts(choice);
             if !content.is_empty() {
+                usage::record(&db_pool, UsageKind::Ideas, UsageEvent {
+                    project_path: request.project_path.as_deref(),
+                    duration_ms: Some(requested.elapsed().as_millis() as u64),
+                    cache_hit: None,
+                    tokens: usage::response_tokens(&response_json),
+                });
                 let ideas = parse_structured_response(&content);
+                if let Some(path) = request.project_path.as_deref() {
+                    let saved = db_pool.get().map_err(|e| e.to_string()).and_then(|mut conn| ideas::save_generated(&mut conn, path, &ideas, request.focus_area.as_deref()));
+                    if let Err(e) = saved {
+                        crate::errors::report_error("ideas", format!("Failed to save ideas to the board: {}", e), Some(path));
+                    }
+                }
                 return Ok(ideas);
             }
         }
@@ -828,24 +1224,45 @@ Start directly with '1.' and end after '10.'."#,
 }
 
 #[tauri::command]
-pub async fn generate_project_summary(request: SummaryRequest) -> Result<ProjectSummary, String> {
-    let client = reqwest::Client::new();
+pub async fn generate_project_summary(
+    db_pool: State<'_, Arc<DbPool>>,
+    queue: State<'_, RequestQueue>,
+    request: SummaryRequest,
+) -> Result<ProjectSummary, String> {
+    let requested = std::time::Instant::now();
+    let redact = request.settings.should_redact_previews();
     let file_previews: Vec<String> = request
         .analysis
         .files
         .iter()
         .take(15)
         .map(|f| {
-            let preview = if f.content.len() > 300 {
-                format!("{}...", &f.content[..300])
-            } else {
-                f.content.clone()
-            };
+            let preview = file_preview(&f.content, redact);
             format!("File: {} ({})\nContent snippet:\n{}\n", f.path, f.language, preview)
         })
         .collect();
 
-    let prompt = format!(
+    // Fingerprint for the new summary, and what changed since the old one
+    let (fingerprint, drift) = {
+        let pool = db_pool.inner().clone();
+        let path = request.project_path.clone();
+        let analysis = &request.analysis;
+        let (metrics, technologies, sizes) =
+            (analysis.metrics.clone(), analysis.technologies.clone(), analysis.size_metrics.size_by_language.clone());
+        tokio::task::spawn_blocking(move || {
+            let current = freshness::fingerprint(&metrics, &technologies, &sizes, freshness::head_commit(&path));
+            let previous = pool.get().ok().and_then(|conn| {
+                let project = db::get_project_by_path(&conn, &path).ok()??;
+                db::load_summary(&conn, project.id, &path).ok()?.and_then(|s| s.fingerprint)
+            });
+            let drift = previous.and_then(|before| freshness::drift_since(&path, &before, &current).describe());
+            (current, drift)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    };
+
+    let mut prompt = format!(
         "Analyze this code repository and create a concise, code-grounded summary.
 
 Repository Analysis:
@@ -877,6 +1294,12 @@ Rules:
         request.analysis.structure.len(),
         file_previews.join("\n---\n")
     );
+    if let Some(drift) = drift {
+        prompt.push_str(&format!(
+            "\n\nThis replaces an earlier summary. Since then: {}. Mention what changed in the Overview or Architecture where it matters.",
+            drift
+        ));
+    }
 
     let mut headers = HeaderMap::new();
     if !request.settings.api_key.is_empty() {
@@ -895,23 +1318,37 @@ Rules:
         "presence_penalty": request.settings.presence_penalty_summary
     });
 
-    let response = client
+    let http_request = queue
+        .client()
         .post(&request.settings.api_url)
         .headers(headers)
         .json(&body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .build()
+        .map_err(|e| ai_error("Request failed", e))?;
+    let response = queue
+        .submit(Submission {
+            kind: "summary",
+            model: request.settings.model.clone(),
+            project_path: Some(request.project_path.clone()),
+            request: http_request,
+        })
+        .await?;
 
     let response_json: serde_json::Value = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+        .map_err(|e| ai_error("Failed to parse response", e))?;
 
     if let Some(choices) = response_json["choices"].as_array() {
         if let Some(choice) = choices.first() {
             let (_thinking, summary_text) = extract_choice_texts(choice);
             if !summary_text.is_empty() {
+                usage::record(&db_pool, UsageKind::Summary, UsageEvent {
+                    project_path: Some(&request.project_path),
+                    duration_ms: Some(requested.elapsed().as_millis() as u64),
+                    cache_hit: None,
+                    tokens: usage::response_tokens(&response_json),
+                });
                 let key_features = extract_key_features(&summary_text);
                 let summary = ProjectSummary {
                     project_path: request.project_path,
@@ -919,6 +1356,7 @@ Rules:
                     generated_at: chrono::Utc::now().to_rfc3339(),
                     technologies: request.analysis.technologies.clone(),
                     key_features,
+                    fingerprint: Some(fingerprint),
                 };
                 return Ok(summary);
             }
@@ -932,6 +1370,32 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_model_cache_expires_after_ttl() {
+        let url = "http://model-cache-test.invalid/v1/chat/completions";
+        let models = vec![ModelInfo { id: "llama3".into(), name: None, description: None }];
+        store_models(url, &models, 1_000);
+
+        let hit = cached_models(url, 1_000 + MODEL_CACHE_TTL_SECS - 1).unwrap();
+        assert_eq!(hit[0].id, "llama3");
+        assert!(cached_models(url, 1_000 + MODEL_CACHE_TTL_SECS).is_none());
+        assert!(cached_models("http://other.invalid", 1_000).is_none());
+    }
+
+    #[test]
+    fn test_is_noise_file_skips_lockfiles_and_generated_output() {
+        for path in [
+            "package-lock.json", "web/pnpm-lock.yaml", "yarn.lock", "Cargo.lock", "bun.lockb",
+            "types/index.d.ts", "dist/app.min.js", "api/user.pb.go", "proto/user_pb2.py",
+            "src/Schema.generated.cs",
+        ] {
+            assert!(is_noise_file(path), "{} should be noise", path);
+        }
+        for path in ["src/clock.rs", "src/lockfile.ts", "src/main.go", "src/index.ts"] {
+            assert!(!is_noise_file(path), "{} should be kept", path);
+        }
+    }
+
     #[test]
     fn test_extract_thinking_and_response_simple() {
         let input = "Final answer only.";
@@ -1005,20 +1469,153 @@ mod tests {
 
     #[test]
     fn test_parse_structured_response_ignores_preamble_and_think() {
-        let input = "<think> CoT here </think>\nIntro line before ideas.\n1. First idea with enough detail to pass filter\n2. Second idea also sufficiently descriptive";
+        let input = "<think> CoT here </think>\nIntro line before ideas.\n1. First idea with enough detail to pass the length filter\n2. Second idea that is also sufficiently descriptive to keep";
         let ideas = parse_structured_response(input);
         assert_eq!(ideas.len(), 2);
         assert!(!ideas[0].contains("<think>"));
-        assert_eq!(ideas[0], "First idea with enough detail to pass filter");
-        assert_eq!(ideas[1], "Second idea also sufficiently descriptive");
+        assert_eq!(ideas[0], "First idea with enough detail to pass the length filter");
+        assert_eq!(ideas[1], "Second idea that is also sufficiently descriptive to keep");
     }
 
     #[test]
     fn test_parse_structured_response_handles_unclosed_think_by_skipping_preamble() {
-        let input = "<think> CoT without closing tag\nStill thinking...\n1. Start ideas here with details beyond threshold\ncontinuation of first idea, elaboration continues\n2. Another idea with adequate length";
+        let input = "<think> CoT without closing tag\nStill thinking...\n1. Start ideas here with details beyond threshold\ncontinuation of first idea, elaboration continues\n2. Another idea with adequate length to clear the minimum";
         let ideas = parse_structured_response(input);
         assert_eq!(ideas.len(), 2);
         assert_eq!(ideas[0], "Start ideas here with details beyond threshold continuation of first idea, elaboration continues");
-        assert_eq!(ideas[1], "Another idea with adequate length");
+        assert_eq!(ideas[1], "Another idea with adequate length to clear the minimum");
+    }
+
+    #[test]
+    fn test_parse_structured_response_drops_fragments_and_caps_length() {
+        let runaway = "é".repeat(MAX_IDEA_LENGTH + 500);
+        let input = format!("1. Add more tests.\n2. {}\n3. Cache parsed manifests in `src/manifest.rs` between insight calls", runaway);
+        let ideas = parse_structured_response(&input);
+        assert_eq!(ideas.len(), 2);
+        assert_eq!(ideas[0].chars().count(), MAX_IDEA_LENGTH);
+        assert!(ideas[0].ends_with('…'));
+        assert!(ideas[1].starts_with("Cache parsed manifests"));
+    }
+
+    fn analysis_with_files(files: &[(&str, &str)]) -> RepoAnalysis {
+        RepoAnalysis {
+            files: files
+                .iter()
+                .map(|(path, content)| crate::analysis::FileInfo {
+                    path: path.to_string(),
+                    content: content.to_string(),
+                    language: "rust".to_string(),
+                    size: content.len() as u64,
+                    encoding: Default::default(),
+                    role: crate::fs_utils::classify_file(path).role,
+                    prefix_hash: 0,
+                })
+                .collect(),
+            structure: std::collections::HashMap::new(),
+            technologies: vec!["Rust".to_string()],
+            metrics: std::collections::HashMap::new(),
+            size_metrics: crate::analysis::SizeMetrics {
+                total_size_bytes: 64,
+                total_size_kb: 0,
+                total_size_mb: 0,
+                analyzed_size_bytes: 64,
+                analyzed_size_kb: 0,
+                analyzed_size_mb: 0,
+                largest_files: Vec::new(),
+                size_by_language: std::collections::HashMap::new(),
+            },
+            generated_at: None,
+            from_cache: None,
+            is_lazy_scan: None,
+            scan_progress: None,
+        }
+    }
+
+    fn analysis_with_secret_file() -> RepoAnalysis {
+        analysis_with_files(&[(
+            "src/settings.rs",
+            "const OPENAI_KEY: &str = \"sk-proj-abcdefghijklmnop1234567890\";\n",
+        )])
+    }
+
+    #[test]
+    fn test_extract_project_keywords_keeps_sibling_files() {
+        let analysis = analysis_with_files(&[
+            ("src/api/routes.ts", "export const endpoint = '/users';"),
+            ("src/api/types.ts", "// REST api types"),
+            ("src/api/types.ts", "// REST api types"),
+            ("src/auth/login.ts", "export function login(password: string) {}"),
+        ]);
+        let keywords = extract_project_keywords(&analysis);
+        assert_eq!(
+            keywords.api_related.iter().collect::<Vec<_>>(),
+            vec!["src/api/routes.ts", "src/api/types.ts"]
+        );
+        assert_eq!(keywords.auth_related.len(), 1);
+        assert!(keywords.database_related.is_empty());
+    }
+
+    #[test]
+    fn test_technology_profile_detects_modern_frameworks() {
+        let analysis = analysis_with_files(&[
+            ("src/App.svelte", "<script>let count = 0;</script>"),
+            ("src/store.ts", "import { writable } from \"svelte/store\";"),
+            ("src/counter.tsx", "import { createSignal } from 'solid-js';"),
+            ("astro.config.mjs", "export default {};"),
+        ]);
+        let profile = analyze_technology_profile(&analysis, &extract_project_keywords(&analysis));
+        assert_eq!(profile.frameworks, vec!["Astro", "SolidJS", "Svelte"]);
+        assert_eq!(profile.project_type, "Meta-Framework Application");
+    }
+
+    #[test]
+    fn test_technology_profile_uses_compose_database_services() {
+        let analysis = analysis_with_files(&[
+            ("docker-compose.yml", "services:\n  app:\n    build: .\n  store:\n    image: mariadb:11\n"),
+            ("src/main.rs", "fn main() {}"),
+        ]);
+        let keywords = extract_project_keywords(&analysis);
+        assert!(keywords.database_related.is_empty());
+        assert!(analyze_technology_profile(&analysis, &keywords).has_database);
+    }
+
+    #[test]
+    fn test_comprehensive_context_redacts_previews_when_enabled() {
+        let analysis = analysis_with_secret_file();
+        let redacted = build_comprehensive_context(&analysis, true);
+        assert!(redacted.contains("src/settings.rs"));
+        assert!(!redacted.contains("sk-proj-abcdefghijklmnop1234567890"));
+
+        let raw = build_comprehensive_context(&analysis, false);
+        assert!(raw.contains("sk-proj-abcdefghijklmnop1234567890"));
+    }
+
+    #[test]
+    fn test_file_preview_cuts_multibyte_content_on_char_boundary() {
+        // "é" is two bytes, so byte 300 falls inside a character
+        let content = format!("a{}", "é".repeat(400));
+        let preview = file_preview(&content, false);
+        assert!(preview.ends_with("..."));
+        assert_eq!(preview.trim_end_matches("...").chars().count(), 300);
+        assert_eq!(file_preview("short 日本語", false), "short 日本語");
+    }
+
+    #[test]
+    fn test_file_preview_redacts_secret_crossing_the_cut() {
+        let secret = "sk-proj-abcdefghijklmnop1234567890";
+        let content = format!("{} {}", "a".repeat(290), secret);
+        let preview = file_preview(&content, true);
+        assert!(!preview.contains("sk-proj-abcd"));
+        assert!(preview.starts_with(&"a".repeat(290)));
+    }
+
+    #[test]
+    fn test_redact_previews_defaults_on_for_remote_urls() {
+        let mut settings = Settings::default();
+        assert!(!settings.should_redact_previews());
+        settings.api_url = "https://api.openai.com/v1/chat/completions".to_string();
+        assert!(settings.should_redact_previews());
+        settings.redact_previews = Some(false);
+        assert!(!settings.should_redact_previews());
     }
 }
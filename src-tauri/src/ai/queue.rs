@@ -0,0 +1,459 @@
+// Process-wide queue for outgoing AI requests. One dispatcher task owns
+// execution: it caps requests per minute and in flight, retries rate-limited
+// and overloaded replies with backoff, and reports positions so the UI can
+// say how many requests are ahead
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, Request, Response, StatusCode};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+use crate::storage::Settings;
+
+// Attempts per request, counting the first one
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+// Assumed request duration for ETAs until some have finished
+const INITIAL_AVERAGE: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueueLimits {
+    // 0 for no limit
+    pub max_per_minute: u32,
+    pub max_concurrent: usize,
+}
+
+impl QueueLimits {
+    pub fn from_settings(settings: &Settings) -> Self {
+        QueueLimits {
+            max_per_minute: settings.ai_requests_per_minute,
+            max_concurrent: settings.ai_max_concurrent_requests.max(1) as usize,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct QueuedRequest {
+    pub id: u64,
+    pub kind: String,
+    pub project_path: Option<String>,
+    // Running requests plus those waiting in front of this one
+    pub ahead: usize,
+    pub eta_seconds: u64,
+}
+
+// Payload of `ai:queue_status`, emitted whenever it changes
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct QueueStatus {
+    pub running: usize,
+    // In start order
+    pub waiting: Vec<QueuedRequest>,
+    pub max_per_minute: u32,
+    pub max_concurrent: usize,
+}
+
+pub type StatusListener = Arc<dyn Fn(&QueueStatus) + Send + Sync>;
+
+pub struct Submission {
+    // "ideas", "summary" or "models"; used in logs and status events
+    pub kind: &'static str,
+    pub model: String,
+    pub project_path: Option<String>,
+    // Built with RequestQueue::client(); the body must be cloneable so it
+    // can be retried
+    pub request: Request,
+}
+
+struct Job {
+    id: u64,
+    submission: Submission,
+    attempt: u32,
+    not_before: Option<Instant>,
+    reply: oneshot::Sender<Result<Response, String>>,
+}
+
+enum Message {
+    Submit(Job),
+    Limits(QueueLimits),
+    // `retry` is the job again when it should run another attempt
+    Finished { retry: Option<Job>, took: Duration },
+}
+
+#[derive(Clone)]
+pub struct RequestQueue {
+    tx: mpsc::UnboundedSender<Message>,
+    next_id: Arc<AtomicU64>,
+    // Shared so connections to the endpoint are pooled across requests
+    client: Client,
+}
+
+impl RequestQueue {
+    pub fn start(limits: QueueLimits, listener: StatusListener) -> Self {
+        Self::start_with_window(limits, RATE_WINDOW, listener)
+    }
+
+    fn start_with_window(limits: QueueLimits, window: Duration, listener: StatusListener) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let client = Client::new();
+        let dispatcher = Dispatcher {
+            client: client.clone(),
+            limits,
+            window,
+            waiting: VecDeque::new(),
+            running: 0,
+            started: VecDeque::new(),
+            average: INITIAL_AVERAGE,
+            tx: tx.clone(),
+            listener,
+            published: None,
+        };
+        tauri::async_runtime::spawn(dispatcher.run(rx));
+        RequestQueue { tx, next_id: Arc::new(AtomicU64::new(1)), client }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn set_limits(&self, limits: QueueLimits) {
+        let _ = self.tx.send(Message::Limits(limits));
+    }
+
+    // Waits for a turn, then for the final response after any retries
+    pub async fn submit(&self, submission: Submission) -> Result<Response, String> {
+        let (reply, response) = oneshot::channel();
+        let job = Job {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            submission,
+            attempt: 0,
+            not_before: None,
+            reply,
+        };
+        self.tx.send(Message::Submit(job)).map_err(|_| "The AI request queue has stopped".to_string())?;
+        response.await.map_err(|_| "The AI request queue has stopped".to_string())?
+    }
+}
+
+// How long to wait before retrying, or None when the reply is final
+fn retry_delay(status: StatusCode, headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let retryable = status == StatusCode::TOO_MANY_REQUESTS
+        || matches!(status.as_u16(), 502..=504);
+    if !retryable || attempt + 1 >= MAX_ATTEMPTS {
+        return None;
+    }
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    Some(retry_after.unwrap_or(BASE_BACKOFF * 2u32.pow(attempt)).min(MAX_BACKOFF))
+}
+
+async fn execute(client: Client, mut job: Job, tx: mpsc::UnboundedSender<Message>) {
+    let started = Instant::now();
+    let Some(request) = job.submission.request.try_clone() else {
+        let _ = job.reply.send(Err("AI request body can't be queued".to_string()));
+        let _ = tx.send(Message::Finished { retry: None, took: started.elapsed() });
+        return;
+    };
+    let result = super::send_logged(&client, job.submission.kind, &job.submission.model, request).await;
+    let took = started.elapsed();
+
+    if let Ok(response) = &result {
+        if let Some(delay) = retry_delay(response.status(), response.headers(), job.attempt) {
+            tracing::warn!(
+                kind = job.submission.kind,
+                status = response.status().as_u16(),
+                attempt = job.attempt + 1,
+                delay_ms = delay.as_millis() as u64,
+                "AI request will be retried"
+            );
+            job.attempt += 1;
+            job.not_before = Some(Instant::now() + delay);
+            let _ = tx.send(Message::Finished { retry: Some(job), took });
+            return;
+        }
+    }
+    let _ = job.reply.send(result.map_err(|e| super::ai_error("Request failed", e)));
+    let _ = tx.send(Message::Finished { retry: None, took });
+}
+
+struct Dispatcher {
+    client: Client,
+    limits: QueueLimits,
+    window: Duration,
+    waiting: VecDeque<Job>,
+    running: usize,
+    // Start times inside the current rate window, oldest first
+    started: VecDeque<Instant>,
+    average: Duration,
+    tx: mpsc::UnboundedSender<Message>,
+    listener: StatusListener,
+    published: Option<QueueStatus>,
+}
+
+impl Dispatcher {
+    async fn run(mut self, mut rx: mpsc::UnboundedReceiver<Message>) {
+        loop {
+            self.start_ready();
+            self.publish();
+            let message = match self.next_wake() {
+                Some(at) => tokio::select! {
+                    message = rx.recv() => message,
+                    _ = tokio::time::sleep_until(at) => continue,
+                },
+                None => rx.recv().await,
+            };
+            // The dispatcher holds a sender itself, so this never ends
+            let Some(message) = message else { return };
+            match message {
+                Message::Submit(job) => self.waiting.push_back(job),
+                Message::Limits(limits) => self.limits = limits,
+                Message::Finished { retry, took } => {
+                    self.running -= 1;
+                    self.average = (self.average * 3 + took) / 4;
+                    // Retries go first; they were submitted before anything waiting
+                    if let Some(job) = retry {
+                        self.waiting.push_front(job);
+                    }
+                }
+            }
+        }
+    }
+
+    fn max_concurrent(&self) -> usize {
+        self.limits.max_concurrent.max(1)
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self.started.front().is_some_and(|at| now.duration_since(*at) >= self.window) {
+            self.started.pop_front();
+        }
+    }
+
+    fn has_rate_slot(&self) -> bool {
+        self.limits.max_per_minute == 0 || self.started.len() < self.limits.max_per_minute as usize
+    }
+
+    fn start_ready(&mut self) {
+        let now = Instant::now();
+        self.prune(now);
+        while self.running < self.max_concurrent() && self.has_rate_slot() {
+            let Some(index) = self.waiting.iter().position(|job| job.not_before.is_none_or(|at| at <= now)) else {
+                return;
+            };
+            let Some(job) = self.waiting.remove(index) else { return };
+            self.running += 1;
+            self.started.push_back(now);
+            tokio::spawn(execute(self.client.clone(), job, self.tx.clone()));
+        }
+    }
+
+    // When a waiting job could next start without a new message arriving
+    fn next_wake(&self) -> Option<Instant> {
+        let backoff = self.waiting.iter().filter_map(|job| job.not_before).min();
+        let rate = (!self.waiting.is_empty() && self.running < self.max_concurrent() && !self.has_rate_slot())
+            .then(|| self.started.front().map(|at| *at + self.window))
+            .flatten();
+        backoff.into_iter().chain(rate).min()
+    }
+
+    fn eta(&self, index: usize, job: &Job, now: Instant) -> Duration {
+        let max_concurrent = self.max_concurrent();
+        let ahead = self.running + index;
+        let concurrency = if ahead < max_concurrent { Duration::ZERO } else { self.average * (ahead / max_concurrent) as u32 };
+        let rate = match self.limits.max_per_minute as usize {
+            0 => Duration::ZERO,
+            per_window => {
+                let slot = self.started.len() + index;
+                if slot < per_window {
+                    Duration::ZERO
+                } else if let Some(at) = self.started.get(slot - per_window) {
+                    (*at + self.window).saturating_duration_since(now)
+                } else {
+                    self.window * ((slot - per_window) / per_window + 1) as u32
+                }
+            }
+        };
+        let backoff = job.not_before.map(|at| at.saturating_duration_since(now)).unwrap_or_default();
+        concurrency.max(rate).max(backoff)
+    }
+
+    fn publish(&mut self) {
+        let now = Instant::now();
+        let status = QueueStatus {
+            running: self.running,
+            waiting: self
+                .waiting
+                .iter()
+                .enumerate()
+                .map(|(index, job)| QueuedRequest {
+                    id: job.id,
+                    kind: job.submission.kind.to_string(),
+                    project_path: job.submission.project_path.clone(),
+                    ahead: self.running + index,
+                    eta_seconds: self.eta(index, job, now).as_secs_f64().ceil() as u64,
+                })
+                .collect(),
+            max_per_minute: self.limits.max_per_minute,
+            max_concurrent: self.max_concurrent(),
+        };
+        if self.published.as_ref() != Some(&status) {
+            (self.listener)(&status);
+            self.published = Some(status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[derive(Clone, Default)]
+    struct MockServer {
+        url: String,
+        arrivals: Arc<Mutex<Vec<Instant>>>,
+        active: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    // Answers every request after `delay`; the first `rate_limited` get a
+    // 429 with `Retry-After: 0`
+    async fn mock_server(delay: Duration, rate_limited: usize) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = MockServer { url: format!("http://{}/v1/chat/completions", listener.local_addr().unwrap()), ..Default::default() };
+        let state = server.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let Ok(read) = socket.read(&mut buffer).await else { return };
+                        if read == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buffer[..read]);
+                    }
+                    let hit = {
+                        let mut arrivals = state.arrivals.lock().unwrap();
+                        arrivals.push(Instant::now());
+                        arrivals.len()
+                    };
+                    let active = state.active.fetch_add(1, Ordering::SeqCst) + 1;
+                    state.peak.fetch_max(active, Ordering::SeqCst);
+                    tokio::time::sleep(delay).await;
+                    state.active.fetch_sub(1, Ordering::SeqCst);
+                    let response = if hit <= rate_limited {
+                        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    } else {
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}"
+                    };
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        server
+    }
+
+    fn submission(queue: &RequestQueue, url: &str) -> Submission {
+        Submission {
+            kind: "ideas",
+            model: "test".to_string(),
+            project_path: None,
+            request: queue.client().post(url).body("{}").build().unwrap(),
+        }
+    }
+
+    async fn submit_all(queue: &RequestQueue, url: &str, count: usize) -> Vec<StatusCode> {
+        let handles: Vec<_> = (0..count)
+            .map(|_| {
+                let (queue, request) = (queue.clone(), submission(queue, url));
+                tokio::spawn(async move { queue.submit(request).await.map(|r| r.status()) })
+            })
+            .collect();
+        let mut statuses = Vec::new();
+        for handle in handles {
+            statuses.push(handle.await.unwrap().unwrap());
+        }
+        statuses
+    }
+
+    #[tokio::test]
+    async fn test_queue_caps_requests_in_flight() {
+        let server = mock_server(Duration::from_millis(150), 0).await;
+        let seen: Arc<Mutex<Vec<QueueStatus>>> = Arc::default();
+        let record = seen.clone();
+        let listener: StatusListener = Arc::new(move |status| record.lock().unwrap().push(status.clone()));
+        let queue = RequestQueue::start(QueueLimits { max_per_minute: 0, max_concurrent: 2 }, listener);
+
+        let statuses = submit_all(&queue, &server.url, 6).await;
+        assert!(statuses.iter().all(|s| s.is_success()));
+        assert_eq!(server.arrivals.lock().unwrap().len(), 6);
+        assert_eq!(server.peak.load(Ordering::SeqCst), 2);
+
+        // Replies go out before the dispatcher hears the request finished
+        for _ in 0..50 {
+            if seen.lock().unwrap().last().is_some_and(|s| s.running == 0) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let seen = seen.lock().unwrap();
+        let busiest = seen.iter().max_by_key(|s| s.waiting.len()).unwrap();
+        assert_eq!(busiest.running, 2);
+        assert_eq!(busiest.waiting[0].ahead, 2);
+        assert!(busiest.waiting.iter().all(|w| w.kind == "ideas"));
+        assert_eq!(seen.last().unwrap().running, 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_paces_requests_per_window() {
+        let server = mock_server(Duration::ZERO, 0).await;
+        let window = Duration::from_millis(300);
+        let limits = QueueLimits { max_per_minute: 2, max_concurrent: 10 };
+        let queue = RequestQueue::start_with_window(limits, window, Arc::new(|_| {}));
+
+        let started = Instant::now();
+        let statuses = submit_all(&queue, &server.url, 5).await;
+        assert!(statuses.iter().all(|s| s.is_success()));
+        // Five requests at two per window need two full windows of waiting
+        assert!(started.elapsed() >= window * 2);
+        let arrivals = server.arrivals.lock().unwrap();
+        for pair in arrivals.windows(3) {
+            assert!(pair[2].duration_since(pair[0]) >= window - Duration::from_millis(50));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_retries_rate_limited_replies() {
+        let server = mock_server(Duration::ZERO, 2).await;
+        let queue = RequestQueue::start(QueueLimits { max_per_minute: 0, max_concurrent: 1 }, Arc::new(|_| {}));
+
+        let statuses = submit_all(&queue, &server.url, 1).await;
+        assert_eq!(statuses, vec![StatusCode::OK]);
+        assert_eq!(server.arrivals.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_retry_delay_honors_retry_after_and_gives_up() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_delay(StatusCode::TOO_MANY_REQUESTS, &headers, 0), Some(BASE_BACKOFF));
+        assert_eq!(retry_delay(StatusCode::SERVICE_UNAVAILABLE, &headers, 2), Some(BASE_BACKOFF * 4));
+        assert_eq!(retry_delay(StatusCode::TOO_MANY_REQUESTS, &headers, MAX_ATTEMPTS - 1), None);
+        assert_eq!(retry_delay(StatusCode::BAD_REQUEST, &headers, 0), None);
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_delay(StatusCode::TOO_MANY_REQUESTS, &headers, 0), Some(Duration::from_secs(7)));
+    }
+}
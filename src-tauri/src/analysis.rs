@@ -10,8 +10,15 @@ use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use tauri::{Emitter, State};
 
+use crate::background;
+use crate::errors;
 use crate::db::{self, DbPool};
-use crate::fs_utils::{get_language_from_extension, read_text_prefix_limited, should_analyze_file, walker};
+use crate::fs_utils::{self, classify_file, read_files_batch, walker_with_options, PrefixOptions, SymlinkPolicy, WalkOptions, WalkStats, FileFilter, FileRole, FilterDecision, FilterReason, TextEncoding, MAX_WALK_DEPTH};
+use crate::inventory;
+use crate::languages;
+use crate::onboarding;
+use crate::performance;
+use crate::usage::{self, UsageEvent, UsageKind};
 
 // Analysis data structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +27,14 @@ pub struct FileInfo {
   pub content: String,
   pub language: String,
   pub size: u64,
+  #[serde(default)]
+  pub encoding: TextEncoding,
+  #[serde(default)]
+  pub role: FileRole,
+  // xxh3 of the bytes `content` was read from. Only set on fresh analyses,
+  // where it lets the cache notice edits that leave the metrics unchanged
+  #[serde(skip)]
+  pub prefix_hash: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,7 +45,7 @@ pub struct FileSizeInfo {
   pub language: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SizeMetrics {
   pub total_size_bytes: u64,
   pub total_size_kb: u64,
@@ -50,6 +65,13 @@ pub struct ScanProgress {
   pub estimated_total_files: Option<usize>,
 }
 
+// Version of the cached RepoAnalysis encoding. bincode can't skip or default
+// fields, so bump this whenever RepoAnalysis (or a type inside it) changes
+// shape; cached rows with another version are treated as misses
+pub const CURRENT_ANALYSIS_SCHEMA_VERSION: u32 = 3;
+// Previews drop a partial last line when a newline is this close to the cap
+const PREVIEW_LINE_TOLERANCE: usize = 1024;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoAnalysis {
   pub files: Vec<FileInfo>,
@@ -63,18 +85,62 @@ pub struct RepoAnalysis {
   pub scan_progress: Option<ScanProgress>,
 }
 
+// Everything a dashboard card needs from a cached analysis, minus the file
+// contents; stored as JSON next to the blob so it reads without decoding it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisMetricsSummary {
+  pub metrics: HashMap<String, i32>,
+  pub technologies: Vec<String>,
+  pub size_metrics: SizeMetrics,
+  pub generated_at: Option<String>,
+  pub from_cache: Option<bool>,
+  // None on summaries stored before this was recorded
+  #[serde(default)]
+  pub is_lazy_scan: Option<bool>,
+}
+
+impl From<&RepoAnalysis> for AnalysisMetricsSummary {
+  fn from(analysis: &RepoAnalysis) -> Self {
+    AnalysisMetricsSummary {
+      metrics: analysis.metrics.clone(),
+      technologies: analysis.technologies.clone(),
+      size_metrics: analysis.size_metrics.clone(),
+      generated_at: analysis.generated_at.clone(),
+      from_cache: analysis.from_cache,
+      is_lazy_scan: analysis.is_lazy_scan,
+    }
+  }
+}
+
 // Internal structures for processing
 struct FileMetadata {
   pub path: String,
   pub size: u64,
   pub language: String,
+  pub role: FileRole,
   pub parent: Option<String>,
 }
 
+impl FileMetadata {
+  // Classified by the path below the root, so a checkout under `~/tests/`
+  // or `~/.github/workflows/` doesn't turn every file into a test or CI file
+  fn new(root: &Path, path: &Path, size: u64) -> Self {
+    let class = classify_file(&path.strip_prefix(root).unwrap_or(path).to_string_lossy());
+    FileMetadata {
+      path: path.to_string_lossy().to_string(),
+      size,
+      language: class.language,
+      role: class.role,
+      parent: path.parent().map(|p| p.to_string_lossy().to_string()),
+    }
+  }
+}
+
 struct FileProcessResult {
   pub file_info: Option<FileInfo>,
   pub lines: usize,
   pub language: String,
+  pub role: FileRole,
   pub parent: Option<String>,
   pub path: String,
   pub size: u64,
@@ -116,18 +182,25 @@ struct ProgressUpdate {
   bytes_processed: u64,
   total_bytes: Option<u64>,
   skipped_filtered: Option<usize>,
+  // The same skips broken down by why the filter rejected them
+  skipped_by_reason: Option<HashMap<FilterReason, usize>>,
   dirs_seen: Option<usize>,
+  // Entries the walk couldn't read, such as directories without permission;
+  // nonzero means the counts are low
+  io_errors: Option<usize>,
+  error_message: Option<String>,
 }
 
 struct ProgressTracker {
   start: Instant,
   phase: Mutex<String>,
   current_file: Mutex<Option<String>>,
-  files_discovered: AtomicUsize,
+  // Files discovered, directories seen, filter skips and walk errors
+  walk: WalkStats,
   files_processed: AtomicUsize,
   total_files: AtomicUsize,
-  skipped_filtered: AtomicUsize,
-  dirs_seen: AtomicUsize,
+  // Indexed like `FilterReason::ALL`
+  skipped_by_reason: [AtomicUsize; FilterReason::ALL.len()],
   bytes_processed: AtomicU64,
   total_bytes: AtomicU64,
   complete: AtomicBool,
@@ -139,11 +212,10 @@ impl ProgressTracker {
       start: Instant::now(),
       phase: Mutex::new("init".to_string()),
       current_file: Mutex::new(None),
-      files_discovered: AtomicUsize::new(0),
+      walk: WalkStats::default(),
       files_processed: AtomicUsize::new(0),
       total_files: AtomicUsize::new(0),
-      skipped_filtered: AtomicUsize::new(0),
-      dirs_seen: AtomicUsize::new(0),
+      skipped_by_reason: Default::default(),
       bytes_processed: AtomicU64::new(0),
       total_bytes: AtomicU64::new(0),
       complete: AtomicBool::new(false),
@@ -156,18 +228,15 @@ impl ProgressTracker {
   fn set_current_file(&self, file: Option<String>) {
     if let Ok(mut cf) = self.current_file.lock() { *cf = file; }
   }
-  fn increment_discovered(&self) {
-    self.files_discovered.fetch_add(1, Ordering::Relaxed);
-  }
   fn increment_processed(&self, bytes: usize) {
     self.files_processed.fetch_add(1, Ordering::Relaxed);
     self.bytes_processed.fetch_add(bytes as u64, Ordering::Relaxed);
   }
-  fn increment_skipped_filtered(&self) {
-    self.skipped_filtered.fetch_add(1, Ordering::Relaxed);
-  }
-  fn increment_dirs_seen(&self) {
-    self.dirs_seen.fetch_add(1, Ordering::Relaxed);
+  fn increment_skipped_filtered(&self, reason: FilterReason) {
+    self.walk.record_skipped();
+    if let Some(index) = FilterReason::ALL.iter().position(|r| *r == reason) {
+      self.skipped_by_reason[index].fetch_add(1, Ordering::Relaxed);
+    }
   }
   fn set_total_files(&self, total: usize) { self.total_files.store(total, Ordering::Relaxed); }
   fn set_total_bytes(&self, total: usize) { self.total_bytes.store(total as u64, Ordering::Relaxed); }
@@ -196,7 +265,7 @@ impl ProgressTracker {
     ProgressUpdate {
       folder_path: folder_path.to_string(),
       phase,
-      files_discovered: self.files_discovered.load(Ordering::Relaxed),
+      files_discovered: self.walk.files(),
       files_processed: processed,
       total_files: total,
       percentage,
@@ -207,8 +276,17 @@ impl ProgressTracker {
       estimated_remaining_ms: None,
       bytes_processed,
       total_bytes: Some(total_bytes),
-      skipped_filtered: Some(self.skipped_filtered.load(Ordering::Relaxed)),
-      dirs_seen: Some(self.dirs_seen.load(Ordering::Relaxed)),
+      skipped_filtered: Some(self.walk.skipped_by_filter()),
+      skipped_by_reason: Some(
+        FilterReason::ALL
+          .iter()
+          .zip(&self.skipped_by_reason)
+          .map(|(reason, count)| (*reason, count.load(Ordering::Relaxed)))
+          .collect(),
+      ),
+      dirs_seen: Some(self.walk.dirs()),
+      io_errors: Some(self.walk.io_errors()),
+      error_message: None,
     }
   }
 }
@@ -226,11 +304,42 @@ fn set_cancel_flag(path: &str, flag: Arc<AtomicBool>) {
   }
 }
 
-fn take_cancel_flag(path: &str) -> Option<Arc<AtomicBool>> {
-  if let Ok(mut map) = CANCEL_FLAGS.lock() { map.remove(path) } else { None }
+// Unregisters the run's flag however it ends, including early error
+// returns; a newer run of the same path keeps its own flag
+struct CancelFlagGuard {
+  path: String,
+  flag: Arc<AtomicBool>,
 }
 
-async fn is_favorite_project(db_pool: &Arc<DbPool>, folder_path: &str) -> bool {
+impl CancelFlagGuard {
+  fn register(path: &str) -> Self {
+    let flag = Arc::new(AtomicBool::new(false));
+    set_cancel_flag(path, flag.clone());
+    Self { path: path.to_string(), flag }
+  }
+}
+
+impl Drop for CancelFlagGuard {
+  fn drop(&mut self) {
+    if let Ok(mut map) = CANCEL_FLAGS.lock() {
+      if map.get(&self.path).is_some_and(|flag| Arc::ptr_eq(flag, &self.flag)) {
+        map.remove(&self.path);
+      }
+    }
+  }
+}
+
+// True while any analysis is scanning or writing its cache
+pub fn is_analysis_running() -> bool {
+  running_analyses() > 0
+}
+
+pub fn running_analyses() -> usize {
+  CANCEL_FLAGS.lock().map(|map| map.len()).unwrap_or(0)
+}
+
+// Read from the projects table; paths not yet in the database are not favorites
+fn is_favorite_project(db_pool: &DbPool, folder_path: &str) -> bool {
   if let Ok(conn) = db_pool.get() {
     if let Ok(Some(project)) = db::get_project_by_path(&conn, folder_path) {
       return project.is_favorite;
@@ -250,69 +359,105 @@ pub async fn cancel_analysis(folder_path: String) -> Result<(), String> {
   Ok(())
 }
 
-// Process files in parallel batches
+// Cancel every running analysis, e.g. on quit; returns how many were asked
+pub fn cancel_all() -> usize {
+  CANCEL_FLAGS
+    .lock()
+    .map(|map| {
+      map.values().for_each(|flag| flag.store(true, Ordering::Relaxed));
+      map.len()
+    })
+    .unwrap_or(0)
+}
+
+// Process files in parallel batches. Also returns an estimate of the peak
+// bytes held for file contents: one pooled read buffer per worker plus the
+// previews kept for the result
 async fn process_files_parallel(
   files: &[FileMetadata],
   is_favorite: bool,
   sample_limit: usize,
   tracker: &Arc<ProgressTracker>,
-) -> Vec<FileProcessResult> {
-  let sampled_count = Arc::new(AtomicUsize::new(0));
+  cancel_flag: &AtomicBool,
+) -> (Vec<FileProcessResult>, usize) {
   let max_content_size = if is_favorite { 150_000 } else { 100_000 } as u64;
   let content_limit = if is_favorite { 7500 } else { 5000 };
 
   let chunk_size = 50;
+  let mut sampled = 0usize;
   let mut all_results = Vec::with_capacity(files.len());
   
   for chunk in files.chunks(chunk_size) {
+    if cancel_flag.load(Ordering::Relaxed) { break; }
+    // Choose the sampled files up front so the limit holds exactly, then
+    // read them as one batch
+    let to_load: Vec<bool> = chunk
+      .iter()
+      .map(|metadata| {
+        let load = metadata.size < max_content_size && sampled < sample_limit;
+        sampled += load as usize;
+        load
+      })
+      .collect();
+    let paths: Vec<&str> = chunk
+      .iter()
+      .zip(&to_load)
+      .filter(|(_, load)| **load)
+      .map(|(metadata, _)| metadata.path.as_str())
+      .collect();
+    let mut reads = read_files_batch(&paths, content_limit, PrefixOptions::whole_lines(PREVIEW_LINE_TOLERANCE)).into_iter();
+    let reads: Vec<_> = to_load.iter().map(|load| if *load { reads.next() } else { None }).collect();
+
     let chunk_results: Vec<FileProcessResult> = chunk
       .par_iter()
-      .map(|metadata| {
+      .zip(reads)
+      .map(|(metadata, read)| {
         tracker.set_current_file(Some(metadata.path.clone()));
-        let current_sampled = sampled_count.load(Ordering::Relaxed);
-        let should_load = (metadata.size < max_content_size) && (current_sampled < sample_limit);
-        let result: FileProcessResult;
-
-        if should_load {
-          sampled_count.fetch_add(1, Ordering::Relaxed);
-          
-          let (content, was_truncated) = read_text_prefix_limited(&metadata.path, content_limit)
-            .unwrap_or_else(|_| (String::new(), false));
-          
-          let lines = content.lines().count();
-          let display_content = if was_truncated {
-            format!("{}...(truncated)", content)
-          } else {
-            content
-          };
-          
-          let file_info = Some(FileInfo {
-            path: metadata.path.clone(),
-            content: display_content,
-            language: metadata.language.clone(),
-            size: metadata.size,
-          });
-          
-          result = FileProcessResult {
-            file_info,
-            lines,
-            language: metadata.language.clone(),
-            parent: metadata.parent.clone(),
-            path: metadata.path.clone(),
-            size: metadata.size,
-            is_analyzed: true,
-          };
-        } else {
-          result = FileProcessResult {
-            file_info: None,
-            lines: 0,
-            language: metadata.language.clone(),
-            parent: metadata.parent.clone(),
-            path: metadata.path.clone(),
-            size: metadata.size,
-            is_analyzed: false,
-          };
-        }
+        let result = match read {
+          Some(Ok(prefix)) => {
+            let lines = prefix.complete_lines;
+            let encoding = prefix.encoding;
+            let prefix_hash = prefix.prefix_hash;
+            let display_content = if prefix.truncated {
+              format!("{}...(truncated)", prefix.content)
+            } else {
+              prefix.content
+            };
+            FileProcessResult {
+              file_info: Some(FileInfo {
+                path: metadata.path.clone(),
+                content: display_content,
+                language: metadata.language.clone(),
+                size: metadata.size,
+                encoding,
+                role: metadata.role,
+                prefix_hash,
+              }),
+              lines,
+              language: metadata.language.clone(),
+              role: metadata.role,
+              parent: metadata.parent.clone(),
+              path: metadata.path.clone(),
+              size: metadata.size,
+              is_analyzed: true,
+            }
+          }
+          unread => {
+            if let Some(Err(e)) = unread {
+              tracing::warn!(path = %metadata.path, error = %e, "Could not read file");
+            }
+            FileProcessResult {
+              file_info: None,
+              lines: 0,
+              language: metadata.language.clone(),
+              role: metadata.role,
+              parent: metadata.parent.clone(),
+              path: metadata.path.clone(),
+              size: metadata.size,
+              is_analyzed: false,
+            }
+          }
+        };
 
         tracker.increment_processed(metadata.size as usize);
         result
@@ -322,7 +467,9 @@ async fn process_files_parallel(
     all_results.extend(chunk_results);
   }
   
-  all_results
+  let read_buffers = rayon::current_num_threads().min(sampled) * (content_limit + 1);
+  let previews: usize = all_results.iter().filter_map(|r| r.file_info.as_ref()).map(|f| f.content.len()).sum();
+  (all_results, read_buffers + previews)
 }
 
 fn aggregate_results(results: Vec<FileProcessResult>) -> (
@@ -380,10 +527,15 @@ fn aggregate_results(results: Vec<FileProcessResult>) -> (
   
   let technologies: Vec<String> = technologies_set.into_iter().collect();
   
-  let mut metrics = HashMap::with_capacity(3);
+  let mut metrics = HashMap::with_capacity(3 + FileRole::ALL.len());
   metrics.insert("total_files".to_string(), total_files);
   metrics.insert("total_lines".to_string(), total_lines);
   metrics.insert("analyzed_files".to_string(), files.len() as i32);
+  // "role_source", "role_test", ... for every role, zero included
+  for role in FileRole::ALL {
+    let count = results.iter().filter(|r| r.role == role).count();
+    metrics.insert(format!("role_{}", role.as_str()), count as i32);
+  }
   
   let size_metrics = SizeMetrics {
     total_size_bytes,
@@ -399,30 +551,53 @@ fn aggregate_results(results: Vec<FileProcessResult>) -> (
   (files, structure, technologies, metrics, size_metrics)
 }
 
+// What the analysis walk descends into beyond the defaults
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanScope {
+  pub include_submodules: bool,
+  // Every hidden entry, not just the allowlisted ones; `.git` stays out
+  pub include_hidden: bool,
+  pub symlinks: SymlinkPolicy,
+}
+
+impl ScanScope {
+  // Only the default scope reads or writes the cache; a wider one is a
+  // one-off view whose counts must not reach the next default analysis
+  fn is_cacheable(&self) -> bool {
+    !self.include_submodules && !self.include_hidden && !self.symlinks.follow
+  }
+}
+
 // Main analysis implementation with SQLite caching
+#[tracing::instrument(level = "info", skip_all, fields(path = %folder_path, force, lazy = use_lazy_scan))]
 async fn analyze_repository_impl(
   db_pool: Arc<DbPool>,
   folder_path: String,
   force: bool,
   use_lazy_scan: bool,
   trigger_full_scan: bool,
+  scope: ScanScope,
   window: Option<tauri::Window>,
 ) -> Result<RepoAnalysis, String> {
+  if crate::shutdown::is_shutting_down() {
+    return Err("RepoMuse is shutting down".to_string());
+  }
   let path = Path::new(&folder_path);
-  if !path.exists() || !path.is_dir() {
-    return Err("Invalid folder path".to_string());
+  if !fs_utils::is_existing_dir(path) {
+    return Err(emit_error(window.as_ref(), &folder_path, false, "Invalid folder path".to_string()));
   }
 
-  let is_favorite = is_favorite_project(&db_pool, &folder_path).await;
+  let is_favorite = is_favorite_project(&db_pool, &folder_path);
   
   if is_favorite {
-    println!("[Analysis] Analyzing favorite project with priority: {}", folder_path);
+    tracing::debug!("Analyzing favorite project with priority");
   }
+  let fail = |e: String| emit_error(window.as_ref(), &folder_path, is_favorite, e);
 
   // Get or create project in database
-  let conn = db_pool.get().map_err(|e| e.to_string())?;
+  let conn = db_pool.get().map_err(|e| fail(e.to_string()))?;
   let project = db::get_project_by_path(&conn, &folder_path)
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| fail(e.to_string()))?;
   
   let project_id = if let Some(p) = project {
     p.id
@@ -436,14 +611,20 @@ async fn analyze_repository_impl(
     let is_git = path.join(".git").exists();
     
     let id = db::upsert_project(&conn, &folder_path, &name, None, is_git)
-      .map_err(|e| e.to_string())?;
+      .map_err(|e| fail(e.to_string()))?;
     id
   };
+  // Don't hold a pooled connection for the whole scan
+  drop(conn);
 
-  // Cache check using SQLite
-  if !force && !trigger_full_scan {
-    if let Ok(Some(cached)) = db::get_cached_analysis(&conn, project_id) {
-      let mut a = cached.clone();
+  // Cache check using SQLite; cached analyses never include submodules,
+  // non-allowlisted hidden files or followed symlinks
+  if !force && !trigger_full_scan && scope.is_cacheable() {
+    let lookup_started = Instant::now();
+    let cached = load_cached_analysis(&db_pool, project_id).await;
+    tracing::debug!(hit = cached.is_some(), "Analysis cache lookup");
+    if let Some(cached) = cached {
+      let mut a = cached;
       a.from_cache = Some(true);
       
       if a.is_lazy_scan.unwrap_or(false) && trigger_full_scan {
@@ -465,15 +646,25 @@ async fn analyze_repository_impl(
             bytes_processed: a.size_metrics.total_size_bytes,
             total_bytes: Some(a.size_metrics.total_size_bytes),
             skipped_filtered: None,
+            skipped_by_reason: None,
             dirs_seen: None,
+            io_errors: a.metrics.get("io_errors").map(|n| *n as usize),
+            error_message: None,
           });
         }
+        usage::record(&db_pool, UsageKind::Analysis, UsageEvent {
+          project_path: Some(&folder_path),
+          duration_ms: Some(lookup_started.elapsed().as_millis() as u64),
+          cache_hit: Some(true),
+          tokens: None,
+        });
         return Ok(a);
       }
     }
   }
 
   // Perform analysis
+  let started = Instant::now();
   let mut config = LazyLoadConfig::default();
   if is_favorite {
     config.initial_scan_limit = 150;
@@ -485,8 +676,12 @@ async fn analyze_repository_impl(
   let tracker = Arc::new(ProgressTracker::new());
   tracker.set_phase("discovery");
 
-  let cancel_flag = Arc::new(AtomicBool::new(false));
-  set_cancel_flag(&folder_path, cancel_flag.clone());
+  let cancel_guard = CancelFlagGuard::register(&folder_path);
+  let cancel_flag = cancel_guard.flag.clone();
+  // Checked after registering, so maintenance starting now sees this analysis
+  if crate::maintenance::is_maintenance_running() {
+    return Err(fail("Database maintenance is running; try again once it finishes".to_string()));
+  }
 
   let progress_handle = if let Some(w) = &window {
     Some(spawn_progress_emitter(
@@ -497,7 +692,8 @@ async fn analyze_repository_impl(
     ).await)
   } else { None };
 
-  // Discover files
+  // Discover files; the filter is fixed for the whole run
+  let filter = FileFilter::current();
   let mut file_metadatas: Vec<FileMetadata> = Vec::with_capacity(1000);
   
   let scan_limit = if use_lazy_scan && !trigger_full_scan {
@@ -506,43 +702,46 @@ async fn analyze_repository_impl(
     usize::MAX
   };
 
-  for result in walker(path).take(scan_limit) {
+  for result in walker_with_options(path, WalkOptions {
+    include_submodules: scope.include_submodules,
+    include_hidden: scope.include_hidden,
+    symlinks: scope.symlinks,
+    max_depth: Some(MAX_WALK_DEPTH),
+  }).take(scan_limit) {
     if cancel_flag.load(Ordering::Relaxed) { break; }
-    if let Ok(entry) = result {
+    if let Some(entry) = tracker.walk.observe(result) {
       if entry.file_type().map_or(false, |ft| ft.is_file()) {
-        tracker.increment_discovered();
-        if should_analyze_file(&entry.path().to_string_lossy()) {
-          if let Ok(metadata) = entry.metadata() {
-            let path_str = entry.path().to_string_lossy().to_string();
-            file_metadatas.push(FileMetadata {
-              path: path_str,
-              size: metadata.len(),
-              language: get_language_from_extension(&entry.path().to_string_lossy()),
-              parent: entry.path().parent().map(|p| p.to_string_lossy().to_string()),
-            });
-          }
+        if let FilterDecision::Skip(reason) = filter.matches(path, entry.path()) {
+          tracker.increment_skipped_filtered(reason);
+        } else if let Ok(metadata) = entry.metadata().or_else(|_| fs_utils::metadata(entry.path())) {
+          file_metadatas.push(FileMetadata::new(path, entry.path(), metadata.len()));
         } else {
-          tracker.increment_skipped_filtered();
+          tracker.walk.record_error(&format!("{}: metadata unreadable", entry.path().display()));
         }
-      } else if entry.file_type().map_or(false, |ft| ft.is_dir()) {
-        tracker.increment_dirs_seen();
       }
     }
   }
+  let walk = tracker.walk.summary();
+  if walk.io_errors > 0 {
+    tracing::warn!(io_errors = walk.io_errors, samples = ?walk.error_samples, "Unreadable entries during walk");
+  }
 
   tracker.set_phase("processing");
   tracker.set_total_files(file_metadatas.len());
   let total_bytes: usize = file_metadatas.iter().map(|m| m.size as usize).sum();
   tracker.set_total_bytes(total_bytes);
 
-  let results = process_files_parallel(
+  let (results, estimated_peak_read_bytes) = process_files_parallel(
     &file_metadatas,
     is_favorite,
     if use_lazy_scan { config.sample_content_limit } else { file_metadatas.len() },
     &tracker,
+    &cancel_flag,
   ).await;
 
-  let (files, structure, technologies, metrics, size_metrics) = aggregate_results(results);
+  let (files, structure, technologies, mut metrics, size_metrics) = aggregate_results(results);
+  metrics.insert("io_errors".to_string(), walk.io_errors as i32);
+  metrics.insert("estimated_peak_read_bytes".to_string(), estimated_peak_read_bytes.min(i32::MAX as usize) as i32);
 
   let is_lazy = use_lazy_scan && !trigger_full_scan;
   let analysis = RepoAnalysis {
@@ -566,27 +765,172 @@ async fn analyze_repository_impl(
     },
   };
 
-  // Cache the analysis in SQLite
-  let ttl_hours = if is_favorite { 2 } else { 1 };
-  if let Err(e) = db::cache_analysis(&conn, project_id, &analysis, ttl_hours) {
-    eprintln!("Failed to cache analysis: {}", e);
-  }
+  let ttl_hours = cache_ttl_hours(is_favorite);
+  let (analysis, cancelled) = if scope.is_cacheable() {
+    persist_unless_cancelled(&db_pool, project_id, analysis, ttl_hours, &cancel_flag, window.is_none())
+      .await
+      .map_err(fail)?
+  } else {
+    (analysis, cancel_flag.load(Ordering::Relaxed))
+  };
 
-  if cancel_flag.load(Ordering::Relaxed) {
+  if cancelled {
     tracker.set_phase("cancelled");
   } else {
     tracker.set_phase("complete");
+    onboarding::mark_step_in(&db_pool, onboarding::Step::FirstAnalysisDone);
+    usage::record(&db_pool, UsageKind::Analysis, UsageEvent {
+      project_path: Some(&folder_path),
+      duration_ms: Some(started.elapsed().as_millis() as u64),
+      cache_hit: Some(false),
+      tokens: None,
+    });
   }
+  tracing::info!(
+    files = file_metadatas.len(),
+    analyzed = analysis.files.len(),
+    elapsed_ms = started.elapsed().as_millis() as u64,
+    cancelled,
+    "Analysis finished"
+  );
   tracker.mark_complete();
   
   if let Some(handle) = progress_handle { 
     let _ = tokio::time::timeout(Duration::from_secs(1), handle).await; 
   }
-  let _ = take_cancel_flag(&folder_path);
+  drop(cancel_guard);
 
   Ok(analysis)
 }
 
+// Cache the analysis in SQLite unless the run was cancelled: a cancelled
+// walk or read loop stops early, and its partial result must not replace
+// a complete cached one. Returns the analysis and whether it was cancelled.
+// A failed cache write is an error only when `cache_required`: background
+// runs have no window, so the cache is all they produce
+async fn persist_unless_cancelled(
+  db_pool: &Arc<DbPool>,
+  project_id: i64,
+  analysis: RepoAnalysis,
+  ttl_hours: i64,
+  cancel_flag: &AtomicBool,
+  cache_required: bool,
+) -> Result<(RepoAnalysis, bool), String> {
+  if cancel_flag.load(Ordering::Relaxed) {
+    tracing::debug!(project_id, "Analysis cancelled; not caching partial result");
+    return Ok((analysis, true));
+  }
+  let (analysis, cached) = store_cached_analysis(db_pool, project_id, analysis, ttl_hours).await?;
+  if let Err(e) = cached {
+    let message = format!("Failed to cache analysis: {}", e);
+    if cache_required {
+      return Err(message);
+    }
+    errors::report_error("analysis", message, Some(&format!("project {}", project_id)));
+  }
+  Ok((analysis, false))
+}
+
+// The cached blob for a large repo runs to tens of MB; decoding and writing
+// it happen on a blocking thread so the async runtime keeps serving events
+async fn load_cached_analysis(db_pool: &Arc<DbPool>, project_id: i64) -> Option<RepoAnalysis> {
+  let pool = db_pool.clone();
+  tokio::task::spawn_blocking(move || {
+    let conn = pool.get().ok()?;
+    db::get_cached_analysis(&conn, project_id).ok().flatten()
+  })
+  .await
+  .ok()
+  .flatten()
+}
+
+async fn store_cached_analysis(
+  db_pool: &Arc<DbPool>,
+  project_id: i64,
+  analysis: RepoAnalysis,
+  ttl_hours: i64,
+) -> Result<(RepoAnalysis, Result<(), String>), String> {
+  let pool = db_pool.clone();
+  let handle = tokio::task::spawn_blocking(move || {
+    let cached = pool
+      .get()
+      .map_err(|e| e.to_string())
+      .and_then(|conn| {
+        db::cache_analysis(&conn, project_id, &analysis, ttl_hours).map_err(|e| e.to_string())?;
+        let breakdown = languages::breakdown(&analysis.size_metrics.size_by_language, false);
+        db::save_language_breakdown(&conn, project_id, &breakdown).map_err(|e| e.to_string())
+      });
+    if cached.is_ok() {
+      tracing::debug!(project_id, ttl_hours, "Analysis cached");
+    }
+    let indexed = pool
+      .get()
+      .map_err(|e| e.to_string())
+      .and_then(|mut conn| inventory::refresh_project(&mut conn, project_id, &analysis.technologies));
+    if let Err(e) = indexed {
+      errors::report_error("inventory", format!("Failed to index technologies: {}", e), Some(&format!("project {}", project_id)));
+    }
+    (analysis, cached)
+  });
+  handle.await.map_err(|e| format!("Cache write task failed: {}", e))
+}
+
+// Cached metrics without loading file contents; errors when the project has
+// no unexpired analysis
+#[tauri::command]
+pub async fn get_analysis_metrics(
+  db_pool: State<'_, Arc<DbPool>>,
+  folder_path: String,
+) -> Result<AnalysisMetricsSummary, String> {
+  let pool = db_pool.inner().clone();
+  tokio::task::spawn_blocking(move || {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    let project = db::get_project_by_path(&conn, &folder_path)
+      .map_err(|e| e.to_string())?
+      .ok_or_else(|| format!("No analysis cached for {}", folder_path))?;
+    let mut summary = db::get_cached_analysis_summary(&conn, project.id)
+      .map_err(|e| e.to_string())?
+      .ok_or_else(|| format!("No analysis cached for {}", folder_path))?;
+    summary.from_cache = Some(true);
+    Ok(summary)
+  })
+  .await
+  .map_err(|e| format!("Metrics task failed: {}", e))?
+}
+
+// Report a failed analysis as a terminal progress event so the UI stops waiting;
+// returns the message so callers can `return Err(emit_error(..))`
+fn emit_error(
+  window: Option<&tauri::Window>,
+  folder_path: &str,
+  is_favorite: bool,
+  message: String,
+) -> String {
+  if let Some(w) = window {
+    let _ = w.emit("analysis:progress", &ProgressUpdate {
+      folder_path: folder_path.to_string(),
+      phase: "error".to_string(),
+      files_discovered: 0,
+      files_processed: 0,
+      total_files: 0,
+      percentage: 0.0,
+      current_file: None,
+      is_complete: true,
+      is_favorite,
+      elapsed_ms: 0,
+      estimated_remaining_ms: None,
+      bytes_processed: 0,
+      total_bytes: None,
+      skipped_filtered: None,
+      skipped_by_reason: None,
+      dirs_seen: None,
+      io_errors: None,
+      error_message: Some(message.clone()),
+    });
+  }
+  message
+}
+
 async fn spawn_progress_emitter(
   window: tauri::Window,
   tracker: Arc<ProgressTracker>,
@@ -594,7 +938,7 @@ async fn spawn_progress_emitter(
   is_favorite: bool,
 ) -> tokio::task::JoinHandle<()> {
   tokio::spawn(async move {
-    let mut interval = tokio::time::interval(Duration::from_millis(500));
+    let mut interval = tokio::time::interval(performance::progress_interval());
     
     loop {
       interval.tick().await;
@@ -613,8 +957,16 @@ pub async fn analyze_repository(
   db_pool: State<'_, Arc<DbPool>>,
   window: tauri::Window,
   folder_path: String,
+  include_submodules: Option<bool>,
+  include_hidden: Option<bool>,
+  symlinks: Option<SymlinkPolicy>,
 ) -> Result<RepoAnalysis, String> {
-  analyze_repository_impl(db_pool.inner().clone(), folder_path, false, false, false, Some(window)).await
+  let scope = ScanScope {
+    include_submodules: include_submodules.unwrap_or(false),
+    include_hidden: include_hidden.unwrap_or(false),
+    symlinks: symlinks.unwrap_or_default(),
+  };
+  analyze_repository_impl(db_pool.inner().clone(), folder_path, false, false, false, scope, Some(window)).await
 }
 
 #[tauri::command]
@@ -622,8 +974,16 @@ pub async fn analyze_repository_fresh(
   db_pool: State<'_, Arc<DbPool>>,
   window: tauri::Window,
   folder_path: String,
+  include_submodules: Option<bool>,
+  include_hidden: Option<bool>,
+  symlinks: Option<SymlinkPolicy>,
 ) -> Result<RepoAnalysis, String> {
-  analyze_repository_impl(db_pool.inner().clone(), folder_path, true, false, true, Some(window)).await
+  let scope = ScanScope {
+    include_submodules: include_submodules.unwrap_or(false),
+    include_hidden: include_hidden.unwrap_or(false),
+    symlinks: symlinks.unwrap_or_default(),
+  };
+  analyze_repository_impl(db_pool.inner().clone(), folder_path, true, false, true, scope, Some(window)).await
 }
 
 #[tauri::command]
@@ -632,7 +992,7 @@ pub async fn analyze_repository_lazy(
   window: tauri::Window,
   folder_path: String,
 ) -> Result<RepoAnalysis, String> {
-  analyze_repository_impl(db_pool.inner().clone(), folder_path, false, true, false, Some(window)).await
+  analyze_repository_impl(db_pool.inner().clone(), folder_path, false, true, false, ScanScope::default(), Some(window)).await
 }
 
 #[tauri::command]
@@ -641,7 +1001,34 @@ pub async fn trigger_full_scan(
   window: tauri::Window,
   folder_path: String,
 ) -> Result<RepoAnalysis, String> {
-  analyze_repository_impl(db_pool.inner().clone(), folder_path, false, false, true, Some(window)).await
+  analyze_repository_impl(db_pool.inner().clone(), folder_path, false, false, true, ScanScope::default(), Some(window)).await
+}
+
+// Warm the projects the user is most likely to open first: by most recent
+// open, or with `by_open_count` by how often they were opened. The rest keep
+// their requested order
+fn prioritize_projects(db_pool: &Arc<DbPool>, paths: &mut [String], by_open_count: bool) {
+  let Ok(conn) = db_pool.get() else { return };
+  let mut keyed: Vec<_> = paths
+    .iter()
+    .map(|p| {
+      let project = db::get_project_by_path(&conn, p).ok().flatten();
+      let opened = project.as_ref().and_then(|project| project.last_opened_at);
+      let count = if by_open_count { project.map_or(0, |project| project.opened_count) } else { 0 };
+      ((count, opened), p.clone())
+    })
+    .collect();
+  keyed.sort_by_key(|(key, _)| std::cmp::Reverse(*key));
+  for (slot, (_, path)) in paths.iter_mut().zip(keyed) {
+    *slot = path;
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResult {
+  pub analyses: Vec<RepoAnalysis>,
+  // (folder_path, error) for every repository that failed
+  pub errors: Vec<(String, String)>,
 }
 
 #[tauri::command]
@@ -649,30 +1036,394 @@ pub async fn analyze_multiple_repositories(
   db_pool: State<'_, Arc<DbPool>>,
   window: tauri::Window,
   folder_paths: Vec<String>,
-) -> Result<Vec<RepoAnalysis>, String> {
-  let mut results = Vec::with_capacity(folder_paths.len());
+  stop_on_error: Option<bool>,
+  priority: Option<String>,
+) -> Result<BatchResult, String> {
+  let stop_on_error = stop_on_error.unwrap_or(false);
+  let mut folder_paths = folder_paths;
+  // "recent" (default) or "most_opened"
+  prioritize_projects(&db_pool, &mut folder_paths, priority.as_deref() == Some("most_opened"));
+
+  // Up to `max_concurrent_analyses` run at once; results keep the input order
+  let semaphore = Arc::new(tokio::sync::Semaphore::new(performance::max_concurrent_analyses()));
+  let failed = Arc::new(AtomicBool::new(false));
+  let mut handles = Vec::with_capacity(folder_paths.len());
   
   for (index, path) in folder_paths.iter().enumerate() {
+    background::wait_until_resumed().await;
+    let permit = semaphore.clone().acquire_owned().await.map_err(|e| e.to_string())?;
+    // Don't start new work once a failure is going to abort the batch
+    if stop_on_error && failed.load(Ordering::Relaxed) {
+      break;
+    }
     let _ = window.emit("batch:progress", serde_json::json!({
       "current": index + 1,
       "total": folder_paths.len(),
       "current_project": path,
     }));
     
-    match analyze_repository_impl(
-      db_pool.inner().clone(),
-      path.clone(),
-      false,
-      true,
-      false,
-      Some(window.clone()),
-    ).await {
-      Ok(analysis) => results.push(analysis),
+    let db_pool = db_pool.inner().clone();
+    let window = window.clone();
+    let path = path.clone();
+    let failed = failed.clone();
+    handles.push(tokio::spawn(async move {
+      let _permit = permit;
+      let result = analyze_repository_impl(db_pool, path.clone(), false, true, false, ScanScope::default(), Some(window)).await;
+      if result.is_err() {
+        failed.store(true, Ordering::Relaxed);
+      }
+      (path, result)
+    }));
+  }
+  
+  let mut batch = BatchResult { analyses: Vec::with_capacity(handles.len()), errors: Vec::new() };
+  let mut handles = handles.into_iter();
+  while let Some(handle) = handles.next() {
+    let (path, result) = match handle.await {
+      Ok(outcome) => outcome,
+      Err(e) => ("<batch task>".to_string(), Err(e.to_string())),
+    };
+    match result {
+      Ok(analysis) => batch.analyses.push(analysis),
+      Err(e) if stop_on_error => {
+        for remaining in handles {
+          remaining.abort();
+        }
+        let _ = window.emit("batch:partial_results", &batch.analyses);
+        return Err(format!("Failed on {}: {}", path, e));
+      }
       Err(e) => {
-        eprintln!("Failed to analyze {}: {}", path, e);
+        errors::report_error("analysis", &e, Some(&path));
+        batch.errors.push((path, e));
       }
     }
   }
   
-  Ok(results)
+  Ok(batch)
+}
+
+// Background batch for callers without a window, such as bulk project
+// actions; same concurrency cap as `analyze_multiple_repositories`.
+// `force` skips the cache
+pub async fn queue_analyses(db_pool: Arc<DbPool>, folder_paths: Vec<String>, force: bool) {
+  let semaphore = Arc::new(tokio::sync::Semaphore::new(performance::max_concurrent_analyses()));
+  let mut tasks = tokio::task::JoinSet::new();
+  for path in folder_paths {
+    background::wait_until_resumed().await;
+    let Ok(permit) = semaphore.clone().acquire_owned().await else { break };
+    let db_pool = db_pool.clone();
+    tasks.spawn(async move {
+      let _permit = permit;
+      if let Err(e) = analyze_repository_impl(db_pool, path.clone(), force, true, false, ScanScope::default(), None).await {
+        errors::report_actionable_error("analysis", format!("Background analysis failed: {}", e), Some(&path));
+      }
+    });
+  }
+  while tasks.join_next().await.is_some() {}
+}
+
+// Fresh analyses of every favorite in the background; returns how many
+// were queued. Shared by the command and the tray menu
+pub async fn queue_favorites(db_pool: Arc<DbPool>) -> Result<usize, String> {
+  let favorites = db_pool.get().map_err(|e| e.to_string()).and_then(|conn| db::get_favorites(&conn).map_err(|e| e.to_string()))?;
+  let count = favorites.len();
+  tauri::async_runtime::spawn(queue_analyses(db_pool, favorites, true));
+  Ok(count)
+}
+
+fn cache_ttl_hours(is_favorite: bool) -> i64 {
+  if is_favorite { 2 } else { 1 }
+}
+
+// What a default-scope analysis would discover, from metadata alone
+#[derive(Debug, Default, PartialEq)]
+struct TreeState {
+  files: usize,
+  bytes: u64,
+  // Over analyzable files and every directory, so renames and deletions count
+  newest_mtime: Option<chrono::DateTime<Utc>>,
+  io_errors: usize,
+}
+
+fn tree_state(path: &Path) -> TreeState {
+  let filter = FileFilter::current();
+  let scope = ScanScope::default();
+  let stats = WalkStats::default();
+  let mut state = TreeState::default();
+  for result in walker_with_options(path, WalkOptions {
+    include_submodules: scope.include_submodules,
+    include_hidden: scope.include_hidden,
+    symlinks: scope.symlinks,
+    max_depth: Some(MAX_WALK_DEPTH),
+  }) {
+    let Some(entry) = stats.observe(result) else { continue };
+    let Some(file_type) = entry.file_type() else { continue };
+    let counted = file_type.is_file() && filter.matches(path, entry.path()) == FilterDecision::Analyze;
+    if !counted && !file_type.is_dir() {
+      continue;
+    }
+    let Ok(metadata) = entry.metadata().or_else(|_| fs_utils::metadata(entry.path())) else {
+      state.io_errors += 1;
+      continue;
+    };
+    if counted {
+      state.files += 1;
+      state.bytes += metadata.len();
+    }
+    if let Ok(modified) = metadata.modified() {
+      state.newest_mtime = state.newest_mtime.max(Some(modified.into()));
+    }
+  }
+  state.io_errors += stats.summary().io_errors;
+  state
+}
+
+// The cached analysis still describes the tree: a complete scan of the same
+// files, none of which (nor any directory) changed after it was generated
+fn matches_tree(summary: &AnalysisMetricsSummary, tree: &TreeState) -> bool {
+  let generated_at = summary
+    .generated_at
+    .as_deref()
+    .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+    .map(|at| at.with_timezone(&Utc));
+  let metric = |name: &str| summary.metrics.get(name).copied().unwrap_or(0) as i64;
+  summary.is_lazy_scan == Some(false)
+    && tree.io_errors == 0
+    && metric("io_errors") == 0
+    && metric("total_files") == tree.files as i64
+    && summary.size_metrics.total_size_bytes == tree.bytes
+    && matches!((tree.newest_mtime, generated_at), (Some(newest), Some(at)) if newest < at)
+}
+
+#[derive(Debug, Clone)]
+pub struct RefreshOutcome {
+  pub generated_at: Option<String>,
+  // False when the cached analysis still matched the tree and was kept
+  pub rescanned: bool,
+}
+
+// Keeps the cached analysis, expired or not, when `matches_tree` holds and
+// moves its expiry forward; None means it has to be rescanned
+async fn revalidate_cache(db_pool: &Arc<DbPool>, folder_path: &str) -> Option<RefreshOutcome> {
+  let started = Instant::now();
+  let ttl_hours = cache_ttl_hours(is_favorite_project(db_pool, folder_path));
+  let (pool, path) = (db_pool.clone(), folder_path.to_string());
+  let outcome = tokio::task::spawn_blocking(move || {
+    let (project_id, summary) = {
+      let conn = pool.get().ok()?;
+      let project = db::get_project_by_path(&conn, &path).ok()??;
+      (project.id, db::get_stored_analysis_summary(&conn, project.id).ok()??)
+    };
+    let tree = tree_state(Path::new(&path));
+    if !matches_tree(&summary, &tree) {
+      tracing::debug!(project_id, files = tree.files, "Cached analysis out of date");
+      return None;
+    }
+    let conn = pool.get().ok()?;
+    db::extend_analysis_cache(&conn, project_id, ttl_hours)
+      .ok()?
+      .then_some(RefreshOutcome { generated_at: summary.generated_at, rescanned: false })
+  })
+  .await
+  .ok()
+  .flatten()?;
+  usage::record(db_pool, UsageKind::Analysis, UsageEvent {
+    project_path: Some(folder_path),
+    duration_ms: Some(started.elapsed().as_millis() as u64),
+    cache_hit: Some(true),
+    tokens: None,
+  });
+  Some(outcome)
+}
+
+// Scheduled refresh: the cached analysis is kept while it matches the files
+// on disk, and replaced by a full scan once anything changed
+pub async fn refresh_analysis(db_pool: Arc<DbPool>, folder_path: String) -> Result<RefreshOutcome, String> {
+  if let Some(outcome) = revalidate_cache(&db_pool, &folder_path).await {
+    return Ok(outcome);
+  }
+  let analysis = analyze_repository_impl(db_pool, folder_path, true, false, false, ScanScope::default(), None).await?;
+  Ok(RefreshOutcome { generated_at: analysis.generated_at, rescanned: true })
+}
+
+#[tauri::command]
+pub async fn reanalyze_favorites(db_pool: State<'_, Arc<DbPool>>) -> Result<usize, String> {
+  queue_favorites(db_pool.inner().clone()).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  async fn analysis_of(files: &[FileMetadata], cancel_flag: &AtomicBool) -> RepoAnalysis {
+    let tracker = Arc::new(ProgressTracker::new());
+    let (results, _) = process_files_parallel(files, false, files.len(), &tracker, cancel_flag).await;
+    let (files, structure, technologies, metrics, size_metrics) = aggregate_results(results);
+    RepoAnalysis {
+      files,
+      structure,
+      technologies,
+      metrics,
+      size_metrics,
+      generated_at: None,
+      from_cache: Some(false),
+      is_lazy_scan: Some(false),
+      scan_progress: None,
+    }
+  }
+
+  #[test]
+  fn test_file_roles_ignore_directories_above_the_root() {
+    let root = Path::new("/home/dev/tests/myapp");
+    let role = |relative: &str| FileMetadata::new(root, &root.join(relative), 1).role;
+    assert_eq!(role("src/main.rs"), FileRole::Source);
+    assert_eq!(role("tests/api.rs"), FileRole::Test);
+    assert_eq!(role(".github/workflows/ci.yml"), FileRole::Ci);
+
+    let root = Path::new("/srv/.github/workflows/checkout");
+    assert_eq!(FileMetadata::new(root, &root.join("lib.rs"), 1).role, FileRole::Source);
+  }
+
+  #[tokio::test]
+  async fn test_wider_scan_scope_is_not_cached() {
+    let (dir, pool) = db::test_pool();
+    let project = dir.path().join("project");
+    std::fs::create_dir_all(project.join(".idea")).unwrap();
+    std::fs::write(project.join("main.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(project.join(".idea/notes.rs"), "fn notes() {}\n").unwrap();
+    let pool = Arc::new(pool);
+    let folder_path = project.to_string_lossy().to_string();
+    let analyze = |scope: ScanScope| analyze_repository_impl(pool.clone(), folder_path.clone(), false, false, false, scope, None);
+
+    let wide = analyze(ScanScope { include_hidden: true, ..Default::default() }).await.unwrap();
+    assert_eq!(wide.files.len(), 2);
+    let default = analyze(ScanScope::default()).await.unwrap();
+    assert_eq!((default.files.len(), default.from_cache), (1, Some(false)));
+    let cached = analyze(ScanScope::default()).await.unwrap();
+    assert_eq!((cached.files.len(), cached.from_cache), (1, Some(true)));
+  }
+
+  #[tokio::test]
+  async fn test_refresh_rescans_only_when_the_tree_changed() {
+    let (dir, pool) = db::test_pool();
+    let project = dir.path().join("project");
+    std::fs::create_dir(&project).unwrap();
+    std::fs::write(project.join("main.rs"), "fn main() {}\n").unwrap();
+    let pool = Arc::new(pool);
+    let folder_path = project.to_string_lossy().to_string();
+
+    let first = refresh_analysis(pool.clone(), folder_path.clone()).await.unwrap();
+    assert!(first.rescanned);
+    // Past its expiry the cache is still checked against the tree
+    pool.get().unwrap().execute("UPDATE analysis_cache SET expires_at = datetime('now', '-1 hours')", []).unwrap();
+    let kept = refresh_analysis(pool.clone(), folder_path.clone()).await.unwrap();
+    assert_eq!((kept.rescanned, &kept.generated_at), (false, &first.generated_at));
+    let project_id = db::get_project_by_path(&pool.get().unwrap(), &folder_path).unwrap().unwrap().id;
+    assert!(db::get_cached_analysis_summary(&pool.get().unwrap(), project_id).unwrap().is_some());
+
+    std::fs::write(project.join("lib.rs"), "pub fn lib() {}\n").unwrap();
+    let changed = refresh_analysis(pool.clone(), folder_path.clone()).await.unwrap();
+    assert!(changed.rescanned);
+    let summary = db::get_cached_analysis_summary(&pool.get().unwrap(), project_id).unwrap().unwrap();
+    assert_eq!(summary.metrics.get("total_files"), Some(&2));
+  }
+
+  #[tokio::test]
+  async fn test_cancelled_analysis_leaves_no_cache_entry() {
+    let (dir, pool) = db::test_pool();
+    let project = dir.path().join("project");
+    std::fs::create_dir(&project).unwrap();
+    let files: Vec<FileMetadata> = ["main.rs", "lib.rs", "README.md"]
+      .iter()
+      .map(|name| {
+        let path = project.join(name);
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+        FileMetadata::new(&project, &path, 13)
+      })
+      .collect();
+
+    let pool = Arc::new(pool);
+    let project_id = db::upsert_project(&pool.get().unwrap(), &project.to_string_lossy(), "project", None, false).unwrap();
+
+    // Cancelled before the first chunk is read: nothing processed, nothing cached
+    let cancelled = AtomicBool::new(true);
+    let partial = analysis_of(&files, &cancelled).await;
+    assert!(partial.files.is_empty());
+    let (_, was_cancelled) = persist_unless_cancelled(&pool, project_id, partial, 1, &cancelled, true).await.unwrap();
+    assert!(was_cancelled);
+    assert!(db::get_cached_analysis(&pool.get().unwrap(), project_id).unwrap().is_none());
+
+    let running = AtomicBool::new(false);
+    let complete = analysis_of(&files, &running).await;
+    assert_eq!(complete.files.len(), 3);
+    let (_, was_cancelled) = persist_unless_cancelled(&pool, project_id, complete, 1, &running, true).await.unwrap();
+    assert!(!was_cancelled);
+    assert_eq!(db::get_cached_analysis(&pool.get().unwrap(), project_id).unwrap().unwrap().files.len(), 3);
+
+    // A write that fails only fails the run when nothing else will show it
+    pool.get().unwrap().execute("DROP TABLE analysis_cache", []).unwrap();
+    let rerun = analysis_of(&files, &running).await;
+    assert!(persist_unless_cancelled(&pool, project_id, rerun.clone(), 1, &running, true).await.is_err());
+    assert!(persist_unless_cancelled(&pool, project_id, rerun, 1, &running, false).await.is_ok());
+  }
+
+  #[tokio::test]
+  async fn test_cancel_between_chunks_discards_partial_result() {
+    let (dir, pool) = db::test_pool();
+    let project = dir.path().join("project");
+    std::fs::create_dir(&project).unwrap();
+    let folder_path = project.to_string_lossy().to_string();
+    let files: Vec<FileMetadata> = (0..2000)
+      .map(|i| {
+        let path = project.join(format!("file{}.rs", i));
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+        FileMetadata::new(&project, &path, 13)
+      })
+      .collect();
+
+    let pool = Arc::new(pool);
+    let project_id = db::upsert_project(&pool.get().unwrap(), &folder_path, "project", None, false).unwrap();
+
+    // Registered the way analyze_repository_impl does; the cancel arrives
+    // through the command once the first chunk of 50 is done
+    let guard = CancelFlagGuard::register(&folder_path);
+    let tracker = Arc::new(ProgressTracker::new());
+    let canceller = {
+      let tracker = tracker.clone();
+      let folder_path = folder_path.clone();
+      std::thread::spawn(move || {
+        while tracker.files_processed.load(Ordering::Relaxed) < 50 {
+          std::hint::spin_loop();
+        }
+        tauri::async_runtime::block_on(cancel_analysis(folder_path)).unwrap();
+      })
+    };
+    let (results, _) = process_files_parallel(&files, false, files.len(), &tracker, &guard.flag).await;
+    canceller.join().unwrap();
+    assert!(results.len() >= 50 && results.len() < files.len(), "stopped after {} files", results.len());
+
+    let (files, structure, technologies, metrics, size_metrics) = aggregate_results(results);
+    let partial = RepoAnalysis {
+      files,
+      structure,
+      technologies,
+      metrics,
+      size_metrics,
+      generated_at: None,
+      from_cache: Some(false),
+      is_lazy_scan: Some(false),
+      scan_progress: None,
+    };
+    let (_, was_cancelled) = persist_unless_cancelled(&pool, project_id, partial, 1, &guard.flag, true).await.unwrap();
+    assert!(was_cancelled);
+    let rows: i64 = pool
+      .get()
+      .unwrap()
+      .query_row("SELECT COUNT(*) FROM analysis_cache WHERE project_id = ?1", [project_id], |row| row.get(0))
+      .unwrap();
+    assert_eq!(rows, 0);
+
+    drop(guard);
+    assert!(!CANCEL_FLAGS.lock().unwrap().contains_key(&folder_path));
+  }
 }
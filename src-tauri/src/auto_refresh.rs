@@ -0,0 +1,270 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::db::{self, AutoRefreshProject, DbPool};
+use crate::{analysis, background, errors, fs_utils, performance};
+
+// How often the scheduler looks for due projects
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const MAX_INTERVAL_HOURS: u32 = 24 * 30;
+// Backoff doubles per consecutive failure up to this
+const MAX_BACKOFF_HOURS: i64 = 24 * 7;
+// Failures in a row before the user is told
+const FAILURES_BEFORE_NOTICE: u32 = 3;
+// `kind` of this module's activity log entries
+const ACTIVITY_KIND: &str = "auto_refresh";
+// Entries returned by `list_project_activity`
+const ACTIVITY_PAGE: i64 = 50;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AutoRefreshStatus {
+    #[serde(flatten)]
+    pub project: AutoRefreshProject,
+    pub next_run_at: DateTime<Utc>,
+}
+
+// Payload of `analysis:updated`
+#[derive(Debug, Serialize, Clone)]
+struct AnalysisUpdated {
+    path: String,
+    generated_at: Option<String>,
+}
+
+fn hours(n: i64) -> chrono::Duration {
+    chrono::Duration::hours(n)
+}
+
+// Interval after the last successful analysis (now if never analyzed),
+// held back by any retry time from a failure or a missing directory
+fn next_run_at(project: &AutoRefreshProject, now: DateTime<Utc>) -> DateTime<Utc> {
+    let due = project.last_analyzed_at.map_or(now, |at| at + hours(project.interval_hours as i64));
+    project.retry_at.map_or(due, |retry| due.max(retry))
+}
+
+// Retry delay after `failures` consecutive failures: the interval, doubled
+// for each further failure
+fn backoff(interval_hours: u32, failures: u32) -> chrono::Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    hours((interval_hours as i64).saturating_mul(1 << doublings).min(MAX_BACKOFF_HOURS))
+}
+
+fn due_projects(db_pool: &DbPool, now: DateTime<Utc>) -> Result<Vec<AutoRefreshProject>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let projects = db::get_auto_refresh_projects(&conn).map_err(|e| e.to_string())?;
+    Ok(projects.into_iter().filter(|project| next_run_at(project, now) <= now).collect())
+}
+
+async fn refresh(app: &AppHandle, db_pool: Arc<DbPool>, project: AutoRefreshProject) {
+    let now = Utc::now();
+    // An unmounted drive isn't a failure; look again after one interval
+    if !fs_utils::is_existing_dir(Path::new(&project.path)) {
+        tracing::debug!(path = %project.path, "Auto-refresh skipped; directory missing");
+        let retry = now + hours(project.interval_hours as i64);
+        record(&db_pool, &project, project.failures, Some(retry), Outcome::Skipped, Some("directory missing"));
+        return;
+    }
+
+    match analysis::refresh_analysis(db_pool.clone(), project.path.clone()).await {
+        Ok(refreshed) => {
+            let outcome = if refreshed.rescanned { Outcome::Rescanned } else { Outcome::Revalidated };
+            record(&db_pool, &project, 0, None, outcome, None);
+            let _ = app.emit("analysis:updated", &AnalysisUpdated { path: project.path.clone(), generated_at: refreshed.generated_at });
+        }
+        Err(e) => {
+            let failures = project.failures + 1;
+            record(&db_pool, &project, failures, Some(now + backoff(project.interval_hours, failures)), Outcome::Failed, Some(&e));
+            let message = format!("Auto-refresh failed {} times in a row: {}", failures, e);
+            if failures == FAILURES_BEFORE_NOTICE {
+                errors::report_actionable_error("auto_refresh", message, Some(&project.path));
+            } else {
+                errors::report_error("auto_refresh", message, Some(&project.path));
+            }
+        }
+    }
+}
+
+// How a run ended, as written to the activity log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Skipped,
+    // The cached analysis still matched the files and was kept
+    Revalidated,
+    Rescanned,
+    Failed,
+}
+
+impl Outcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Skipped => "skipped",
+            Outcome::Revalidated => "revalidated",
+            Outcome::Rescanned => "rescanned",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+fn record(
+    db_pool: &DbPool,
+    project: &AutoRefreshProject,
+    failures: u32,
+    retry_at: Option<DateTime<Utc>>,
+    outcome: Outcome,
+    detail: Option<&str>,
+) {
+    let result = db_pool.get().map_err(|e| e.to_string()).and_then(|conn| {
+        db::record_auto_refresh_attempt(&conn, project.id, failures, retry_at)
+            .and_then(|_| db::record_activity(&conn, project.id, ACTIVITY_KIND, outcome.as_str(), detail))
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        errors::report_error("auto_refresh", e, Some(&project.path));
+    }
+}
+
+async fn run_due(app: &AppHandle, db_pool: Arc<DbPool>) {
+    let due = match due_projects(&db_pool, Utc::now()) {
+        Ok(due) => due,
+        Err(e) => return errors::report_error("auto_refresh", e, None),
+    };
+    if due.is_empty() {
+        return;
+    }
+    tracing::info!(projects = due.len(), "Auto-refreshing projects");
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(performance::max_concurrent_analyses()));
+    let mut tasks = tokio::task::JoinSet::new();
+    for project in due {
+        background::wait_until_resumed().await;
+        if crate::shutdown::is_shutting_down() {
+            break;
+        }
+        let Ok(permit) = semaphore.clone().acquire_owned().await else { break };
+        let (app, db_pool) = (app.clone(), db_pool.clone());
+        tasks.spawn(async move {
+            let _permit = permit;
+            refresh(&app, db_pool, project).await;
+        });
+    }
+    while tasks.join_next().await.is_some() {}
+}
+
+// Background loop started from main.rs; idle until a project opts in
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            if crate::shutdown::is_shutting_down() {
+                return;
+            }
+            if background::is_paused() {
+                continue;
+            }
+            if let Some(db_pool) = app.try_state::<Arc<DbPool>>() {
+                run_due(&app, db_pool.inner().clone()).await;
+            }
+        }
+    });
+}
+
+// `hours` of None or 0 turns auto-refresh off for the project
+#[tauri::command]
+pub async fn set_project_auto_refresh(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    hours: Option<u32>,
+) -> Result<(), String> {
+    let hours = hours.filter(|h| *h > 0);
+    if hours.is_some_and(|h| h > MAX_INTERVAL_HOURS) {
+        return Err(format!("Auto-refresh interval must be at most {} hours", MAX_INTERVAL_HOURS));
+    }
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    if !db::set_auto_refresh_interval(&conn, &project_path, hours).map_err(|e| e.to_string())? {
+        return Err(format!("Unknown project: {}", project_path));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_auto_refresh_projects(db_pool: State<'_, Arc<DbPool>>) -> Result<Vec<AutoRefreshStatus>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let now = Utc::now();
+    Ok(db::get_auto_refresh_projects(&conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|project| AutoRefreshStatus { next_run_at: next_run_at(&project, now), project })
+        .collect())
+}
+
+// Recent background runs for one project, newest first
+#[tauri::command]
+pub async fn list_project_activity(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<Vec<db::ActivityEntry>, String> {
+    let project_path = fs_utils::canonical_path(Path::new(&project_path));
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let Some(project) = db::get_project_by_path(&conn, &project_path).map_err(|e| e.to_string())? else {
+        return Ok(Vec::new());
+    };
+    db::get_project_activity(&conn, project.id, ACTIVITY_PAGE).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(interval_hours: u32, last_analyzed_at: Option<DateTime<Utc>>, retry_at: Option<DateTime<Utc>>) -> AutoRefreshProject {
+        AutoRefreshProject {
+            id: 1,
+            path: "/p".to_string(),
+            name: "p".to_string(),
+            interval_hours,
+            last_analyzed_at,
+            failures: 0,
+            retry_at,
+        }
+    }
+
+    #[test]
+    fn test_next_run_follows_last_analysis_and_retry() {
+        let now = Utc::now();
+        assert_eq!(next_run_at(&project(6, None, None), now), now);
+        assert_eq!(next_run_at(&project(6, Some(now - hours(2)), None), now), now + hours(4));
+        assert_eq!(next_run_at(&project(6, Some(now - hours(8)), Some(now + hours(1))), now), now + hours(1));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_a_week() {
+        assert_eq!(backoff(6, 1), hours(6));
+        assert_eq!(backoff(6, 2), hours(12));
+        assert_eq!(backoff(6, 3), hours(24));
+        assert_eq!(backoff(6, 40), hours(MAX_BACKOFF_HOURS));
+    }
+
+    #[test]
+    fn test_due_projects_reads_intervals_from_the_database() {
+        let (_dir, pool) = db::test_pool();
+        let conn = pool.get().unwrap();
+        db::upsert_project(&conn, "/p/never", "never", None, false).unwrap();
+        let fresh = db::upsert_project(&conn, "/p/fresh", "fresh", None, false).unwrap();
+        db::upsert_project(&conn, "/p/off", "off", None, false).unwrap();
+        conn.execute("UPDATE projects SET last_analyzed_at = CURRENT_TIMESTAMP WHERE id = ?1", [fresh]).unwrap();
+        assert!(db::set_auto_refresh_interval(&conn, "/p/never", Some(6)).unwrap());
+        assert!(db::set_auto_refresh_interval(&conn, "/p/fresh", Some(6)).unwrap());
+        assert!(!db::set_auto_refresh_interval(&conn, "/p/missing", Some(6)).unwrap());
+        drop(conn);
+
+        let due: Vec<String> = due_projects(&pool, Utc::now()).unwrap().into_iter().map(|p| p.path).collect();
+        assert_eq!(due, vec!["/p/never"]);
+
+        let conn = pool.get().unwrap();
+        let never = db::get_auto_refresh_projects(&conn).unwrap().into_iter().find(|p| p.path == "/p/never").unwrap();
+        db::record_auto_refresh_attempt(&conn, never.id, 1, Some(Utc::now() + hours(6))).unwrap();
+        drop(conn);
+        assert!(due_projects(&pool, Utc::now()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+// Set from the tray or settings for this session. The backup scheduler,
+// watch mode and batch analyses check it before starting new work;
+// analyses already running finish normally
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+const RESUME_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+// Emits `background:paused_changed` and refreshes the tray
+pub fn set_paused(app: &AppHandle, paused: bool) {
+    if PAUSED.swap(paused, Ordering::Relaxed) != paused {
+        tracing::info!(paused, "Background work pause changed");
+        let _ = app.emit("background:paused_changed", paused);
+        crate::tray::refresh(app);
+    }
+}
+
+pub async fn wait_until_resumed() {
+    while is_paused() {
+        tokio::time::sleep(RESUME_POLL_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub async fn get_background_paused() -> Result<bool, String> {
+    Ok(is_paused())
+}
+
+#[tauri::command]
+pub async fn set_background_paused(app: AppHandle, paused: bool) -> Result<bool, String> {
+    set_paused(&app, paused);
+    Ok(is_paused())
+}
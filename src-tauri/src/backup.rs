@@ -0,0 +1,364 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::analysis::is_analysis_running;
+use crate::db::{self, DbPool};
+
+const SCHEDULE_KEY: &str = "backup_schedule";
+const LAST_BACKUP_KEY: &str = "last_backup_at";
+const BACKUP_PREFIX: &str = "repomuse-";
+// How often the scheduler wakes up to see whether a backup is due
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Backups in progress; shutdown waits for these so VACUUM INTO isn't cut off
+static BACKUPS_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+struct RunningGuard;
+
+impl RunningGuard {
+    fn start() -> Self {
+        BACKUPS_RUNNING.fetch_add(1, Ordering::SeqCst);
+        RunningGuard
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        BACKUPS_RUNNING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn is_backup_running() -> bool {
+    BACKUPS_RUNNING.load(Ordering::SeqCst) > 0
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFrequency {
+    Off,
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupSchedule {
+    pub frequency: BackupFrequency,
+    #[serde(default = "default_retention")]
+    pub retention: u32,
+}
+
+fn default_retention() -> u32 { 7 }
+
+impl Default for BackupSchedule {
+    fn default() -> Self {
+        Self { frequency: BackupFrequency::Off, retention: default_retention() }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupInfo {
+    pub filename: String,
+    pub size_bytes: u64,
+    pub created_at: Option<String>,
+}
+
+fn backups_dir(conn: &Connection) -> Result<PathBuf, String> {
+    let db_path = db::database_path(conn).ok_or("Database has no file on disk")?;
+    let dir = db_path
+        .parent()
+        .ok_or("Database path has no parent directory")?
+        .join("backups");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backups directory: {}", e))?;
+    Ok(dir)
+}
+
+fn load_schedule(conn: &Connection) -> BackupSchedule {
+    db::load_setting(conn, SCHEDULE_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn last_backup_at(conn: &Connection) -> Option<DateTime<Utc>> {
+    db::load_setting(conn, LAST_BACKUP_KEY)
+        .ok()
+        .flatten()
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn is_backup_due(schedule: &BackupSchedule, last: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    let interval = match schedule.frequency {
+        BackupFrequency::Off => return false,
+        BackupFrequency::Daily => chrono::Duration::days(1),
+        BackupFrequency::Weekly => chrono::Duration::weeks(1),
+    };
+    match last {
+        Some(last) => now - last >= interval,
+        None => true,
+    }
+}
+
+fn is_backup_filename(name: &str) -> bool {
+    name.starts_with(BACKUP_PREFIX)
+        && name.ends_with(".db")
+        && !name.contains('/')
+        && !name.contains('\\')
+}
+
+fn list_backup_files(dir: &Path) -> Vec<BackupInfo> {
+    let mut backups: Vec<BackupInfo> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|entry| {
+                    let filename = entry.file_name().to_string_lossy().to_string();
+                    if !is_backup_filename(&filename) {
+                        return None;
+                    }
+                    let metadata = entry.metadata().ok()?;
+                    Some(BackupInfo {
+                        filename,
+                        size_bytes: metadata.len(),
+                        created_at: metadata
+                            .modified()
+                            .ok()
+                            .map(|t| DateTime::<Utc>::from(t).to_rfc3339()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Date-stamped names sort chronologically; newest first
+    backups.sort_by_key(|b| std::cmp::Reverse(b.filename.clone()));
+    backups
+}
+
+fn prune_backups(dir: &Path, retention: u32) {
+    for old in list_backup_files(dir).iter().skip(retention.max(1) as usize) {
+        if let Err(e) = std::fs::remove_file(dir.join(&old.filename)) {
+            crate::errors::report_error("backup", format!("Failed to prune backup: {}", e), Some(&old.filename));
+        }
+    }
+}
+
+// Snapshot the live database into backups/repomuse-YYYYMMDD.db (one per day).
+// VACUUM INTO writes a sibling temp file that replaces today's backup only
+// once it's complete, so a failed run keeps the earlier one
+fn create_backup(conn: &Connection) -> Result<BackupInfo, String> {
+    let dir = backups_dir(conn)?;
+    let filename = format!("{}{}.db", BACKUP_PREFIX, Utc::now().format("%Y%m%d"));
+    let target = dir.join(&filename);
+    let partial = dir.join(format!("{}.partial", filename));
+    if partial.exists() {
+        std::fs::remove_file(&partial).map_err(|e| e.to_string())?;
+    }
+
+    if let Err(e) = conn.execute("VACUUM INTO ?1", [partial.to_string_lossy()]) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(format!("Backup failed: {}", e));
+    }
+    std::fs::rename(&partial, &target).map_err(|e| {
+        let _ = std::fs::remove_file(&partial);
+        format!("Backup failed: {}", e)
+    })?;
+    db::save_setting(conn, LAST_BACKUP_KEY, &Utc::now().to_rfc3339()).map_err(|e| e.to_string())?;
+
+    let size_bytes = std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupInfo { filename, size_bytes, created_at: Some(Utc::now().to_rfc3339()) })
+}
+
+fn run_backup<R: Runtime>(app: &AppHandle<R>, db_pool: &DbPool) -> Result<BackupInfo, String> {
+    if crate::shutdown::is_shutting_down() {
+        return Err("RepoMuse is shutting down".to_string());
+    }
+    let _running = RunningGuard::start();
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let info = create_backup(&conn)?;
+    if let Ok(dir) = backups_dir(&conn) {
+        prune_backups(&dir, load_schedule(&conn).retention);
+    }
+    let _ = app.emit("backup:completed", &info);
+    Ok(info)
+}
+
+// Background loop started from main.rs; does nothing while the schedule is off
+pub fn spawn_scheduler<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Some(db_pool) = app.try_state::<Arc<DbPool>>() {
+                let db_pool = db_pool.inner().clone();
+                let due = db_pool
+                    .get()
+                    .map(|conn| is_backup_due(&load_schedule(&conn), last_backup_at(&conn), Utc::now()))
+                    .unwrap_or(false);
+
+                if due && !is_analysis_running() && !crate::background::is_paused() {
+                    let app = app.clone();
+                    let result = tokio::task::spawn_blocking(move || run_backup(&app, &db_pool)).await;
+                    if let Ok(Err(e)) = result {
+                        crate::errors::report_actionable_error("backup", format!("Scheduled backup failed: {}", e), None);
+                    }
+                }
+            }
+            tokio::time::sleep(SCHEDULER_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_backup_schedule(
+    db_pool: State<'_, Arc<DbPool>>,
+) -> Result<BackupSchedule, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(load_schedule(&conn))
+}
+
+#[tauri::command]
+pub async fn save_backup_schedule(
+    db_pool: State<'_, Arc<DbPool>>,
+    schedule: BackupSchedule,
+) -> Result<(), String> {
+    if schedule.retention == 0 {
+        return Err("Retention must keep at least one backup".to_string());
+    }
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&schedule).map_err(|e| e.to_string())?;
+    db::save_setting(&conn, SCHEDULE_KEY, &json).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn backup_database(
+    app: AppHandle,
+    db_pool: State<'_, Arc<DbPool>>,
+) -> Result<BackupInfo, String> {
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || run_backup(&app, &pool))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn list_backups(
+    db_pool: State<'_, Arc<DbPool>>,
+) -> Result<Vec<BackupInfo>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(list_backup_files(&backups_dir(&conn)?))
+}
+
+// Run after the live database was replaced by a backup or an imported
+// bundle: older data may lack newer tables and columns, and some settings
+// are only read at startup
+pub fn reload_restored_data(db_pool: &DbPool) -> Result<(), String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::init_schema(&conn).map_err(|e| format!("Restored data could not be upgraded: {}", e))?;
+    drop(conn);
+    crate::projects::init_walk_settings(db_pool);
+    crate::logging::apply_saved_level(db_pool);
+    Ok(())
+}
+
+fn restore_from_backup(db_pool: &DbPool, filename: &str) -> Result<(), String> {
+    // Counted with backups so shutdown waits for the copy to finish
+    let _running = RunningGuard::start();
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    let source_path = backups_dir(&conn)?.join(filename);
+    if !source_path.exists() {
+        return Err(format!("Backup {} not found", filename));
+    }
+
+    let source = Connection::open(&source_path).map_err(|e| e.to_string())?;
+    let backup = rusqlite::backup::Backup::new(&source, &mut conn).map_err(|e| e.to_string())?;
+    backup
+        .run_to_completion(256, Duration::from_millis(10), None)
+        .map_err(|e| format!("Restore failed: {}", e))?;
+    drop(backup);
+    drop(conn);
+    reload_restored_data(db_pool)
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    db_pool: State<'_, Arc<DbPool>>,
+    filename: String,
+) -> Result<(), String> {
+    if !is_backup_filename(&filename) {
+        return Err("Invalid backup filename".to_string());
+    }
+    if crate::shutdown::is_shutting_down() {
+        return Err("RepoMuse is shutting down".to_string());
+    }
+    if is_analysis_running() {
+        return Err("An analysis is in progress; try again once it finishes".to_string());
+    }
+
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || restore_from_backup(&pool, &filename))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_backup_due_respects_frequency() {
+        let now = Utc::now();
+        let daily = BackupSchedule { frequency: BackupFrequency::Daily, retention: 3 };
+        assert!(is_backup_due(&daily, None, now));
+        assert!(!is_backup_due(&daily, Some(now - chrono::Duration::hours(2)), now));
+        assert!(is_backup_due(&daily, Some(now - chrono::Duration::hours(25)), now));
+        assert!(!is_backup_due(&BackupSchedule::default(), None, now));
+    }
+
+    #[test]
+    fn test_create_backup_and_prune_to_retention() {
+        let (dir, pool) = db::test_pool();
+        let conn = pool.get().unwrap();
+        db::save_setting(&conn, "probe", "backed-up").unwrap();
+
+        create_backup(&conn).unwrap();
+        // A second run the same day replaces the first
+        db::save_setting(&conn, "probe", "backed-up again").unwrap();
+        let info = create_backup(&conn).unwrap();
+        let backups = dir.path().join("backups");
+        let copy = Connection::open(backups.join(&info.filename)).unwrap();
+        assert_eq!(db::load_setting(&copy, "probe").unwrap().as_deref(), Some("backed-up again"));
+        assert!(!backups.join(format!("{}.partial", info.filename)).exists());
+
+        for day in ["20200101", "20200102"] {
+            std::fs::write(backups.join(format!("repomuse-{}.db", day)), b"old").unwrap();
+        }
+        prune_backups(&backups, 2);
+        let remaining: Vec<String> = list_backup_files(&backups).into_iter().map(|b| b.filename).collect();
+        assert_eq!(remaining, vec![info.filename, "repomuse-20200102.db".to_string()]);
+    }
+
+    #[test]
+    fn test_restore_upgrades_backups_from_older_schemas() {
+        let (dir, pool) = db::test_pool();
+        let conn = pool.get().unwrap();
+        db::save_setting(&conn, "probe", "backed-up").unwrap();
+        let info = create_backup(&conn).unwrap();
+        // Stand-in for a backup made before the ideas table existed
+        let old = Connection::open(dir.path().join("backups").join(&info.filename)).unwrap();
+        old.execute_batch("DROP TABLE idea_generations; DROP TABLE ideas;").unwrap();
+        drop(old);
+        db::save_setting(&conn, "probe", "changed").unwrap();
+        drop(conn);
+
+        restore_from_backup(&pool, &info.filename).unwrap();
+        let conn = pool.get().unwrap();
+        assert_eq!(db::load_setting(&conn, "probe").unwrap().as_deref(), Some("backed-up"));
+        let ideas: i64 = conn.query_row("SELECT COUNT(*) FROM ideas", [], |row| row.get(0)).unwrap();
+        assert_eq!(ideas, 0);
+    }
+}
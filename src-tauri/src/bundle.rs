@@ -0,0 +1,418 @@
+use chrono::Utc;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::analysis::{is_analysis_running, CURRENT_ANALYSIS_SCHEMA_VERSION};
+use crate::db::{self, DbPool};
+
+// Bumped when the bundle layout changes; newer bundles are refused
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "repomuse.db";
+const API_SETTINGS_KEY: &str = "api_settings";
+
+// Pages per online-backup step; small enough to report progress often
+const BACKUP_STEP_PAGES: std::os::raw::c_int = 1024;
+const COPY_BUFFER_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BundleEntry {
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub analysis_schema_version: u32,
+    pub created_at: String,
+    // False when the API key was blanked before packing
+    pub includes_secrets: bool,
+    pub entries: Vec<BundleEntry>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportReport {
+    pub path: String,
+    pub size_bytes: u64,
+    pub manifest: BundleManifest,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportReport {
+    pub manifest: BundleManifest,
+    // Where the data replaced by the import was moved
+    pub previous_data_dir: String,
+}
+
+// Payload of `app_data:progress`; bytes are per step
+#[derive(Debug, Serialize, Clone)]
+pub struct TransferProgress {
+    pub operation: &'static str,
+    pub step: String,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+type ProgressFn<'a> = &'a dyn Fn(TransferProgress);
+
+fn data_dir(conn: &Connection) -> Result<PathBuf, String> {
+    let db_path = db::database_path(conn).ok_or("Database has no file on disk")?;
+    db_path.parent().map(Path::to_path_buf).ok_or_else(|| "Database path has no parent directory".to_string())
+}
+
+fn timestamp() -> String {
+    Utc::now().format("%Y%m%d%H%M%S").to_string()
+}
+
+// Online backup from `source` into `dest`, page by page so it stays
+// consistent while the app keeps writing
+fn copy_database(source: &Connection, dest: &mut Connection, operation: &'static str, progress: ProgressFn) -> Result<(), String> {
+    let page_size: i64 = source.query_row("PRAGMA page_size", [], |row| row.get(0)).map_err(|e| e.to_string())?;
+    let backup = Backup::new(source, dest).map_err(|e| e.to_string())?;
+    loop {
+        let step = backup.step(BACKUP_STEP_PAGES).map_err(|e| format!("Database copy failed: {}", e))?;
+        let p = backup.progress();
+        let total = p.pagecount.max(0) as u64 * page_size as u64;
+        let done = (p.pagecount - p.remaining).max(0) as u64 * page_size as u64;
+        progress(TransferProgress { operation, step: "database".to_string(), bytes_done: done, bytes_total: total });
+        match step {
+            StepResult::Done => return Ok(()),
+            StepResult::More => {}
+            StepResult::Busy | StepResult::Locked => std::thread::sleep(Duration::from_millis(10)),
+            _ => {}
+        }
+    }
+}
+
+fn copy_with_progress(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    total: u64,
+    operation: &'static str,
+    step: &str,
+    progress: ProgressFn,
+) -> Result<(), String> {
+    let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+    let mut done = 0u64;
+    loop {
+        let read = reader.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Ok(());
+        }
+        writer.write_all(&buffer[..read]).map_err(|e| e.to_string())?;
+        done += read as u64;
+        progress(TransferProgress { operation, step: step.to_string(), bytes_done: done, bytes_total: total });
+    }
+}
+
+fn api_key(conn: &Connection) -> Option<String> {
+    let json = db::load_setting(conn, API_SETTINGS_KEY).ok().flatten()?;
+    let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+    value.get("api_key").and_then(|key| key.as_str()).map(str::to_string)
+}
+
+// No-op when API settings were never saved
+fn set_api_key(conn: &Connection, key: &str) -> Result<(), String> {
+    let Some(json) = db::load_setting(conn, API_SETTINGS_KEY).map_err(|e| e.to_string())? else { return Ok(()) };
+    let mut value: serde_json::Value = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("api_key".to_string(), serde_json::Value::String(key.to_string()));
+    }
+    db::save_setting(conn, API_SETTINGS_KEY, &value.to_string()).map_err(|e| e.to_string())
+}
+
+fn export_bundle(
+    conn: &Connection,
+    output: &Path,
+    app_version: &str,
+    include_secrets: bool,
+    progress: ProgressFn,
+) -> Result<ExportReport, String> {
+    let dir = data_dir(conn)?;
+    let snapshot_path = dir.join(format!(".export-{}.db", timestamp()));
+    let result = (|| {
+        let mut snapshot = Connection::open(&snapshot_path).map_err(|e| e.to_string())?;
+        copy_database(conn, &mut snapshot, "export", progress)?;
+        if !include_secrets {
+            set_api_key(&snapshot, "")?;
+        }
+        drop(snapshot);
+        write_bundle(&snapshot_path, output, app_version, include_secrets, progress)
+    })();
+    let _ = std::fs::remove_file(&snapshot_path);
+    result
+}
+
+fn write_bundle(
+    snapshot_path: &Path,
+    output: &Path,
+    app_version: &str,
+    include_secrets: bool,
+    progress: ProgressFn,
+) -> Result<ExportReport, String> {
+    let sources = [(DATABASE_ENTRY.to_string(), snapshot_path.to_path_buf())];
+    let mut entries = Vec::with_capacity(sources.len());
+    for (name, path) in &sources {
+        let size_bytes = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+        entries.push(BundleEntry { name: name.clone(), size_bytes });
+    }
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: app_version.to_string(),
+        analysis_schema_version: CURRENT_ANALYSIS_SCHEMA_VERSION,
+        created_at: Utc::now().to_rfc3339(),
+        includes_secrets: include_secrets,
+        entries,
+    };
+
+    // Written beside the target and renamed, so a failed export never
+    // leaves a truncated zip under the chosen name
+    let partial = output.with_extension("zip.partial");
+    let result = (|| {
+        let mut zip = ZipWriter::new(File::create(&partial).map_err(|e| e.to_string())?);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated).large_file(true);
+        zip.start_file(MANIFEST_ENTRY, options).map_err(|e| e.to_string())?;
+        zip.write_all(serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?.as_bytes())
+            .map_err(|e| e.to_string())?;
+        for ((name, path), entry) in sources.iter().zip(&manifest.entries) {
+            zip.start_file(name.as_str(), options).map_err(|e| e.to_string())?;
+            let mut file = File::open(path).map_err(|e| e.to_string())?;
+            copy_with_progress(&mut file, &mut zip, entry.size_bytes, "export", name, progress)?;
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+        std::fs::rename(&partial, output).map_err(|e| e.to_string())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    result?;
+
+    let size_bytes = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    Ok(ExportReport { path: output.to_string_lossy().to_string(), size_bytes, manifest })
+}
+
+fn read_manifest(archive: &mut ZipArchive<File>) -> Result<BundleManifest, String> {
+    let mut text = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| "Not a RepoMuse data bundle: manifest.json is missing".to_string())?
+        .read_to_string(&mut text)
+        .map_err(|e| e.to_string())?;
+    let manifest: BundleManifest = serde_json::from_str(&text).map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION || manifest.analysis_schema_version > CURRENT_ANALYSIS_SCHEMA_VERSION {
+        return Err(format!(
+            "This bundle was made by RepoMuse {}, which is newer than this version; update RepoMuse to import it",
+            manifest.app_version
+        ));
+    }
+    if !manifest.entries.iter().any(|entry| entry.name == DATABASE_ENTRY) {
+        return Err("Bundle has no database".to_string());
+    }
+    Ok(manifest)
+}
+
+// Entries are looked up by the fixed names above, never by paths taken
+// from the archive, so a crafted bundle can't write outside the data directory
+fn extract_entry(archive: &mut ZipArchive<File>, entry: &BundleEntry, target: &Path, progress: ProgressFn) -> Result<(), String> {
+    let mut source = archive.by_name(&entry.name).map_err(|e| format!("Bundle entry {}: {}", entry.name, e))?;
+    let mut file = File::create(target).map_err(|e| e.to_string())?;
+    copy_with_progress(&mut source, &mut file, entry.size_bytes, "import", &entry.name, progress)?;
+    file.sync_all().map_err(|e| e.to_string())
+}
+
+fn import_bundle(conn: &mut Connection, input: &Path, progress: ProgressFn) -> Result<ImportReport, String> {
+    let mut archive = ZipArchive::new(File::open(input).map_err(|e| e.to_string())?).map_err(|e| format!("Not a zip file: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+    let dir = data_dir(conn)?;
+    let stamp = timestamp();
+
+    // Unpack and check the database before touching current data; other
+    // entries are ignored
+    let staged_db = dir.join(format!(".import-{}.db", stamp));
+    let result = (|| {
+        let entry = manifest.entries.iter().find(|entry| entry.name == DATABASE_ENTRY).ok_or("Bundle has no database")?;
+        extract_entry(&mut archive, entry, &staged_db, progress)?;
+        let imported = Connection::open(&staged_db).map_err(|e| e.to_string())?;
+        if !db::quick_check_ok(&imported).map_err(|e| format!("Bundled database is unreadable: {}", e))? {
+            return Err("Bundled database failed its integrity check".to_string());
+        }
+        // Bundles made without secrets keep this machine's API key
+        if !manifest.includes_secrets {
+            if let Some(key) = api_key(conn) {
+                set_api_key(&imported, &key)?;
+            }
+        }
+
+        // Copy the current database aside, then restore over it as the last step
+        let aside = dir.join(format!("pre-import-{}", stamp));
+        std::fs::create_dir_all(&aside).map_err(|e| e.to_string())?;
+        let mut previous = Connection::open(aside.join(DATABASE_ENTRY)).map_err(|e| e.to_string())?;
+        copy_database(conn, &mut previous, "import", progress)?;
+        copy_database(&imported, conn, "import", progress)?;
+        // Bundles from older versions lack the newer columns and tables
+        db::init_schema(conn).map_err(|e| format!("Imported data could not be upgraded: {}", e))?;
+        Ok(aside)
+    })();
+    let _ = std::fs::remove_file(&staged_db);
+    let aside = result?;
+    Ok(ImportReport { manifest, previous_data_dir: aside.to_string_lossy().to_string() })
+}
+
+fn emitter(app: &AppHandle) -> impl Fn(TransferProgress) + '_ {
+    move |update| {
+        let _ = app.emit("app_data:progress", &update);
+    }
+}
+
+// The database, settings included, in one zip; the API key only when
+// `include_secrets`. Emits `app_data:progress`
+#[tauri::command]
+pub async fn export_app_data(
+    app: AppHandle,
+    db_pool: State<'_, Arc<DbPool>>,
+    output_zip: String,
+    include_secrets: Option<bool>,
+) -> Result<ExportReport, String> {
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let version = app.package_info().version.to_string();
+        export_bundle(&conn, Path::new(&output_zip), &version, include_secrets.unwrap_or(false), &emitter(&app))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Replaces all app data with a bundle from `export_app_data`; current
+// data is moved to pre-import-<timestamp> in the data directory first
+#[tauri::command]
+pub async fn import_app_data(
+    app: AppHandle,
+    db_pool: State<'_, Arc<DbPool>>,
+    input_zip: String,
+) -> Result<ImportReport, String> {
+    if crate::shutdown::is_shutting_down() {
+        return Err("RepoMuse is shutting down".to_string());
+    }
+    if is_analysis_running() {
+        return Err("An analysis is in progress; try again once it finishes".to_string());
+    }
+    let pool = db_pool.inner().clone();
+    let report = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(|e| e.to_string())?;
+        let report = import_bundle(&mut conn, Path::new(&input_zip), &emitter(&app))?;
+        drop(conn);
+        crate::backup::reload_restored_data(&pool)?;
+        let _ = app.emit("app_data:imported", &report);
+        Ok::<_, String>(report)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    tracing::info!(from = %report.manifest.app_version, moved_to = %report.previous_data_dir, "Imported app data");
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_progress(_: TransferProgress) {}
+
+    fn data_dir_with_project(name: &str, api_key: &str) -> (tempfile::TempDir, DbPool) {
+        let (dir, pool) = db::test_pool();
+        let conn = pool.get().unwrap();
+        db::upsert_project(&conn, &format!("/projects/{}", name), name, None, false).unwrap();
+        db::save_setting(&conn, API_SETTINGS_KEY, &format!(r#"{{"model":"llama2","api_key":"{}"}}"#, api_key)).unwrap();
+        drop(conn);
+        (dir, pool)
+    }
+
+    #[test]
+    fn test_export_then_import_restores_data_and_keeps_local_key() {
+        let (source_dir, source) = data_dir_with_project("exported", "source-key");
+        let zip_path = source_dir.path().join("bundle.zip");
+        let report = export_bundle(&source.get().unwrap(), &zip_path, "9.9.9", false, &no_progress).unwrap();
+        assert!(!report.manifest.includes_secrets);
+        assert_eq!(report.manifest.entries.len(), 1);
+        assert!(!source_dir.path().join("bundle.zip.partial").exists());
+
+        let (_target_dir, target) = data_dir_with_project("local", "target-key");
+        let mut conn = target.get().unwrap();
+        let imported = import_bundle(&mut conn, &zip_path, &no_progress).unwrap();
+
+        assert!(db::get_project_by_path(&conn, "/projects/exported").unwrap().is_some());
+        assert!(db::get_project_by_path(&conn, "/projects/local").unwrap().is_none());
+        assert_eq!(api_key(&conn).as_deref(), Some("target-key"));
+
+        let previous = Connection::open(Path::new(&imported.previous_data_dir).join(DATABASE_ENTRY)).unwrap();
+        assert!(db::get_project_by_path(&previous, "/projects/local").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_import_upgrades_bundles_with_an_older_schema() {
+        let (dir, pool) = data_dir_with_project("local", "");
+        let old_db = dir.path().join("old.db");
+        let old = Connection::open(&old_db).unwrap();
+        old.execute_batch("
+            CREATE TABLE projects (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT UNIQUE NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                is_git_repo BOOLEAN DEFAULT FALSE,
+                is_favorite BOOLEAN DEFAULT FALSE,
+                last_analyzed_at TIMESTAMP,
+                file_count INTEGER DEFAULT 0,
+                total_size_bytes INTEGER DEFAULT 0,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP);
+            INSERT INTO projects (path, name) VALUES ('/projects/old', 'old');
+        ").unwrap();
+        drop(old);
+        let zip_path = dir.path().join("old.zip");
+        write_bundle(&old_db, &zip_path, "0.1.0", false, &no_progress).unwrap();
+
+        let mut conn = pool.get().unwrap();
+        import_bundle(&mut conn, &zip_path, &no_progress).unwrap();
+        let project = db::get_project_by_path(&conn, "/projects/old").unwrap().unwrap();
+        assert!(!project.is_archived);
+        let ideas: i64 = conn.query_row("SELECT COUNT(*) FROM ideas", [], |row| row.get(0)).unwrap();
+        assert_eq!(ideas, 0);
+    }
+
+    #[test]
+    fn test_import_refuses_newer_bundles() {
+        let (dir, pool) = data_dir_with_project("current", "");
+        let zip_path = dir.path().join("future.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path).unwrap());
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION + 1,
+            app_version: "99.0.0".to_string(),
+            analysis_schema_version: CURRENT_ANALYSIS_SCHEMA_VERSION,
+            created_at: Utc::now().to_rfc3339(),
+            includes_secrets: false,
+            entries: vec![BundleEntry { name: DATABASE_ENTRY.to_string(), size_bytes: 0 }],
+        };
+        zip.start_file(MANIFEST_ENTRY, SimpleFileOptions::default()).unwrap();
+        zip.write_all(serde_json::to_string(&manifest).unwrap().as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let error = import_bundle(&mut pool.get().unwrap(), &zip_path, &no_progress).unwrap_err();
+        assert!(error.contains("99.0.0"), "{}", error);
+        assert!(db::get_project_by_path(&pool.get().unwrap(), "/projects/current").unwrap().is_some());
+    }
+}
@@ -0,0 +1,466 @@
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+use crate::fs_utils::read_text_prefix_limited;
+
+const MAX_FILE_BYTES: usize = 256 * 1024;
+// Top-level .gitlab-ci.yml keys that configure the pipeline rather than name a job
+const GITLAB_RESERVED_KEYS: &[&str] = &[
+    "stages", "variables", "image", "services", "before_script", "after_script", "cache",
+    "include", "default", "workflow",
+];
+
+const TEST_MARKERS: &[&str] = &[
+    "cargo test", "cargo nextest", "npm test", "npm run test", "yarn test", "pnpm test", "pytest",
+    "go test", "jest", "vitest", "tox", "mvn test", "gradle test", "./gradlew test", "dotnet test",
+    "rspec", "phpunit",
+];
+const LINT_MARKERS: &[&str] = &[
+    "lint", "clippy", "fmt --check", "fmt -- --check", "prettier --check", "ruff", "flake8",
+    "black --check", "golangci", "rubocop", "mypy",
+];
+// A job releases when its id or name says so, or when its body runs a publish
+// command or release action. "release" alone in a body is usually `--release`
+const RELEASE_JOB_NAMES: &[&str] = &["release", "publish"];
+const RELEASE_MARKERS: &[&str] = &[
+    "goreleaser", "tauri-action", "docker/build-push-action", "twine upload", "action-gh-release",
+    "actions/create-release", "gh release create", "semantic-release", "changesets/action",
+    "cargo publish", "npm publish", "yarn publish", "pnpm publish", "poetry publish",
+];
+const SECURITY_MARKERS: &[&str] = &[
+    "codeql", "snyk", "trivy", "cargo audit", "cargo-audit", "npm audit", "pip-audit",
+    "dependency-review", "gitleaks", "semgrep", "osv-scanner", "bandit", "sast",
+];
+const BUILD_MARKERS: &[&str] = &["build", "compile", "cargo check", "tsc"];
+// `strategy.matrix` keys naming runner platforms and toolchain versions
+const MATRIX_OS_KEYS: &[&str] = &["os", "operating-system", "platform"];
+const MATRIX_VERSION_KEYS: &[(&str, &str)] = &[
+    ("node-version", "node"),
+    ("python-version", "python"),
+    ("rust-version", "rust"),
+    ("rust", "rust"),
+    ("toolchain", "rust"),
+    ("go-version", "go"),
+    ("java-version", "java"),
+];
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct CIMatrix {
+    pub os_targets: Vec<String>,
+    // "node 20", "python 3.12", "rust stable"
+    pub language_versions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct CIWorkflow {
+    pub file: String,
+    pub name: Option<String>,
+    // "push", "pull_request", "schedule", "tags" (push restricted to tags), ...
+    pub triggers: Vec<String>,
+    pub jobs: Vec<String>,
+    pub runs_tests: bool,
+    pub runs_lint: bool,
+    pub runs_build: bool,
+    pub builds_release: bool,
+    pub runs_security_scan: bool,
+    // Union of every job's `strategy.matrix`, None when no job uses one
+    pub matrix: Option<CIMatrix>,
+    // False when the file couldn't be read as YAML; only `file` is meaningful then
+    pub parsed: bool,
+}
+
+// The document's top-level mapping, with `<<` merge keys applied. None when
+// the file isn't valid YAML or isn't a mapping
+fn parse_document(content: &str) -> Option<Mapping> {
+    let mut document: Value = serde_yaml::from_str(content).ok()?;
+    // An unresolvable merge leaves the document as written
+    let _ = document.apply_merge();
+    match document {
+        Value::Mapping(mapping) => Some(mapping),
+        _ => None,
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Tagged(tagged) => scalar(&tagged.value),
+        _ => None,
+    }
+}
+
+// A scalar or a sequence of scalars; anything else is empty
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::Sequence(items) => items.iter().filter_map(scalar).collect(),
+        value => scalar(value).into_iter().collect(),
+    }
+}
+
+// Every key and scalar under `value`, one `key: value` or bare entry per line,
+// so job bodies can be searched for commands and actions
+fn flatten(value: &Value, out: &mut String) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let key = scalar(key).unwrap_or_default();
+                match scalar(value) {
+                    Some(value) => out.push_str(&format!("{}: {}\n", key, value)),
+                    None => {
+                        out.push_str(&key);
+                        out.push('\n');
+                        flatten(value, out);
+                    }
+                }
+            }
+        }
+        Value::Sequence(items) => items.iter().for_each(|item| flatten(item, out)),
+        Value::Tagged(tagged) => flatten(&tagged.value, out),
+        value => {
+            if let Some(text) = scalar(value) {
+                out.push_str(&text);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn contains_any(haystack: &str, needles: &[&str]) -> bool {
+    needles.iter().any(|n| haystack.contains(n))
+}
+
+// Classify a job from its id, display name and everything in its body
+fn classify(workflow: &mut CIWorkflow, job_id: &str, job_name: &str, body: &Value) {
+    let names = format!("{}\n{}", job_id, job_name).to_lowercase();
+    let mut text = String::new();
+    flatten(body, &mut text);
+    let text = format!("{}\n{}", job_id, text).to_lowercase();
+    workflow.runs_tests |= contains_any(&text, TEST_MARKERS) || job_id.to_lowercase().contains("test");
+    workflow.runs_lint |= contains_any(&text, LINT_MARKERS);
+    workflow.builds_release |= contains_any(&names, RELEASE_JOB_NAMES) || contains_any(&text, RELEASE_MARKERS);
+    workflow.runs_security_scan |= contains_any(&text, SECURITY_MARKERS);
+    workflow.runs_build |= contains_any(&text, BUILD_MARKERS);
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+// Platforms and versions from one `strategy.matrix` mapping or `include:` entry
+fn matrix_entries(entries: &Mapping, matrix: &mut CIMatrix) {
+    for (key, value) in entries {
+        let Some(key) = key.as_str() else { continue };
+        if key == "include" {
+            for entry in value.as_sequence().into_iter().flatten().filter_map(Value::as_mapping) {
+                matrix_entries(entry, matrix);
+            }
+            continue;
+        }
+        let values = strings(value).into_iter().filter(|v| !v.contains("${{"));
+        if MATRIX_OS_KEYS.contains(&key) {
+            for os in values {
+                push_unique(&mut matrix.os_targets, os);
+            }
+        } else if let Some((_, language)) = MATRIX_VERSION_KEYS.iter().find(|(k, _)| *k == key) {
+            for version in values {
+                push_unique(&mut matrix.language_versions, format!("{} {}", language, version));
+            }
+        }
+    }
+}
+
+// Platforms and versions listed under `strategy.matrix`, including
+// `include:` entries; `${{ ... }}` expressions are skipped. False when the
+// job has no matrix
+fn job_matrix(job: &Value, matrix: &mut CIMatrix) -> bool {
+    let Some(value) = job.get("strategy").and_then(|strategy| strategy.get("matrix")) else { return false };
+    if let Some(entries) = value.as_mapping() {
+        matrix_entries(entries, matrix);
+    }
+    true
+}
+
+fn github_triggers(on: &Value) -> Vec<String> {
+    let Some(events) = on.as_mapping() else { return strings(on) };
+    let mut triggers = Vec::new();
+    for (name, config) in events {
+        let Some(name) = scalar(name) else { continue };
+        let restricted_to_tags = config.get("tags").is_some() && config.get("branches").is_none();
+        triggers.push(if name == "push" && restricted_to_tags { "tags".to_string() } else { name });
+    }
+    triggers
+}
+
+pub fn parse_github_workflow(file: &str, content: &str) -> CIWorkflow {
+    let mut workflow = CIWorkflow { file: file.to_string(), ..Default::default() };
+    let Some(document) = parse_document(content) else { return workflow };
+    let Some(jobs) = document.get("jobs").and_then(Value::as_mapping) else { return workflow };
+
+    workflow.name = document.get("name").and_then(scalar);
+    // `on` becomes `true` under YAML 1.1 parsers, so both spellings are read
+    if let Some(on) = document.get("on").or_else(|| document.get(Value::Bool(true))) {
+        workflow.triggers = github_triggers(on);
+    }
+    let (mut matrix, mut has_matrix) = (CIMatrix::default(), false);
+    for (id, job) in jobs {
+        let Some(id) = scalar(id) else { continue };
+        let display = job.get("name").and_then(scalar).unwrap_or_else(|| id.clone());
+        classify(&mut workflow, &id, &display, job);
+        workflow.jobs.push(display);
+        if job_matrix(job, &mut matrix) {
+            has_matrix = true;
+        }
+    }
+    workflow.matrix = has_matrix.then_some(matrix);
+    workflow.parsed = true;
+    workflow
+}
+
+// `only:`/`except:` as a list, or the `refs` list inside their mapping form
+fn gitlab_refs(job: &Value, key: &str) -> Vec<String> {
+    match job.get(key) {
+        Some(value) if value.is_mapping() => value.get("refs").map(strings).unwrap_or_default(),
+        Some(value) => strings(value),
+        None => Vec::new(),
+    }
+}
+
+pub fn parse_gitlab_ci(file: &str, content: &str) -> CIWorkflow {
+    let mut workflow = CIWorkflow { file: file.to_string(), ..Default::default() };
+    let Some(document) = parse_document(content) else { return workflow };
+    let mut triggers = vec!["push".to_string()];
+    for (key, job) in &document {
+        let Some(key) = key.as_str() else { continue };
+        if key.starts_with('.') || GITLAB_RESERVED_KEYS.contains(&key) || !job.is_mapping() {
+            continue;
+        }
+        let only = gitlab_refs(job, "only");
+        let mut rules = String::new();
+        if let Some(value) = job.get("rules") {
+            flatten(value, &mut rules);
+        }
+        if rules.contains("CI_COMMIT_TAG") || only.iter().any(|r| r == "tags") {
+            triggers.push("tags".to_string());
+        }
+        if rules.contains("merge_request") || only.iter().any(|r| r.starts_with("merge_request")) {
+            triggers.push("merge_request".to_string());
+        }
+        if rules.contains("\"schedule\"") || only.iter().any(|r| r == "schedules") {
+            triggers.push("schedule".to_string());
+        }
+        classify(&mut workflow, key, key, job);
+        workflow.jobs.push(key.to_string());
+    }
+    triggers.sort();
+    triggers.dedup();
+    workflow.triggers = triggers;
+    workflow.parsed = true;
+    workflow
+}
+
+// Structured workflows for GitHub Actions and GitLab CI; a file that can't be
+// read or parsed still appears with `parsed: false`
+pub fn read_workflows(root: &Path) -> Vec<CIWorkflow> {
+    let mut workflows = Vec::new();
+    let workflow_dir = root.join(".github").join("workflows");
+    if let Ok(entries) = std::fs::read_dir(&workflow_dir) {
+        let mut names: Vec<String> = entries
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".yml") || n.ends_with(".yaml"))
+            .collect();
+        names.sort();
+        for name in names {
+            let file = format!(".github/workflows/{}", name);
+            let workflow = match read_text_prefix_limited(&workflow_dir.join(&name).to_string_lossy(), MAX_FILE_BYTES) {
+                Ok((content, _)) => parse_github_workflow(&file, &content),
+                Err(_) => CIWorkflow { file, ..Default::default() },
+            };
+            workflows.push(workflow);
+        }
+    }
+    let gitlab = root.join(".gitlab-ci.yml");
+    if gitlab.is_file() {
+        let file = ".gitlab-ci.yml";
+        workflows.push(match read_text_prefix_limited(&gitlab.to_string_lossy(), MAX_FILE_BYTES) {
+            Ok((content, _)) => parse_gitlab_ci(file, &content),
+            Err(_) => CIWorkflow { file: file.to_string(), ..Default::default() },
+        });
+    }
+    workflows
+}
+
+// Every platform and version tested across all workflows
+pub fn merged_matrix(workflows: &[CIWorkflow]) -> Option<CIMatrix> {
+    let mut merged: Option<CIMatrix> = None;
+    for matrix in workflows.iter().filter_map(|w| w.matrix.as_ref()) {
+        let merged = merged.get_or_insert_with(CIMatrix::default);
+        for os in &matrix.os_targets {
+            push_unique(&mut merged.os_targets, os.clone());
+        }
+        for version in &matrix.language_versions {
+            push_unique(&mut merged.language_versions, version.clone());
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE_WORKFLOW: &str = r#"
+name: Release
+on:
+  push:
+    tags:
+      - "v*"
+  workflow_dispatch:
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - run: cargo clippy -- -D warnings
+      - run: |
+          cargo test --workspace
+  publish:
+    name: Publish binaries
+    needs: test
+    runs-on: ubuntu-latest
+    steps:
+      - uses: tauri-apps/tauri-action@v0
+"#;
+
+    #[test]
+    fn test_parse_github_workflow_jobs_and_triggers() {
+        let workflow = parse_github_workflow(".github/workflows/release.yml", RELEASE_WORKFLOW);
+        assert!(workflow.parsed);
+        assert_eq!(workflow.name.as_deref(), Some("Release"));
+        assert_eq!(workflow.triggers, vec!["tags", "workflow_dispatch"]);
+        assert_eq!(workflow.jobs, vec!["test", "Publish binaries"]);
+        assert!(workflow.runs_tests && workflow.runs_lint && workflow.builds_release);
+        assert!(!workflow.runs_security_scan);
+
+        let codeql = parse_github_workflow(
+            "codeql.yml",
+            "on: [push, pull_request]\njobs:\n  analyze:\n    steps:\n    - uses: github/codeql-action/init@v3\n",
+        );
+        assert_eq!(codeql.triggers, vec!["push", "pull_request"]);
+        assert!(codeql.runs_security_scan);
+    }
+
+    #[test]
+    fn test_parse_github_matrix() {
+        let workflow = parse_github_workflow(
+            "ci.yml",
+            r#"on: push
+jobs:
+  test:
+    runs-on: ${{ matrix.os }}
+    strategy:
+      fail-fast: false
+      matrix:
+        os: [ubuntu-latest, windows-latest, macos-latest]
+        node-version:
+          - 18
+          - 20
+        include:
+          - os: ubuntu-22.04
+            node-version: "22"
+    steps:
+      - run: npm test
+  lint:
+    runs-on: ubuntu-latest
+    steps:
+      - run: npm run lint
+"#,
+        );
+        let matrix = workflow.matrix.clone().unwrap();
+        assert_eq!(matrix.os_targets, vec!["ubuntu-latest", "windows-latest", "macos-latest", "ubuntu-22.04"]);
+        assert_eq!(matrix.language_versions, vec!["node 18", "node 20", "node 22"]);
+
+        let plain = parse_github_workflow("plain.yml", "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n");
+        assert_eq!(plain.matrix, None);
+        assert_eq!(merged_matrix(&[plain, workflow]), Some(matrix));
+    }
+
+    #[test]
+    fn test_parse_yaml_anchors_flow_lists_and_block_scalars() {
+        let workflow = parse_github_workflow(
+            "ci.yml",
+            r#""on":
+  push:
+    branches: [main]
+jobs:
+  test:
+    strategy:
+      matrix:
+        "os": [
+          ubuntu-latest,
+          windows-latest,
+        ]
+        python-version: &versions ["3.11", "3.12"]
+    steps:
+      - run: >
+          pytest -q
+  audit:
+    strategy:
+      matrix:
+        python-version: *versions
+    steps:
+      - uses: pypa/gh-action-pip-audit@v1
+"#,
+        );
+        assert_eq!(workflow.triggers, vec!["push"]);
+        assert!(workflow.runs_tests && workflow.runs_security_scan);
+        let matrix = workflow.matrix.unwrap();
+        assert_eq!(matrix.os_targets, vec!["ubuntu-latest", "windows-latest"]);
+        assert_eq!(matrix.language_versions, vec!["python 3.11", "python 3.12"]);
+
+        let gitlab = parse_gitlab_ci(
+            ".gitlab-ci.yml",
+            ".defaults: &defaults\n  only: [tags]\nrelease:\n  <<: *defaults\n  script: [cargo publish]\n",
+        );
+        assert_eq!(gitlab.jobs, vec!["release"]);
+        assert_eq!(gitlab.triggers, vec!["push", "tags"]);
+    }
+
+    #[test]
+    fn test_unparseable_workflow_degrades_to_file_name() {
+        let workflow = parse_github_workflow("broken.yml", "jobs:\n\tbuild:\n\t\truns-on: x\n");
+        assert_eq!(workflow, CIWorkflow { file: "broken.yml".into(), ..Default::default() });
+    }
+
+    #[test]
+    fn test_parse_gitlab_ci_skips_reserved_keys() {
+        let workflow = parse_gitlab_ci(
+            ".gitlab-ci.yml",
+            "stages: [test, deploy]\n.template:\n  image: rust\nunit:\n  stage: test\n  script:\n    - cargo test\nrelease:\n  stage: deploy\n  rules:\n    - if: $CI_COMMIT_TAG\n  script:\n    - cargo publish\n",
+        );
+        assert_eq!(workflow.jobs, vec!["unit", "release"]);
+        assert_eq!(workflow.triggers, vec!["push", "tags"]);
+        assert!(workflow.runs_tests && workflow.builds_release);
+    }
+
+    #[test]
+    fn test_release_build_flag_is_not_a_release_job() {
+        let build = parse_github_workflow(
+            "ci.yml",
+            "on: push\njobs:\n  build:\n    steps:\n      - name: Build release binary\n        run: cargo build --release\n",
+        );
+        assert!(build.runs_build);
+        assert!(!build.builds_release);
+
+        let action = parse_github_workflow(
+            "ship.yml",
+            "on: push\njobs:\n  ship:\n    steps:\n      - uses: softprops/action-gh-release@v2\n",
+        );
+        assert!(action.builds_release);
+    }
+}
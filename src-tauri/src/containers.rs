@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::fs_utils::{read_text_prefix_limited, walker_with_depth};
+
+const MAX_DEPTH: usize = 4;
+const MAX_FILE_BYTES: usize = 256 * 1024;
+// Image names (without registry or tag) that mean a compose stack runs a database
+const DATABASE_IMAGES: &[&str] = &[
+    "postgres", "postgis", "mysql", "mariadb", "mongo", "redis", "valkey", "cassandra",
+    "couchdb", "cockroach", "clickhouse-server", "neo4j", "elasticsearch", "opensearch",
+    "mssql-server", "timescaledb", "supabase/postgres",
+];
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct DockerfileInfo {
+    pub path: String,
+    // External images in FROM order; `FROM builder` style stage references are left out
+    pub base_images: Vec<String>,
+    pub stages: Vec<String>,
+    pub exposed_ports: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct ComposeService {
+    pub name: String,
+    pub image: Option<String>,
+    pub builds_locally: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct ComposeFile {
+    pub path: String,
+    pub services: Vec<ComposeService>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct ContainerInfo {
+    pub dockerfiles: Vec<DockerfileInfo>,
+    pub compose_files: Vec<ComposeFile>,
+    pub has_dockerignore: bool,
+    pub has_devcontainer: bool,
+    pub has_database_service: bool,
+}
+
+pub fn is_dockerfile(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    lower == "dockerfile" || lower.starts_with("dockerfile.") || lower.ends_with(".dockerfile")
+}
+
+pub fn is_compose_file(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    let stem = lower.strip_suffix(".yml").or_else(|| lower.strip_suffix(".yaml"));
+    matches!(stem, Some(s) if s == "compose" || s == "docker-compose" || s.starts_with("docker-compose.") || s.starts_with("compose."))
+}
+
+pub fn is_database_image(image: &str) -> bool {
+    // "docker.io/library/postgres:16-alpine" -> "postgres"
+    let without_tag = image.split('@').next().unwrap_or(image);
+    let without_tag = match without_tag.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => name,
+        _ => without_tag,
+    };
+    let name = without_tag.trim_start_matches("docker.io/").trim_start_matches("library/");
+    let short = name.rsplit('/').next().unwrap_or(name);
+    DATABASE_IMAGES.iter().any(|db| name == *db || short == *db || name.ends_with(&format!("/{}", db)))
+}
+
+pub fn parse_dockerfile(path: &str, content: &str) -> DockerfileInfo {
+    let mut info = DockerfileInfo { path: path.to_string(), ..Default::default() };
+    let joined = content.replace("\\\r\n", " ").replace("\\\n", " ");
+    for line in joined.lines().map(str::trim) {
+        let mut words = line.split_whitespace();
+        let instruction = words.next().unwrap_or("").to_uppercase();
+        let args: Vec<&str> = words.filter(|w| !w.starts_with("--")).collect();
+        match instruction.as_str() {
+            "FROM" => {
+                let Some(image) = args.first() else { continue };
+                if !info.stages.iter().any(|s| s.eq_ignore_ascii_case(image)) && *image != "scratch" {
+                    info.base_images.push(image.to_string());
+                }
+                if args.len() >= 3 && args[1].eq_ignore_ascii_case("as") {
+                    info.stages.push(args[2].to_string());
+                }
+            }
+            "EXPOSE" => info.exposed_ports.extend(args.iter().map(|p| p.to_string())),
+            _ => {}
+        }
+    }
+    info
+}
+
+// Services under the top-level `services:` key, read by indentation rather
+// than a full YAML parse
+pub fn parse_compose(content: &str) -> Vec<ComposeService> {
+    let mut services: Vec<ComposeService> = Vec::new();
+    let mut in_services = false;
+    let mut service_indent: Option<usize> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            in_services = trimmed == "services:";
+            service_indent = None;
+            continue;
+        }
+        if !in_services {
+            continue;
+        }
+        let indent_of_services = *service_indent.get_or_insert(indent);
+        if indent == indent_of_services {
+            if let Some(name) = trimmed.strip_suffix(':') {
+                services.push(ComposeService { name: name.trim_matches('"').to_string(), ..Default::default() });
+            }
+        } else if indent > indent_of_services {
+            let Some(service) = services.last_mut() else { continue };
+            if let Some(image) = trimmed.strip_prefix("image:") {
+                service.image = Some(image.trim().trim_matches('"').trim_matches('\'').to_string());
+            } else if trimmed == "build:" || trimmed.starts_with("build:") {
+                service.builds_locally = true;
+            }
+        }
+    }
+    services
+}
+
+pub fn get_container_info(root: &Path) -> ContainerInfo {
+    let mut info = ContainerInfo {
+        has_dockerignore: root.join(".dockerignore").exists(),
+        has_devcontainer: root.join(".devcontainer.json").exists() || root.join(".devcontainer").is_dir(),
+        ..Default::default()
+    };
+
+    for entry in walker_with_depth(root, Some(MAX_DEPTH)).filter_map(|e| e.ok()) {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy();
+        let (dockerfile, compose) = (is_dockerfile(&file_name), is_compose_file(&file_name));
+        if !dockerfile && !compose {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        let Ok((content, _)) = read_text_prefix_limited(&entry.path().to_string_lossy(), MAX_FILE_BYTES) else {
+            continue;
+        };
+        if dockerfile {
+            info.dockerfiles.push(parse_dockerfile(&relative, &content));
+        } else {
+            info.compose_files.push(ComposeFile { path: relative, services: parse_compose(&content) });
+        }
+    }
+
+    info.dockerfiles.sort_by(|a, b| a.path.cmp(&b.path));
+    info.compose_files.sort_by(|a, b| a.path.cmp(&b.path));
+    info.has_database_service = info
+        .compose_files
+        .iter()
+        .flat_map(|c| &c.services)
+        .any(|s| s.image.as_deref().is_some_and(is_database_image));
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dockerfile_multi_stage() {
+        let info = parse_dockerfile(
+            "Dockerfile",
+            "FROM --platform=$BUILDPLATFORM node:20-alpine AS deps\nRUN npm ci\n\
+             FROM deps AS build\nRUN npm run build\n\
+             FROM nginx:1.27 \\\n  AS runtime\nEXPOSE 80 443/tcp\n",
+        );
+        assert_eq!(info.base_images, vec!["node:20-alpine", "nginx:1.27"]);
+        assert_eq!(info.stages, vec!["deps", "build", "runtime"]);
+        assert_eq!(info.exposed_ports, vec!["80", "443/tcp"]);
+    }
+
+    #[test]
+    fn test_parse_compose_services_and_database_detection() {
+        let services = parse_compose(
+            "version: \"3.9\"\nservices:\n  web:\n    build: .\n    ports:\n      - \"3000:3000\"\n  db:\n    image: postgres:16-alpine\n    environment:\n      POSTGRES_PASSWORD: example\nvolumes:\n  data:\n",
+        );
+        assert_eq!(
+            services,
+            vec![
+                ComposeService { name: "web".into(), image: None, builds_locally: true },
+                ComposeService { name: "db".into(), image: Some("postgres:16-alpine".into()), builds_locally: false },
+            ]
+        );
+        assert!(is_database_image("docker.io/library/mongo:7"));
+        assert!(is_database_image("bitnami/redis"));
+        assert!(!is_database_image("localhost:5000/web:latest"));
+        assert!(is_compose_file("docker-compose.override.yml"));
+        assert!(!is_compose_file("compose.json"));
+    }
+
+    #[test]
+    fn test_get_container_info_lists_nested_dockerfiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("services/api")).unwrap();
+        std::fs::write(root.join("Dockerfile"), "FROM rust:1.80\n").unwrap();
+        std::fs::write(root.join("services/api/Dockerfile.dev"), "FROM python:3.12-slim\nEXPOSE 8000\n").unwrap();
+        std::fs::write(root.join("compose.yaml"), "services:\n  cache:\n    image: redis:7\n").unwrap();
+        std::fs::write(root.join(".dockerignore"), "target\n").unwrap();
+
+        let info = get_container_info(root);
+        let paths: Vec<&str> = info.dockerfiles.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec!["Dockerfile", "services/api/Dockerfile.dev"]);
+        assert!(info.has_dockerignore && info.has_database_service && !info.has_devcontainer);
+    }
+}
@@ -5,7 +5,9 @@ use r2d2_sqlite::SqliteConnectionManager;
 use r2d2::Pool;
 use chrono::{DateTime, Utc};
 
-use crate::analysis::RepoAnalysis;
+use crate::analysis::{AnalysisMetricsSummary, RepoAnalysis, CURRENT_ANALYSIS_SCHEMA_VERSION};
+use crate::git::GitSummary;
+use crate::languages::LanguageShare;
 use crate::storage::{ProjectSummary, Task, TaskList};
 
 pub type DbPool = Pool<SqliteConnectionManager>;
@@ -23,6 +25,36 @@ pub struct Project {
     pub total_size_bytes: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub last_opened_at: Option<DateTime<Utc>>,
+    pub opened_count: i64,
+    pub language_breakdown: Option<Vec<LanguageShare>>,
+    pub parent_id: Option<i64>,
+    pub last_activity_at: Option<DateTime<Utc>>,
+    // Largest code language by bytes and its share (0-100)
+    pub primary_language: Option<String>,
+    pub primary_language_percentage: Option<f64>,
+    // Ecosystem the listing detected the project by, e.g. "rust" or "git"
+    pub project_kind: Option<String>,
+    // Archived projects stay stored but are hidden unless asked for
+    pub is_archived: bool,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectStorage {
+    pub project_id: i64,
+    pub name: String,
+    pub path: String,
+    pub file_rows: i64,
+    pub file_rows_bytes: i64,
+    pub analysis_bytes: i64,
+    pub total_bytes: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +72,62 @@ pub struct FileMetadata {
 }
 
 pub fn init_db_pool(db_path: &Path) -> Result<DbPool, Box<dyn std::error::Error>> {
+    let problem = match open_pool(db_path) {
+        Ok(pool) => match ensure_integrity(&pool) {
+            Ok(()) => return Ok(pool),
+            Err(e) => e,
+        },
+        Err(e) => e,
+    };
+    // Lock, permission and migration errors are returned as they are; only
+    // a damaged file is worth losing the user's data over
+    if !is_corruption(problem.as_ref()) {
+        return Err(problem);
+    }
+    // Never refuse to start over corruption: set the damaged file aside and begin fresh
+    let backup = quarantine_database(db_path)?;
+    tracing::error!(
+        error = %problem,
+        moved_to = %backup.display(),
+        "Database failed integrity check; created a new one"
+    );
+    open_pool(db_path)
+}
+
+// The error SQLite gives for a damaged file or one that isn't a database
+// at all, or `ensure_integrity`'s own verdict
+fn is_corruption(error: &(dyn std::error::Error + 'static)) -> bool {
+    match error.downcast_ref::<rusqlite::Error>() {
+        Some(rusqlite::Error::SqliteFailure(failure, _)) => matches!(
+            failure.code,
+            rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase
+        ),
+        Some(_) => false,
+        None => error.downcast_ref::<IntegrityCheckFailed>().is_some(),
+    }
+}
+
+#[derive(Debug)]
+struct IntegrityCheckFailed;
+
+impl std::fmt::Display for IntegrityCheckFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("integrity_check did not return ok")
+    }
+}
+
+impl std::error::Error for IntegrityCheckFailed {}
+
+// A fresh database in a temporary directory; the pool is only usable
+// while the TempDir is alive
+#[cfg(test)]
+pub fn test_pool() -> (tempfile::TempDir, DbPool) {
+    let dir = tempfile::tempdir().unwrap();
+    let pool = init_db_pool(&dir.path().join("repomuse.db")).unwrap();
+    (dir, pool)
+}
+
+fn open_pool(db_path: &Path) -> Result<DbPool, Box<dyn std::error::Error>> {
     let manager = SqliteConnectionManager::file(db_path);
     let pool = Pool::builder()
         .max_size(15)
@@ -52,7 +140,58 @@ pub fn init_db_pool(db_path: &Path) -> Result<DbPool, Box<dyn std::error::Error>
     Ok(pool)
 }
 
-fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
+// Cheaper than integrity_check (skips index cross-checks); for diagnostics
+pub fn quick_check_ok(conn: &Connection) -> Result<bool, rusqlite::Error> {
+    let result: String = conn.query_row("PRAGMA quick_check(1)", [], |row| row.get(0))?;
+    Ok(result == "ok")
+}
+
+fn integrity_ok(conn: &Connection) -> Result<bool, rusqlite::Error> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let results = stmt.query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results.len() == 1 && results[0] == "ok")
+}
+
+// Check integrity, retrying once after folding the WAL back into the main file
+fn ensure_integrity(pool: &DbPool) -> Result<(), Box<dyn std::error::Error>> {
+    let conn = pool.get()?;
+    if integrity_ok(&conn)? {
+        return Ok(());
+    }
+
+    tracing::warn!("Integrity check failed; checkpointing WAL and retrying");
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    if integrity_ok(&conn)? {
+        return Ok(());
+    }
+
+    Err(Box::new(IntegrityCheckFailed))
+}
+
+// Rename a corrupt database (and its WAL/SHM side files) out of the way
+fn quarantine_database(db_path: &Path) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+    let suffix = format!("corrupt.{}", Utc::now().format("%Y%m%d%H%M%S"));
+    let file_name = db_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("repomuse.db");
+    let backup = db_path.with_file_name(format!("{}.{}", file_name, suffix));
+
+    if db_path.exists() {
+        std::fs::rename(db_path, &backup)?;
+    }
+    for side in ["-wal", "-shm"] {
+        let side_path = db_path.with_file_name(format!("{}{}", file_name, side));
+        if side_path.exists() {
+            let _ = std::fs::rename(&side_path, db_path.with_file_name(format!("{}{}.{}", file_name, side, suffix)));
+        }
+    }
+    Ok(backup)
+}
+
+// Creates missing tables and columns; safe to re-run on an existing database
+pub fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch("
         PRAGMA foreign_keys = ON;
         PRAGMA journal_mode = WAL;
@@ -79,7 +218,31 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
         )
     ", [])?;
 
+    // Columns added after the initial schema
+    add_column_if_missing(conn, "projects", "last_opened_at", "TIMESTAMP")?;
+    add_column_if_missing(conn, "projects", "opened_count", "INTEGER DEFAULT 0")?;
+    // JSON array of LanguageShare, refreshed whenever a breakdown is computed
+    add_column_if_missing(conn, "projects", "language_breakdown", "TEXT")?;
+    // When file_count was last computed; NULL means never
+    add_column_if_missing(conn, "projects", "counted_at", "TIMESTAMP")?;
+    // Monorepo root a workspace member was expanded from; NULL for top-level projects
+    add_column_if_missing(conn, "projects", "parent_id", "INTEGER REFERENCES projects(id) ON DELETE SET NULL")?;
+    // Newer of the last commit and the newest file mtime, refreshed with the file count
+    add_column_if_missing(conn, "projects", "last_activity_at", "TIMESTAMP")?;
+    // Set when the user edits the description, so rescans leave it alone
+    add_column_if_missing(conn, "projects", "description_overridden", "BOOLEAN DEFAULT FALSE")?;
+    add_column_if_missing(conn, "projects", "primary_language", "TEXT")?;
+    add_column_if_missing(conn, "projects", "primary_language_percentage", "REAL")?;
+    add_column_if_missing(conn, "projects", "project_kind", "TEXT")?;
+    add_column_if_missing(conn, "projects", "is_archived", "BOOLEAN DEFAULT FALSE")?;
+    // Background re-analysis every N hours; NULL means off
+    add_column_if_missing(conn, "projects", "auto_refresh_interval_hours", "INTEGER")?;
+    add_column_if_missing(conn, "projects", "auto_refresh_failures", "INTEGER DEFAULT 0")?;
+    // Earliest next attempt after a failure or a missing directory
+    add_column_if_missing(conn, "projects", "auto_refresh_retry_at", "TIMESTAMP")?;
+
     conn.execute("CREATE INDEX IF NOT EXISTS idx_projects_path ON projects(path)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_projects_opened ON projects(last_opened_at DESC)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_projects_favorite ON projects(is_favorite)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_projects_updated ON projects(updated_at DESC)", [])?;
 
@@ -123,6 +286,13 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
     ", [])?;
 
     conn.execute("CREATE INDEX IF NOT EXISTS idx_analysis_expires ON analysis_cache(expires_at)", [])?;
+    // Hash of metrics + technologies; an unchanged analysis only has its expiry extended
+    add_column_if_missing(conn, "analysis_cache", "content_hash", "TEXT")?;
+    // AnalysisMetricsSummary as JSON, so cards don't decode the whole blob
+    add_column_if_missing(conn, "analysis_cache", "metrics_summary", "TEXT")?;
+    // CURRENT_ANALYSIS_SCHEMA_VERSION the blob was written with; rows from
+    // before versioning are 0 and never match
+    add_column_if_missing(conn, "analysis_cache", "schema_version", "INTEGER NOT NULL DEFAULT 0")?;
 
     // Tasks table
     conn.execute("
@@ -141,6 +311,11 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
         )
     ", [])?;
 
+    // Where a task came from ("insight" for gap suggestions) and, for
+    // insight tasks, which gap it closes
+    add_column_if_missing(conn, "tasks", "source", "TEXT")?;
+    add_column_if_missing(conn, "tasks", "source_ref", "TEXT")?;
+
     conn.execute("CREATE INDEX IF NOT EXISTS idx_tasks_project ON tasks(project_id)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_tasks_completed ON tasks(completed)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_tasks_due ON tasks(due_date)", [])?;
@@ -159,6 +334,8 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
         )
     ", [])?;
 
+    add_column_if_missing(conn, "summaries", "fingerprint", "TEXT")?;
+
     // Git info table
     conn.execute("
         CREATE TABLE IF NOT EXISTS git_info (
@@ -173,6 +350,50 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             UNIQUE(project_id)
         )
     ", [])?;
+    add_column_if_missing(conn, "git_info", "last_commit_message", "TEXT")?;
+    add_column_if_missing(conn, "git_info", "has_uncommitted_changes", "BOOLEAN DEFAULT FALSE")?;
+    // NULL on rows written before the flag was stored; those count as misses
+    add_column_if_missing(conn, "git_info", "is_git_repo", "BOOLEAN")?;
+
+    // Latest published versions from package registries (npm, crates.io, PyPI)
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS registry_versions (
+            ecosystem TEXT NOT NULL,
+            name TEXT NOT NULL,
+            latest TEXT NOT NULL,
+            fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (ecosystem, name)
+        )
+    ", [])?;
+
+    // Computed ProjectInsights, one row per aspect so aspects refresh independently
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS insights_cache (
+            project_path TEXT NOT NULL,
+            aspect TEXT NOT NULL,
+            fingerprint TEXT NOT NULL,
+            data TEXT NOT NULL,
+            computed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (project_path, aspect)
+        )
+    ", [])?;
+
+    // Free-form labels on projects
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS project_tags (
+            project_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (project_id, tag)
+        )
+    ", [])?;
+
+    // Project paths and glob patterns left out of every listing
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS ignored_projects (
+            pattern TEXT PRIMARY KEY,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )
+    ", [])?;
 
     // Settings table
     conn.execute("
@@ -183,9 +404,148 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
         )
     ", [])?;
 
+    // Local-only usage log: analyses, idea and summary generations
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS usage_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            project_path TEXT,
+            duration_ms INTEGER,
+            cache_hit BOOLEAN,
+            tokens INTEGER,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )
+    ", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_usage_events_created ON usage_events(created_at)", [])?;
+
+    // Background runs per project and how they ended; trimmed per project
+    // to ACTIVITY_LOG_LIMIT
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS activity_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            detail TEXT,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        )
+    ", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_activity_log_project ON activity_log(project_id, id)", [])?;
+
+    // Generated ideas, kept per project as a small kanban board
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS ideas (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+            UNIQUE(project_id, text)
+        )
+    ", [])?;
+    add_column_if_missing(conn, "ideas", "status", "TEXT NOT NULL DEFAULT 'new'")?;
+    // Order within the status column, from 0
+    add_column_if_missing(conn, "ideas", "position", "INTEGER NOT NULL DEFAULT 0")?;
+    // Task created when the idea moved to in_progress
+    add_column_if_missing(conn, "ideas", "task_id", "TEXT")?;
+    add_column_if_missing(conn, "ideas", "updated_at", "TIMESTAMP")?;
+    // Focus area the generation was asked for; NULL for a general one
+    add_column_if_missing(conn, "ideas", "focus_area", "TEXT")?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_ideas_project_status ON ideas(project_id, status, position)", [])?;
+
+    // One row per idea generation, so focus areas are remembered even when
+    // every idea it produced was already on the board
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS idea_generations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            focus_area TEXT,
+            idea_count INTEGER NOT NULL DEFAULT 0,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        )
+    ", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_idea_generations_project ON idea_generations(project_id, created_at)", [])?;
+
+    // Detected technologies and manifest dependencies, rewritten per project
+    // after each analysis; `ecosystem` and `version` are '' when unknown
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS project_technologies (
+            project_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            ecosystem TEXT NOT NULL DEFAULT '',
+            name TEXT NOT NULL,
+            version TEXT NOT NULL DEFAULT '',
+            dev BOOLEAN NOT NULL DEFAULT FALSE,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+            PRIMARY KEY (project_id, kind, ecosystem, name, version)
+        )
+    ", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_project_technologies_name ON project_technologies(name)", [])?;
+
+    Ok(())
+}
+
+// Add a column to an existing table when upgrading from an older schema
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
     Ok(())
 }
 
+const PROJECT_COLUMNS: &str = "id, path, name, description, is_git_repo, is_favorite,
+                last_analyzed_at, file_count, total_size_bytes, created_at, updated_at,
+                last_opened_at, COALESCE(opened_count, 0), language_breakdown, parent_id,
+                last_activity_at, primary_language, primary_language_percentage,
+                project_kind, COALESCE(is_archived, FALSE),
+                (SELECT group_concat(tag, char(10)) FROM
+                    (SELECT tag FROM project_tags WHERE project_id = projects.id ORDER BY tag))";
+
+fn project_from_row(row: &rusqlite::Row) -> Result<Project, rusqlite::Error> {
+    Ok(Project {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+        is_git_repo: row.get(4)?,
+        is_favorite: row.get(5)?,
+        last_analyzed_at: row.get(6)?,
+        file_count: row.get(7)?,
+        total_size_bytes: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        last_opened_at: row.get(11)?,
+        opened_count: row.get(12)?,
+        language_breakdown: row
+            .get::<_, Option<String>>(13)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        parent_id: row.get(14)?,
+        last_activity_at: row.get(15)?,
+        primary_language: row.get(16)?,
+        primary_language_percentage: row.get(17)?,
+        project_kind: row.get(18)?,
+        is_archived: row.get(19)?,
+        tags: row
+            .get::<_, Option<String>>(20)?
+            .map(|tags| tags.split('\n').map(str::to_string).collect())
+            .unwrap_or_default(),
+    })
+}
+
 // Project operations
 pub fn upsert_project(
     conn: &Connection,
@@ -213,25 +573,9 @@ pub fn get_project_by_path(
     path: &str,
 ) -> Result<Option<Project>, rusqlite::Error> {
     conn.query_row(
-        "SELECT id, path, name, description, is_git_repo, is_favorite, 
-                last_analyzed_at, file_count, total_size_bytes, created_at, updated_at
-         FROM projects WHERE path = ?1",
+        &format!("SELECT {} FROM projects WHERE path = ?1", PROJECT_COLUMNS),
         params![path],
-        |row| {
-            Ok(Project {
-                id: row.get(0)?,
-                path: row.get(1)?,
-                name: row.get(2)?,
-                description: row.get(3)?,
-                is_git_repo: row.get(4)?,
-                is_favorite: row.get(5)?,
-                last_analyzed_at: row.get(6)?,
-                file_count: row.get(7)?,
-                total_size_bytes: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        },
+        project_from_row,
     ).optional()
 }
 
@@ -240,14 +584,273 @@ pub fn update_project_file_count(
     conn: &Connection,
     project_id: i64,
     count: i64,
+    total_size_bytes: i64,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE projects SET file_count = ?1, total_size_bytes = ?2, counted_at = CURRENT_TIMESTAMP,
+            updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?3",
+        params![count, total_size_bytes, project_id],
+    )?;
+    Ok(())
+}
+
+pub fn set_project_last_activity(
+    conn: &Connection,
+    project_id: i64,
+    last_activity_at: Option<DateTime<Utc>>,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE projects SET last_activity_at = ?1 WHERE id = ?2",
+        params![last_activity_at, project_id],
+    )?;
+    Ok(())
+}
+
+// Projects whose last activity is older than `cutoff`, least recently active
+// first; never-measured projects are left out
+pub fn get_stale_projects(conn: &Connection, cutoff: DateTime<Utc>) -> Result<Vec<Project>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM projects
+         WHERE last_activity_at IS NOT NULL AND last_activity_at < ?1
+         ORDER BY last_activity_at",
+        PROJECT_COLUMNS
+    ))?;
+    let projects = stmt.query_map(params![cutoff], project_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+// Link a workspace member to its monorepo root by path; a no-op until both
+// rows exist
+pub fn set_project_parent(conn: &Connection, path: &str, parent_path: Option<&str>) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE projects SET parent_id = (SELECT id FROM projects WHERE path = ?2) WHERE path = ?1",
+        params![path, parent_path],
+    )?;
+    Ok(())
+}
+
+// True when file_count was computed within the last `max_age_minutes`; a
+// stored 0 from a recent count is a real empty project, not a missing count
+pub fn file_count_is_fresh(conn: &Connection, project_id: i64, max_age_minutes: i64) -> Result<bool, rusqlite::Error> {
+    conn.query_row(
+        "SELECT counted_at IS NOT NULL AND counted_at >= datetime('now', '-' || ?2 || ' minutes')
+         FROM projects WHERE id = ?1",
+        params![project_id, max_age_minutes],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(|fresh| fresh.unwrap_or(false))
+}
+
+// Manual overrides of the detected name and description; false when no
+// project has that path
+pub fn update_project_description(
+    conn: &Connection,
+    project_path: &str,
+    description: Option<&str>,
+) -> Result<bool, rusqlite::Error> {
+    // Clearing the override lets the next rescan fill in the detected description
+    let updated = conn.execute(
+        "UPDATE projects SET description = ?1, description_overridden = ?1 IS NOT NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE path = ?2",
+        params![description, project_path],
+    )?;
+    Ok(updated > 0)
+}
+
+// Detected metadata from a rescan; a description the user set is kept
+pub fn refresh_project_metadata(
+    conn: &Connection,
+    project_path: &str,
+    description: Option<&str>,
+    is_git_repo: bool,
+) -> Result<bool, rusqlite::Error> {
+    let updated = conn.execute(
+        "UPDATE projects
+         SET description = CASE WHEN COALESCE(description_overridden, FALSE) THEN description ELSE ?1 END,
+             is_git_repo = ?2, updated_at = CURRENT_TIMESTAMP
+         WHERE path = ?3",
+        params![description, is_git_repo, project_path],
+    )?;
+    Ok(updated > 0)
+}
+
+// Moves a project row to a new path. When a row already lives there the two
+// are merged: the old row's tasks, ideas, tags and caches move over (the
+// existing row's entry wins where only one per project is kept), favorite
+// and last-opened carry over, and the old row is deleted. Runs in a
+// savepoint so it also works inside a caller's transaction
+pub fn rename_project_path(conn: &Connection, old_path: &str, new_path: &str) -> Result<bool, rusqlite::Error> {
+    let id_of = |path: &str| {
+        conn.query_row("SELECT id FROM projects WHERE path = ?1", params![path], |row| row.get::<_, i64>(0))
+            .optional()
+    };
+    let Some(old_id) = id_of(old_path)? else { return Ok(false) };
+    let Some(new_id) = id_of(new_path)? else {
+        conn.execute("UPDATE projects SET path = ?2 WHERE id = ?1", params![old_id, new_path])?;
+        return Ok(true);
+    };
+
+    conn.execute_batch("SAVEPOINT merge_project")?;
+    let merged = (|| {
+        for table in PROJECT_TABLES {
+            conn.execute(&format!("UPDATE OR IGNORE {} SET project_id = ?2 WHERE project_id = ?1", table), params![old_id, new_id])?;
+        }
+        conn.execute("UPDATE projects SET parent_id = ?2 WHERE parent_id = ?1", params![old_id, new_id])?;
+        // Scalar MAX is NULL when either side is, hence the COALESCE
+        conn.execute(
+            "UPDATE projects SET
+                 is_favorite = projects.is_favorite OR old.is_favorite,
+                 last_opened_at = COALESCE(MAX(projects.last_opened_at, old.last_opened_at), projects.last_opened_at, old.last_opened_at),
+                 opened_count = COALESCE(projects.opened_count, 0) + COALESCE(old.opened_count, 0)
+             FROM (SELECT is_favorite, last_opened_at, opened_count FROM projects WHERE id = ?1) AS old
+             WHERE projects.id = ?2",
+            params![old_id, new_id],
+        )?;
+        delete_project_rows(conn, old_path)
+    })();
+    match merged {
+        Ok(_) => conn.execute_batch("RELEASE merge_project")?,
+        Err(e) => {
+            conn.execute_batch("ROLLBACK TO merge_project; RELEASE merge_project")?;
+            return Err(e);
+        }
+    }
+    Ok(true)
+}
+
+pub fn set_project_archived(conn: &Connection, project_path: &str, archived: bool) -> Result<bool, rusqlite::Error> {
+    let updated = conn.execute(
+        "UPDATE projects SET is_archived = ?1, updated_at = CURRENT_TIMESTAMP WHERE path = ?2",
+        params![archived, project_path],
+    )?;
+    Ok(updated > 0)
+}
+
+// False when the project is unknown; adding a tag it already has is fine
+pub fn add_project_tag(conn: &Connection, project_path: &str, tag: &str) -> Result<bool, rusqlite::Error> {
+    let project_id: Option<i64> = conn
+        .query_row("SELECT id FROM projects WHERE path = ?1", params![project_path], |row| row.get(0))
+        .optional()?;
+    let Some(project_id) = project_id else { return Ok(false) };
+    conn.execute(
+        "INSERT OR IGNORE INTO project_tags (project_id, tag) VALUES (?1, ?2)",
+        params![project_id, tag],
+    )?;
+    Ok(true)
+}
+
+pub fn remove_project_tag(conn: &Connection, project_path: &str, tag: &str) -> Result<bool, rusqlite::Error> {
+    let removed = conn.execute(
+        "DELETE FROM project_tags WHERE tag = ?2 AND project_id = (SELECT id FROM projects WHERE path = ?1)",
+        params![project_path, tag],
+    )?;
+    Ok(removed > 0)
+}
+
+pub fn set_project_kind(conn: &Connection, project_path: &str, kind: Option<&str>) -> Result<(), rusqlite::Error> {
+    conn.execute("UPDATE projects SET project_kind = ?1 WHERE path = ?2", params![kind, project_path])?;
+    Ok(())
+}
+
+pub fn get_project_path(conn: &Connection, project_id: i64) -> Result<Option<String>, rusqlite::Error> {
+    conn.query_row("SELECT path FROM projects WHERE id = ?1", params![project_id], |row| row.get(0))
+        .optional()
+}
+
+pub fn update_project_name(conn: &Connection, project_path: &str, name: &str) -> Result<bool, rusqlite::Error> {
+    let updated = conn.execute(
+        "UPDATE projects SET name = ?1, updated_at = CURRENT_TIMESTAMP WHERE path = ?2",
+        params![name, project_path],
+    )?;
+    Ok(updated > 0)
+}
+
+pub fn save_language_breakdown(
+    conn: &Connection,
+    project_id: i64,
+    breakdown: &[LanguageShare],
+) -> Result<(), rusqlite::Error> {
+    let json = serde_json::to_string(breakdown).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "UPDATE projects SET language_breakdown = ?1 WHERE id = ?2",
+        params![json, project_id],
+    )?;
+    // The breakdown is sorted largest first
+    save_primary_language(conn, project_id, breakdown.first())
+}
+
+pub fn save_primary_language(
+    conn: &Connection,
+    project_id: i64,
+    primary: Option<&LanguageShare>,
 ) -> Result<(), rusqlite::Error> {
     conn.execute(
-        "UPDATE projects SET file_count = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
-        params![count, project_id],
+        "UPDATE projects SET primary_language = ?1, primary_language_percentage = ?2 WHERE id = ?3",
+        params![primary.map(|p| &p.language), primary.map(|p| p.percentage), project_id],
     )?;
     Ok(())
 }
 
+pub fn record_project_opened(conn: &Connection, project_path: &str) -> Result<bool, rusqlite::Error> {
+    let updated = conn.execute(
+        "UPDATE projects
+         SET last_opened_at = CURRENT_TIMESTAMP, opened_count = COALESCE(opened_count, 0) + 1
+         WHERE path = ?1",
+        params![project_path],
+    )?;
+    Ok(updated > 0)
+}
+
+// Most recently opened projects, ties broken by how often they were opened
+pub fn get_recent_projects(conn: &Connection, limit: u32) -> Result<Vec<Project>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM projects
+         WHERE last_opened_at IS NOT NULL
+         ORDER BY last_opened_at DESC, opened_count DESC
+         LIMIT ?1",
+        PROJECT_COLUMNS
+    ))?;
+    let projects = stmt.query_map(params![limit], project_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+// Keyset page of every project in id order, starting after `after_id`
+pub fn get_projects_page(
+    conn: &Connection,
+    after_id: Option<i64>,
+    page_size: usize,
+    include_archived: bool,
+) -> Result<Vec<Project>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM projects
+         WHERE id > ?1 AND (?3 OR NOT COALESCE(is_archived, FALSE))
+         ORDER BY id
+         LIMIT ?2",
+        PROJECT_COLUMNS
+    ))?;
+    let projects = stmt.query_map(params![after_id.unwrap_or(0), page_size as i64, include_archived], project_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+// Most frequently opened projects; never-opened ones are left out
+pub fn get_most_opened_projects(conn: &Connection, limit: u32) -> Result<Vec<Project>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM projects
+         WHERE COALESCE(opened_count, 0) > 0
+         ORDER BY opened_count DESC, last_opened_at DESC
+         LIMIT ?1",
+        PROJECT_COLUMNS
+    ))?;
+    let projects = stmt.query_map(params![limit], project_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
 pub fn toggle_favorite(
     conn: &Connection,
     project_path: &str,
@@ -267,23 +870,159 @@ pub fn get_favorites(conn: &Connection) -> Result<Vec<String>, rusqlite::Error>
     Ok(paths)
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct AutoRefreshProject {
+    pub id: i64,
+    pub path: String,
+    pub name: String,
+    pub interval_hours: u32,
+    pub last_analyzed_at: Option<DateTime<Utc>>,
+    pub failures: u32,
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+// `None` turns auto-refresh off; either way the failure backoff resets.
+// Returns false when no project has this path
+pub fn set_auto_refresh_interval(conn: &Connection, path: &str, hours: Option<u32>) -> Result<bool, rusqlite::Error> {
+    let changed = conn.execute(
+        "UPDATE projects SET auto_refresh_interval_hours = ?1, auto_refresh_failures = 0, auto_refresh_retry_at = NULL
+         WHERE path = ?2",
+        params![hours, path],
+    )?;
+    Ok(changed > 0)
+}
+
+pub fn get_auto_refresh_projects(conn: &Connection) -> Result<Vec<AutoRefreshProject>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, path, name, auto_refresh_interval_hours, last_analyzed_at,
+                COALESCE(auto_refresh_failures, 0), auto_refresh_retry_at
+         FROM projects
+         WHERE auto_refresh_interval_hours IS NOT NULL
+         ORDER BY name",
+    )?;
+    let projects = stmt.query_map([], |row| {
+        Ok(AutoRefreshProject {
+            id: row.get(0)?,
+            path: row.get(1)?,
+            name: row.get(2)?,
+            interval_hours: row.get(3)?,
+            last_analyzed_at: row.get(4)?,
+            failures: row.get(5)?,
+            retry_at: row.get(6)?,
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+// Success clears the backoff; otherwise `failures` and `retry_at` are kept
+pub fn record_auto_refresh_attempt(
+    conn: &Connection,
+    project_id: i64,
+    failures: u32,
+    retry_at: Option<DateTime<Utc>>,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE projects SET auto_refresh_failures = ?1, auto_refresh_retry_at = ?2 WHERE id = ?3",
+        params![failures, retry_at, project_id],
+    )?;
+    Ok(())
+}
+
+// Activity log operations
+const ACTIVITY_LOG_LIMIT: i64 = 200;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ActivityEntry {
+    pub kind: String,
+    pub outcome: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub fn record_activity(
+    conn: &Connection,
+    project_id: i64,
+    kind: &str,
+    outcome: &str,
+    detail: Option<&str>,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO activity_log (project_id, kind, outcome, detail) VALUES (?1, ?2, ?3, ?4)",
+        params![project_id, kind, outcome, detail],
+    )?;
+    conn.execute(
+        "DELETE FROM activity_log WHERE project_id = ?1 AND id <= (
+             SELECT id FROM activity_log WHERE project_id = ?1 ORDER BY id DESC LIMIT 1 OFFSET ?2
+         )",
+        params![project_id, ACTIVITY_LOG_LIMIT],
+    )?;
+    Ok(())
+}
+
+// Newest first
+pub fn get_project_activity(conn: &Connection, project_id: i64, limit: i64) -> Result<Vec<ActivityEntry>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT kind, outcome, detail, created_at FROM activity_log
+         WHERE project_id = ?1 ORDER BY id DESC LIMIT ?2",
+    )?;
+    let entries = stmt.query_map(params![project_id, limit], |row| {
+        Ok(ActivityEntry {
+            kind: row.get(0)?,
+            outcome: row.get(1)?,
+            detail: row.get(2)?,
+            created_at: row.get(3)?,
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
 // Analysis cache operations
+// Cheap proxy for "same analysis": metrics (key-sorted, since HashMap order
+// varies between runs), the technology list and the prefix hash of every
+// sampled file, so an edit that keeps the line counts still refreshes previews
+fn analysis_content_hash(analysis: &RepoAnalysis) -> Result<String, serde_json::Error> {
+    let metrics: std::collections::BTreeMap<_, _> = analysis.metrics.iter().collect();
+    let files: std::collections::BTreeMap<_, _> = analysis.files.iter().map(|f| (f.path.as_str(), f.prefix_hash)).collect();
+    let key = format!("{}{}{:?}", serde_json::to_string(&metrics)?, analysis.technologies.join(","), files);
+    Ok(format!("{:016x}", xxhash_rust::xxh3::xxh3_64(key.as_bytes())))
+}
+
+// Returns false when the stored analysis had the same content hash; the blob
+// is left alone then and only its expiry moves forward
 pub fn cache_analysis(
     conn: &Connection,
     project_id: i64,
     analysis: &RepoAnalysis,
     ttl_hours: i64,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let analysis_blob = bincode::serialize(analysis)?;
+) -> Result<bool, Box<dyn std::error::Error>> {
     let technologies = analysis.technologies.join(",");
     let metrics = serde_json::to_string(&analysis.metrics)?;
-    
-    conn.execute(
-        "INSERT OR REPLACE INTO analysis_cache 
-         (project_id, analysis_data, technologies, metrics, cached_at, expires_at)
-         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP, datetime('now', '+' || ?5 || ' hours'))",
-        params![project_id, analysis_blob, technologies, metrics, ttl_hours],
-    )?;
+    let content_hash = analysis_content_hash(analysis)?;
+    let summary = serde_json::to_string(&AnalysisMetricsSummary::from(analysis))?;
+
+    // Sizes and generated_at aren't part of the hash, so the summary is
+    // refreshed even when the blob is kept. A blob from another schema
+    // version is always rewritten
+    let unchanged = conn.execute(
+        "UPDATE analysis_cache SET expires_at = datetime('now', '+' || ?3 || ' hours'), metrics_summary = ?4
+         WHERE project_id = ?1 AND content_hash = ?2 AND schema_version = ?5",
+        params![project_id, content_hash, ttl_hours, summary, CURRENT_ANALYSIS_SCHEMA_VERSION],
+    )? > 0;
+    if !unchanged {
+        let analysis_blob = bincode::serialize(analysis)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO analysis_cache 
+             (project_id, analysis_data, technologies, metrics, content_hash, metrics_summary, schema_version,
+              cached_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP, datetime('now', '+' || ?8 || ' hours'))",
+            params![
+                project_id, analysis_blob, technologies, metrics, content_hash, summary,
+                CURRENT_ANALYSIS_SCHEMA_VERSION, ttl_hours
+            ],
+        )?;
+    }
     
     // Update last analyzed timestamp
     conn.execute(
@@ -291,7 +1030,7 @@ pub fn cache_analysis(
         params![project_id],
     )?;
     
-    Ok(())
+    Ok(!unchanged)
 }
 
 pub fn get_cached_analysis(
@@ -300,8 +1039,8 @@ pub fn get_cached_analysis(
 ) -> Result<Option<RepoAnalysis>, Box<dyn std::error::Error>> {
     let result: Option<Vec<u8>> = conn.query_row(
         "SELECT analysis_data FROM analysis_cache 
-         WHERE project_id = ?1 AND expires_at > CURRENT_TIMESTAMP",
-        params![project_id],
+         WHERE project_id = ?1 AND expires_at > CURRENT_TIMESTAMP AND schema_version = ?2",
+        params![project_id, CURRENT_ANALYSIS_SCHEMA_VERSION],
         |row| row.get(0),
     ).optional()?;
     
@@ -313,23 +1052,68 @@ pub fn get_cached_analysis(
     }
 }
 
-// File operations
+// Summary columns only; rows cached before metrics_summary existed fall
+// back to decoding the blob once
+pub fn get_cached_analysis_summary(
+    conn: &Connection,
+    project_id: i64,
+) -> Result<Option<AnalysisMetricsSummary>, Box<dyn std::error::Error>> {
+    let summary: Option<Option<String>> = conn.query_row(
+        "SELECT metrics_summary FROM analysis_cache
+         WHERE project_id = ?1 AND expires_at > CURRENT_TIMESTAMP AND schema_version = ?2",
+        params![project_id, CURRENT_ANALYSIS_SCHEMA_VERSION],
+        |row| row.get(0),
+    ).optional()?;
 
-// Task operations
-pub fn save_task_list(
+    match summary {
+        None => Ok(None),
+        Some(Some(json)) => Ok(Some(serde_json::from_str(&json)?)),
+        Some(None) => Ok(get_cached_analysis(conn, project_id)?.as_ref().map(AnalysisMetricsSummary::from)),
+    }
+}
+
+// Summary of the cached analysis even after it expired, so a scheduled
+// refresh can check it against the tree before rescanning
+pub fn get_stored_analysis_summary(
     conn: &Connection,
     project_id: i64,
-    tasks: &[Task],
-) -> Result<(), Box<dyn std::error::Error>> {
-    let tx = conn.unchecked_transaction()?;
-    
-    // Clear existing tasks for this project
-    tx.execute("DELETE FROM tasks WHERE project_id = ?1", params![project_id])?;
-    
-    // Insert new tasks
+) -> Result<Option<AnalysisMetricsSummary>, Box<dyn std::error::Error>> {
+    let summary: Option<Option<String>> = conn.query_row(
+        "SELECT metrics_summary FROM analysis_cache WHERE project_id = ?1 AND schema_version = ?2",
+        params![project_id, CURRENT_ANALYSIS_SCHEMA_VERSION],
+        |row| row.get(0),
+    ).optional()?;
+    match summary.flatten() {
+        Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+        None => Ok(None),
+    }
+}
+
+// Keeps the cached analysis another `ttl_hours` and counts as analyzing the
+// project. Returns false when there is no current-schema row to keep
+pub fn extend_analysis_cache(conn: &Connection, project_id: i64, ttl_hours: i64) -> Result<bool, rusqlite::Error> {
+    let extended = conn.execute(
+        "UPDATE analysis_cache SET expires_at = datetime('now', '+' || ?2 || ' hours')
+         WHERE project_id = ?1 AND schema_version = ?3",
+        params![project_id, ttl_hours, CURRENT_ANALYSIS_SCHEMA_VERSION],
+    )? > 0;
+    if extended {
+        conn.execute("UPDATE projects SET last_analyzed_at = CURRENT_TIMESTAMP WHERE id = ?1", params![project_id])?;
+    }
+    Ok(extended)
+}
+
+// File operations
+
+// Task operations
+fn insert_tasks(
+    tx: &rusqlite::Transaction,
+    project_id: i64,
+    tasks: &[Task],
+) -> Result<(), Box<dyn std::error::Error>> {
     let mut stmt = tx.prepare(
-        "INSERT INTO tasks (id, project_id, text, completed, created_at, completed_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        "INSERT INTO tasks (id, project_id, text, description, tags, completed, created_at, completed_at, due_date, source, source_ref)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
     )?;
     
     for task in tasks {
@@ -339,36 +1123,98 @@ pub fn save_task_list(
         let completed_at = task.completed_at.as_ref()
             .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
             .map(|dt| dt.with_timezone(&Utc));
+        let tags = if task.tags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&task.tags)?)
+        };
             
         stmt.execute(params![
             task.id,
             project_id,
             task.text,
+            task.description,
+            tags,
             task.completed,
             created_at,
-            completed_at
+            completed_at,
+            task.due_date,
+            task.source,
+            task.source_ref
         ])?;
     }
     
-    // Ensure statement is dropped before committing the transaction
-    drop(stmt);
+    Ok(())
+}
+
+pub fn save_task_list(
+    conn: &mut Connection,
+    project_id: i64,
+    tasks: &[Task],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tx = conn.transaction()?;
+    
+    // Clear existing tasks for this project
+    tx.execute("DELETE FROM tasks WHERE project_id = ?1", params![project_id])?;
+    
+    // Insert new tasks
+    insert_tasks(&tx, project_id, tasks)?;
+    // Unlink ideas whose task was removed, so they can create one again
+    tx.execute(
+        "UPDATE ideas SET task_id = NULL
+         WHERE project_id = ?1 AND task_id NOT IN (SELECT id FROM tasks WHERE project_id = ?1)",
+        params![project_id],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+// Add tasks while keeping the project's existing ones
+pub fn append_tasks(
+    conn: &mut Connection,
+    project_id: i64,
+    tasks: &[Task],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tx = conn.transaction()?;
+    insert_tasks(&tx, project_id, tasks)?;
     tx.commit()?;
     Ok(())
 }
 
+// Marks the project's open tasks from `source` whose source_ref is in
+// `refs` as done; returns how many were completed
+pub fn complete_tasks_by_source(
+    conn: &Connection,
+    project_id: i64,
+    source: &str,
+    refs: &[&str],
+) -> Result<usize, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "UPDATE tasks SET completed = TRUE, completed_at = ?1
+         WHERE project_id = ?2 AND source = ?3 AND source_ref = ?4 AND NOT completed"
+    )?;
+    let now = Utc::now();
+    let mut completed = 0;
+    for source_ref in refs {
+        completed += stmt.execute(params![now, project_id, source, source_ref])?;
+    }
+    Ok(completed)
+}
+
 pub fn load_task_list(
     conn: &Connection,
     project_id: i64,
     project_path: &str,
 ) -> Result<Option<TaskList>, rusqlite::Error> {
     let mut stmt = conn.prepare(
-        "SELECT id, text, completed, created_at, completed_at 
+        "SELECT id, text, completed, created_at, completed_at, description, tags, due_date, source, source_ref
          FROM tasks WHERE project_id = ?1 ORDER BY created_at DESC"
     )?;
     
     let tasks: Vec<Task> = stmt.query_map(params![project_id], |row| {
         let created_at: DateTime<Utc> = row.get(3)?;
         let completed_at: Option<DateTime<Utc>> = row.get(4)?;
+        let tags: Option<String> = row.get(6)?;
         
         Ok(Task {
             id: row.get(0)?,
@@ -376,6 +1222,13 @@ pub fn load_task_list(
             completed: row.get(2)?,
             created_at: created_at.to_rfc3339(),
             completed_at: completed_at.map(|dt| dt.to_rfc3339()),
+            description: row.get(5)?,
+            tags: tags
+                .and_then(|t| serde_json::from_str(&t).ok())
+                .unwrap_or_default(),
+            due_date: row.get(7)?,
+            source: row.get(8)?,
+            source_ref: row.get(9)?,
         })
     })?.collect::<Result<Vec<_>, _>>()?;
     
@@ -390,6 +1243,253 @@ pub fn load_task_list(
     }
 }
 
+pub fn get_projects_with_tasks(conn: &Connection) -> Result<Vec<(i64, String, String)>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT p.id, p.name, p.path
+         FROM projects p JOIN tasks t ON t.project_id = p.id
+         ORDER BY p.name COLLATE NOCASE"
+    )?;
+    let projects = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Idea {
+    pub id: i64,
+    pub text: String,
+    pub status: String,
+    pub position: i64,
+    pub task_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub focus_area: Option<String>,
+}
+
+const IDEA_COLUMNS: &str = "id, text, status, position, task_id, created_at, updated_at, focus_area";
+
+fn idea_from_row(row: &rusqlite::Row) -> Result<Idea, rusqlite::Error> {
+    Ok(Idea {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        status: row.get(2)?,
+        position: row.get(3)?,
+        task_id: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        focus_area: row.get(7)?,
+    })
+}
+
+// New ideas go to the bottom of the `new` column; text already on the
+// board is skipped. The generation itself is always recorded. Returns how
+// many ideas were added
+pub fn insert_ideas(
+    conn: &mut Connection,
+    project_id: i64,
+    texts: &[String],
+    focus_area: Option<&str>,
+) -> Result<usize, rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let mut added = 0;
+    for text in texts {
+        added += tx.execute(
+            "INSERT OR IGNORE INTO ideas (project_id, text, status, position, focus_area)
+             SELECT ?1, ?2, 'new', COALESCE(MAX(position) + 1, 0), ?3 FROM ideas WHERE project_id = ?1 AND status = 'new'",
+            params![project_id, text, focus_area],
+        )?;
+    }
+    tx.execute(
+        "INSERT INTO idea_generations (project_id, focus_area, idea_count) VALUES (?1, ?2, ?3)",
+        params![project_id, focus_area, texts.len() as i64],
+    )?;
+    tx.commit()?;
+    Ok(added)
+}
+
+// Focus area of every generation (None for general ones), newest first
+pub fn get_generation_focus_areas(conn: &Connection, project_id: i64) -> Result<Vec<Option<String>>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT focus_area FROM idea_generations WHERE project_id = ?1 ORDER BY created_at DESC, id DESC",
+    )?;
+    let generations = stmt.query_map(params![project_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(generations)
+}
+
+// Ordered by status column position, then newest first within ties
+pub fn get_ideas(conn: &Connection, project_id: i64) -> Result<Vec<Idea>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM ideas WHERE project_id = ?1 ORDER BY position, created_at DESC",
+        IDEA_COLUMNS
+    ))?;
+    let ideas = stmt.query_map(params![project_id], idea_from_row)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(ideas)
+}
+
+pub fn get_idea(conn: &Connection, id: i64) -> Result<Option<(i64, Idea)>, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT {}, project_id FROM ideas WHERE id = ?1", IDEA_COLUMNS),
+        params![id],
+        |row| Ok((row.get(8)?, idea_from_row(row)?)),
+    )
+    .optional()
+}
+
+// Put an idea at `position` in the `status` column (the end when None or
+// past it) and renumber both columns it touched
+pub fn move_idea(conn: &mut Connection, id: i64, status: &str, position: Option<usize>) -> Result<bool, rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let Some((project_id, status_before)): Option<(i64, String)> = tx
+        .query_row("SELECT project_id, status FROM ideas WHERE id = ?1", params![id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .optional()?
+    else {
+        return Ok(false);
+    };
+
+    let column_ids = |status: &str| -> Result<Vec<i64>, rusqlite::Error> {
+        let mut stmt = tx.prepare(
+            "SELECT id FROM ideas WHERE project_id = ?1 AND status = ?2 AND id != ?3 ORDER BY position, created_at DESC",
+        )?;
+        let ids = stmt.query_map(params![project_id, status, id], |row| row.get(0))?.collect();
+        ids
+    };
+    let mut target = column_ids(status)?;
+    let source = if status_before != status { Some(column_ids(&status_before)?) } else { None };
+    target.insert(position.unwrap_or(usize::MAX).min(target.len()), id);
+
+    let renumber = |ids: &[i64]| -> Result<(), rusqlite::Error> {
+        for (position, idea_id) in ids.iter().enumerate() {
+            tx.execute("UPDATE ideas SET position = ?1 WHERE id = ?2", params![position as i64, idea_id])?;
+        }
+        Ok(())
+    };
+    renumber(&target)?;
+    if let Some(source) = source {
+        renumber(&source)?;
+    }
+    tx.execute(
+        "UPDATE ideas SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        params![status, id],
+    )?;
+    tx.commit()?;
+    Ok(true)
+}
+
+pub fn set_idea_task(conn: &Connection, id: i64, task_id: &str) -> Result<(), rusqlite::Error> {
+    conn.execute("UPDATE ideas SET task_id = ?1 WHERE id = ?2", params![task_id, id])?;
+    Ok(())
+}
+
+// Most recently closed first
+pub fn get_idea_texts_with_status(
+    conn: &Connection,
+    project_path: &str,
+    statuses: &[&str],
+    limit: usize,
+) -> Result<Vec<String>, rusqlite::Error> {
+    let placeholders = vec!["?"; statuses.len()].join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT i.text FROM ideas i JOIN projects p ON p.id = i.project_id
+         WHERE p.path = ? AND i.status IN ({})
+         ORDER BY COALESCE(i.updated_at, i.created_at) DESC LIMIT {}",
+        placeholders, limit
+    ))?;
+    let mut values: Vec<&dyn rusqlite::ToSql> = vec![&project_path];
+    values.extend(statuses.iter().map(|s| s as &dyn rusqlite::ToSql));
+    let texts = stmt.query_map(values.as_slice(), |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(texts)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TechnologyRecord {
+    // "technology" (from analysis), "dependency" or "toolchain"
+    pub kind: String,
+    pub ecosystem: String,
+    pub name: String,
+    pub version: String,
+    pub dev: bool,
+}
+
+pub fn replace_project_technologies(
+    conn: &mut Connection,
+    project_id: i64,
+    records: &[TechnologyRecord],
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM project_technologies WHERE project_id = ?1", params![project_id])?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR IGNORE INTO project_technologies (project_id, kind, ecosystem, name, version, dev)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for record in records {
+            stmt.execute(params![project_id, record.kind, record.ecosystem, record.name, record.version, record.dev])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+// Every indexed record with its project's path and name, optionally only
+// names containing `filter` (case-insensitive)
+pub fn get_technology_records(
+    conn: &Connection,
+    filter: Option<&str>,
+) -> Result<Vec<(String, String, TechnologyRecord)>, rusqlite::Error> {
+    let pattern = format!("%{}%", filter.unwrap_or("").replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+    let mut stmt = conn.prepare(
+        "SELECT p.path, p.name, t.kind, t.ecosystem, t.name, t.version, t.dev
+         FROM project_technologies t JOIN projects p ON p.id = t.project_id
+         WHERE t.name LIKE ?1 ESCAPE '\\'
+         ORDER BY t.name COLLATE NOCASE, p.name COLLATE NOCASE, t.version",
+    )?;
+    let records = stmt.query_map(params![pattern], |row| {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            TechnologyRecord {
+                kind: row.get(2)?,
+                ecosystem: row.get(3)?,
+                name: row.get(4)?,
+                version: row.get(5)?,
+                dev: row.get(6)?,
+            },
+        ))
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(records)
+}
+
+// (id, path, name) of projects with a current cached analysis, most
+// recently analyzed first
+pub fn get_analyzed_projects(conn: &Connection) -> Result<Vec<(i64, String, String)>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.path, p.name FROM projects p
+         WHERE EXISTS (
+             SELECT 1 FROM analysis_cache a
+             WHERE a.project_id = p.id AND a.expires_at > CURRENT_TIMESTAMP AND a.schema_version = ?1
+         )
+         ORDER BY p.last_analyzed_at DESC, p.name COLLATE NOCASE",
+    )?;
+    let projects = stmt
+        .query_map(params![CURRENT_ANALYSIS_SCHEMA_VERSION], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+// Projects with a current cached analysis but nothing indexed yet, such as
+// ones analyzed before the inventory existed
+pub fn get_projects_missing_technologies(conn: &Connection) -> Result<Vec<(i64, String)>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.path FROM projects p
+         WHERE EXISTS (SELECT 1 FROM analysis_cache a WHERE a.project_id = p.id AND a.expires_at > CURRENT_TIMESTAMP)
+           AND NOT EXISTS (SELECT 1 FROM project_technologies t WHERE t.project_id = p.id)",
+    )?;
+    let projects = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
 // Summary operations
 pub fn save_summary(
     conn: &Connection,
@@ -398,17 +1498,19 @@ pub fn save_summary(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let key_features = serde_json::to_string(&summary.key_features)?;
     let technologies = serde_json::to_string(&summary.technologies)?;
+    let fingerprint = summary.fingerprint.as_ref().map(serde_json::to_string).transpose()?;
     
     conn.execute(
         "INSERT OR REPLACE INTO summaries 
-         (project_id, summary_text, key_features, technologies, generated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
+         (project_id, summary_text, key_features, technologies, generated_at, fingerprint)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
         params![
             project_id,
             summary.summary,
             key_features,
             technologies,
-            summary.generated_at
+            summary.generated_at,
+            fingerprint
         ],
     )?;
     
@@ -421,7 +1523,7 @@ pub fn load_summary(
     project_path: &str,
 ) -> Result<Option<ProjectSummary>, Box<dyn std::error::Error>> {
     let result = conn.query_row(
-        "SELECT summary_text, key_features, technologies, generated_at 
+        "SELECT summary_text, key_features, technologies, generated_at, fingerprint
          FROM summaries WHERE project_id = ?1",
         params![project_id],
         |row| {
@@ -433,13 +1535,16 @@ pub fn load_summary(
                 key_features_str,
                 technologies_str,
                 row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
             ))
         },
     ).optional()?;
     
-    if let Some((summary, kf_str, tech_str, generated_at)) = result {
+    if let Some((summary, kf_str, tech_str, generated_at, fingerprint)) = result {
         let key_features: Vec<String> = serde_json::from_str(&kf_str)?;
         let technologies: Vec<String> = serde_json::from_str(&tech_str)?;
+        // An unreadable fingerprint just makes freshness unknown
+        let fingerprint = fingerprint.and_then(|json| serde_json::from_str(&json).ok());
         
         Ok(Some(ProjectSummary {
             project_path: project_path.to_string(),
@@ -447,12 +1552,90 @@ pub fn load_summary(
             generated_at,
             technologies,
             key_features,
+            fingerprint,
         }))
     } else {
         Ok(None)
     }
 }
 
+// Row counts for every user table, so tables added later show up automatically
+pub fn table_row_counts(conn: &Connection) -> Result<Vec<TableRowCount>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+         ORDER BY name",
+    )?;
+    let tables = stmt.query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    tables
+        .into_iter()
+        .map(|table| {
+            let rows = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| row.get(0))?;
+            Ok(TableRowCount { table, rows })
+        })
+        .collect()
+}
+
+// Projects ranked by the bytes they occupy: file rows plus the cached analysis blob.
+// Each table is aggregated by project_id first (served by its project index)
+// and only the grouped results are joined.
+pub fn largest_projects_by_storage(conn: &Connection, limit: u32) -> Result<Vec<ProjectStorage>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.name, p.path,
+                COALESCE(f.file_rows, 0), COALESCE(f.file_bytes, 0), COALESCE(a.analysis_bytes, 0)
+         FROM projects p
+         LEFT JOIN (
+             SELECT project_id,
+                    COUNT(*) AS file_rows,
+                    SUM(LENGTH(path) + LENGTH(relative_path) + COALESCE(LENGTH(language), 0)
+                        + COALESCE(LENGTH(content_hash), 0)) AS file_bytes
+             FROM files GROUP BY project_id
+         ) f ON f.project_id = p.id
+         LEFT JOIN (
+             SELECT project_id,
+                    SUM(COALESCE(LENGTH(analysis_data), 0) + COALESCE(LENGTH(technologies), 0)
+                        + COALESCE(LENGTH(metrics), 0)) AS analysis_bytes
+             FROM analysis_cache GROUP BY project_id
+         ) a ON a.project_id = p.id
+         ORDER BY COALESCE(f.file_bytes, 0) + COALESCE(a.analysis_bytes, 0) DESC
+         LIMIT ?1",
+    )?;
+
+    let projects = stmt.query_map(params![limit], |row| {
+        let file_rows_bytes: i64 = row.get(4)?;
+        let analysis_bytes: i64 = row.get(5)?;
+        Ok(ProjectStorage {
+            project_id: row.get(0)?,
+            name: row.get(1)?,
+            path: row.get(2)?,
+            file_rows: row.get(3)?,
+            file_rows_bytes,
+            analysis_bytes,
+            total_bytes: file_rows_bytes + analysis_bytes,
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+// On-disk path of the main database (None for in-memory databases)
+pub fn database_path(conn: &Connection) -> Option<std::path::PathBuf> {
+    conn.query_row("SELECT file FROM pragma_database_list WHERE name = 'main'", [], |row| row.get::<_, String>(0))
+        .ok()
+        .filter(|p| !p.is_empty())
+        .map(std::path::PathBuf::from)
+}
+
+// Size of the -wal side file for the main database (0 when absent or in-memory)
+pub fn wal_size_bytes(conn: &Connection) -> u64 {
+    database_path(conn)
+        .and_then(|p| std::fs::metadata(format!("{}-wal", p.display())).ok())
+        .map(|m| m.len())
+        .unwrap_or(0)
+}
+
 // Settings operations
 pub fn save_setting(
     conn: &Connection,
@@ -467,6 +1650,11 @@ pub fn save_setting(
     Ok(())
 }
 
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
 pub fn load_setting(
     conn: &Connection,
     key: &str,
@@ -478,4 +1666,573 @@ pub fn load_setting(
     ).optional()
 }
 
+// Ignore list operations
+pub fn add_ignored_project(conn: &Connection, pattern: &str) -> Result<(), rusqlite::Error> {
+    conn.execute("INSERT OR IGNORE INTO ignored_projects (pattern) VALUES (?1)", params![pattern])?;
+    Ok(())
+}
+
+pub fn remove_ignored_project(conn: &Connection, pattern: &str) -> Result<bool, rusqlite::Error> {
+    Ok(conn.execute("DELETE FROM ignored_projects WHERE pattern = ?1", params![pattern])? > 0)
+}
+
+pub fn list_ignored_projects(conn: &Connection) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT pattern FROM ignored_projects ORDER BY pattern")?;
+    let patterns = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(patterns)
+}
+
+pub fn all_project_paths(conn: &Connection) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT path FROM projects")?;
+    let paths = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(paths)
+}
+
+// Tables whose rows belong to one project through `project_id`
+const PROJECT_TABLES: &[&str] = &[
+    "tasks", "summaries", "analysis_cache", "files", "git_info", "project_tags", "ideas", "idea_generations", "project_technologies",
+    "activity_log",
+];
+
+// Remove a project row and everything stored for it. Pooled connections don't
+// enable foreign keys, so dependent rows are deleted explicitly
+pub fn delete_project(conn: &mut Connection, project_path: &str) -> Result<bool, rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let deleted = delete_project_rows(&tx, project_path)?;
+    tx.commit()?;
+    Ok(deleted)
+}
+
+// The deletes behind `delete_project`, for callers already in a transaction
+pub fn delete_project_rows(conn: &Connection, project_path: &str) -> Result<bool, rusqlite::Error> {
+    let project_id: Option<i64> = conn
+        .query_row("SELECT id FROM projects WHERE path = ?1", params![project_path], |row| row.get(0))
+        .optional()?;
+    if let Some(id) = project_id {
+        for table in PROJECT_TABLES {
+            conn.execute(&format!("DELETE FROM {} WHERE project_id = ?1", table), params![id])?;
+        }
+        conn.execute("UPDATE projects SET parent_id = NULL WHERE parent_id = ?1", params![id])?;
+        conn.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
+    }
+    conn.execute("DELETE FROM insights_cache WHERE project_path = ?1", params![project_path])?;
+    Ok(project_id.is_some())
+}
+
+// Registry version cache; entries older than a day count as missing
+pub fn get_registry_version(
+    conn: &Connection,
+    ecosystem: &str,
+    name: &str,
+) -> Result<Option<String>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT latest FROM registry_versions
+         WHERE ecosystem = ?1 AND name = ?2 AND fetched_at > datetime('now', '-1 day')",
+        params![ecosystem, name],
+        |row| row.get(0),
+    ).optional()
+}
+
+pub fn save_registry_version(
+    conn: &Connection,
+    ecosystem: &str,
+    name: &str,
+    latest: &str,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO registry_versions (ecosystem, name, latest, fetched_at)
+         VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+        params![ecosystem, name, latest],
+    )?;
+    Ok(())
+}
+
+// Git summary younger than `max_age_minutes`; a project without a row
+// (never summarised) and a stale row both miss
+pub fn get_git_info(
+    conn: &Connection,
+    project_id: i64,
+    max_age_minutes: i64,
+) -> Result<Option<GitSummary>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT current_branch, commit_count, remotes, last_commit_date, last_commit_message,
+                COALESCE(has_uncommitted_changes, FALSE), is_git_repo
+         FROM git_info
+         WHERE project_id = ?1 AND is_git_repo IS NOT NULL
+           AND updated_at >= datetime('now', '-' || ?2 || ' minutes')",
+        params![project_id, max_age_minutes],
+        |row| {
+            Ok(GitSummary {
+                is_git_repo: row.get(6)?,
+                current_branch: row.get(0)?,
+                commit_count: row.get::<_, Option<i64>>(1)?.map(|c| c as usize),
+                remotes: row
+                    .get::<_, Option<String>>(2)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                last_commit_date: row.get(3)?,
+                last_commit_message: row.get(4)?,
+                has_uncommitted_changes: row.get(5)?,
+            })
+        },
+    )
+    .optional()
+}
+
+// Last commit date from git_info however old the row is; activity tracking
+// only needs the date, not a fresh summary
+pub fn get_last_commit_date(conn: &Connection, project_id: i64) -> Result<Option<String>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT last_commit_date FROM git_info WHERE project_id = ?1",
+        params![project_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(Option::flatten)
+}
+
+pub fn save_git_info(conn: &Connection, project_id: i64, summary: &GitSummary) -> Result<(), rusqlite::Error> {
+    let remotes = serde_json::to_string(&summary.remotes).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT INTO git_info (project_id, current_branch, commit_count, remotes, last_commit_date,
+                               last_commit_message, has_uncommitted_changes, is_git_repo, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, CURRENT_TIMESTAMP)
+         ON CONFLICT(project_id) DO UPDATE SET
+            current_branch = excluded.current_branch,
+            commit_count = excluded.commit_count,
+            remotes = excluded.remotes,
+            last_commit_date = excluded.last_commit_date,
+            last_commit_message = excluded.last_commit_message,
+            has_uncommitted_changes = excluded.has_uncommitted_changes,
+            is_git_repo = excluded.is_git_repo,
+            updated_at = CURRENT_TIMESTAMP",
+        params![
+            project_id,
+            summary.current_branch,
+            summary.commit_count.map(|c| c as i64),
+            remotes,
+            summary.last_commit_date,
+            summary.last_commit_message,
+            summary.has_uncommitted_changes,
+            summary.is_git_repo,
+        ],
+    )?;
+    Ok(())
+}
+
+// Cached insight aspect; misses when the fingerprint changed or the entry
+// is older than `ttl_minutes`
+pub fn get_cached_insight(
+    conn: &Connection,
+    project_path: &str,
+    aspect: &str,
+    fingerprint: &str,
+    ttl_minutes: i64,
+) -> Result<Option<(String, DateTime<Utc>)>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT data, computed_at FROM insights_cache
+         WHERE project_path = ?1 AND aspect = ?2 AND fingerprint = ?3
+           AND computed_at > datetime('now', '-' || ?4 || ' minutes')",
+        params![project_path, aspect, fingerprint, ttl_minutes],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()
+}
+
+pub fn save_cached_insight(
+    conn: &Connection,
+    project_path: &str,
+    aspect: &str,
+    fingerprint: &str,
+    data: &str,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO insights_cache (project_path, aspect, fingerprint, data, computed_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)",
+        params![project_path, aspect, fingerprint, data],
+    )?;
+    Ok(())
+}
+
 // Utility functions
+
+// Wipe user data but keep the schema. With `preserve_favorites` the favorite
+// project rows survive (their caches, tasks and summaries are still cleared)
+pub fn clear_all_data(
+    conn: &mut Connection,
+    preserve_settings: bool,
+    preserve_favorites: bool,
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.transaction()?;
+    for table in [
+        "tasks", "summaries", "analysis_cache", "files", "git_info", "registry_versions", "insights_cache", "usage_events",
+        "ideas", "idea_generations", "project_technologies", "activity_log",
+    ] {
+        tx.execute(&format!("DELETE FROM {}", table), [])?;
+    }
+    if preserve_favorites {
+        // Kept favorites keep their tags
+        tx.execute(
+            "DELETE FROM project_tags WHERE project_id NOT IN (SELECT id FROM projects WHERE is_favorite IS TRUE)",
+            [],
+        )?;
+        tx.execute("DELETE FROM projects WHERE is_favorite IS NOT TRUE", [])?;
+    } else {
+        tx.execute("DELETE FROM project_tags", [])?;
+        tx.execute("DELETE FROM projects", [])?;
+    }
+    if !preserve_settings {
+        tx.execute("DELETE FROM settings", [])?;
+    }
+    tx.commit()?;
+
+    // Run vacuum to reclaim space
+    conn.execute("VACUUM", [])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_db_pool_replaces_corrupt_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("repomuse.db");
+        std::fs::write(&db_path, b"definitely not a sqlite database, just garbage bytes").unwrap();
+
+        let pool = init_db_pool(&db_path).expect("a fresh database should be created");
+        let conn = pool.get().unwrap();
+        assert!(integrity_ok(&conn).unwrap());
+
+        let quarantined = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .any(|e| e.file_name().to_string_lossy().starts_with("repomuse.db.corrupt."));
+        assert!(quarantined);
+    }
+
+    #[test]
+    fn test_largest_projects_by_storage_ranks_by_cached_bytes() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+
+        let small = upsert_project(&conn, "/code/small", "small", None, false).unwrap();
+        let large = upsert_project(&conn, "/code/large", "large", None, false).unwrap();
+        for (id, blob_len) in [(small, 10usize), (large, 5000)] {
+            conn.execute(
+                "INSERT INTO analysis_cache (project_id, analysis_data) VALUES (?1, ?2)",
+                params![id, vec![0u8; blob_len]],
+            ).unwrap();
+        }
+
+        let ranked = largest_projects_by_storage(&conn, 10).unwrap();
+        assert_eq!(ranked[0].name, "large");
+        assert_eq!(ranked[0].analysis_bytes, 5000);
+        assert!(table_row_counts(&conn).unwrap().iter().any(|t| t.table == "projects" && t.rows == 2));
+    }
+
+    #[test]
+    fn test_cache_analysis_skips_unchanged_content() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+        let id = upsert_project(&conn, "/code/demo", "demo", None, false).unwrap();
+
+        let mut analysis = RepoAnalysis {
+            files: Vec::new(),
+            structure: std::collections::HashMap::new(),
+            technologies: vec!["Rust".to_string()],
+            metrics: [("total_files", 3), ("total_lines", 120), ("rust_files", 3)]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            size_metrics: crate::analysis::SizeMetrics {
+                total_size_bytes: 0,
+                total_size_kb: 0,
+                total_size_mb: 0,
+                analyzed_size_bytes: 0,
+                analyzed_size_kb: 0,
+                analyzed_size_mb: 0,
+                largest_files: Vec::new(),
+                size_by_language: std::collections::HashMap::new(),
+            },
+            generated_at: None,
+            from_cache: None,
+            is_lazy_scan: None,
+            scan_progress: None,
+        };
+        assert!(cache_analysis(&conn, id, &analysis, 24).unwrap());
+        conn.execute("UPDATE analysis_cache SET expires_at = datetime('now', '-1 hours')", []).unwrap();
+        assert!(get_cached_analysis(&conn, id).unwrap().is_none());
+
+        // Same content: not rewritten, but the expiry is extended again
+        assert!(!cache_analysis(&conn, id, &analysis, 24).unwrap());
+        assert!(get_cached_analysis(&conn, id).unwrap().is_some());
+
+        // A sampled file edited without changing any metric
+        analysis.files.push(crate::analysis::FileInfo {
+            path: "/code/demo/src/main.rs".to_string(),
+            content: "fn main() {}\n".to_string(),
+            language: "Rust".to_string(),
+            size: 13,
+            encoding: Default::default(),
+            role: Default::default(),
+            prefix_hash: 1,
+        });
+        assert!(cache_analysis(&conn, id, &analysis, 24).unwrap());
+        analysis.files[0].prefix_hash = 2;
+        assert!(cache_analysis(&conn, id, &analysis, 24).unwrap());
+        assert!(!cache_analysis(&conn, id, &analysis, 24).unwrap());
+
+        // A row from an older schema version is a miss and is rewritten even
+        // with the same content
+        conn.execute("UPDATE analysis_cache SET schema_version = 0", []).unwrap();
+        assert!(get_cached_analysis(&conn, id).unwrap().is_none());
+        assert!(get_cached_analysis_summary(&conn, id).unwrap().is_none());
+        assert!(cache_analysis(&conn, id, &analysis, 24).unwrap());
+        assert!(get_cached_analysis(&conn, id).unwrap().is_some());
+
+        analysis.metrics.insert("total_lines".to_string(), 121);
+        assert!(cache_analysis(&conn, id, &analysis, 24).unwrap());
+
+        let summary = get_cached_analysis_summary(&conn, id).unwrap().unwrap();
+        assert_eq!(summary.metrics.get("total_lines"), Some(&121));
+        assert_eq!(summary.technologies, vec!["Rust"]);
+        // Rows written before the summary column existed decode the blob instead
+        conn.execute("UPDATE analysis_cache SET metrics_summary = NULL", []).unwrap();
+        let legacy = get_cached_analysis_summary(&conn, id).unwrap().unwrap();
+        assert_eq!(legacy.metrics, summary.metrics);
+    }
+
+    #[test]
+    fn test_clear_all_data_preserve_flags() {
+        let (_dir, pool) = test_pool();
+        let mut conn = pool.get().unwrap();
+        let seed = |conn: &Connection| {
+            upsert_project(conn, "/code/fav", "fav", None, false).unwrap();
+            upsert_project(conn, "/code/other", "other", None, false).unwrap();
+            toggle_favorite(conn, "/code/fav", true).unwrap();
+            save_setting(conn, "api_settings", "{}").unwrap();
+            save_registry_version(conn, "npm", "react", "18.3.1").unwrap();
+        };
+
+        seed(&conn);
+        clear_all_data(&mut conn, true, true).unwrap();
+        assert_eq!(get_favorites(&conn).unwrap(), vec!["/code/fav"]);
+        assert!(get_project_by_path(&conn, "/code/other").unwrap().is_none());
+        assert!(load_setting(&conn, "api_settings").unwrap().is_some());
+        assert!(get_registry_version(&conn, "npm", "react").unwrap().is_none());
+
+        seed(&conn);
+        clear_all_data(&mut conn, false, false).unwrap();
+        assert!(get_favorites(&conn).unwrap().is_empty());
+        assert!(load_setting(&conn, "api_settings").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_clear_all_data_keeps_tags_of_kept_favorites_only() {
+        let (_dir, pool) = test_pool();
+        let mut conn = pool.get().unwrap();
+        let tag_rows = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM project_tags", [], |row| row.get(0)).unwrap()
+        };
+        let seed = |conn: &Connection| {
+            upsert_project(conn, "/code/fav", "fav", None, false).unwrap();
+            upsert_project(conn, "/code/other", "other", None, false).unwrap();
+            toggle_favorite(conn, "/code/fav", true).unwrap();
+            add_project_tag(conn, "/code/fav", "work").unwrap();
+            add_project_tag(conn, "/code/other", "old").unwrap();
+        };
+
+        seed(&conn);
+        clear_all_data(&mut conn, true, true).unwrap();
+        assert_eq!(get_project_by_path(&conn, "/code/fav").unwrap().unwrap().tags, vec!["work"]);
+        assert_eq!(tag_rows(&conn), 1);
+
+        seed(&conn);
+        clear_all_data(&mut conn, true, false).unwrap();
+        assert_eq!(tag_rows(&conn), 0);
+    }
+
+    #[test]
+    fn test_file_count_freshness_tracks_counted_at() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+        let id = upsert_project(&conn, "/code/empty", "empty", None, false).unwrap();
+
+        assert!(!file_count_is_fresh(&conn, id, 5).unwrap());
+        update_project_file_count(&conn, id, 0, 0).unwrap();
+        assert!(file_count_is_fresh(&conn, id, 5).unwrap());
+        conn.execute("UPDATE projects SET counted_at = datetime('now', '-6 minutes')", []).unwrap();
+        assert!(!file_count_is_fresh(&conn, id, 5).unwrap());
+        assert!(!file_count_is_fresh(&conn, id + 1, 5).unwrap());
+    }
+
+    #[test]
+    fn test_activity_log_is_trimmed_per_project_and_deleted_with_it() {
+        let (_dir, pool) = test_pool();
+        let mut conn = pool.get().unwrap();
+        let busy = upsert_project(&conn, "/code/busy", "busy", None, false).unwrap();
+        let quiet = upsert_project(&conn, "/code/quiet", "quiet", None, false).unwrap();
+        record_activity(&conn, quiet, "auto_refresh", "failed", Some("disk full")).unwrap();
+        for n in 0..ACTIVITY_LOG_LIMIT + 5 {
+            record_activity(&conn, busy, "auto_refresh", "rescanned", Some(&n.to_string())).unwrap();
+        }
+
+        let busy_log = get_project_activity(&conn, busy, ACTIVITY_LOG_LIMIT * 2).unwrap();
+        assert_eq!(busy_log.len() as i64, ACTIVITY_LOG_LIMIT);
+        assert_eq!(busy_log[0].detail.as_deref(), Some((ACTIVITY_LOG_LIMIT + 4).to_string().as_str()));
+        let quiet_log = get_project_activity(&conn, quiet, 10).unwrap();
+        assert_eq!((quiet_log.len(), quiet_log[0].outcome.as_str()), (1, "failed"));
+
+        assert!(delete_project(&mut conn, "/code/quiet").unwrap());
+        let rows: i64 = conn.query_row("SELECT COUNT(*) FROM activity_log WHERE project_id = ?1", [quiet], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 0);
+    }
+
+    #[test]
+    fn test_git_info_round_trip_and_expiry() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+        let id = upsert_project(&conn, "/code/demo", "demo", None, true).unwrap();
+        assert_eq!(get_git_info(&conn, id, 15).unwrap(), None);
+
+        let summary = GitSummary {
+            is_git_repo: true,
+            current_branch: Some("main".into()),
+            commit_count: Some(42),
+            last_commit_date: Some("2026-01-02T03:04:05+00:00".into()),
+            last_commit_message: Some("fix: handle empty repos".into()),
+            remotes: vec![crate::git::GitRemote {
+                name: "origin".into(),
+                url: "git@github.com:o/r.git".into(),
+                web_url: Some("https://github.com/o/r".into()),
+                platform: Some(crate::git::RemotePlatform::GitHub),
+                owner: Some("o".into()),
+                repo: Some("r".into()),
+            }],
+            has_uncommitted_changes: true,
+        };
+        save_git_info(&conn, id, &summary).unwrap();
+        save_git_info(&conn, id, &summary).unwrap();
+        assert_eq!(get_git_info(&conn, id, 15).unwrap(), Some(summary));
+
+        conn.execute("UPDATE git_info SET updated_at = datetime('now', '-16 minutes')", []).unwrap();
+        assert_eq!(get_git_info(&conn, id, 15).unwrap(), None);
+
+        // A plain directory is cached as one, and pre-flag rows miss
+        save_git_info(&conn, id, &GitSummary::default()).unwrap();
+        assert_eq!(get_git_info(&conn, id, 15).unwrap(), Some(GitSummary::default()));
+        conn.execute("UPDATE git_info SET is_git_repo = NULL", []).unwrap();
+        assert_eq!(get_git_info(&conn, id, 15).unwrap(), None);
+    }
+
+    #[test]
+    fn test_update_project_name_and_description() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+        upsert_project(&conn, "/code/app", "app", Some("detected"), false).unwrap();
+
+        assert!(update_project_name(&conn, "/code/app", "My App").unwrap());
+        assert!(update_project_description(&conn, "/code/app", Some("Hand written")).unwrap());
+        assert!(!update_project_name(&conn, "/code/missing", "x").unwrap());
+
+        let project = get_project_by_path(&conn, "/code/app").unwrap().unwrap();
+        assert_eq!(project.name, "My App");
+        assert_eq!(project.description.as_deref(), Some("Hand written"));
+    }
+
+    #[test]
+    fn test_refresh_metadata_keeps_manual_description() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+        upsert_project(&conn, "/code/app", "app", None, false).unwrap();
+
+        assert!(refresh_project_metadata(&conn, "/code/app", Some("from README"), true).unwrap());
+        let project = get_project_by_path(&conn, "/code/app").unwrap().unwrap();
+        assert_eq!(project.description.as_deref(), Some("from README"));
+        assert!(project.is_git_repo);
+
+        update_project_description(&conn, "/code/app", Some("Hand written")).unwrap();
+        refresh_project_metadata(&conn, "/code/app", Some("from Cargo.toml"), true).unwrap();
+        let project = get_project_by_path(&conn, "/code/app").unwrap().unwrap();
+        assert_eq!(project.description.as_deref(), Some("Hand written"));
+        assert_eq!(get_project_path(&conn, project.id).unwrap().as_deref(), Some("/code/app"));
+    }
+
+    #[test]
+    fn test_get_projects_page_walks_ids_in_order() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+        let ids: Vec<i64> = (0..5)
+            .map(|i| upsert_project(&conn, &format!("/code/p{}", i), "p", None, false).unwrap())
+            .collect();
+
+        let first = get_projects_page(&conn, None, 2, false).unwrap();
+        assert_eq!(first.iter().map(|p| p.id).collect::<Vec<_>>(), ids[..2]);
+        let rest = get_projects_page(&conn, Some(ids[1]), 10, false).unwrap();
+        assert_eq!(rest.iter().map(|p| p.id).collect::<Vec<_>>(), ids[2..]);
+        assert!(get_projects_page(&conn, Some(ids[4]), 10, false).unwrap().is_empty());
+
+        set_project_archived(&conn, "/code/p2", true).unwrap();
+        let active = get_projects_page(&conn, Some(ids[1]), 10, false).unwrap();
+        assert_eq!(active.iter().map(|p| p.id).collect::<Vec<_>>(), ids[3..]);
+        assert_eq!(get_projects_page(&conn, Some(ids[1]), 10, true).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_get_most_opened_projects_orders_by_count() {
+        let (_dir, pool) = test_pool();
+        let conn = pool.get().unwrap();
+        for path in ["/code/a", "/code/b", "/code/never"] {
+            upsert_project(&conn, path, path, None, false).unwrap();
+        }
+        record_project_opened(&conn, "/code/a").unwrap();
+        for _ in 0..3 {
+            record_project_opened(&conn, "/code/b").unwrap();
+        }
+
+        let ranked: Vec<(String, i64)> = get_most_opened_projects(&conn, 10)
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.path, p.opened_count))
+            .collect();
+        assert_eq!(ranked, vec![("/code/b".to_string(), 3), ("/code/a".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_init_db_pool_returns_errors_other_than_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("repomuse.db");
+        // A healthy file the migrations can't handle
+        Connection::open(&db_path).unwrap().execute_batch("CREATE VIEW projects AS SELECT 1 AS id").unwrap();
+
+        assert!(init_db_pool(&db_path).is_err());
+        let kept: String = Connection::open(&db_path)
+            .unwrap()
+            .query_row("SELECT type FROM sqlite_master WHERE name = 'projects'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(kept, "view");
+        let quarantined = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .any(|e| e.file_name().to_string_lossy().contains(".corrupt."));
+        assert!(!quarantined);
+    }
+
+    #[test]
+    fn test_init_db_pool_keeps_healthy_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("repomuse.db");
+        {
+            let pool = init_db_pool(&db_path).unwrap();
+            let conn = pool.get().unwrap();
+            save_setting(&conn, "probe", "kept").unwrap();
+        }
+
+        let pool = init_db_pool(&db_path).unwrap();
+        let conn = pool.get().unwrap();
+        assert_eq!(load_setting(&conn, "probe").unwrap().as_deref(), Some("kept"));
+    }
+}
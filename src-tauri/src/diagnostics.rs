@@ -0,0 +1,228 @@
+use serde::Serialize;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use crate::ai::{self, EndpointStatus};
+use crate::db::{self, DbPool};
+use crate::{analysis, performance, projects};
+
+// Budget for each blocking probe; a wedged database shows up as a timeout
+// in `unavailable` rather than hanging the command
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DatabaseHealth {
+    pub path: Option<String>,
+    pub size_bytes: i64,
+    pub wal_size_bytes: u64,
+    pub user_version: i64,
+    pub integrity_ok: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PoolStats {
+    pub max_size: u32,
+    pub connections: u32,
+    pub idle_connections: u32,
+}
+
+// A cache table: its row count and the bytes of its payload column
+#[derive(Debug, Serialize, Clone)]
+pub struct CacheStats {
+    pub name: String,
+    pub entries: i64,
+    pub size_bytes: i64,
+}
+
+// Everything here is safe to paste into a bug report: no API key, no
+// endpoint URL beyond its host, no project contents
+#[derive(Debug, Serialize, Clone)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub database: Option<DatabaseHealth>,
+    pub pool: PoolStats,
+    pub caches: Vec<CacheStats>,
+    pub running_analyses: usize,
+    pub active_watchers: usize,
+    pub rayon_threads: usize,
+    pub walker_threads: usize,
+    pub background_paused: bool,
+    pub last_ai_response: Option<EndpointStatus>,
+    // Sections that failed or timed out, with the reason
+    pub unavailable: Vec<String>,
+}
+
+// Cache tables and the column holding each entry's payload
+const CACHE_TABLES: &[(&str, &str)] = &[
+    ("analysis_cache", "analysis_data"),
+    ("insights_cache", "data"),
+    ("registry_versions", "latest"),
+    ("summaries", "summary_text"),
+];
+
+fn database_health(pool: &DbPool) -> Result<(DatabaseHealth, Vec<CacheStats>), String> {
+    let conn = pool.get_timeout(PROBE_TIMEOUT).map_err(|e| e.to_string())?;
+    let path = db::database_path(&conn);
+    let health = DatabaseHealth {
+        path: path.as_ref().map(|p| p.to_string_lossy().to_string()),
+        size_bytes: crate::maintenance::database_size_bytes(&conn),
+        wal_size_bytes: db::wal_size_bytes(&conn),
+        user_version: conn.query_row("PRAGMA user_version", [], |row| row.get(0)).map_err(|e| e.to_string())?,
+        integrity_ok: db::quick_check_ok(&conn).map_err(|e| e.to_string())?,
+    };
+
+    let mut caches = Vec::new();
+    for (table, column) in CACHE_TABLES {
+        let sql = format!("SELECT COUNT(*), COALESCE(SUM(LENGTH({})), 0) FROM {}", column, table);
+        let (entries, size_bytes) = conn.query_row(&sql, [], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+        caches.push(CacheStats { name: table.to_string(), entries, size_bytes });
+    }
+    Ok((health, caches))
+}
+
+async fn gather(app_version: String, pool: Arc<DbPool>) -> Diagnostics {
+    let mut unavailable = Vec::new();
+    let state = pool.state();
+    let pool_stats = PoolStats {
+        max_size: pool.max_size(),
+        connections: state.connections,
+        idle_connections: state.idle_connections,
+    };
+
+    let probe = tokio::task::spawn_blocking(move || database_health(&pool));
+    let (database, caches) = match tokio::time::timeout(PROBE_TIMEOUT, probe).await {
+        Ok(Ok(Ok((health, caches)))) => (Some(health), caches),
+        Ok(Ok(Err(e))) => {
+            unavailable.push(format!("database: {}", e));
+            (None, Vec::new())
+        }
+        Ok(Err(e)) => {
+            unavailable.push(format!("database: {}", e));
+            (None, Vec::new())
+        }
+        Err(_) => {
+            unavailable.push(format!("database: no answer within {}s", PROBE_TIMEOUT.as_secs()));
+            (None, Vec::new())
+        }
+    };
+
+    Diagnostics {
+        app_version,
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        database,
+        pool: pool_stats,
+        caches,
+        running_analyses: analysis::running_analyses(),
+        active_watchers: projects::active_watchers(),
+        rayon_threads: rayon::current_num_threads(),
+        walker_threads: performance::walker_threads(),
+        background_paused: crate::background::is_paused(),
+        last_ai_response: ai::last_endpoint_status(),
+        unavailable,
+    }
+}
+
+impl Diagnostics {
+    // Plain text for pasting into an issue
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "RepoMuse {} on {}/{}", self.app_version, self.os, self.arch);
+        match &self.database {
+            Some(db) => {
+                let _ = writeln!(text, "Database: {}", db.path.as_deref().unwrap_or("(in memory)"));
+                let _ = writeln!(
+                    text,
+                    "  size {} bytes, WAL {} bytes, user_version {}, integrity {}",
+                    db.size_bytes,
+                    db.wal_size_bytes,
+                    db.user_version,
+                    if db.integrity_ok { "ok" } else { "FAILED" }
+                );
+            }
+            None => {
+                let _ = writeln!(text, "Database: unavailable");
+            }
+        }
+        let _ = writeln!(
+            text,
+            "Pool: {} open, {} idle, max {}",
+            self.pool.connections, self.pool.idle_connections, self.pool.max_size
+        );
+        let _ = writeln!(text, "Caches:");
+        for cache in &self.caches {
+            let _ = writeln!(text, "  {}: {} entries, {} bytes", cache.name, cache.entries, cache.size_bytes);
+        }
+        let _ = writeln!(
+            text,
+            "Running analyses: {}, watchers: {}, background {}",
+            self.running_analyses,
+            self.active_watchers,
+            if self.background_paused { "paused" } else { "active" }
+        );
+        let _ = writeln!(text, "Threads: rayon {}, walker {}", self.rayon_threads, self.walker_threads);
+        match &self.last_ai_response {
+            Some(last) => {
+                let status = last.status.map(|s| s.to_string()).unwrap_or_else(|| "no response".to_string());
+                let _ = writeln!(
+                    text,
+                    "Last AI request: {} to {} -> {} ({}) at {}",
+                    last.kind,
+                    last.host,
+                    status,
+                    if last.ok { "ok" } else { "failed" },
+                    last.at
+                );
+            }
+            None => {
+                let _ = writeln!(text, "Last AI request: none this session");
+            }
+        }
+        for reason in &self.unavailable {
+            let _ = writeln!(text, "Unavailable: {}", reason);
+        }
+        text
+    }
+}
+
+#[tauri::command]
+pub async fn get_diagnostics(app: AppHandle, db_pool: State<'_, Arc<DbPool>>) -> Result<Diagnostics, String> {
+    Ok(gather(app.package_info().version.to_string(), db_pool.inner().clone()).await)
+}
+
+// Same report as `get_diagnostics`, rendered for the clipboard
+#[tauri::command]
+pub async fn get_diagnostics_text(app: AppHandle, db_pool: State<'_, Arc<DbPool>>) -> Result<String, String> {
+    Ok(gather(app.package_info().version.to_string(), db_pool.inner().clone()).await.to_text())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_gather_reports_database_and_cache_tables() {
+        let (_dir, pool) = db::test_pool();
+        let pool = Arc::new(pool);
+        let mut diagnostics = gather("1.2.3".to_string(), pool).await;
+
+        assert!(diagnostics.unavailable.is_empty(), "{:?}", diagnostics.unavailable);
+        let database = diagnostics.database.as_ref().unwrap();
+        assert!(database.integrity_ok);
+        assert!(database.path.as_deref().is_some_and(|p| p.ends_with("repomuse.db")));
+        let names: Vec<&str> = diagnostics.caches.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["analysis_cache", "insights_cache", "registry_versions", "summaries"]);
+        assert!(diagnostics.caches.iter().all(|c| c.entries == 0));
+
+        // Queue tests in this process may already have sent AI requests
+        diagnostics.last_ai_response = None;
+        let text = diagnostics.to_text();
+        assert!(text.starts_with("RepoMuse 1.2.3 on "));
+        assert!(text.contains("integrity ok"));
+        assert!(text.contains("Last AI request: none this session"));
+    }
+}
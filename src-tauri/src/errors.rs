@@ -0,0 +1,106 @@
+use chrono::Utc;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::redact::redact_secrets;
+
+// Oldest entries drop off once the buffer is full
+const MAX_RECENT_ERRORS: usize = 200;
+
+// A failure in background work that no command returns to the frontend
+#[derive(Debug, Serialize, Clone)]
+pub struct AppError {
+    pub timestamp: String,
+    pub source_module: String,
+    pub message: String,
+    // What was being worked on, usually a project path
+    pub context: Option<String>,
+    // True when the user can do something about it; these raise `app:error`
+    pub actionable: bool,
+}
+
+static RECENT_ERRORS: Lazy<Mutex<VecDeque<AppError>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+// Called from setup; before this, errors are recorded but not emitted
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+// Record a background failure for the problems panel and the log
+pub fn report_error(source_module: &str, message: impl Display, context: Option<&str>) {
+    record(source_module, message, context, false);
+}
+
+// Same, and also emit `app:error` so the frontend can tell the user now
+pub fn report_actionable_error(source_module: &str, message: impl Display, context: Option<&str>) {
+    record(source_module, message, context, true);
+}
+
+fn record(source_module: &str, message: impl Display, context: Option<&str>, actionable: bool) {
+    // Messages can quote requests or config, so they're redacted before
+    // being stored, logged or emitted
+    let error = AppError {
+        timestamp: Utc::now().to_rfc3339(),
+        source_module: source_module.to_string(),
+        message: redact_secrets(&message.to_string()).into_owned(),
+        context: context.map(|c| redact_secrets(c).into_owned()),
+        actionable,
+    };
+    tracing::warn!(source = %error.source_module, context = ?error.context, "{}", error.message);
+    push(error.clone());
+    if actionable {
+        if let Some(app) = APP.get() {
+            let _ = app.emit("app:error", &error);
+        }
+    }
+}
+
+fn push(error: AppError) {
+    if let Ok(mut recent) = RECENT_ERRORS.lock() {
+        if recent.len() >= MAX_RECENT_ERRORS {
+            recent.pop_front();
+        }
+        recent.push_back(error);
+    }
+}
+
+// Newest first
+fn recent(limit: usize) -> Vec<AppError> {
+    RECENT_ERRORS
+        .lock()
+        .map(|recent| recent.iter().rev().take(limit).cloned().collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_recent_errors(limit: Option<usize>) -> Result<Vec<AppError>, String> {
+    Ok(recent(limit.unwrap_or(MAX_RECENT_ERRORS)))
+}
+
+#[tauri::command]
+pub async fn clear_recent_errors() -> Result<(), String> {
+    RECENT_ERRORS.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_are_redacted_and_newest_first() {
+        report_error("test", "first", None);
+        report_error("test", "Bearer sk-proj-abcdefghijklmnop1234567890 rejected", Some("/tmp/project"));
+
+        let errors: Vec<AppError> = recent(MAX_RECENT_ERRORS).into_iter().filter(|e| e.source_module == "test").collect();
+        assert_eq!(errors.len(), 2);
+        assert!(!errors[0].message.contains("sk-proj-abcdefghijklmnop1234567890"));
+        assert_eq!(errors[0].context.as_deref(), Some("/tmp/project"));
+        assert_eq!(errors[1].message, "first");
+    }
+}
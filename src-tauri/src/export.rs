@@ -0,0 +1,593 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::analysis::AnalysisMetricsSummary;
+use crate::db::{self, DbPool, Idea};
+use crate::insights::{self, ProjectInsights};
+use crate::languages;
+use crate::storage::{ProjectSummary, Task};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectTasks {
+    pub project_name: String,
+    pub project_path: String,
+    pub tasks: Vec<Task>,
+}
+
+// Render date portion of an RFC 3339 timestamp (falls back to the raw value)
+fn format_date(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+// Punctuation that would turn task text into links, emphasis, headings or
+// tags; the task importer strips the backslashes again
+const MARKDOWN_ESCAPES: &[char] = &['\\', '`', '*', '_', '[', ']', '<', '>', '#', '~', '|'];
+
+// One line of literal text: line breaks fold into spaces and Markdown
+// punctuation is backslash-escaped. `joiner` goes between words
+fn escape_inline(text: &str, joiner: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, word) in text.split_whitespace().enumerate() {
+        if i > 0 {
+            out.push_str(joiner);
+        }
+        for c in word.chars() {
+            if MARKDOWN_ESCAPES.contains(&c) {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+    }
+    out
+}
+
+// Render one checklist line plus an indented description when present. Tags
+// can't hold spaces, so multi-word tags are hyphenated
+fn render_task_line(out: &mut String, task: &Task) {
+    let mut line = format!("- [{}] {}", if task.completed { "x" } else { " " }, escape_inline(&task.text, " "));
+    if let Some(due) = &task.due_date {
+        let _ = write!(line, " (due: {})", due);
+    }
+    if task.completed {
+        if let Some(at) = &task.completed_at {
+            let _ = write!(line, " (completed {})", format_date(at));
+        }
+    }
+    for tag in &task.tags {
+        let _ = write!(line, " #{}", escape_inline(tag, "-"));
+    }
+    let _ = writeln!(out, "{}", line);
+    if let Some(description) = task.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        for desc_line in description.lines() {
+            let _ = writeln!(out, "  {}", desc_line);
+        }
+    }
+}
+
+// Render a GitHub-flavored checklist; completed tasks are grouped at the bottom
+fn render_tasks_markdown(out: &mut String, tasks: &[Task], heading_level: usize) {
+    let (completed, open): (Vec<&Task>, Vec<&Task>) = tasks.iter().partition(|t| t.completed);
+
+    if open.is_empty() && completed.is_empty() {
+        let _ = writeln!(out, "_No tasks_");
+        return;
+    }
+
+    for task in &open {
+        render_task_line(out, task);
+    }
+
+    if !completed.is_empty() {
+        if !open.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "{} Completed\n", "#".repeat(heading_level));
+        for task in &completed {
+            render_task_line(out, task);
+        }
+    }
+}
+
+fn render_project_tasks(project: &ProjectTasks, format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Markdown => {
+            let mut out = String::with_capacity(1024);
+            let _ = writeln!(out, "# Tasks: {}\n", project.project_name);
+            render_tasks_markdown(&mut out, &project.tasks, 2);
+            Ok(out)
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(project).map_err(|e| e.to_string()),
+    }
+}
+
+fn render_all_tasks(projects: &[ProjectTasks], format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Markdown => {
+            let mut out = String::with_capacity(4096);
+            let _ = writeln!(out, "# Tasks");
+            for project in projects {
+                let _ = writeln!(out, "\n## {}\n", project.project_name);
+                render_tasks_markdown(&mut out, &project.tasks, 3);
+            }
+            Ok(out)
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(projects).map_err(|e| e.to_string()),
+    }
+}
+
+fn write_output(output_path: Option<String>, rendered: &str) -> Result<(), String> {
+    if let Some(path) = output_path {
+        std::fs::write(&path, rendered)
+            .map_err(|e| format!("Failed to write export to {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn export_tasks(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    format: ExportFormat,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+
+    let project = db::get_project_by_path(&conn, &project_path)
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+
+    let tasks = db::load_task_list(&conn, project.id, &project_path)
+        .map_err(|e| e.to_string())?
+        .map(|list| list.tasks)
+        .unwrap_or_default();
+
+    let rendered = render_project_tasks(
+        &ProjectTasks { project_name: project.name, project_path, tasks },
+        format,
+    )?;
+    write_output(output_path, &rendered)?;
+    Ok(rendered)
+}
+
+#[tauri::command]
+pub async fn export_all_tasks(
+    db_pool: State<'_, Arc<DbPool>>,
+    format: ExportFormat,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+
+    let mut projects = Vec::new();
+    for (id, name, path) in db::get_projects_with_tasks(&conn).map_err(|e| e.to_string())? {
+        if let Some(list) = db::load_task_list(&conn, id, &path).map_err(|e| e.to_string())? {
+            projects.push(ProjectTasks { project_name: name, project_path: path, tasks: list.tasks });
+        }
+    }
+
+    let rendered = render_all_tasks(&projects, format)?;
+    write_output(output_path, &rendered)?;
+    Ok(rendered)
+}
+
+// Sections of a project report; all are included unless switched off
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct ReportSections {
+    pub summary: bool,
+    pub metrics: bool,
+    pub languages: bool,
+    pub insights: bool,
+    pub tasks: bool,
+    pub ideas: bool,
+}
+
+impl Default for ReportSections {
+    fn default() -> Self {
+        ReportSections { summary: true, metrics: true, languages: true, insights: true, tasks: true, ideas: true }
+    }
+}
+
+// Everything a report is rendered from; None where nothing is stored
+pub struct ProjectReport {
+    pub project_name: String,
+    pub project_path: String,
+    pub generated_at: DateTime<Utc>,
+    pub summary: Option<ProjectSummary>,
+    pub analysis: Option<AnalysisMetricsSummary>,
+    pub insights: Option<ProjectInsights>,
+    pub tasks: Vec<Task>,
+    pub ideas: Vec<Idea>,
+}
+
+const NOT_AVAILABLE: &str = "_Not available_";
+
+// Ideas still in play, in board order
+const REPORT_IDEA_STATUSES: [(&str, &str); 3] = [("new", "New"), ("shortlisted", "Shortlisted"), ("in_progress", "In progress")];
+
+fn render_summary(out: &mut String, summary: Option<&ProjectSummary>) {
+    let Some(summary) = summary else {
+        let _ = writeln!(out, "{}", NOT_AVAILABLE);
+        return;
+    };
+    let _ = writeln!(out, "{}\n", summary.summary.trim());
+    if !summary.key_features.is_empty() {
+        let _ = writeln!(out, "**Key features:** {}\n", summary.key_features.join(", "));
+    }
+    let _ = writeln!(out, "_Summary generated {}_", format_date(&summary.generated_at));
+}
+
+fn render_metrics(out: &mut String, analysis: Option<&AnalysisMetricsSummary>) {
+    let Some(analysis) = analysis else {
+        let _ = writeln!(out, "{}", NOT_AVAILABLE);
+        return;
+    };
+    let metric = |key: &str| analysis.metrics.get(key).map(|v| v.to_string()).unwrap_or_else(|| "not available".to_string());
+    let _ = writeln!(out, "- Files: {}", metric("total_files"));
+    let _ = writeln!(out, "- Lines: {}", metric("total_lines"));
+    let _ = writeln!(out, "- Size: {} KB", analysis.size_metrics.total_size_kb);
+    if !analysis.technologies.is_empty() {
+        let _ = writeln!(out, "- Technologies: {}", analysis.technologies.join(", "));
+    }
+    if let Some(at) = &analysis.generated_at {
+        let _ = writeln!(out, "\n_Analysis from {}_", format_date(at));
+    }
+}
+
+fn render_languages(out: &mut String, analysis: Option<&AnalysisMetricsSummary>) {
+    let shares = analysis
+        .map(|a| languages::breakdown(&a.size_metrics.size_by_language, false))
+        .unwrap_or_default();
+    if shares.is_empty() {
+        let _ = writeln!(out, "{}", NOT_AVAILABLE);
+        return;
+    }
+    let _ = writeln!(out, "| Language | Share |\n| --- | ---: |");
+    for share in shares {
+        let _ = writeln!(out, "| {} | {:.1}% |", share.language, share.percentage);
+    }
+}
+
+fn render_insights(out: &mut String, insights: Option<&ProjectInsights>) {
+    let Some(insights) = insights else {
+        let _ = writeln!(out, "{}", NOT_AVAILABLE);
+        return;
+    };
+    let git = &insights.git_status;
+    if git.is_git_repo {
+        let branch = git.current_branch.as_deref().unwrap_or("detached HEAD");
+        let changes = match git.uncommitted_files.len() {
+            0 => "clean".to_string(),
+            1 => "1 uncommitted file".to_string(),
+            n => format!("{} uncommitted files", n),
+        };
+        let _ = writeln!(out, "- Git: {} ({})", branch, changes);
+        if let Some(date) = &git.last_commit_date {
+            let _ = writeln!(out, "- Last commit: {}", format_date(date));
+        }
+    } else {
+        let _ = writeln!(out, "- Git: not a repository");
+    }
+    let ci = &insights.ci_info;
+    if ci.has_ci {
+        let _ = writeln!(out, "- CI: {}", ci.ci_platforms.join(", "));
+    } else {
+        let _ = writeln!(out, "- CI: not configured");
+    }
+    let testing = &insights.testing_info;
+    if testing.has_test_files || testing.has_testing_framework {
+        let mut line = format!("- Tests: {} test file{}", testing.test_file_count, if testing.test_file_count == 1 { "" } else { "s" });
+        if !testing.testing_frameworks.is_empty() {
+            let _ = write!(line, " ({})", testing.testing_frameworks.join(", "));
+        }
+        let _ = writeln!(out, "{}", line);
+    } else {
+        let _ = writeln!(out, "- Tests: none detected");
+    }
+}
+
+fn render_ideas(out: &mut String, ideas: &[Idea]) {
+    let mut any = false;
+    for (status, label) in REPORT_IDEA_STATUSES {
+        let column: Vec<&Idea> = ideas.iter().filter(|idea| idea.status == status).collect();
+        if column.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "{}### {}\n", if any { "\n" } else { "" }, label);
+        for idea in column {
+            let _ = writeln!(out, "- {}", idea.text.trim());
+        }
+        any = true;
+    }
+    if !any {
+        let _ = writeln!(out, "_No ideas_");
+    }
+}
+
+pub fn render_project_report(report: &ProjectReport, sections: ReportSections) -> String {
+    let mut out = String::with_capacity(4096);
+    let _ = writeln!(out, "# Project report: {}\n", report.project_name);
+    let _ = writeln!(out, "_Generated {} for `{}`_", report.generated_at.format("%Y-%m-%d %H:%M UTC"), report.project_path);
+
+    let mut section = |title: &str, render: &dyn Fn(&mut String)| {
+        let _ = writeln!(out, "\n## {}\n", title);
+        render(&mut out);
+    };
+    if sections.summary {
+        section("Summary", &|out| render_summary(out, report.summary.as_ref()));
+    }
+    if sections.metrics {
+        section("Key metrics", &|out| render_metrics(out, report.analysis.as_ref()));
+    }
+    if sections.languages {
+        section("Languages", &|out| render_languages(out, report.analysis.as_ref()));
+    }
+    if sections.insights {
+        section("Insights", &|out| render_insights(out, report.insights.as_ref()));
+    }
+    if sections.tasks {
+        let open: Vec<Task> = report.tasks.iter().filter(|t| !t.completed).cloned().collect();
+        section("Open tasks", &|out| render_tasks_markdown(out, &open, 3));
+    }
+    if sections.ideas {
+        section("Ideas", &|out| render_ideas(out, &report.ideas));
+    }
+    out
+}
+
+// Insights for the report; a missing folder or a failure leaves them out
+fn report_insights(conn: &rusqlite::Connection, project_path: &str) -> Option<ProjectInsights> {
+    let aspects = Some(vec!["git".to_string(), "ci".to_string(), "testing".to_string()]);
+    insights::cached_insights(conn, Path::new(project_path), &aspects, None, false).ok()
+}
+
+fn load_report(db_pool: &DbPool, project_path: &str, sections: ReportSections) -> Result<ProjectReport, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let project = db::get_project_by_path(&conn, project_path)
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+    let wants_analysis = sections.metrics || sections.languages;
+    Ok(ProjectReport {
+        generated_at: Utc::now(),
+        summary: if sections.summary { db::load_summary(&conn, project.id, project_path).ok().flatten() } else { None },
+        analysis: if wants_analysis { db::get_cached_analysis_summary(&conn, project.id).ok().flatten() } else { None },
+        insights: if sections.insights && Path::new(project_path).is_dir() { report_insights(&conn, project_path) } else { None },
+        tasks: db::load_task_list(&conn, project.id, project_path)
+            .map_err(|e| e.to_string())?
+            .map(|list| list.tasks)
+            .unwrap_or_default(),
+        ideas: db::get_ideas(&conn, project.id).map_err(|e| e.to_string())?,
+        project_name: project.name,
+        project_path: project_path.to_string(),
+    })
+}
+
+// Markdown review of one project: summary, metrics, languages, insights,
+// open tasks and ideas. Data that isn't stored renders as "Not available"
+#[tauri::command]
+pub async fn export_project_report(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    output_path: Option<String>,
+    sections: Option<ReportSections>,
+) -> Result<String, String> {
+    let sections = sections.unwrap_or_default();
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let report = load_report(&pool, &project_path, sections)?;
+        let rendered = render_project_report(&report, sections);
+        write_output(output_path, &rendered)?;
+        Ok(rendered)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(text: &str, completed_at: Option<&str>) -> Task {
+        Task {
+            id: text.to_string(),
+            text: text.to_string(),
+            completed: completed_at.is_some(),
+            created_at: "2026-01-01T00:00:00+00:00".to_string(),
+            completed_at: completed_at.map(|s| s.to_string()),
+            description: None,
+            tags: Vec::new(),
+            due_date: None,
+            source: None,
+            source_ref: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown_groups_completed_tasks_at_bottom() {
+        let project = ProjectTasks {
+            project_name: "demo".to_string(),
+            project_path: "/code/demo".to_string(),
+            tasks: vec![
+                task("Ship it", Some("2026-02-03T10:00:00+00:00")),
+                task("Write docs", None),
+            ],
+        };
+        let md = render_project_tasks(&project, ExportFormat::Markdown).unwrap();
+        assert_eq!(
+            md,
+            "# Tasks: demo\n\n- [ ] Write docs\n\n## Completed\n\n- [x] Ship it (completed 2026-02-03)\n"
+        );
+    }
+
+    #[test]
+    fn test_all_tasks_markdown_groups_by_project() {
+        let projects = vec![
+            ProjectTasks { project_name: "alpha".to_string(), project_path: "/a".to_string(), tasks: vec![task("One", None)] },
+            ProjectTasks { project_name: "beta".to_string(), project_path: "/b".to_string(), tasks: vec![task("Two", None)] },
+        ];
+        let md = render_all_tasks(&projects, ExportFormat::Markdown).unwrap();
+        assert!(md.contains("## alpha\n\n- [ ] One"));
+        assert!(md.contains("## beta\n\n- [ ] Two"));
+    }
+
+    #[test]
+    fn test_markdown_includes_due_date_tags_and_description() {
+        let mut t = task("Write docs", None);
+        t.due_date = Some("2026-03-01".to_string());
+        t.tags = vec!["docs".to_string()];
+        t.description = Some("Cover the CLI".to_string());
+        let mut out = String::new();
+        render_task_line(&mut out, &t);
+        assert_eq!(out, "- [ ] Write docs (due: 2026-03-01) #docs\n  Cover the CLI\n");
+    }
+
+    #[test]
+    fn test_markdown_escapes_task_text_and_tags() {
+        let mut t = task("# Fix [link](x) and\n*bold* text", None);
+        t.tags = vec!["good first\nissue".to_string()];
+        let mut out = String::new();
+        render_task_line(&mut out, &t);
+        assert_eq!(out, "- [ ] \\# Fix \\[link\\](x) and \\*bold\\* text #good-first-issue\n");
+    }
+
+    fn idea(text: &str, status: &str) -> Idea {
+        Idea {
+            id: 0,
+            text: text.to_string(),
+            status: status.to_string(),
+            position: 0,
+            task_id: None,
+            created_at: Utc::now(),
+            updated_at: None,
+            focus_area: None,
+        }
+    }
+
+    fn seeded_report() -> ProjectReport {
+        let mut insights = ProjectInsights::default();
+        insights.git_status.is_git_repo = true;
+        insights.git_status.current_branch = Some("main".to_string());
+        insights.git_status.uncommitted_files = vec!["src/lib.rs".to_string()];
+        insights.git_status.last_commit_date = Some("2026-03-01T09:30:00+00:00".to_string());
+        insights.ci_info.has_ci = true;
+        insights.ci_info.ci_platforms = vec!["GitHub Actions".to_string()];
+        insights.testing_info.has_test_files = true;
+        insights.testing_info.test_file_count = 12;
+        insights.testing_info.testing_frameworks = vec!["vitest".to_string()];
+
+        let mut analysis = AnalysisMetricsSummary {
+            metrics: [("total_files".to_string(), 120), ("total_lines".to_string(), 8400)].into_iter().collect(),
+            technologies: vec!["React".to_string(), "Tauri".to_string()],
+            size_metrics: Default::default(),
+            generated_at: Some("2026-03-02T08:00:00+00:00".to_string()),
+            from_cache: Some(true),
+            is_lazy_scan: Some(false),
+        };
+        analysis.size_metrics.total_size_kb = 512;
+        analysis.size_metrics.size_by_language = [("Rust".to_string(), 300), ("TypeScript".to_string(), 100)].into_iter().collect();
+
+        ProjectReport {
+            project_name: "demo".to_string(),
+            project_path: "/code/demo".to_string(),
+            generated_at: DateTime::parse_from_rfc3339("2026-03-03T12:00:00+00:00").unwrap().with_timezone(&Utc),
+            summary: Some(ProjectSummary {
+                project_path: "/code/demo".to_string(),
+                summary: "A desktop app for browsing repositories.".to_string(),
+                generated_at: "2026-02-28T10:00:00+00:00".to_string(),
+                technologies: Vec::new(),
+                key_features: vec!["Search".to_string(), "Ideas".to_string()],
+                fingerprint: None,
+            }),
+            analysis: Some(analysis),
+            insights: Some(insights),
+            tasks: vec![task("Write docs", None), task("Ship it", Some("2026-02-03T10:00:00+00:00"))],
+            ideas: vec![idea("Cache builds", "new"), idea("Add CSP", "rejected"), idea("Dark mode", "in_progress")],
+        }
+    }
+
+    #[test]
+    fn test_project_report_snapshot() {
+        let md = render_project_report(&seeded_report(), ReportSections::default());
+        assert_eq!(
+            md,
+            "# Project report: demo
+
+_Generated 2026-03-03 12:00 UTC for `/code/demo`_
+
+## Summary
+
+A desktop app for browsing repositories.
+
+**Key features:** Search, Ideas
+
+_Summary generated 2026-02-28_
+
+## Key metrics
+
+- Files: 120
+- Lines: 8400
+- Size: 512 KB
+- Technologies: React, Tauri
+
+_Analysis from 2026-03-02_
+
+## Languages
+
+| Language | Share |
+| --- | ---: |
+| Rust | 75.0% |
+| TypeScript | 25.0% |
+
+## Insights
+
+- Git: main (1 uncommitted file)
+- Last commit: 2026-03-01
+- CI: GitHub Actions
+- Tests: 12 test files (vitest)
+
+## Open tasks
+
+- [ ] Write docs
+
+## Ideas
+
+### New
+
+- Cache builds
+
+### In progress
+
+- Dark mode
+"
+        );
+    }
+
+    #[test]
+    fn test_project_report_renders_missing_data_and_skips_sections() {
+        let report = ProjectReport {
+            summary: None,
+            analysis: None,
+            insights: None,
+            tasks: Vec::new(),
+            ideas: Vec::new(),
+            ..seeded_report()
+        };
+        let sections = ReportSections { ideas: false, ..Default::default() };
+        let md = render_project_report(&report, sections);
+        assert_eq!(md.matches(NOT_AVAILABLE).count(), 4);
+        assert!(md.contains("## Open tasks\n\n_No tasks_\n"));
+        assert!(!md.contains("## Ideas"));
+    }
+}
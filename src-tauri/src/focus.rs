@@ -0,0 +1,221 @@
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::ai::{self, TechnologyProfile};
+use crate::db::{self, DbPool, Idea};
+
+// Suggestable focus areas and the word prefixes that map a free-text focus
+// or an idea's [Category: ...] tag onto them. Earlier entries win, so
+// "Developer Experience" lands on DX before "experience" could matter
+const FOCUS_AREAS: &[(&str, &[&str])] = &[
+    ("testing", &["test", "coverage", "qa"]),
+    ("documentation", &["doc", "docs", "document", "readme"]),
+    ("performance", &["perf", "speed", "optimi", "cach"]),
+    ("security", &["secur", "vulnerab", "auth", "authenticat", "authoriz"]),
+    ("database", &["database", "sql", "schema", "migration", "persist"]),
+    ("api", &["api", "apis", "endpoint", "integration"]),
+    ("ci/cd", &["ci", "cd", "deploy", "pipeline", "devops"]),
+    ("developer experience", &["developer", "dx", "tooling", "lint"]),
+    ("ui/ux", &["ui", "ux", "user", "interface", "accessib", "design"]),
+    ("technical debt", &["debt", "refactor", "cleanup"]),
+    ("monitoring", &["monitor", "observab", "logging", "metric", "telemetry"]),
+];
+
+// Below this many ideas in an area the status mix says little
+const MIN_IDEAS_FOR_RATIO: usize = 3;
+// Open ideas in an area past which more of them would only pile up
+const OPEN_BACKLOG: usize = 5;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FocusSuggestion {
+    pub focus_area: String,
+    pub score: i32,
+    // Why it ranks where it does, most important first
+    pub reasons: Vec<String>,
+}
+
+// Keys too short to be prefixes: "ci" would take "circular", "doc"
+// "docker" and "auth" "author", so these only match whole words
+const WHOLE_WORD_KEYS: &[&str] = &["ci", "cd", "ui", "ux", "qa", "dx", "api", "doc", "docs", "auth"];
+
+// Focus area for free text, matching word prefixes case-insensitively
+fn area_for(text: &str) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    let matches = |word: &str, key: &str| if WHOLE_WORD_KEYS.contains(&key) { word == key } else { word.starts_with(key) };
+    FOCUS_AREAS
+        .iter()
+        .find(|(_, keys)| words.iter().any(|&w| keys.iter().any(|&k| matches(w, k))))
+        .map(|(area, _)| *area)
+}
+
+fn idea_category(text: &str) -> Option<&str> {
+    let start = text.find("[Category:")? + "[Category:".len();
+    let end = text[start..].find(']')? + start;
+    Some(text[start..end].trim())
+}
+
+// Area of an idea: the focus it was generated for, else its category tag
+fn idea_area(idea: &Idea) -> Option<&'static str> {
+    idea.focus_area.as_deref().and_then(area_for).or_else(|| idea_category(&idea.text).and_then(area_for))
+}
+
+// Points and a reason from what the analysis detected
+fn profile_signal(area: &str, profile: &TechnologyProfile, has_ideas: bool) -> Option<(i32, String)> {
+    let present = |detected: bool, what: &str| {
+        (detected && !has_ideas).then(|| (2, format!("{} detected but no {} ideas yet", what, area)))
+    };
+    match area {
+        "testing" if !profile.has_testing => Some((2, "No tests detected".to_string())),
+        "ci/cd" if !profile.has_cicd => Some((2, "No CI/CD configuration detected".to_string())),
+        "database" => present(profile.has_database, "Database code"),
+        "api" => present(profile.has_api, "API code"),
+        "security" => present(profile.has_auth, "Authentication code"),
+        "ui/ux" => present(profile.has_ui, "UI code"),
+        _ => None,
+    }
+}
+
+// Ranks every focus area from past generations (focus areas, newest first),
+// the ideas on the board and, when there's a cached analysis, its profile
+pub fn rank(generations: &[Option<String>], ideas: &[Idea], profile: Option<&TechnologyProfile>) -> Vec<FocusSuggestion> {
+    let used: Vec<Option<&str>> = generations.iter().map(|f| f.as_deref().and_then(area_for)).collect();
+    let mut suggestions: Vec<FocusSuggestion> = FOCUS_AREAS
+        .iter()
+        .map(|(area, _)| {
+            let mut weighted: Vec<(i32, String)> = Vec::new();
+            let area_ideas: Vec<&Idea> = ideas.iter().filter(|idea| idea_area(idea) == Some(area)).collect();
+
+            if !used.contains(&Some(area)) {
+                weighted.push((3, "Never used as a focus for this project".to_string()));
+            } else if used.first() == Some(&Some(area)) {
+                weighted.push((-1, "Focus of the most recent generation".to_string()));
+            }
+            if let Some(signal) = profile.and_then(|p| profile_signal(area, p, !area_ideas.is_empty())) {
+                weighted.push(signal);
+            }
+
+            let count = |status: &str| area_ideas.iter().filter(|idea| idea.status == status).count();
+            let rejected = count("rejected");
+            let done = count("done");
+            let open = area_ideas.len() - rejected - done;
+            if area_ideas.len() >= MIN_IDEAS_FOR_RATIO {
+                if rejected * 2 >= area_ideas.len() {
+                    weighted.push((-2, format!("{} of {} {} ideas were rejected", rejected, area_ideas.len(), area)));
+                } else if done * 2 >= area_ideas.len() {
+                    weighted.push((1, format!("{} of {} {} ideas got done", done, area_ideas.len(), area)));
+                }
+            }
+            if open >= OPEN_BACKLOG {
+                weighted.push((-1, format!("{} {} ideas are still open", open, area)));
+            }
+
+            weighted.sort_by_key(|(points, _)| Reverse(points.abs()));
+            FocusSuggestion {
+                focus_area: area.to_string(),
+                score: weighted.iter().map(|(points, _)| points).sum(),
+                reasons: weighted.into_iter().map(|(_, reason)| reason).collect(),
+            }
+        })
+        .collect();
+    // Stable, so ties keep the FOCUS_AREAS order
+    suggestions.sort_by_key(|s| Reverse(s.score));
+    suggestions
+}
+
+#[tauri::command]
+pub async fn suggest_next_focus(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<Vec<FocusSuggestion>, String> {
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let Some(project) = db::get_project_by_path(&conn, &project_path).map_err(|e| e.to_string())? else {
+            return Ok(rank(&[], &[], None));
+        };
+        let generations = db::get_generation_focus_areas(&conn, project.id).map_err(|e| e.to_string())?;
+        let ideas = db::get_ideas(&conn, project.id).map_err(|e| e.to_string())?;
+        let profile = db::get_cached_analysis(&conn, project.id)
+            .ok()
+            .flatten()
+            .map(|analysis| ai::technology_profile(&analysis));
+        Ok(rank(&generations, &ideas, profile.as_ref()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn idea(text: &str, status: &str, focus_area: Option<&str>) -> Idea {
+        Idea {
+            id: 0,
+            text: text.to_string(),
+            status: status.to_string(),
+            position: 0,
+            task_id: None,
+            created_at: Utc::now(),
+            updated_at: None,
+            focus_area: focus_area.map(str::to_string),
+        }
+    }
+
+    fn find<'a>(suggestions: &'a [FocusSuggestion], area: &str) -> &'a FocusSuggestion {
+        suggestions.iter().find(|s| s.focus_area == area).unwrap()
+    }
+
+    #[test]
+    fn test_area_for_matches_focus_text_and_categories() {
+        assert_eq!(area_for("Testing Gaps"), Some("testing"));
+        assert_eq!(area_for("Developer Experience"), Some("developer experience"));
+        assert_eq!(area_for("User Experience"), Some("ui/ux"));
+        assert_eq!(area_for("DB schema migrations"), Some("database"));
+        assert_eq!(area_for("Missing Features"), None);
+        assert_eq!(area_for("Docker setup"), None);
+        assert_eq!(area_for("Improve the docs"), Some("documentation"));
+        assert_eq!(area_for("Author attribution"), None);
+        assert_eq!(area_for("OAuth authentication"), Some("security"));
+        assert_eq!(area_for("Circular imports"), None);
+        assert_eq!(area_for("CI pipeline"), Some("ci/cd"));
+        assert_eq!(area_for("Optimizing startup"), Some("performance"));
+        assert_eq!(area_for("Accessibility"), Some("ui/ux"));
+        assert_eq!(idea_category("[Category: Performance] [Affected: `a.rs`] Cache it"), Some("Performance"));
+    }
+
+    #[test]
+    fn test_unused_areas_and_profile_gaps_rank_first() {
+        let profile = TechnologyProfile { has_database: true, has_testing: true, has_cicd: true, ..Default::default() };
+        let generations = vec![Some("testing".to_string()), Some("Docs".to_string())];
+        let suggestions = rank(&generations, &[], Some(&profile));
+
+        assert_eq!(suggestions[0].focus_area, "database");
+        assert_eq!(suggestions[0].score, 5);
+        assert_eq!(suggestions[0].reasons[0], "Never used as a focus for this project");
+        assert_eq!(find(&suggestions, "testing").score, -1);
+        assert_eq!(find(&suggestions, "documentation").score, 0);
+    }
+
+    #[test]
+    fn test_rejected_and_open_ideas_lower_an_area() {
+        let ideas = vec![
+            idea("[Category: Security] Add CSP", "rejected", None),
+            idea("[Category: Security] Rotate keys", "rejected", None),
+            idea("Pin dependencies", "done", Some("security")),
+            idea("[Category: Performance] Cache builds", "done", None),
+            idea("[Category: Performance] Lazy load", "done", None),
+            idea("[Category: Performance] Index table", "new", None),
+        ];
+        let generations = vec![Some("security".to_string()), Some("performance".to_string())];
+        let suggestions = rank(&generations, &ideas, None);
+
+        let security = find(&suggestions, "security");
+        assert_eq!((security.score, security.reasons[0].as_str()), (-3, "2 of 3 security ideas were rejected"));
+        assert_eq!(find(&suggestions, "performance").score, 1);
+    }
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{self, DbPool};
+use crate::insights::run_git_line;
+use crate::languages;
+
+// Technologies kept in a fingerprint; the rest rarely matter for a summary
+const FINGERPRINT_TECHNOLOGIES: usize = 8;
+// At or above this the summary is reported as stale
+const STALE_SCORE: f64 = 0.25;
+
+// What the code looked like when a summary was generated
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SummaryFingerprint {
+    pub file_count: i64,
+    pub total_lines: i64,
+    pub technologies: Vec<String>,
+    pub primary_language: Option<String>,
+    // None outside git or on an unborn branch
+    pub head_commit: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FreshnessState {
+    Fresh,
+    Stale,
+    // No summary, no fingerprint on it, or no cached analysis to compare with
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SummaryFreshness {
+    pub state: FreshnessState,
+    // 0 (unchanged) to 1 (rewritten)
+    pub score: f64,
+    // Human-readable reasons, biggest first
+    pub drivers: Vec<String>,
+    pub summary_generated_at: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Drift {
+    pub score: f64,
+    pub drivers: Vec<String>,
+}
+
+impl Drift {
+    // One line for the summary prompt, or None when nothing changed
+    pub fn describe(&self) -> Option<String> {
+        (!self.drivers.is_empty()).then(|| self.drivers.join(", "))
+    }
+}
+
+pub fn fingerprint(
+    metrics: &HashMap<String, i32>,
+    technologies: &[String],
+    size_by_language: &HashMap<String, u64>,
+    head_commit: Option<String>,
+) -> SummaryFingerprint {
+    SummaryFingerprint {
+        file_count: *metrics.get("total_files").unwrap_or(&0) as i64,
+        total_lines: *metrics.get("total_lines").unwrap_or(&0) as i64,
+        technologies: technologies.iter().take(FINGERPRINT_TECHNOLOGIES).cloned().collect(),
+        primary_language: languages::primary_language(size_by_language).map(|share| share.language),
+        head_commit,
+    }
+}
+
+pub fn head_commit(project_path: &str) -> Option<String> {
+    run_git_line(Path::new(project_path), &["rev-parse", "HEAD"])
+}
+
+fn commits_since(project_path: &str, commit: &str) -> Option<u64> {
+    run_git_line(Path::new(project_path), &["rev-list", "--count", &format!("{}..HEAD", commit)])?.parse().ok()
+}
+
+fn relative_change(before: i64, after: i64) -> f64 {
+    ((after - before).abs() as f64 / before.max(1) as f64).min(1.0)
+}
+
+fn count_change(before: i64, after: i64, noun: &str) -> Option<String> {
+    let delta = after - before;
+    let plural = if delta.abs() == 1 { "" } else { "s" };
+    match delta {
+        0 => None,
+        d if d > 0 => Some(format!("{} {}{} added", d, noun, plural)),
+        d => Some(format!("{} {}{} removed", -d, noun, plural)),
+    }
+}
+
+// `commits` is how many commits HEAD moved since the summary, when known
+pub fn drift(before: &SummaryFingerprint, after: &SummaryFingerprint, commits: Option<u64>) -> Drift {
+    let mut weighted: Vec<(f64, String)> = Vec::new();
+
+    if let Some(driver) = count_change(before.file_count, after.file_count, "file") {
+        weighted.push((0.3 * relative_change(before.file_count, after.file_count), driver));
+    }
+    if let Some(driver) = count_change(before.total_lines, after.total_lines, "line") {
+        weighted.push((0.3 * relative_change(before.total_lines, after.total_lines), driver));
+    }
+    if before.primary_language != after.primary_language {
+        let name = |l: &Option<String>| l.clone().unwrap_or_else(|| "none".to_string());
+        weighted.push((
+            0.3,
+            format!("primary language changed from {} to {}", name(&before.primary_language), name(&after.primary_language)),
+        ));
+    }
+    let added: Vec<&String> = after.technologies.iter().filter(|t| !before.technologies.contains(t)).collect();
+    let removed: Vec<&String> = before.technologies.iter().filter(|t| !after.technologies.contains(t)).collect();
+    if !added.is_empty() {
+        let names: Vec<&str> = added.iter().map(|t| t.as_str()).collect();
+        weighted.push(((0.1 * added.len() as f64).min(0.3), format!("new technologies: {}", names.join(", "))));
+    }
+    if !removed.is_empty() {
+        let names: Vec<&str> = removed.iter().map(|t| t.as_str()).collect();
+        weighted.push(((0.1 * removed.len() as f64).min(0.3), format!("technologies no longer detected: {}", names.join(", "))));
+    }
+    if before.head_commit.is_some() && before.head_commit != after.head_commit {
+        let (weight, driver) = match commits {
+            Some(n) => ((n as f64 / 100.0).min(0.2), format!("{} commit{} since the summary", n, if n == 1 { "" } else { "s" })),
+            None => (0.05, "HEAD moved since the summary".to_string()),
+        };
+        weighted.push((weight, driver));
+    }
+
+    weighted.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Drift {
+        score: weighted.iter().map(|(weight, _)| weight).sum::<f64>().min(1.0),
+        drivers: weighted.into_iter().map(|(_, driver)| driver).collect(),
+    }
+}
+
+// Drift of a stored fingerprint against `current`, counting commits in the
+// repository; blocks on git
+pub fn drift_since(project_path: &str, before: &SummaryFingerprint, current: &SummaryFingerprint) -> Drift {
+    let commits = match (&before.head_commit, &current.head_commit) {
+        (Some(old), Some(new)) if old != new => commits_since(project_path, old),
+        _ => None,
+    };
+    drift(before, current, commits)
+}
+
+fn unknown(summary_generated_at: Option<String>, reason: &str) -> SummaryFreshness {
+    SummaryFreshness {
+        state: FreshnessState::Unknown,
+        score: 0.0,
+        drivers: vec![reason.to_string()],
+        summary_generated_at,
+    }
+}
+
+fn check(db_pool: &DbPool, project_path: &str) -> Result<SummaryFreshness, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let Some(project) = db::get_project_by_path(&conn, project_path).map_err(|e| e.to_string())? else {
+        return Ok(unknown(None, "No summary has been generated"));
+    };
+    let Some(summary) = db::load_summary(&conn, project.id, project_path).map_err(|e| e.to_string())? else {
+        return Ok(unknown(None, "No summary has been generated"));
+    };
+    let Some(before) = summary.fingerprint else {
+        return Ok(unknown(Some(summary.generated_at), "Summary predates freshness tracking"));
+    };
+    let Some(analysis) = db::get_cached_analysis_summary(&conn, project.id).map_err(|e| e.to_string())? else {
+        return Ok(unknown(Some(summary.generated_at), "No current analysis to compare with"));
+    };
+    drop(conn);
+
+    let current = fingerprint(
+        &analysis.metrics,
+        &analysis.technologies,
+        &analysis.size_metrics.size_by_language,
+        head_commit(project_path),
+    );
+    let drift = drift_since(project_path, &before, &current);
+    Ok(SummaryFreshness {
+        state: if drift.score >= STALE_SCORE { FreshnessState::Stale } else { FreshnessState::Fresh },
+        score: drift.score,
+        drivers: drift.drivers,
+        summary_generated_at: Some(summary.generated_at),
+    })
+}
+
+#[tauri::command]
+pub async fn check_summary_freshness(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<SummaryFreshness, String> {
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || check(&pool, &project_path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print(files: i64, lines: i64, technologies: &[&str], language: &str) -> SummaryFingerprint {
+        SummaryFingerprint {
+            file_count: files,
+            total_lines: lines,
+            technologies: technologies.iter().map(|t| t.to_string()).collect(),
+            primary_language: Some(language.to_string()),
+            head_commit: Some("abc".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_unchanged_fingerprint_has_no_drift() {
+        let before = print(100, 5000, &["React"], "TypeScript");
+        assert_eq!(drift(&before, &before.clone(), None), Drift::default());
+    }
+
+    #[test]
+    fn test_drift_lists_drivers_biggest_first() {
+        let before = print(100, 5000, &["React"], "TypeScript");
+        let mut after = print(134, 5100, &["React", "Tauri"], "Rust");
+        after.head_commit = Some("def".to_string());
+        let drift = drift(&before, &after, Some(12));
+
+        assert_eq!(drift.drivers[0], "primary language changed from TypeScript to Rust");
+        assert!(drift.drivers.contains(&"34 files added".to_string()));
+        assert!(drift.drivers.contains(&"new technologies: Tauri".to_string()));
+        assert!(drift.drivers.contains(&"12 commits since the summary".to_string()));
+        assert!(drift.score >= STALE_SCORE && drift.score <= 1.0);
+    }
+
+    #[test]
+    fn test_small_edits_stay_fresh() {
+        let before = print(100, 5000, &["React"], "TypeScript");
+        let after = print(101, 5040, &["React"], "TypeScript");
+        let drift = drift(&before, &after, None);
+        assert_eq!(drift.drivers, vec!["1 file added", "40 lines added"]);
+        assert!(drift.score < STALE_SCORE);
+    }
+}
@@ -1,16 +1,88 @@
 use std::path::{Path, PathBuf};
 use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::overrides::{Override, OverrideBuilder};
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::Read;
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
-use std::collections::HashMap;
- 
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use xxhash_rust::xxh3::Xxh3;
+use serde::{Deserialize, Serialize};
 
-// Cache for walker builders to avoid recreating them
-static WALKER_CACHE: Lazy<Mutex<HashMap<PathBuf, Override>>> = 
-    Lazy::new(|| Mutex::new(HashMap::with_capacity(10)));
+pub mod watch;
+
+// Safety cap for whole-project walks; real trees never get this deep, but
+// un-ignored node_modules or symlink loops can
+pub const MAX_WALK_DEPTH: usize = 20;
+
+// Symlinks a followed path may pass through before the walk stops there
+pub const MAX_SYMLINK_DEPTH: usize = 8;
+
+// Per-project exclusions in gitignore syntax, read from the walk root
+pub const REPOMUSE_IGNORE_FILE: &str = ".repomuseignore";
+
+// User patterns from settings, applied to every walk on top of the
+// built-in exclusions; see `set_global_ignore_patterns`
+static GLOBAL_IGNORE_PATTERNS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+// Hidden paths worth analyzing (CI config, tool rc files), in gitignore
+// syntax relative to the walk root; replaced by the "hidden_allowlist" setting
+pub const DEFAULT_HIDDEN_ALLOWLIST: &[&str] = &[
+    ".github/", ".gitlab/", ".circleci/", ".storybook/",
+    "/.{eslintrc,prettierrc,babelrc,stylelintrc,lintstagedrc,commitlintrc,nycrc,mocharc,swcrc}",
+    "/.{eslintrc,prettierrc,babelrc,stylelintrc,lintstagedrc,commitlintrc,nycrc,mocharc,swcrc}.{json,js,cjs,mjs,yml,yaml}",
+    "/.editorconfig", "/.nvmrc", "/.*.yml", "/.*.yaml",
+    ".env.example",
+];
+
+// Credential stores that stay out of allowlisted walks whatever the
+// allowlist says; the security scan still sees them via `walker_with_hidden`
+const CREDENTIAL_FILES: &[&str] = &[".npmrc", ".pypirc", ".netrc"];
+
+static HIDDEN_ALLOWLIST: Lazy<RwLock<Vec<String>>> =
+    Lazy::new(|| RwLock::new(DEFAULT_HIDDEN_ALLOWLIST.iter().map(|p| p.to_string()).collect()));
+
+// Roots whose overrides are kept; the least recently walked goes first
+const WALKER_CACHE_CAPACITY: usize = 100;
+
+// Cache for walker builders to avoid recreating them, keyed by root and
+// tagged with `overrides_key` so ignore edits invalidate the entry
+static WALKER_CACHE: Lazy<Mutex<WalkerCache>> = Lazy::new(|| Mutex::new(WalkerCache::default()));
+
+#[derive(Default)]
+struct WalkerCache {
+    entries: HashMap<PathBuf, CachedOverrides>,
+    // Bumped on every hit or insert; entries remember when they were last used
+    clock: u64,
+}
+
+struct CachedOverrides {
+    overrides: Override,
+    key: u64,
+    last_used: u64,
+}
+
+impl WalkerCache {
+    fn get(&mut self, root: &Path, key: u64) -> Option<Override> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(root).filter(|e| e.key == key)?;
+        entry.last_used = self.clock;
+        Some(entry.overrides.clone())
+    }
+
+    fn insert(&mut self, root: PathBuf, overrides: Override, key: u64, capacity: usize) {
+        self.clock += 1;
+        if !self.entries.contains_key(&root) && self.entries.len() >= capacity {
+            let oldest = self.entries.iter().min_by_key(|(_, e)| e.last_used).map(|(root, _)| root.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(root, CachedOverrides { overrides, key, last_used: self.clock });
+    }
+}
 
 // Determine language from file extension
 pub fn get_language_from_extension(path: &str) -> String {
@@ -36,183 +108,1611 @@ pub fn get_language_from_extension(path: &str) -> String {
         Some("yml") | Some("yaml") => "YAML".to_string(),
         Some("toml") => "TOML".to_string(),
         Some("md") => "Markdown".to_string(),
+        Some("scala") | Some("sc") => "Scala".to_string(),
+        Some("ex") | Some("exs") => "Elixir".to_string(),
+        Some("clj") | Some("cljs") => "Clojure".to_string(),
+        Some("dart") => "Dart".to_string(),
+        Some("zig") => "Zig".to_string(),
+        Some("lua") => "Lua".to_string(),
+        Some("r") | Some("R") => "R".to_string(),
+        Some("jl") => "Julia".to_string(),
+        Some("nim") => "Nim".to_string(),
+        Some("v") => "V".to_string(),
+        Some("sh") | Some("bash") => "Shell".to_string(),
+        Some("fish") => "Fish".to_string(),
         _ => "Unknown".to_string(),
     }
 }
 
-// Filter files we should analyze
-pub fn should_analyze_file(path: &str) -> bool {
-    let ignore_extensions = vec![
-        "png", "jpg", "jpeg", "gif", "svg", "ico", "woff", "woff2", "ttf", "eot", "pdf", "zip", "tar", "gz",
-    ];
-    let ignore_dirs = vec![
-        "node_modules", "target", "build", "dist", ".git", ".svn", "vendor", "__pycache__",
+// What a file is for, independent of its language
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FileRole {
+    #[default]
+    Source,
+    Config,
+    Manifest,
+    Lockfile,
+    Ci,
+    Docs,
+    Test,
+}
+
+impl FileRole {
+    pub const ALL: [FileRole; 7] = [
+        FileRole::Source, FileRole::Config, FileRole::Manifest, FileRole::Lockfile,
+        FileRole::Ci, FileRole::Docs, FileRole::Test,
     ];
 
-    for ignore_dir in ignore_dirs {
-        if path.contains(&format!("/{}/", ignore_dir)) || path.contains(&format!("\\{}\\", ignore_dir)) {
-            return false;
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileRole::Source => "source",
+            FileRole::Config => "config",
+            FileRole::Manifest => "manifest",
+            FileRole::Lockfile => "lockfile",
+            FileRole::Ci => "ci",
+            FileRole::Docs => "docs",
+            FileRole::Test => "test",
+        }
+    }
+}
+
+// Files recognised by exact name (case-insensitive), ahead of their
+// extension: `tsconfig.json` is TypeScript config, not generic JSON
+const FILENAME_TABLE: &[(&str, &str, FileRole)] = &[
+    ("Dockerfile", "Dockerfile", FileRole::Config),
+    ("Containerfile", "Dockerfile", FileRole::Config),
+    ("Makefile", "Makefile", FileRole::Config),
+    ("GNUmakefile", "Makefile", FileRole::Config),
+    ("Justfile", "Justfile", FileRole::Config),
+    ("CMakeLists.txt", "CMake", FileRole::Manifest),
+    ("Vagrantfile", "Ruby", FileRole::Config),
+    ("Procfile", "Procfile", FileRole::Config),
+    ("Jenkinsfile", "Groovy", FileRole::Ci),
+    ("Brewfile", "Ruby", FileRole::Manifest),
+    ("Gemfile", "Ruby", FileRole::Manifest),
+    ("Rakefile", "Ruby", FileRole::Config),
+    (".gitlab-ci.yml", "YAML", FileRole::Ci),
+    (".travis.yml", "YAML", FileRole::Ci),
+    ("azure-pipelines.yml", "YAML", FileRole::Ci),
+    ("bitbucket-pipelines.yml", "YAML", FileRole::Ci),
+    ("tsconfig.json", "TypeScript config", FileRole::Config),
+    ("jsconfig.json", "JavaScript config", FileRole::Config),
+    ("package.json", "JSON", FileRole::Manifest),
+    ("Cargo.toml", "TOML", FileRole::Manifest),
+    ("pyproject.toml", "TOML", FileRole::Manifest),
+    ("requirements.txt", "Python", FileRole::Manifest),
+    ("go.mod", "Go module", FileRole::Manifest),
+    ("composer.json", "JSON", FileRole::Manifest),
+    ("pom.xml", "XML", FileRole::Manifest),
+    ("build.gradle", "Groovy", FileRole::Manifest),
+    ("build.gradle.kts", "Kotlin", FileRole::Manifest),
+    ("Cargo.lock", "TOML", FileRole::Lockfile),
+    ("package-lock.json", "JSON", FileRole::Lockfile),
+    ("yarn.lock", "YAML", FileRole::Lockfile),
+    ("pnpm-lock.yaml", "YAML", FileRole::Lockfile),
+    ("bun.lock", "JSON", FileRole::Lockfile),
+    ("poetry.lock", "TOML", FileRole::Lockfile),
+    ("uv.lock", "TOML", FileRole::Lockfile),
+    ("Pipfile.lock", "JSON", FileRole::Lockfile),
+    ("Gemfile.lock", "Ruby", FileRole::Lockfile),
+    ("composer.lock", "JSON", FileRole::Lockfile),
+    ("go.sum", "Go module", FileRole::Lockfile),
+    ("LICENSE", "Unknown", FileRole::Docs),
+    ("COPYING", "Unknown", FileRole::Docs),
+    (".editorconfig", "EditorConfig", FileRole::Config),
+    (".gitignore", "Unknown", FileRole::Config),
+    (".dockerignore", "Unknown", FileRole::Config),
+];
+
+// Extensions whose files are configuration or prose rather than code
+const CONFIG_EXTENSIONS: &[&str] = &["json", "yml", "yaml", "toml", "xml", "ini", "cfg", "conf", "env", "properties"];
+const DOCS_EXTENSIONS: &[&str] = &["md", "mdx", "rst", "txt", "adoc"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileClass {
+    pub language: String,
+    pub role: FileRole,
+}
+
+// Language and role for a path: the exact-name table first, then name
+// patterns (Dockerfile.dev, tsconfig.build.json, CI directories, tests),
+// then the extension. Pass the path relative to the project root; the CI
+// and test checks look at every directory in it
+pub fn classify_file(path: &str) -> FileClass {
+    let normalized = path.replace('\\', "/");
+    let name = normalized.rsplit('/').next().unwrap_or("");
+    let lower = name.to_lowercase();
+    if let Some((_, language, role)) = FILENAME_TABLE.iter().find(|(n, _, _)| n.eq_ignore_ascii_case(name)) {
+        return FileClass { language: language.to_string(), role: *role };
+    }
+    if lower.starts_with("dockerfile.") || lower.ends_with(".dockerfile") {
+        return FileClass { language: "Dockerfile".to_string(), role: FileRole::Config };
+    }
+    if lower.starts_with("tsconfig.") && lower.ends_with(".json") {
+        return FileClass { language: "TypeScript config".to_string(), role: FileRole::Config };
+    }
+
+    let language = get_language_from_extension(path);
+    let extension = lower.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+    let in_ci_dir = [".github/workflows/", ".circleci/", ".buildkite/"]
+        .iter()
+        .any(|dir| normalized.starts_with(dir) || normalized.contains(&format!("/{}", dir)));
+    let role = if in_ci_dir {
+        FileRole::Ci
+    } else if is_test_file(path) {
+        FileRole::Test
+    } else if DOCS_EXTENSIONS.contains(&extension) || lower.starts_with("readme") || lower.starts_with("changelog") {
+        FileRole::Docs
+    } else if CONFIG_EXTENSIONS.contains(&extension) || lower.contains(".config.") || (lower.starts_with('.') && lower.ends_with("rc")) {
+        FileRole::Config
+    } else {
+        FileRole::Source
+    };
+    FileClass { language, role }
+}
+
+// Directories never analyzed, regardless of .gitignore. Shared by
+// `FileFilter` and `default_overrides` so counting and analysis agree.
+const IGNORED_DIRS: &[&str] = &[
+    "node_modules", ".git", ".svn", "dist", "build", "target", "vendor", "__pycache__",
+    ".next", ".svelte-kit", ".venv", "venv", ".pnpm-store", ".yardoc", ".bundle",
+    ".terraform", ".m2", "coverage", "Pods", "DerivedData", ".cache", "tmp",
+];
+
+// Binary and non-code file types to skip
+const IGNORED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "bmp", "tiff",
+    "woff", "woff2", "ttf", "eot",
+    "pdf", "zip", "tar", "gz", "bz2", "xz", "7z",
+    "mp3", "mp4", "mkv", "mov", "avi", "wav", "flac",
+    "wasm",
+];
+
+// User additions to the built-in lists, from the "file_filter" setting.
+// Extensions are written without the dot
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct FileFilterSettings {
+    pub extra_ignored_extensions: Vec<String>,
+    pub extra_ignored_dirs: Vec<String>,
+    // Built-in ignored extensions to analyze anyway, e.g. "svg"
+    pub allowed_extensions: Vec<String>,
+}
+
+static FILE_FILTER_SETTINGS: Lazy<RwLock<FileFilterSettings>> = Lazy::new(|| RwLock::new(FileFilterSettings::default()));
+
+pub fn set_file_filter_settings(settings: FileFilterSettings) {
+    if let Ok(mut current) = FILE_FILTER_SETTINGS.write() {
+        *current = settings;
+    }
+}
+
+pub fn file_filter_settings() -> FileFilterSettings {
+    FILE_FILTER_SETTINGS.read().map(|s| s.clone()).unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterReason {
+    IgnoredDirectory,
+    IgnoredExtension,
+}
+
+impl FilterReason {
+    pub const ALL: [FilterReason; 2] = [FilterReason::IgnoredDirectory, FilterReason::IgnoredExtension];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterDecision {
+    Analyze,
+    Skip(FilterReason),
+}
+
+// Which files are worth analyzing. Build one per run with
+// `FileFilter::current()` and pass it down rather than rebuilding per file
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    ignored_dirs: BTreeSet<String>,
+    ignored_extensions: BTreeSet<String>,
+}
+
+impl Default for FileFilter {
+    fn default() -> Self {
+        Self::new(&FileFilterSettings::default())
+    }
+}
+
+impl FileFilter {
+    pub fn new(settings: &FileFilterSettings) -> Self {
+        let clean = |names: &[String]| -> Vec<String> {
+            names
+                .iter()
+                .map(|n| n.trim().trim_start_matches('.').to_string())
+                .filter(|n| !n.is_empty())
+                .collect()
+        };
+        let allowed = clean(&settings.allowed_extensions);
+        let mut ignored_extensions: BTreeSet<String> = IGNORED_EXTENSIONS.iter().map(|e| e.to_string()).collect();
+        ignored_extensions.extend(clean(&settings.extra_ignored_extensions));
+        ignored_extensions.retain(|e| !allowed.contains(e));
+
+        let mut ignored_dirs: BTreeSet<String> = IGNORED_DIRS.iter().map(|d| d.to_string()).collect();
+        // Directory names may legitimately start with a dot, so only trim
+        ignored_dirs.extend(
+            settings.extra_ignored_dirs.iter().map(|d| d.trim().trim_matches(['/', '\\']).to_string()).filter(|d| !d.is_empty()),
+        );
+        Self { ignored_dirs, ignored_extensions }
+    }
+
+    // Built-in lists plus the saved settings
+    pub fn current() -> Self {
+        Self::new(&file_filter_settings())
+    }
+
+    // Only directories below the walk root count, so a project that lives
+    // under ~/build or /tmp is still analyzed. User ignore patterns live in
+    // the walker overrides, so anything filtering walker output with this
+    // honors them too
+    pub fn matches(&self, root: &Path, path: &Path) -> FilterDecision {
+        let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+        let mut segments: Vec<&str> = relative.split(['/', '\\']).collect();
+        segments.pop();
+        if segments.iter().any(|s| self.ignored_dirs.contains(*s)) {
+            return FilterDecision::Skip(FilterReason::IgnoredDirectory);
         }
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if self.ignored_extensions.contains(ext) => FilterDecision::Skip(FilterReason::IgnoredExtension),
+            _ => FilterDecision::Analyze,
+        }
+    }
+
+    pub fn should_analyze(&self, root: &Path, path: &Path) -> bool {
+        self.matches(root, path) == FilterDecision::Analyze
+    }
+}
+
+// Directories whose contents are tests regardless of file name
+const TEST_DIRS: &[&str] = &["test", "tests", "__tests__", "spec", "specs", "e2e"];
+const TEST_SUFFIXES: &[&str] = &[
+    ".test.js", ".test.ts", ".test.jsx", ".test.tsx", ".test.mjs", ".test.cjs",
+    ".spec.js", ".spec.ts", ".spec.jsx", ".spec.tsx", ".spec.mjs", ".spec.cjs",
+    "_test.py", "_test.go", "_spec.rb", "_test.rb",
+];
+// JVM/.NET conventions, matched case-sensitively so `Latest.java` isn't a test
+const CAMEL_TEST_SUFFIXES: &[&str] = &["Test.java", "Tests.java", "Test.kt", "Tests.kt", "Test.cs", "Tests.cs"];
+
+// Test-file classifier shared by insights and the AI context builder.
+// Rust `#[cfg(test)]` modules need a content read and are handled by callers
+pub fn is_test_file(path: &str) -> bool {
+    let normalized = path.replace('\\', "/");
+    let mut components = normalized.split('/').collect::<Vec<_>>();
+    let name = components.pop().unwrap_or("");
+    if components.iter().any(|c| TEST_DIRS.contains(&c.to_lowercase().as_str())) {
+        return true;
+    }
+    let lower = name.to_lowercase();
+    (lower.starts_with("test_") && lower.ends_with(".py"))
+        || TEST_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix))
+        || CAMEL_TEST_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+// Newest modification time of the root .gitignore and .gitmodules in
+// nanoseconds (0 when both are absent)
+fn gitignore_mtime(root: &Path) -> u64 {
+    [".gitignore", ".gitmodules"]
+        .iter()
+        .filter_map(|name| std::fs::metadata(root.join(name)).and_then(|m| m.modified()).ok())
+        .filter_map(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .max()
+        .unwrap_or(0)
+}
+
+// Submodule paths declared in the root .gitmodules, relative to the root
+pub fn submodule_paths(root: &Path) -> Vec<String> {
+    std::fs::read_to_string(root.join(".gitmodules"))
+        .map(|text| crate::insights::parse_gitmodules(&text).into_iter().map(|m| m.path).collect())
+        .unwrap_or_default()
+}
+
+pub fn set_global_ignore_patterns(patterns: Vec<String>) {
+    if let Ok(mut current) = GLOBAL_IGNORE_PATTERNS.write() {
+        *current = patterns;
+    }
+}
+
+pub fn global_ignore_patterns() -> Vec<String> {
+    GLOBAL_IGNORE_PATTERNS.read().map(|p| p.clone()).unwrap_or_default()
+}
+
+pub fn set_hidden_allowlist(patterns: Vec<String>) {
+    if let Ok(mut current) = HIDDEN_ALLOWLIST.write() {
+        *current = patterns;
     }
+}
+
+pub fn hidden_allowlist() -> Vec<String> {
+    HIDDEN_ALLOWLIST.read().map(|p| p.clone()).unwrap_or_default()
+}
 
-    if let Some(ext) = Path::new(path).extension().and_then(|ext| ext.to_str()) {
-        return !ignore_extensions.contains(&ext);
+fn hidden_allowlist_matcher(root: &Path, patterns: &[String]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in patterns {
+        let _ = builder.add_line(None, pattern);
     }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+// Patterns from a .repomuseignore. Comments and blanks are skipped, and so
+// are `!` re-includes: an override can only add exclusions
+pub fn parse_ignore_file(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .map(str::to_string)
+        .collect()
+}
+
+fn project_ignore_patterns(root: &Path) -> Vec<String> {
+    std::fs::read_to_string(root.join(REPOMUSE_IGNORE_FILE))
+        .map(|text| parse_ignore_file(&text))
+        .unwrap_or_default()
+}
 
-    true
+pub fn validate_ignore_pattern(pattern: &str) -> Result<(), String> {
+    OverrideBuilder::new("/")
+        .add(&format!("!{}", pattern))
+        .map(|_| ())
+        .map_err(|e| format!("Invalid pattern \"{}\": {}", pattern, e))
+}
+
+// Changes whenever anything that feeds `default_overrides` does: the root
+// .gitignore/.gitmodules mtime, the .repomuseignore content, the global
+// patterns or the file filter settings
+fn overrides_key(root: &Path) -> u64 {
+    let mut hasher = Xxh3::new();
+    hasher.update(&gitignore_mtime(root).to_le_bytes());
+    if let Ok(content) = std::fs::read(root.join(REPOMUSE_IGNORE_FILE)) {
+        hasher.update(&content);
+    }
+    for pattern in global_ignore_patterns() {
+        hasher.update(pattern.as_bytes());
+        hasher.update(b"\n");
+    }
+    if let Ok(filter) = serde_json::to_vec(&file_filter_settings()) {
+        hasher.update(&filter);
+    }
+    hasher.digest()
 }
 
 // Get or create cached overrides for a path
 fn get_cached_overrides(root: &Path) -> Option<Override> {
-    let root_buf = root.to_path_buf();
+    let key = overrides_key(root);
     
-    // Try to get from cache first
-    if let Ok(cache) = WALKER_CACHE.lock() {
-        if let Some(overrides) = cache.get(&root_buf) {
-            return Some(overrides.clone());
+    // Try to get from cache first; a changed ignore source forces a rebuild
+    if let Ok(mut cache) = WALKER_CACHE.lock() {
+        if let Some(overrides) = cache.get(root, key) {
+            return Some(overrides);
         }
     }
     
     // Create new overrides
-    let overrides = default_overrides(root)?;
+    let overrides = default_overrides(root, false)?;
     
-    // Store in cache
     if let Ok(mut cache) = WALKER_CACHE.lock() {
-        // Limit cache size to prevent unbounded growth
-        if cache.len() > 100 {
-            cache.clear();
-        }
-        cache.insert(root_buf, overrides.clone());
+        cache.insert(root.to_path_buf(), overrides.clone(), key, WALKER_CACHE_CAPACITY);
     }
     
     Some(overrides)
 }
 
-// Build a gitignore-aware walker with sensible defaults
-pub fn walker(path: &Path) -> ignore::Walk {
-    let mut builder = WalkBuilder::new(path);
-    builder
-        .follow_links(false)
-        .git_ignore(true)
-        .git_global(true)
-        .git_exclude(true)
-        .ignore(true)
-        .hidden(true)
-        .parents(true);
-    if let Some(overrides) = get_cached_overrides(path) {
-        builder.overrides(overrides);
+// Drops cached overrides for one root, or for every root when the change
+// (such as the settings-level ignore list) applies everywhere. Stale keys
+// are caught on the next walk anyway; this frees them straight away
+pub fn invalidate_walker_cache(root: Option<&Path>) {
+    if let Ok(mut cache) = WALKER_CACHE.lock() {
+        match root {
+            Some(root) => {
+                cache.entries.remove(root);
+            }
+            None => cache.entries.clear(),
+        }
     }
-    builder.build()
 }
 
-pub fn walker_with_depth(path: &Path, max_depth: Option<usize>) -> ignore::Walk {
+// One spelling per directory for database keys: symlinks resolved and, on
+// Windows, the `\\?\` prefix dropped and the drive letter upper-cased so
+// `c:\code` and `C:\code` agree. Paths that can't be resolved (deleted
+// projects) only get the drive letter fix
+pub fn canonical_path(path: &Path) -> String {
+    let resolved = std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string_lossy().to_string());
+    let resolved = resolved
+        .strip_prefix(r"\\?\UNC\")
+        .map(|rest| format!(r"\\{}", rest))
+        .or_else(|| resolved.strip_prefix(r"\\?\").map(str::to_string))
+        .unwrap_or(resolved);
+    let mut chars = resolved.chars();
+    match (chars.next(), chars.next()) {
+        (Some(drive), Some(':')) if drive.is_ascii_lowercase() => {
+            format!("{}{}", drive.to_ascii_uppercase(), &resolved[1..])
+        }
+        _ => resolved,
+    }
+}
+
+// Windows takes paths up to this length as is in metadata and open calls;
+// 248 rather than MAX_PATH's 260 since a directory must leave room for an
+// 8.3 file name
+const WINDOWS_PATH_LIMIT: usize = 248;
+
+// `path` in the `\\?\` extended-length form, with UNC roots as
+// `\\?\UNC\server\share`. That form skips Windows' own normalization, so
+// separators and `.`/`..` are resolved here. None for short, relative and
+// already-prefixed paths
+#[cfg_attr(not(windows), allow(dead_code))]
+fn extended_length_path(path: &str) -> Option<String> {
+    if path.len() < WINDOWS_PATH_LIMIT || path.starts_with(r"\\?\") {
+        return None;
+    }
+    let path = path.replace('/', "\\");
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{}", resolve_dots(unc, 2)));
+    }
+    let bytes = path.as_bytes();
+    if bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        return Some(format!(r"\\?\{}", resolve_dots(&path, 1)));
+    }
+    None
+}
+
+// Lexical `.`/`..` resolution that never climbs above the first
+// `root_parts` components (the drive, or the UNC server and share)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn resolve_dots(path: &str, root_parts: usize) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.len() > root_parts {
+                    parts.pop();
+                }
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("\\")
+}
+
+// The spelling to hand metadata and open calls: extended-length on Windows
+// when the path is past the limit, unchanged everywhere else
+pub fn long_path(path: &Path) -> std::borrow::Cow<'_, Path> {
+    #[cfg(windows)]
+    if let Some(extended) = path.to_str().and_then(extended_length_path) {
+        return std::borrow::Cow::Owned(PathBuf::from(extended));
+    }
+    std::borrow::Cow::Borrowed(path)
+}
+
+pub fn metadata(path: &Path) -> std::io::Result<std::fs::Metadata> {
+    std::fs::metadata(long_path(path))
+}
+
+// Command input validation; accepts long paths and UNC roots, including
+// `//server/share` written with forward slashes
+pub fn is_existing_dir(path: &Path) -> bool {
+    #[cfg(windows)]
+    if let Some(unc) = path.to_str().filter(|p| p.starts_with("//")) {
+        return is_existing_dir(Path::new(&unc.replace('/', "\\")));
+    }
+    metadata(path).is_ok_and(|m| m.is_dir())
+}
+
+// Whether walks follow symbolic links. Off by default; when on, links that
+// resolve outside the walk root are still skipped unless allowed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SymlinkPolicy {
+    pub follow: bool,
+    #[serde(default)]
+    pub allow_outside_root: bool,
+}
+
+// What a walk descends into beyond the gitignore-aware defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WalkOptions {
+    pub include_submodules: bool,
+    pub include_hidden: bool,
+    pub symlinks: SymlinkPolicy,
+    pub max_depth: Option<usize>,
+}
+
+// Device and inode, or the canonical path where there are no inodes
+#[cfg(unix)]
+type DirIdentity = (u64, u64);
+#[cfg(not(unix))]
+type DirIdentity = PathBuf;
+
+#[cfg(unix)]
+fn dir_identity(path: &Path) -> Option<DirIdentity> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_identity(path: &Path) -> Option<DirIdentity> {
+    std::fs::canonicalize(path).ok()
+}
+
+// Cycle and escape protection for a walk that follows links: each real
+// directory is entered once however many links point at it, so a link back
+// to an ancestor ends there instead of recursing
+struct LinkGuard {
+    root: PathBuf,
+    canonical_root: PathBuf,
+    allow_outside_root: bool,
+    visited: Mutex<HashSet<DirIdentity>>,
+}
+
+impl LinkGuard {
+    fn new(root: &Path, policy: SymlinkPolicy) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            canonical_root: std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()),
+            allow_outside_root: policy.allow_outside_root,
+            visited: Mutex::new(dir_identity(root).into_iter().collect()),
+        }
+    }
+
+    fn allows(&self, entry: &ignore::DirEntry) -> bool {
+        if entry.path_is_symlink() {
+            let depth = entry.path().ancestors().take_while(|p| *p != self.root).filter(|p| p.is_symlink()).count();
+            if depth > MAX_SYMLINK_DEPTH {
+                return false;
+            }
+            // Dangling links resolve to nothing and are skipped too
+            let inside = std::fs::canonicalize(entry.path()).is_ok_and(|t| t.starts_with(&self.canonical_root));
+            if !inside && !self.allow_outside_root {
+                return false;
+            }
+        }
+        if !entry.file_type().is_some_and(|t| t.is_dir()) {
+            return true;
+        }
+        dir_identity(entry.path()).is_some_and(|id| self.visited.lock().unwrap().insert(id))
+    }
+}
+
+// Shared walker settings: gitignore-aware, symlinks followed only when the
+// policy says so. Hidden entries are dropped unless they match the hidden
+// allowlist, or all kept with `include_hidden`; `.git` is never walked
+fn base_builder(path: &Path, max_depth: Option<usize>, include_hidden: bool, symlinks: SymlinkPolicy) -> WalkBuilder {
     let mut builder = WalkBuilder::new(path);
     builder
-        .follow_links(false)
+        .follow_links(symlinks.follow)
         .git_ignore(true)
         .git_global(true)
         .git_exclude(true)
         .ignore(true)
-        .hidden(true)
+        .hidden(false)
         .parents(true)
         .max_depth(max_depth);
-    if let Some(overrides) = get_cached_overrides(path) {
+    let allowlist = (!include_hidden).then(|| hidden_allowlist_matcher(path, &hidden_allowlist()));
+    let guard = symlinks.follow.then(|| LinkGuard::new(path, symlinks));
+    builder.filter_entry(move |entry| keep_entry(entry, allowlist.as_ref(), guard.as_ref()));
+    builder
+}
+
+fn keep_entry(entry: &ignore::DirEntry, allowlist: Option<&Gitignore>, guard: Option<&LinkGuard>) -> bool {
+    let name = entry.file_name();
+    if name == ".git" {
+        return false;
+    }
+    if let Some(allowlist) = allowlist {
+        if entry.depth() > 0 && name.to_string_lossy().starts_with('.') {
+            if CREDENTIAL_FILES.iter().any(|f| name.eq_ignore_ascii_case(f)) {
+                return false;
+            }
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            if !allowlist.matched(entry.path(), is_dir).is_ignore() {
+                return false;
+            }
+        }
+    }
+    guard.is_none_or(|guard| guard.allows(entry))
+}
+
+// Build a gitignore-aware walker with sensible defaults
+pub fn walker(path: &Path) -> ignore::Walk {
+    walker_with_depth(path, None)
+}
+
+// `walker_with_depth`, optionally descending into git submodules, every
+// hidden entry and symlinked directories as well
+pub fn walker_with_options(path: &Path, options: WalkOptions) -> ignore::Walk {
+    let mut builder = base_builder(path, options.max_depth, options.include_hidden, options.symlinks);
+    let overrides = if options.include_submodules { default_overrides(path, true) } else { get_cached_overrides(path) };
+    if let Some(overrides) = overrides {
         builder.overrides(overrides);
     }
     builder.build()
 }
 
-fn default_overrides(root: &Path) -> Option<Override> {
+pub fn walker_with_depth(path: &Path, max_depth: Option<usize>) -> ignore::Walk {
+    walker_with_options(path, WalkOptions { max_depth, ..Default::default() })
+}
+
+// Like `walker_with_depth` but also yields dotfiles such as `.env`; the
+// overrides still keep `.git` and the other heavy directories out
+pub fn walker_with_hidden(path: &Path, max_depth: Option<usize>) -> ignore::Walk {
+    walker_with_options(path, WalkOptions { include_hidden: true, max_depth, ..Default::default() })
+}
+
+// Walk errors whose messages are kept; later ones are only counted
+const WALK_ERROR_SAMPLES: usize = 5;
+
+// What one walk saw. Errors used to be dropped with `.filter_map(|e| e.ok())`,
+// which made an unreadable directory look empty; `observe` counts them
+// instead. Atomic so the parallel walker's callbacks can share one
+pub struct WalkStats {
+    start: std::time::Instant,
+    entries: AtomicUsize,
+    dirs: AtomicUsize,
+    files: AtomicUsize,
+    skipped_by_filter: AtomicUsize,
+    io_errors: AtomicUsize,
+    error_samples: Mutex<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct WalkSummary {
+    pub entries: usize,
+    pub dirs: usize,
+    pub files: usize,
+    pub skipped_by_filter: usize,
+    pub io_errors: usize,
+    // The first WALK_ERROR_SAMPLES error messages
+    pub error_samples: Vec<String>,
+    pub elapsed_ms: u64,
+}
+
+impl Default for WalkStats {
+    fn default() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+            entries: AtomicUsize::new(0),
+            dirs: AtomicUsize::new(0),
+            files: AtomicUsize::new(0),
+            skipped_by_filter: AtomicUsize::new(0),
+            io_errors: AtomicUsize::new(0),
+            error_samples: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl WalkStats {
+    // Counts a walk result and passes the entry through, for use in place
+    // of `.filter_map(|e| e.ok())`
+    pub fn observe(&self, result: Result<ignore::DirEntry, ignore::Error>) -> Option<ignore::DirEntry> {
+        match result {
+            Ok(entry) => {
+                self.entries.fetch_add(1, Ordering::Relaxed);
+                match entry.file_type() {
+                    Some(t) if t.is_dir() => self.dirs.fetch_add(1, Ordering::Relaxed),
+                    Some(t) if t.is_file() => self.files.fetch_add(1, Ordering::Relaxed),
+                    _ => 0,
+                };
+                Some(entry)
+            }
+            Err(e) => {
+                self.record_error(&e);
+                None
+            }
+        }
+    }
+
+    pub fn record_error(&self, error: &dyn std::fmt::Display) {
+        if self.io_errors.fetch_add(1, Ordering::Relaxed) < WALK_ERROR_SAMPLES {
+            if let Ok(mut samples) = self.error_samples.lock() {
+                samples.push(error.to_string());
+            }
+        }
+    }
+
+    pub fn record_skipped(&self) {
+        self.skipped_by_filter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dirs(&self) -> usize {
+        self.dirs.load(Ordering::Relaxed)
+    }
+
+    pub fn files(&self) -> usize {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    pub fn skipped_by_filter(&self) -> usize {
+        self.skipped_by_filter.load(Ordering::Relaxed)
+    }
+
+    pub fn io_errors(&self) -> usize {
+        self.io_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn summary(&self) -> WalkSummary {
+        WalkSummary {
+            entries: self.entries.load(Ordering::Relaxed),
+            dirs: self.dirs(),
+            files: self.files(),
+            skipped_by_filter: self.skipped_by_filter(),
+            io_errors: self.io_errors(),
+            error_samples: self.error_samples.lock().map(|s| s.clone()).unwrap_or_default(),
+            elapsed_ms: self.start.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+// A literal path for override globs, which would otherwise read `[`, `{`,
+// `*` and `?` in directory names as patterns
+fn escape_glob(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Submodules are someone else's code, so they are skipped unless asked for
+fn default_overrides(root: &Path, include_submodules: bool) -> Option<Override> {
     let mut ob = OverrideBuilder::new(root);
     // Common heavy directories (excluded regardless of .gitignore)
-    let dirs = [
-        "**/node_modules/**", "**/.git/**", "**/dist/**", "**/build/**", "**/target/**", "**/vendor/**",
-        "**/__pycache__/**", "**/.next/**", "**/.svelte-kit/**", "**/.venv/**", "**/venv/**",
-        "**/.pnpm-store/**", "**/.yardoc/**", "**/.bundle/**", "**/.terraform/**", "**/.m2/**",
-        "**/.cache/**", "**/coverage/**", "**/Pods/**", "**/DerivedData/**", "**/tmp/**",
-    ];
-    for d in dirs {
-        let _ = ob.add(&format!("!{}", d));
+    let filter = FileFilter::current();
+    for d in &filter.ignored_dirs {
+        let _ = ob.add(&format!("!**/{}/**", d));
+    }
+    if !include_submodules {
+        for path in submodule_paths(root) {
+            let path = escape_glob(path.trim_matches('/'));
+            let _ = ob.add(&format!("!/{}", path));
+            let _ = ob.add(&format!("!/{}/**", path));
+        }
     }
 
     // Binary and non-code file types to skip early
-    let exts = [
-        "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "bmp", "tiff",
-        "woff", "woff2", "ttf", "eot",
-        "pdf", "zip", "tar", "gz", "bz2", "xz", "7z",
-        "mp3", "mp4", "mkv", "mov", "avi", "wav", "flac",
-        "wasm",
-    ];
-    for ext in exts {
+    for ext in &filter.ignored_extensions {
         let _ = ob.add(&format!("!**/*.{}", ext));
     }
 
+    // User exclusions; a malformed pattern is skipped rather than dropping
+    // every override
+    for pattern in global_ignore_patterns().into_iter().chain(project_ignore_patterns(root)) {
+        let _ = ob.add(&format!("!{}", pattern));
+    }
+
     match ob.build() {
         Ok(overrides) => Some(overrides),
         Err(_) => None,
     }
 }
 
+// Text encoding a file prefix was decoded from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    #[serde(rename = "latin-1")]
+    Latin1,
+}
+
+// Bytes looked at when guessing UTF-16 from where the zero bytes fall
+const ENCODING_SAMPLE_BYTES: usize = 1024;
+
+// BOM first; otherwise ASCII-heavy UTF-16 shows up as a zero in every other
+// byte. Anything else that is not valid UTF-8 is read as Latin-1, which
+// never fails. A multi-byte character cut off at the end still counts as
+// UTF-8 since prefixes are read up to a byte cap
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if bytes.starts_with(b"\xEF\xBB\xBF") {
+        return TextEncoding::Utf8;
+    }
+    if bytes.starts_with(b"\xFF\xFE") {
+        return TextEncoding::Utf16Le;
+    }
+    if bytes.starts_with(b"\xFE\xFF") {
+        return TextEncoding::Utf16Be;
+    }
+
+    let sample = &bytes[..bytes.len().min(ENCODING_SAMPLE_BYTES) & !1];
+    let pairs = sample.len() / 2;
+    if pairs >= 2 {
+        let zero_even = sample.iter().step_by(2).filter(|b| **b == 0).count();
+        let zero_odd = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+        // At least 30% of one side zero and almost none of the other
+        if zero_odd * 10 >= pairs * 3 && zero_even * 20 < pairs {
+            return TextEncoding::Utf16Le;
+        }
+        if zero_even * 10 >= pairs * 3 && zero_odd * 20 < pairs {
+            return TextEncoding::Utf16Be;
+        }
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(_) => TextEncoding::Utf8,
+        Err(e) if e.error_len().is_none() => TextEncoding::Utf8,
+        Err(_) => TextEncoding::Latin1,
+    }
+}
+
+// Transcodes to UTF-8 with any byte order mark dropped
+pub fn decode_text(bytes: &[u8]) -> (String, TextEncoding) {
+    let encoding = detect_encoding(bytes);
+    let body = strip_bom(bytes);
+    let text = match encoding {
+        TextEncoding::Utf8 => String::from_utf8_lossy(body).into_owned(),
+        TextEncoding::Latin1 => body.iter().map(|b| *b as char).collect(),
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let units = body.chunks_exact(2).map(|pair| match encoding {
+                TextEncoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+        }
+    };
+    (text, encoding)
+}
+
+// A zero byte means binary, the same rule git uses, except in UTF-16 text
+// where zeros are part of nearly every character
+pub fn is_likely_binary(bytes: &[u8]) -> bool {
+    !matches!(detect_encoding(bytes), TextEncoding::Utf16Le | TextEncoding::Utf16Be)
+        && bytes[..bytes.len().min(8000)].contains(&0)
+}
+
+// How far a truncated prefix backs off from the raw byte cap
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrefixOptions {
+    // Drop a character the cap cut in half instead of decoding it as U+FFFD
+    pub char_boundary: bool,
+    // Also drop a partial last line when a newline falls within this many
+    // bytes of the end; one enormous line is kept as is
+    pub line_tolerance: Option<usize>,
+}
+
+impl PrefixOptions {
+    pub fn whole_lines(tolerance: usize) -> Self {
+        Self { char_boundary: true, line_tolerance: Some(tolerance) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextPrefix {
+    pub content: String,
+    pub truncated: bool,
+    pub encoding: TextEncoding,
+    // Lines ending in a newline, plus the last line when the file ended
+    pub complete_lines: usize,
+    // xxh3 of the raw bytes read, so change detection doesn't need a
+    // second read
+    pub prefix_hash: u64,
+}
+
 // Optimized: Read only up to cap_bytes from a file and return whether it was truncated
 pub fn read_text_prefix_limited(path: &str, cap_bytes: usize) -> Result<(String, bool), std::io::Error> {
-    let file = File::open(path)?;
-    let mut reader = BufReader::with_capacity(8192, file);
-    let mut buffer = Vec::with_capacity(cap_bytes.min(8192));
-    let mut total = 0usize;
-    let mut chunk = [0u8; 4096]; // Smaller chunks for better control
-    let mut was_truncated = false;
-    
-    while total < cap_bytes {
-        let to_read = (cap_bytes - total).min(chunk.len());
-        match reader.read(&mut chunk[..to_read]) {
-            Ok(0) => break, // EOF
-            Ok(n) => {
-                buffer.extend_from_slice(&chunk[..n]);
-                total += n;
-                if total >= cap_bytes {
-                    was_truncated = true;
-                    break;
-                }
-            }
-            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
-            Err(e) => return Err(e),
+    let options = PrefixOptions { char_boundary: true, line_tolerance: None };
+    read_text_prefix(path, cap_bytes, options).map(|prefix| (prefix.content, prefix.truncated))
+}
+
+// Bytes of `bytes` that hold whole characters, for a buffer the cap may
+// have cut mid-character
+fn whole_char_len(bytes: &[u8], encoding: TextEncoding) -> usize {
+    match encoding {
+        TextEncoding::Utf8 => match std::str::from_utf8(bytes) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes.len(),
+        },
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let even = bytes.len() & !1;
+            let last = match (encoding, &bytes[even.saturating_sub(2)..even]) {
+                (TextEncoding::Utf16Le, [lo, hi]) => Some(u16::from_le_bytes([*lo, *hi])),
+                (TextEncoding::Utf16Be, [hi, lo]) => Some(u16::from_be_bytes([*hi, *lo])),
+                _ => None,
+            };
+            // A high surrogate waits for its pair
+            if last.is_some_and(|unit| (0xD800..0xDC00).contains(&unit)) { even - 2 } else { even }
         }
+        TextEncoding::Latin1 => bytes.len(),
     }
-    
-    // Check if there's more data available
-    if !was_truncated {
-        let mut test_byte = [0u8; 1];
-        if reader.read(&mut test_byte)? > 0 {
-            was_truncated = true;
+}
+
+// Largest read buffer a thread keeps between files; a bigger cap still
+// works, its buffer is just released afterwards
+const POOLED_BUFFER_MAX: usize = 1024 * 1024;
+
+thread_local! {
+    // One read buffer per rayon worker, reused across files so a batch
+    // holds `threads x cap` bytes of raw reads rather than one per file
+    static READ_BUFFER: std::cell::RefCell<Vec<u8>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+// Reads up to `cap_bytes` into the calling thread's pooled buffer in one
+// pass and hands it to `f`, with whether the file continues past the cap
+fn with_prefix_bytes<T>(path: &str, cap_bytes: usize, f: impl FnOnce(&mut Vec<u8>, bool) -> T) -> std::io::Result<T> {
+    let file = File::open(long_path(Path::new(path)))?;
+    READ_BUFFER.with(|cell| {
+        let mut buffer = cell.borrow_mut();
+        buffer.clear();
+        buffer.reserve(cap_bytes.saturating_add(1).min(POOLED_BUFFER_MAX));
+        // One byte past the cap tells a truncated file from one that ends there
+        file.take(cap_bytes as u64 + 1).read_to_end(&mut buffer)?;
+        let truncated = buffer.len() > cap_bytes;
+        buffer.truncate(cap_bytes);
+        let result = f(&mut buffer, truncated);
+        if buffer.capacity() > POOLED_BUFFER_MAX {
+            *buffer = Vec::new();
+        }
+        Ok(result)
+    })
+}
+
+pub fn read_text_prefix(path: &str, cap_bytes: usize, options: PrefixOptions) -> Result<TextPrefix, std::io::Error> {
+    with_prefix_bytes(path, cap_bytes, |buffer, truncated| text_prefix(buffer, truncated, options))
+}
+
+fn text_prefix(buffer: &mut Vec<u8>, was_truncated: bool, options: PrefixOptions) -> TextPrefix {
+    // Taken before any backing off, so it covers exactly the bytes read
+    let prefix_hash = xxhash_rust::xxh3::xxh3_64(buffer);
+    if was_truncated && options.char_boundary {
+        let encoding = detect_encoding(buffer);
+        buffer.truncate(whole_char_len(buffer, encoding));
+    }
+    let (mut content, encoding) = decode_text(buffer);
+    if was_truncated {
+        if let (Some(tolerance), Some(newline)) = (options.line_tolerance, content.rfind('\n')) {
+            if content.len() - (newline + 1) <= tolerance {
+                content.truncate(newline + 1);
+            }
         }
     }
-    
-    Ok((String::from_utf8_lossy(&buffer).into_owned(), was_truncated))
+    let mut complete_lines = content.matches('\n').count();
+    if !was_truncated && !content.is_empty() && !content.ends_with('\n') {
+        complete_lines += 1;
+    }
+    TextPrefix { content, truncated: was_truncated, encoding, complete_lines, prefix_hash }
+}
+
+// `read_text_prefix_limited` over many files in parallel, in input order.
+// Each entry keeps its own error so "not found" and "permission denied"
+// stay distinguishable
+pub fn read_files_batch(paths: &[&str], cap_bytes: usize, options: PrefixOptions) -> Vec<Result<TextPrefix, String>> {
+    use rayon::prelude::*;
+    paths
+        .par_iter()
+        .map(|path| read_text_prefix(path, cap_bytes, options).map_err(|e| e.to_string()))
+        .collect()
+}
+
+// Drop a leading UTF-8 / UTF-16 byte order mark so it never reaches prompts or hashes
+fn strip_bom(bytes: &[u8]) -> &[u8] {
+    const BOMS: [&[u8]; 3] = [b"\xEF\xBB\xBF", b"\xFF\xFE", b"\xFE\xFF"];
+    BOMS.iter()
+        .find_map(|bom| bytes.strip_prefix(*bom))
+        .unwrap_or(bytes)
 }
 
 
 // Parallel walker builders
-pub fn walker_parallel(path: &Path) -> ignore::WalkParallel {
-    let mut builder = WalkBuilder::new(path);
-    let num_threads = std::thread::available_parallelism()
-        .map(|n| n.get())
-        .unwrap_or(4)
-        .min(8);
-    
-    builder
-        .follow_links(false)
-        .git_ignore(true)
-        .git_global(true)
-        .git_exclude(true)
-        .ignore(true)
-        .hidden(true)
-        .parents(true)
-        .threads(num_threads);
-        
+pub fn walker_parallel_with_depth(path: &Path, max_depth: Option<usize>, symlinks: SymlinkPolicy) -> ignore::WalkParallel {
+    let mut builder = base_builder(path, max_depth, false, symlinks);
+    builder.threads(crate::performance::walker_threads());
     if let Some(overrides) = get_cached_overrides(path) {
         builder.overrides(overrides);
     }
     builder.build_parallel()
 }
 
- 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_canonical_path_normalizes_drive_letter_and_resolves_links() {
+        assert_eq!(canonical_path(Path::new(r"c:\missing\code")), r"C:\missing\code");
+        assert_eq!(canonical_path(Path::new("/missing/code")), "/missing/code");
+
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/../b");
+        std::fs::create_dir_all(dir.path().join("a")).unwrap();
+        std::fs::create_dir_all(dir.path().join("b")).unwrap();
+        let expected = std::fs::canonicalize(dir.path().join("b")).unwrap();
+        assert_eq!(canonical_path(&nested), expected.to_string_lossy());
+    }
+
+    #[test]
+    fn test_is_test_file_conventions() {
+        for path in [
+            "packages/ui/src/__tests__/button.tsx", "src/app.spec.ts", "tests/integration.rs",
+            "pkg/server_test.go", "app/test_models.py", "src/test/java/com/acme/OrderServiceTest.java",
+            "C:\\code\\app\\Tests\\Unit.cs",
+        ] {
+            assert!(is_test_file(path), "{} should be a test", path);
+        }
+        for path in ["src/Latest.java", "src/contest.ts", "src/testing.py", "src/main.rs"] {
+            assert!(!is_test_file(path), "{} should not be a test", path);
+        }
+    }
+
+    #[test]
+    fn test_get_language_from_extension_newer_languages() {
+        let cases = [
+            ("Main.scala", "Scala"), ("build.sc", "Scala"),
+            ("lib/app.ex", "Elixir"), ("test/app_test.exs", "Elixir"),
+            ("src/core.clj", "Clojure"), ("src/ui.cljs", "Clojure"),
+            ("lib/main.dart", "Dart"),
+            ("src/main.zig", "Zig"),
+            ("init.lua", "Lua"),
+            ("analysis.r", "R"), ("analysis.R", "R"),
+            ("model.jl", "Julia"),
+            ("app.nim", "Nim"),
+            ("main.v", "V"),
+            ("install.sh", "Shell"), ("setup.bash", "Shell"),
+            ("config.fish", "Fish"),
+        ];
+        for (path, language) in cases {
+            assert_eq!(get_language_from_extension(path), language, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_strip_bom_removes_known_markers() {
+        assert_eq!(strip_bom(b"\xEF\xBB\xBFfn main() {}"), b"fn main() {}");
+        assert_eq!(strip_bom(b"\xFF\xFEa\x00"), b"a\x00");
+        assert_eq!(strip_bom(b"\xFE\xFF\x00a"), b"\x00a");
+        assert_eq!(strip_bom(b"plain"), b"plain");
+    }
+
+    #[test]
+    fn test_read_text_prefix_limited_skips_utf8_bom() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bom.rs");
+        std::fs::write(&path, b"\xEF\xBB\xBFuse std::io;\n").unwrap();
+
+        let (content, truncated) = read_text_prefix_limited(path.to_str().unwrap(), 1024).unwrap();
+        assert_eq!(content, "use std::io;\n");
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_read_text_prefix_transcodes_each_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = "// café\nclass Program {}\n";
+        let utf16 = |big_endian: bool| -> Vec<u8> {
+            source
+                .encode_utf16()
+                .flat_map(|u| if big_endian { u.to_be_bytes() } else { u.to_le_bytes() })
+                .collect()
+        };
+        let fixtures: Vec<(&str, Vec<u8>, TextEncoding)> = vec![
+            ("plain.cs", source.as_bytes().to_vec(), TextEncoding::Utf8),
+            ("bom.cs", [b"\xEF\xBB\xBF".as_slice(), source.as_bytes()].concat(), TextEncoding::Utf8),
+            ("le_bom.cs", [b"\xFF\xFE".to_vec(), utf16(false)].concat(), TextEncoding::Utf16Le),
+            ("be_bom.cs", [b"\xFE\xFF".to_vec(), utf16(true)].concat(), TextEncoding::Utf16Be),
+            ("le.cs", utf16(false), TextEncoding::Utf16Le),
+            ("be.cs", utf16(true), TextEncoding::Utf16Be),
+            ("latin1.cs", b"// caf\xE9\nclass Program {}\n".to_vec(), TextEncoding::Latin1),
+        ];
+
+        for (name, bytes, expected) in fixtures {
+            let path = dir.path().join(name);
+            std::fs::write(&path, &bytes).unwrap();
+            let prefix = read_text_prefix(path.to_str().unwrap(), 4096, PrefixOptions::default()).unwrap();
+            assert_eq!((prefix.content.as_str(), prefix.truncated, prefix.encoding), (source, false, expected), "{}", name);
+            assert!(!is_likely_binary(&bytes), "{} looks binary", name);
+        }
+
+        assert!(is_likely_binary(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x00\x01\x00"));
+        // A prefix that ends mid-character is still UTF-8
+        assert_eq!(detect_encoding("é".as_bytes().split_last().unwrap().1), TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn test_hidden_allowlist_and_include_hidden() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "src/main.rs", ".github/workflows/ci.yml", ".gitlab-ci.yml", ".eslintrc", ".prettierrc.json",
+            ".env.example", ".env", ".idea/workspace.xml", "nested/.tool.yml", ".git/config",
+            ".npmrc", ".pypirc", ".netrc",
+        ] {
+            std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        let files = |walk: ignore::Walk| {
+            let mut files: Vec<String> = walk
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+                .map(|e| e.path().strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect();
+            files.sort();
+            files
+        };
+
+        let allowed = files(walker(root));
+        assert_eq!(
+            allowed,
+            vec![".env.example", ".eslintrc", ".github/workflows/ci.yml", ".gitlab-ci.yml", ".prettierrc.json", "src/main.rs"]
+        );
+        // Everything the walk yields still counts as analyzable, so the
+        // skipped-filtered counter doesn't pick up allowlisted files
+        let filter = FileFilter::default();
+        assert!(allowed.iter().all(|f| filter.should_analyze(root, &root.join(f))));
+
+        let everything = files(walker_with_options(root, WalkOptions { include_hidden: true, ..Default::default() }));
+        assert!(everything.contains(&".idea/workspace.xml".to_string()));
+        assert!(everything.contains(&"nested/.tool.yml".to_string()));
+        assert!(everything.contains(&".env".to_string()));
+        assert!(everything.contains(&".npmrc".to_string()));
+        assert!(!everything.iter().any(|f| f.starts_with(".git/")));
+
+        // Credential files stay out even when a user allowlist names them
+        let matcher = hidden_allowlist_matcher(root, &["/.*rc".to_string(), ".netrc".to_string()]);
+        let mut builder = WalkBuilder::new(root);
+        builder.hidden(false).filter_entry(move |entry| keep_entry(entry, Some(&matcher), None));
+        let custom = files(builder.build());
+        assert!(custom.contains(&".eslintrc".to_string()));
+        assert!(!custom.iter().any(|f| [".npmrc", ".pypirc", ".netrc"].contains(&f.as_str())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_followed_symlinks_stop_at_cycles_and_root() {
+        use std::os::unix::fs::symlink;
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("shared-src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("shared-src/lib.rs"), "pub fn x() {}\n").unwrap();
+        // A loop back to the project root, and a link to a sibling checkout
+        symlink("..", root.join("src/loop")).unwrap();
+        symlink("../shared-src", root.join("shared")).unwrap();
+
+        let files = |symlinks: SymlinkPolicy| {
+            let mut files: Vec<String> = walker_with_options(&root, WalkOptions { symlinks, ..Default::default() })
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+                .map(|e| e.path().strip_prefix(&root).unwrap().to_string_lossy().to_string())
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(files(SymlinkPolicy::default()), vec!["src/main.rs"]);
+        assert_eq!(files(SymlinkPolicy { follow: true, allow_outside_root: false }), vec!["src/main.rs"]);
+        let everywhere = SymlinkPolicy { follow: true, allow_outside_root: true };
+        assert_eq!(files(everywhere), vec!["shared/lib.rs", "src/main.rs"]);
+
+        let count = std::sync::atomic::AtomicUsize::new(0);
+        walker_parallel_with_depth(&root, None, everywhere).run(|| {
+            let count = &count;
+            Box::new(move |entry| {
+                if entry.is_ok_and(|e| e.file_type().is_some_and(|t| t.is_file())) {
+                    count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+                ignore::WalkState::Continue
+            })
+        });
+        assert_eq!(count.into_inner(), 2);
+    }
+
+    #[test]
+    fn test_walker_parallel_with_depth_stops_at_max_depth() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b/c")).unwrap();
+        std::fs::write(dir.path().join("top.rs"), "").unwrap();
+        std::fs::write(dir.path().join("a/b/c/deep.rs"), "").unwrap();
+
+        let count = |max_depth| {
+            let files = AtomicUsize::new(0);
+            walker_parallel_with_depth(dir.path(), max_depth, SymlinkPolicy::default()).run(|| {
+                let files = &files;
+                Box::new(move |entry| {
+                    if entry.is_ok_and(|e| e.file_type().is_some_and(|t| t.is_file())) {
+                        files.fetch_add(1, Ordering::Relaxed);
+                    }
+                    ignore::WalkState::Continue
+                })
+            });
+            files.into_inner()
+        };
+        assert_eq!(count(Some(2)), 1);
+        assert_eq!(count(None), 2);
+    }
+
+    #[test]
+    fn test_extended_length_path_forms() {
+        let deep = format!(r"C:\code\{}\..\app\.\src/main.rs", "d".repeat(260));
+        assert_eq!(extended_length_path(&deep), Some(r"\\?\C:\code\app\src\main.rs".to_string()));
+        let unc = format!(r"\\server\share\..\..\{}", "n".repeat(260));
+        assert_eq!(extended_length_path(&unc), Some(format!(r"\\?\UNC\server\share\{}", "n".repeat(260))));
+        assert_eq!(extended_length_path(r"C:\code\app"), None);
+        assert_eq!(extended_length_path(&format!(r"\\?\C:\{}", "x".repeat(300))), None);
+        assert_eq!(extended_length_path(&"relative\\".repeat(40)), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_paths_are_readable_on_windows() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut deep = dir.path().to_path_buf();
+        while deep.as_os_str().len() < 300 {
+            deep.push("a_fairly_long_directory_name");
+        }
+        std::fs::create_dir_all(long_path(&deep)).unwrap();
+        let file = deep.join("main.rs");
+        std::fs::write(long_path(&file), "fn main() {}\n").unwrap();
+        let file = file.to_str().unwrap();
+
+        assert!(file.len() > 260);
+        assert!(is_existing_dir(&deep));
+        assert_eq!(read_text_prefix_limited(file, 64).unwrap(), ("fn main() {}\n".to_string(), false));
+        assert_eq!(metadata(Path::new(file)).unwrap().len(), 13);
+    }
+
+    #[test]
+    fn test_walk_stats_count_entries_and_keep_first_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/bin")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("src/bin/cli.rs"), "fn main() {}\n").unwrap();
+
+        let stats = WalkStats::default();
+        let files = walker(dir.path()).filter_map(|e| stats.observe(e)).filter(|e| e.path().is_file()).count();
+        for i in 0..7 {
+            let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, format!("dir{}", i));
+            assert!(stats.observe(Err(ignore::Error::Io(denied))).is_none());
+        }
+        stats.record_skipped();
+
+        let summary = stats.summary();
+        assert_eq!(files, 2);
+        // The root plus src and src/bin
+        assert_eq!((summary.entries, summary.dirs, summary.files), (5, 3, 2));
+        assert_eq!((summary.io_errors, summary.skipped_by_filter), (7, 1));
+        assert_eq!(summary.error_samples, (0..5).map(|i| format!("dir{}", i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_pooled_single_read_matches_two_pass_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.rs");
+        let text: String = (0..60_000).map(|i| format!("let line_{} = \"é{}\";\n", i, i)).collect();
+        std::fs::write(&path, &text).unwrap();
+        let path = path.to_str().unwrap();
+
+        for cap in [0, 1, 4096, 100_000, text.len(), POOLED_BUFFER_MAX + 10] {
+            let prefix = read_text_prefix(path, cap, PrefixOptions::default()).unwrap();
+            // The old implementation: one read for the preview, another for the hash
+            let bytes = &text.as_bytes()[..cap.min(text.len())];
+            assert_eq!(prefix.content, String::from_utf8_lossy(bytes), "cap {}", cap);
+            assert_eq!(prefix.truncated, cap < text.len(), "cap {}", cap);
+            assert_eq!(prefix.prefix_hash, xxhash_rust::xxh3::xxh3_64(bytes), "cap {}", cap);
+        }
+        // Oversized buffers aren't kept for the next file
+        assert!(READ_BUFFER.with(|b| b.borrow().capacity()) <= POOLED_BUFFER_MAX);
+    }
+
+    #[test]
+    fn test_read_files_batch_keeps_per_file_errors_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let present = dir.path().join("a.rs");
+        std::fs::write(&present, "fn a() {}\n").unwrap();
+        let missing = dir.path().join("gone.rs");
+
+        let results = read_files_batch(&[missing.to_str().unwrap(), present.to_str().unwrap()], 4, PrefixOptions::default());
+        assert!(results[0].as_ref().is_err_and(|e| !e.is_empty()));
+        let read = results[1].as_ref().unwrap();
+        assert_eq!((read.content.as_str(), read.truncated, read.encoding), ("fn a", true, TextEncoding::Utf8));
+    }
+
+    #[test]
+    fn test_read_text_prefix_backs_off_to_char_and_line_boundaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        // "é" is two bytes, so a 6 byte cap splits the first one
+        std::fs::write(&path, "ab\ncdé\néé\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        let raw = read_text_prefix(path, 6, PrefixOptions::default()).unwrap();
+        assert_eq!(raw.content, "ab\ncd\u{FFFD}");
+        let chars = read_text_prefix(path, 6, PrefixOptions { char_boundary: true, line_tolerance: None }).unwrap();
+        assert_eq!((chars.content.as_str(), chars.complete_lines), ("ab\ncd", 1));
+        let lines = read_text_prefix(path, 12, PrefixOptions::whole_lines(8)).unwrap();
+        assert_eq!((lines.content.as_str(), lines.truncated, lines.complete_lines), ("ab\ncdé\n", true, 2));
+        let whole = read_text_prefix(path, 64, PrefixOptions::whole_lines(8)).unwrap();
+        assert_eq!((whole.truncated, whole.complete_lines), (false, 3));
+
+        let utf16 = dir.path().join("utf16.txt");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend("a😀".encode_utf16().flat_map(u16::to_le_bytes));
+        std::fs::write(&utf16, bytes).unwrap();
+        let prefix = read_text_prefix(utf16.to_str().unwrap(), 6, PrefixOptions::whole_lines(8)).unwrap();
+        assert_eq!(prefix.content, "a");
+    }
+
+    #[test]
+    fn test_read_text_prefix_keeps_one_enormous_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.min.js");
+        std::fs::write(&path, format!("{}\n", "x".repeat(100_000))).unwrap();
+
+        let prefix = read_text_prefix(path.to_str().unwrap(), 5000, PrefixOptions::whole_lines(1024)).unwrap();
+        assert_eq!((prefix.content.len(), prefix.truncated, prefix.complete_lines), (5000, true, 0));
+        // A newline further back than the tolerance doesn't throw the preview away either
+        std::fs::write(&path, format!("head\n{}", "x".repeat(100_000))).unwrap();
+        let prefix = read_text_prefix(path.to_str().unwrap(), 5000, PrefixOptions::whole_lines(1024)).unwrap();
+        assert_eq!((prefix.content.len(), prefix.complete_lines), (5000, 1));
+    }
+
+    #[test]
+    fn test_cached_overrides_refresh_when_gitignore_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let gitignore = root.join(".gitignore");
+        std::fs::write(&gitignore, "target/\n").unwrap();
+
+        let cached_key = || WALKER_CACHE.lock().unwrap().entries.get(root).map(|e| e.key);
+        get_cached_overrides(root).expect("overrides should build");
+        let first = cached_key();
+        assert_eq!(first, Some(overrides_key(root)));
+
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::OpenOptions::new().write(true).open(&gitignore).unwrap().set_modified(later).unwrap();
+
+        get_cached_overrides(root).expect("overrides should rebuild");
+        let second = cached_key();
+        assert_ne!(first, second);
+        assert_eq!(second, Some(overrides_key(root)));
+
+        invalidate_walker_cache(Some(root));
+        assert_eq!(cached_key(), None);
+    }
+
+    #[test]
+    fn test_repomuseignore_edit_is_picked_up_by_the_next_walk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in ["src/main.rs", "scratch/notes.rs"] {
+            std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        let count = || walker(root).filter_map(|e| e.ok()).filter(|e| e.file_type().is_some_and(|t| t.is_file())).count();
+
+        assert_eq!(count(), 2);
+        std::fs::write(root.join(REPOMUSE_IGNORE_FILE), "scratch/\n").unwrap();
+        assert_eq!(count(), 1);
+        std::fs::write(root.join(REPOMUSE_IGNORE_FILE), "src/\n").unwrap();
+        assert_eq!(count(), 1);
+        std::fs::remove_file(root.join(REPOMUSE_IGNORE_FILE)).unwrap();
+        assert_eq!(count(), 2);
+    }
+
+    #[test]
+    fn test_submodule_paths_are_matched_literally() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in ["libs/[core]/lib.rs", "libs/c/lib.rs", "src/main.rs"] {
+            std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        std::fs::write(root.join(".gitmodules"), "[submodule \"core\"]\n\tpath = libs/[core]\n").unwrap();
+        let mut files: Vec<String> = walker(root)
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+            .map(|e| e.path().strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["libs/c/lib.rs", "src/main.rs"]);
+    }
+
+    #[test]
+    fn test_walker_cache_evicts_least_recently_used_root() {
+        let overrides = OverrideBuilder::new("/").build().unwrap();
+        let mut cache = WalkerCache::default();
+        for root in ["/a", "/b", "/c"] {
+            cache.insert(PathBuf::from(root), overrides.clone(), 1, 3);
+        }
+        assert!(cache.get(Path::new("/a"), 1).is_some());
+        assert!(cache.get(Path::new("/b"), 2).is_none());
+        cache.insert(PathBuf::from("/d"), overrides, 1, 3);
+
+        let mut roots: Vec<&PathBuf> = cache.entries.keys().collect();
+        roots.sort();
+        assert_eq!(roots, vec![Path::new("/a"), Path::new("/c"), Path::new("/d")]);
+    }
+
+    #[test]
+    fn test_repomuseignore_applies_to_every_walker() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in ["src/main.rs", "src/fixtures/data.rs", "src/__snapshots__/ui.snap", "legacy/old.rs"] {
+            std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        let ignore_file = root.join(REPOMUSE_IGNORE_FILE);
+        std::fs::write(&ignore_file, "# local\nlegacy/\n**/fixtures/**\n*.snap\n!legacy/keep.rs\n").unwrap();
+
+        let relative = |path: &Path| path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+        let files = |walk: ignore::Walk| {
+            let mut files: Vec<String> = walk
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+                .map(|e| relative(e.path()))
+                .collect();
+            files.sort();
+            files
+        };
+        // Counting walks in parallel, analysis with submodules, insights by depth
+        let parallel = Mutex::new(Vec::new());
+        walker_parallel_with_depth(root, None, SymlinkPolicy::default()).run(|| {
+            let parallel = &parallel;
+            Box::new(move |entry| {
+                if let Ok(e) = entry {
+                    if e.file_type().is_some_and(|t| t.is_file()) {
+                        parallel.lock().unwrap().push(relative(e.path()));
+                    }
+                }
+                ignore::WalkState::Continue
+            })
+        });
+        assert_eq!(parallel.into_inner().unwrap(), vec!["src/main.rs"]);
+        assert_eq!(files(walker(root)), vec!["src/main.rs"]);
+        assert_eq!(files(walker_with_depth(root, Some(MAX_WALK_DEPTH))), vec!["src/main.rs"]);
+        assert_eq!(files(walker_with_options(root, WalkOptions { include_submodules: true, ..Default::default() })), vec!["src/main.rs"]);
+
+        // Edits apply to the next walk without clearing the cache
+        std::fs::write(&ignore_file, "# local\n**/fixtures/**\n*.snap\n").unwrap();
+        assert_eq!(files(walker(root)), vec!["legacy/old.rs", "src/main.rs"]);
+
+        assert_eq!(parse_ignore_file("a/\n\n # c\n!b\n*.snap \n"), vec!["a/", "*.snap"]);
+        assert!(validate_ignore_pattern("**/fixtures/**").is_ok());
+        assert!(validate_ignore_pattern("src/[a").is_err());
+    }
+
+    #[test]
+    fn test_file_filter_agrees_with_default_overrides() {
+        let root = Path::new("/repo");
+        let filter = FileFilter::default();
+        let overrides = default_overrides(root, false).expect("overrides should build");
+        let relative_paths = [
+            "src/main.rs",
+            "src/components/App.tsx",
+            "README.md",
+            "assets/logo.png",
+            "assets/intro.mp4",
+            "pkg/module.wasm",
+            "web/fonts/inter.woff2",
+            "docs/manual.pdf",
+            "app/node_modules/react/index.js",
+            "crates/core/target/debug/build.rs",
+            "lib/vendor/dep.go",
+            "py/pkg/__pycache__/mod.pyc",
+            "web/.next/server/page.js",
+            "ios/Pods/Lib/File.swift",
+            "api/.venv/lib/site.py",
+            "web/coverage/lcov.info",
+            "scripts/build.sh",
+        ];
+
+        for rel in relative_paths {
+            let by_filter = filter.should_analyze(root, &root.join(rel));
+            let by_overrides = !overrides.matched(rel, false).is_ignore();
+            assert_eq!(by_filter, by_overrides, "filters disagree on {}", rel);
+        }
+    }
+
+    #[test]
+    fn test_file_filter_settings_and_reasons() {
+        let filter = FileFilter::new(&FileFilterSettings {
+            extra_ignored_extensions: vec![".snap".into()],
+            extra_ignored_dirs: vec!["fixtures/".into()],
+            allowed_extensions: vec!["svg".into()],
+        });
+        let root = Path::new("/repo");
+        let matches = |path: &str| filter.matches(root, Path::new(path));
+        assert_eq!(matches("/repo/src/__snapshots__/ui.snap"), FilterDecision::Skip(FilterReason::IgnoredExtension));
+        assert_eq!(matches("/repo/tests/fixtures/a.rs"), FilterDecision::Skip(FilterReason::IgnoredDirectory));
+        assert_eq!(matches("/repo/app\\node_modules\\x.js"), FilterDecision::Skip(FilterReason::IgnoredDirectory));
+        assert_eq!(matches("/repo/assets/logo.svg"), FilterDecision::Analyze);
+        assert_eq!(
+            FileFilter::default().matches(root, Path::new("/repo/assets/logo.svg")),
+            FilterDecision::Skip(FilterReason::IgnoredExtension)
+        );
+    }
+
+    #[test]
+    fn test_file_filter_ignores_directories_above_the_root() {
+        let filter = FileFilter::new(&FileFilterSettings { extra_ignored_dirs: vec!["work".into()], ..Default::default() });
+        for root in ["/tmp/checkout", "/home/me/.cache/project", "/home/me/build/app", "/home/me/coverage/dist/app", "/work/app"] {
+            let root = Path::new(root);
+            assert!(filter.should_analyze(root, &root.join("src/lib.rs")), "{}", root.display());
+            assert!(!filter.should_analyze(root, &root.join("build/out.rs")), "{}", root.display());
+            assert!(!filter.should_analyze(root, &root.join("tmp/scratch.rs")), "{}", root.display());
+        }
+    }
+
+    #[test]
+    fn test_prefix_hash_ignores_bytes_past_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.bin");
+        let b = dir.path().join("b.bin");
+        std::fs::write(&a, b"same-prefix-AAAA").unwrap();
+        std::fs::write(&b, b"same-prefix-BBBB").unwrap();
+        let hash = |path: &Path| read_text_prefix(&path.to_string_lossy(), 11, PrefixOptions::default()).unwrap().prefix_hash;
+        assert_eq!(hash(&a), hash(&b));
+    }
+
+    #[test]
+    fn test_classify_file_covers_filename_table() {
+        for (name, language, role) in FILENAME_TABLE {
+            for path in [name.to_string(), format!("repo/sub/{}", name), format!("repo/{}", name.to_uppercase())] {
+                let class = classify_file(&path);
+                assert_eq!(class.language, *language, "{}", path);
+                assert_eq!(class.role, *role, "{}", path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_classify_file_patterns() {
+        let cases = [
+            ("docker/Dockerfile.dev", "Dockerfile", FileRole::Config),
+            ("api.dockerfile", "Dockerfile", FileRole::Config),
+            ("tsconfig.build.json", "TypeScript config", FileRole::Config),
+            (".github/workflows/ci.yml", "YAML", FileRole::Ci),
+            ("src/lib.rs", "Rust", FileRole::Source),
+            ("tests/api_test.rs", "Rust", FileRole::Test),
+            ("README.md", "Markdown", FileRole::Docs),
+            ("settings.yaml", "YAML", FileRole::Config),
+            ("vite.config.ts", "TypeScript", FileRole::Config),
+        ];
+        for (path, language, role) in cases {
+            let class = classify_file(path);
+            assert_eq!(class.language, language, "{}", path);
+            assert_eq!(class.role, role, "{}", path);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_prefix_hash_detects_single_byte_change(
+            content in proptest::collection::vec(any::<u8>(), 1..4096),
+            index in any::<prop::sample::Index>(),
+            flip in 1u8..=255,
+        ) {
+            let dir = tempfile::tempdir().unwrap();
+            let original = dir.path().join("original.bin");
+            let changed = dir.path().join("changed.bin");
+            let mut mutated = content.clone();
+            let i = index.index(mutated.len());
+            mutated[i] ^= flip;
+            std::fs::write(&original, &content).unwrap();
+            std::fs::write(&changed, &mutated).unwrap();
+
+            let cap = content.len();
+            let hash = |path: &Path| read_text_prefix(&path.to_string_lossy(), cap, PrefixOptions::default()).unwrap().prefix_hash;
+            prop_assert_ne!(hash(&original), hash(&changed));
+        }
+    }
+}
@@ -0,0 +1,441 @@
+// File-change notifications for a project root, shared by watch mode and
+// the caches that need to drop entries as files change between scans
+use notify::event::{EventKind, ModifyKind, RenameMode};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use super::{get_cached_overrides, FileFilter};
+
+// Quiet period that closes a batch; editors save in several steps
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+// A steady stream of writes still flushes at this multiple of the debounce
+const MAX_BATCH_DELAY_FACTOR: u32 = 10;
+
+// Cached entries across all watched roots before the cache starts over
+const METADATA_CACHE_CAPACITY: usize = 50_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FileChange {
+    Created { path: PathBuf },
+    Modified { path: PathBuf },
+    Removed { path: PathBuf },
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+impl FileChange {
+    fn single_path(&self) -> Option<&Path> {
+        match self {
+            FileChange::Created { path } | FileChange::Modified { path } | FileChange::Removed { path } => Some(path),
+            FileChange::Renamed { .. } => None,
+        }
+    }
+}
+
+// Watches a root until dropped; batches arrive on `next_batch`
+pub struct FileWatcher {
+    root: PathBuf,
+    _watcher: RecommendedWatcher,
+    batches: UnboundedReceiver<Vec<FileChange>>,
+}
+
+impl FileWatcher {
+    // None once the watcher has stopped
+    pub async fn next_batch(&mut self) -> Option<Vec<FileChange>> {
+        self.batches.recv().await
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        if let Ok(mut cache) = METADATA_CACHE.lock() {
+            cache.unwatch(&self.root);
+        }
+    }
+}
+
+// Start watching `root` recursively. Changes skipped by `FileFilter` or the
+// root's ignore overrides are dropped, and the metadata cache is invalidated
+// for every changed path, reported or not, before its batch is delivered
+pub fn watch(root: &Path, debounce: Duration) -> Result<FileWatcher, String> {
+    let canonical = std::fs::canonicalize(root).map_err(|e| format!("Cannot watch {}: {}", root.display(), e))?;
+    let (raw_tx, raw_rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(raw_tx).map_err(|e| e.to_string())?;
+    watcher.watch(&canonical, RecursiveMode::Recursive).map_err(|e| e.to_string())?;
+
+    let (batch_tx, batches) = unbounded_channel();
+    let context = BatchContext { root: root.to_path_buf(), canonical, filter: FileFilter::current() };
+    std::thread::Builder::new()
+        .name("repomuse-watch".to_string())
+        .spawn(move || debounce_loop(raw_rx, batch_tx, context, debounce))
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        cache.watch(root);
+    }
+    Ok(FileWatcher { root: root.to_path_buf(), _watcher: watcher, batches })
+}
+
+struct BatchContext {
+    // As given by the caller; emitted paths are rebased onto it
+    root: PathBuf,
+    canonical: PathBuf,
+    filter: FileFilter,
+}
+
+impl BatchContext {
+    // Backends may report canonical paths (macOS resolves /var to /private/var)
+    fn rebase(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.canonical)
+            .or_else(|_| path.strip_prefix(&self.root))
+            .map(|relative| self.root.join(relative))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    fn rebase_change(&self, change: FileChange) -> FileChange {
+        match change {
+            FileChange::Renamed { from, to } => FileChange::Renamed { from: self.rebase(&from), to: self.rebase(&to) },
+            FileChange::Created { path } => FileChange::Created { path: self.rebase(&path) },
+            FileChange::Modified { path } => FileChange::Modified { path: self.rebase(&path) },
+            FileChange::Removed { path } => FileChange::Removed { path: self.rebase(&path) },
+        }
+    }
+
+    // The part of a change callers see; a rename across the filter becomes
+    // a create or remove
+    fn keep(&self, change: FileChange) -> Option<FileChange> {
+        match change {
+            FileChange::Renamed { from, to } => match (self.wanted(&from), self.wanted(&to)) {
+                (true, true) => Some(FileChange::Renamed { from, to }),
+                (true, false) => Some(FileChange::Removed { path: from }),
+                (false, true) => Some(FileChange::Created { path: to }),
+                (false, false) => None,
+            },
+            change => change.single_path().is_some_and(|path| self.wanted(path)).then_some(change),
+        }
+    }
+
+    fn wanted(&self, path: &Path) -> bool {
+        if !self.filter.should_analyze(&self.root, path) {
+            return false;
+        }
+        get_cached_overrides(&self.root).is_none_or(|overrides| !overrides.matched(path, path.is_dir()).is_ignore())
+    }
+}
+
+fn debounce_loop(
+    raw: mpsc::Receiver<notify::Result<notify::Event>>,
+    batches: UnboundedSender<Vec<FileChange>>,
+    context: BatchContext,
+    debounce: Duration,
+) {
+    // Ends when the watcher (and with it the sending half) is dropped
+    while let Ok(first) = raw.recv() {
+        let started = Instant::now();
+        let mut changes = Vec::new();
+        push_event(&mut changes, first);
+        loop {
+            let remaining = (debounce * MAX_BATCH_DELAY_FACTOR).saturating_sub(started.elapsed());
+            match raw.recv_timeout(debounce.min(remaining)) {
+                Ok(event) => push_event(&mut changes, event),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        let changes: Vec<FileChange> = coalesce(changes).into_iter().map(|change| context.rebase_change(change)).collect();
+        // Before filtering: insights reads excluded files such as lockfiles
+        // through `cached_metadata` too
+        if let Ok(mut cache) = METADATA_CACHE.lock() {
+            changes.iter().for_each(|change| cache.invalidate(change));
+        }
+        let batch: Vec<FileChange> = changes.into_iter().filter_map(|change| context.keep(change)).collect();
+        if batch.is_empty() {
+            continue;
+        }
+        if batches.send(batch).is_err() {
+            return;
+        }
+    }
+}
+
+// Map a raw backend event to high-level changes. Access events are ignored
+fn push_event(changes: &mut Vec<FileChange>, event: notify::Result<notify::Event>) {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            crate::errors::report_error("watch", e, None);
+            return;
+        }
+    };
+    let paths = event.paths;
+    match event.kind {
+        EventKind::Create(_) => changes.extend(paths.into_iter().map(|path| FileChange::Created { path })),
+        EventKind::Remove(_) => changes.extend(paths.into_iter().map(|path| FileChange::Removed { path })),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+            let mut paths = paths.into_iter();
+            if let (Some(from), Some(to)) = (paths.next(), paths.next()) {
+                changes.push(FileChange::Renamed { from, to });
+            }
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            changes.extend(paths.into_iter().map(|path| FileChange::Removed { path }))
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+            changes.extend(paths.into_iter().map(|path| FileChange::Created { path }))
+        }
+        // Unpaired renames: whichever side still exists is the new name
+        EventKind::Modify(ModifyKind::Name(_)) => changes.extend(paths.into_iter().map(|path| {
+            if path.exists() { FileChange::Created { path } } else { FileChange::Removed { path } }
+        })),
+        EventKind::Modify(_) | EventKind::Any | EventKind::Other => {
+            changes.extend(paths.into_iter().map(|path| FileChange::Modified { path }))
+        }
+        EventKind::Access(_) => {}
+    }
+}
+
+// One entry per path and batch, in first-seen order: created then modified
+// is still created, created then removed never happened. A paired rename
+// replaces the removed/created halves some backends report beforehand
+fn coalesce(changes: Vec<FileChange>) -> Vec<FileChange> {
+    let mut slots: Vec<Option<FileChange>> = Vec::with_capacity(changes.len());
+    let mut latest: HashMap<PathBuf, usize> = HashMap::new();
+    for change in changes {
+        if let FileChange::Renamed { from, to } = &change {
+            if let Some(index) = latest.remove(from) {
+                if matches!(slots[index], Some(FileChange::Removed { .. })) {
+                    slots[index] = None;
+                }
+            }
+            if let Some(index) = latest.remove(to) {
+                if matches!(slots[index], Some(FileChange::Created { .. })) {
+                    slots[index] = None;
+                }
+            }
+            slots.push(Some(change));
+            continue;
+        }
+        let path = change.single_path().map(Path::to_path_buf).unwrap_or_default();
+        let Some(&index) = latest.get(&path) else {
+            latest.insert(path, slots.len());
+            slots.push(Some(change));
+            continue;
+        };
+        let merged = match (slots[index].take(), change) {
+            (Some(FileChange::Created { .. }), FileChange::Removed { .. }) => None,
+            (Some(FileChange::Created { path }), _) => Some(FileChange::Created { path }),
+            (Some(FileChange::Removed { path }), FileChange::Created { .. } | FileChange::Modified { .. }) => {
+                Some(FileChange::Modified { path })
+            }
+            (_, change) => Some(change),
+        };
+        if merged.is_none() {
+            latest.remove(&path);
+        }
+        slots[index] = merged;
+    }
+    slots.into_iter().flatten().collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedMetadata {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+// Metadata for files under watched roots, trusted until a change event for
+// the path arrives. Paths outside every watched root always go to disk
+static METADATA_CACHE: Lazy<Mutex<FileMetadataCache>> = Lazy::new(|| Mutex::new(FileMetadataCache::default()));
+
+#[derive(Default)]
+struct FileMetadataCache {
+    // Watcher count per root; the same project may be watched twice
+    roots: HashMap<PathBuf, usize>,
+    entries: HashMap<PathBuf, CachedMetadata>,
+}
+
+impl FileMetadataCache {
+    fn watch(&mut self, root: &Path) {
+        *self.roots.entry(root.to_path_buf()).or_insert(0) += 1;
+    }
+
+    fn unwatch(&mut self, root: &Path) {
+        let Some(count) = self.roots.get_mut(root) else { return };
+        *count -= 1;
+        if *count == 0 {
+            self.roots.remove(root);
+            self.entries.retain(|path, _| !path.starts_with(root));
+        }
+    }
+
+    fn is_watched(&self, path: &Path) -> bool {
+        self.roots.keys().any(|root| path.starts_with(root))
+    }
+
+    fn invalidate(&mut self, change: &FileChange) {
+        let mut forget = |path: &Path| self.entries.retain(|cached, _| !cached.starts_with(path));
+        match change {
+            FileChange::Renamed { from, to } => {
+                forget(from);
+                forget(to);
+            }
+            FileChange::Created { path } | FileChange::Modified { path } | FileChange::Removed { path } => forget(path),
+        }
+    }
+}
+
+// Size and modification time, from the cache when `path` is under a
+// watched root. Used by insights for the manifest/lockfile comparisons
+pub fn cached_metadata(path: &Path) -> Option<CachedMetadata> {
+    if let Ok(cache) = METADATA_CACHE.lock() {
+        if !cache.is_watched(path) {
+            drop(cache);
+            return read_metadata(path);
+        }
+        if let Some(entry) = cache.entries.get(path) {
+            return Some(*entry);
+        }
+    }
+    let entry = read_metadata(path)?;
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        if cache.entries.len() >= METADATA_CACHE_CAPACITY {
+            cache.entries.clear();
+        }
+        if cache.is_watched(path) {
+            cache.entries.insert(path.to_path_buf(), entry);
+        }
+    }
+    Some(entry)
+}
+
+fn read_metadata(path: &Path) -> Option<CachedMetadata> {
+    let metadata = super::metadata(path).ok()?;
+    Some(CachedMetadata { size: metadata.len(), modified: metadata.modified().ok() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created(path: &str) -> FileChange {
+        FileChange::Created { path: PathBuf::from(path) }
+    }
+
+    fn modified(path: &str) -> FileChange {
+        FileChange::Modified { path: PathBuf::from(path) }
+    }
+
+    fn removed(path: &str) -> FileChange {
+        FileChange::Removed { path: PathBuf::from(path) }
+    }
+
+    #[test]
+    fn test_coalesce_merges_per_path() {
+        let merged = coalesce(vec![
+            created("a"), modified("a"),
+            created("tmp"), removed("tmp"),
+            removed("b"), created("b"),
+            modified("c"), removed("c"),
+        ]);
+        assert_eq!(merged, vec![created("a"), modified("b"), removed("c")]);
+    }
+
+    #[test]
+    fn test_coalesce_replaces_rename_halves() {
+        let rename = FileChange::Renamed { from: PathBuf::from("old"), to: PathBuf::from("new") };
+        let merged = coalesce(vec![removed("old"), created("new"), rename.clone(), modified("new")]);
+        assert_eq!(merged, vec![rename, modified("new")]);
+    }
+
+    // Collect batches until `done` accepts everything seen so far
+    async fn collect_until(watcher: &mut FileWatcher, done: impl Fn(&[FileChange]) -> bool) -> Vec<FileChange> {
+        let mut seen = Vec::new();
+        let deadline = Duration::from_secs(10);
+        let _ = tokio::time::timeout(deadline, async {
+            while let Some(batch) = watcher.next_batch().await {
+                seen.extend(batch);
+                if done(&seen) {
+                    break;
+                }
+            }
+        })
+        .await;
+        seen
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_created_modified_renamed_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join("keep.rs"), "fn a() {}").unwrap();
+        let mut watcher = watch(&root, Duration::from_millis(100)).unwrap();
+
+        // Warm the cache so the rename below has an entry to evict
+        assert_eq!(cached_metadata(&root.join("keep.rs")).map(|m| m.size), Some(9));
+        assert!(METADATA_CACHE.lock().unwrap().entries.contains_key(&root.join("keep.rs")));
+
+        std::fs::write(root.join("new.rs"), "fn b() {}").unwrap();
+        std::fs::create_dir(root.join("node_modules")).unwrap();
+        std::fs::write(root.join("node_modules").join("dep.js"), "x").unwrap();
+        let seen = collect_until(&mut watcher, |seen| {
+            seen.iter().any(|c| matches!(c, FileChange::Created { path } if path.ends_with("new.rs")))
+        })
+        .await;
+        assert!(seen.iter().all(|c| c.single_path().is_none_or(|p| !p.to_string_lossy().contains("node_modules/"))));
+        assert!(seen.iter().all(|c| c.single_path().is_none_or(|p| p.starts_with(&root))));
+
+        std::fs::rename(root.join("keep.rs"), root.join("moved.rs")).unwrap();
+        let seen = collect_until(&mut watcher, |seen| {
+            seen.iter().any(|c| match c {
+                FileChange::Renamed { to, .. } | FileChange::Created { path: to } => to.ends_with("moved.rs"),
+                _ => false,
+            })
+        })
+        .await;
+        assert!(seen.iter().any(|c| match c {
+            FileChange::Renamed { from, .. } | FileChange::Removed { path: from } => from.ends_with("keep.rs"),
+            _ => false,
+        }));
+        assert!(!METADATA_CACHE.lock().unwrap().entries.contains_key(&root.join("keep.rs")));
+
+        std::fs::remove_file(root.join("new.rs")).unwrap();
+        let seen = collect_until(&mut watcher, |seen| {
+            seen.iter().any(|c| matches!(c, FileChange::Removed { path } if path.ends_with("new.rs")))
+        })
+        .await;
+        assert!(!seen.is_empty());
+
+        drop(watcher);
+        assert!(!METADATA_CACHE.lock().unwrap().is_watched(&root.join("moved.rs")));
+    }
+
+    #[tokio::test]
+    async fn test_watch_invalidates_metadata_for_excluded_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let excluded = root.join("logo.png");
+        std::fs::write(&excluded, "x").unwrap();
+        let mut watcher = watch(&root, Duration::from_millis(100)).unwrap();
+        assert_eq!(cached_metadata(&excluded).map(|m| m.size), Some(1));
+
+        // The excluded write is never reported, but it comes before the
+        // marker, so its invalidation has happened once the marker arrives
+        std::fs::write(&excluded, "xyz").unwrap();
+        std::fs::write(root.join("marker.rs"), "fn m() {}").unwrap();
+        let seen = collect_until(&mut watcher, |seen| {
+            seen.iter().any(|c| matches!(c, FileChange::Created { path } if path.ends_with("marker.rs")))
+        })
+        .await;
+        assert!(seen.iter().all(|c| c.single_path().is_none_or(|p| !p.ends_with("logo.png"))));
+        assert!(!METADATA_CACHE.lock().unwrap().entries.contains_key(&excluded));
+        assert_eq!(cached_metadata(&excluded).map(|m| m.size), Some(3));
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+// Plain git data shared by the insights commands and the git_info cache in
+// db.rs; the code that reads it from a repository lives in insights.rs
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GitRemote {
+    pub name: String,
+    pub url: String,
+    // Browsable https page for the repository; None for local or unrecognised URLs
+    pub web_url: Option<String>,
+    pub platform: Option<RemotePlatform>,
+    // Org or user; nested GitLab groups keep their full path ("group/subgroup")
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+}
+
+// The cheap subset of git state for overview cards; cached in git_info
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct GitSummary {
+    pub is_git_repo: bool,
+    pub current_branch: Option<String>,
+    pub commit_count: Option<usize>,
+    pub last_commit_date: Option<String>,
+    pub last_commit_message: Option<String>,
+    pub remotes: Vec<GitRemote>,
+    pub has_uncommitted_changes: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum RemotePlatform {
+    #[serde(rename = "github")]
+    GitHub,
+    #[serde(rename = "gitlab")]
+    GitLab,
+    #[serde(rename = "bitbucket")]
+    Bitbucket,
+    #[serde(rename = "gitea")]
+    Gitea,
+    #[serde(rename = "self-hosted")]
+    SelfHosted,
+}
@@ -0,0 +1,213 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{self, DbPool, Idea};
+use crate::storage;
+
+// Closed ideas quoted back to the model, most recent first
+const MAX_EXCLUDED_IDEAS: usize = 30;
+const MAX_EXCLUDED_IDEA_CHARS: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdeaStatus {
+    New,
+    Shortlisted,
+    InProgress,
+    Done,
+    Rejected,
+}
+
+impl IdeaStatus {
+    // Board column order
+    const ALL: [IdeaStatus; 5] = [
+        IdeaStatus::New,
+        IdeaStatus::Shortlisted,
+        IdeaStatus::InProgress,
+        IdeaStatus::Done,
+        IdeaStatus::Rejected,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            IdeaStatus::New => "new",
+            IdeaStatus::Shortlisted => "shortlisted",
+            IdeaStatus::InProgress => "in_progress",
+            IdeaStatus::Done => "done",
+            IdeaStatus::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IdeaColumn {
+    pub status: IdeaStatus,
+    pub ideas: Vec<Idea>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IdeaBoard {
+    pub project_path: String,
+    // One per status, in workflow order, even when empty
+    pub columns: Vec<IdeaColumn>,
+}
+
+fn board(conn: &Connection, project_path: &str) -> Result<IdeaBoard, String> {
+    let ideas = match db::get_project_by_path(conn, project_path).map_err(|e| e.to_string())? {
+        Some(project) => db::get_ideas(conn, project.id).map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+    let columns = IdeaStatus::ALL
+        .iter()
+        .map(|status| IdeaColumn {
+            status: *status,
+            ideas: ideas.iter().filter(|idea| idea.status == status.as_str()).cloned().collect(),
+        })
+        .collect();
+    Ok(IdeaBoard { project_path: project_path.to_string(), columns })
+}
+
+// Keep generated ideas on the project's board, remembering the focus area
+pub fn save_generated(
+    conn: &mut Connection,
+    project_path: &str,
+    ideas: &[String],
+    focus_area: Option<&str>,
+) -> Result<usize, String> {
+    let project_id = storage::get_or_create_project_id(conn, project_path)?;
+    let focus_area = focus_area.map(str::trim).filter(|f| !f.is_empty());
+    db::insert_ideas(conn, project_id, ideas, focus_area).map_err(|e| e.to_string())
+}
+
+// Prompt note listing done and rejected ideas so they aren't suggested again
+pub fn exclusion_note(conn: &Connection, project_path: &str) -> Option<String> {
+    let statuses = [IdeaStatus::Done.as_str(), IdeaStatus::Rejected.as_str()];
+    let texts = db::get_idea_texts_with_status(conn, project_path, &statuses, MAX_EXCLUDED_IDEAS).ok()?;
+    if texts.is_empty() {
+        return None;
+    }
+    let mut note = String::from("\n\nPreviously suggested ideas the user has already done or rejected (do NOT suggest these or close variants again):\n");
+    for text in texts {
+        let text: String = text.chars().take(MAX_EXCLUDED_IDEA_CHARS).collect();
+        note.push_str(&format!("- {}\n", text.trim()));
+    }
+    Some(note)
+}
+
+fn load_idea(conn: &Connection, id: i64) -> Result<(i64, Idea), String> {
+    db::get_idea(conn, id).map_err(|e| e.to_string())?.ok_or_else(|| format!("Unknown idea: {}", id))
+}
+
+fn move_to(conn: &mut Connection, id: i64, status: IdeaStatus, position: Option<usize>) -> Result<(), String> {
+    if !db::move_idea(conn, id, status.as_str(), position).map_err(|e| e.to_string())? {
+        return Err(format!("Unknown idea: {}", id));
+    }
+    Ok(())
+}
+
+// Moves the idea to the end of the status column. With `create_task`,
+// moving to in_progress also adds the idea to the task list (once)
+#[tauri::command]
+pub async fn set_idea_status(
+    db_pool: State<'_, Arc<DbPool>>,
+    id: i64,
+    status: IdeaStatus,
+    create_task: Option<bool>,
+) -> Result<Idea, String> {
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    let (project_id, idea) = load_idea(&conn, id)?;
+    if idea.status != status.as_str() {
+        move_to(&mut conn, id, status, None)?;
+    }
+    if status == IdeaStatus::InProgress && create_task.unwrap_or(false) && idea.task_id.is_none() {
+        let project_path: String = conn
+            .query_row("SELECT path FROM projects WHERE id = ?1", [project_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let task = storage::create_task_from_idea(&mut conn, project_id, &project_path, &idea.text)?;
+        db::set_idea_task(&conn, id, &task.task_id).map_err(|e| e.to_string())?;
+    }
+    Ok(load_idea(&conn, id)?.1)
+}
+
+// Drag and drop: `position` counts from 0 within the target column
+#[tauri::command]
+pub async fn reorder_idea(
+    db_pool: State<'_, Arc<DbPool>>,
+    id: i64,
+    status: IdeaStatus,
+    position: usize,
+) -> Result<(), String> {
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    move_to(&mut conn, id, status, Some(position))
+}
+
+#[tauri::command]
+pub async fn get_idea_board(db_pool: State<'_, Arc<DbPool>>, project_path: String) -> Result<IdeaBoard, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    board(&conn, &project_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(board: &IdeaBoard, status: IdeaStatus) -> Vec<String> {
+        let column = board.columns.iter().find(|c| c.status == status).unwrap();
+        column.ideas.iter().map(|idea| idea.text.clone()).collect()
+    }
+
+    #[test]
+    fn test_ideas_move_between_columns_in_order() {
+        let (_dir, pool) = db::test_pool();
+        let mut conn = pool.get().unwrap();
+        let texts: Vec<String> = ["a", "b", "c", "a"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(save_generated(&mut conn, "/p", &texts, None).unwrap(), 3);
+
+        let ids: Vec<i64> = board(&conn, "/p").unwrap().columns[0].ideas.iter().map(|idea| idea.id).collect();
+        assert_eq!(column(&board(&conn, "/p").unwrap(), IdeaStatus::New), vec!["a", "b", "c"]);
+
+        move_to(&mut conn, ids[2], IdeaStatus::Shortlisted, None).unwrap();
+        move_to(&mut conn, ids[0], IdeaStatus::Shortlisted, Some(0)).unwrap();
+        move_to(&mut conn, ids[1], IdeaStatus::New, Some(5)).unwrap();
+        let board = board(&conn, "/p").unwrap();
+        assert_eq!(column(&board, IdeaStatus::Shortlisted), vec!["a", "c"]);
+        assert_eq!(column(&board, IdeaStatus::New), vec!["b"]);
+        assert_eq!(board.columns.len(), IdeaStatus::ALL.len());
+    }
+
+    #[test]
+    fn test_closed_ideas_are_excluded_from_generation() {
+        let (_dir, pool) = db::test_pool();
+        let mut conn = pool.get().unwrap();
+        let texts: Vec<String> = ["Add CI", "Write docs", "Cache builds"].iter().map(|s| s.to_string()).collect();
+        save_generated(&mut conn, "/p", &texts, None).unwrap();
+        assert!(exclusion_note(&conn, "/p").is_none());
+
+        let ideas = db::get_ideas(&conn, db::get_project_by_path(&conn, "/p").unwrap().unwrap().id).unwrap();
+        move_to(&mut conn, ideas[0].id, IdeaStatus::Done, None).unwrap();
+        move_to(&mut conn, ideas[1].id, IdeaStatus::Rejected, None).unwrap();
+        let note = exclusion_note(&conn, "/p").unwrap();
+        assert!(note.contains("Add CI") && note.contains("Write docs"));
+        assert!(!note.contains("Cache builds"));
+    }
+
+    #[test]
+    fn test_deleting_a_task_unlinks_its_idea() {
+        let (_dir, pool) = db::test_pool();
+        let mut conn = pool.get().unwrap();
+        save_generated(&mut conn, "/p", &["Add CI".to_string()], None).unwrap();
+        let project_id = db::get_project_by_path(&conn, "/p").unwrap().unwrap().id;
+        let idea = db::get_ideas(&conn, project_id).unwrap().remove(0);
+
+        let task = storage::create_task_from_idea(&mut conn, project_id, "/p", &idea.text).unwrap();
+        assert!(task.created);
+        db::set_idea_task(&conn, idea.id, &task.task_id).unwrap();
+        let again = storage::create_task_from_idea(&mut conn, project_id, "/p", " add ci ").unwrap();
+        assert_eq!((again.task_id.as_str(), again.created), (task.task_id.as_str(), false));
+
+        db::save_task_list(&mut conn, project_id, &[]).unwrap();
+        assert_eq!(load_idea(&conn, idea.id).unwrap().1.task_id, None);
+    }
+}
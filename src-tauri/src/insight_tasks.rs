@@ -0,0 +1,199 @@
+use rusqlite::Connection;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{self, DbPool};
+use crate::fs_utils;
+use crate::insights::{self, ChangelogStatus, ProjectInsights};
+use crate::storage::{self, Task};
+
+pub const INSIGHT_SOURCE: &str = "insight";
+
+// (gap, insight aspect that detects it). A gap only counts as resolved when
+// its aspect was part of the refresh
+const GAPS: &[(&str, &str)] = &[
+    ("license", "packages"),
+    ("gitignore", "packages"),
+    ("ci", "ci"),
+    ("tests", "testing"),
+    ("changelog", "release"),
+];
+
+// (task text, description) while the gap is open, None once it's resolved
+fn open_gap(gap: &str, insights: &ProjectInsights) -> Option<(String, String)> {
+    let missing = |file: &str| insights.package_info.missing_common_files.iter().any(|f| f == file);
+    let testing = &insights.testing_info;
+    let release = &insights.release_info;
+    match gap {
+        "license" if missing("LICENSE") => Some((
+            "Add a LICENSE file".to_string(),
+            "No LICENSE at the project root, so it's unclear how others may use the code".to_string(),
+        )),
+        "gitignore" if missing(".gitignore") => Some((
+            "Add a .gitignore".to_string(),
+            "Without one, build output and local files are easy to commit by accident".to_string(),
+        )),
+        "ci" if !insights.ci_info.has_ci => Some((
+            "Set up continuous integration".to_string(),
+            "No CI configuration was found, so nothing checks changes automatically".to_string(),
+        )),
+        "tests" if !testing.has_test_files && !testing.has_testing_framework && testing.rust_test_fn_count == 0 => Some((
+            "Add automated tests".to_string(),
+            "No test files or testing framework were detected".to_string(),
+        )),
+        "changelog" if release.status == Some(ChangelogStatus::BehindTags) => {
+            let tag = release.latest_tag.as_deref().unwrap_or("the latest tag");
+            Some((
+                format!("Update the changelog for {}", tag),
+                format!(
+                    "The newest changelog entry ({}) is older than {}",
+                    release.latest_changelog_version.as_deref().unwrap_or("none"),
+                    tag
+                ),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// Tasks for the open gaps, skipping any that match an open task by
+// normalized text or by the gap it closes
+pub fn candidates(insights: &ProjectInsights, existing: &[Task]) -> Vec<Task> {
+    let open: Vec<&Task> = existing.iter().filter(|t| !t.completed).collect();
+    let now = chrono::Utc::now().to_rfc3339();
+    GAPS.iter()
+        .filter_map(|(gap, _)| Some((*gap, open_gap(gap, insights)?)))
+        .filter(|(gap, (text, _))| {
+            let normalized = storage::normalize_task_text(text);
+            !open.iter().any(|t| {
+                storage::normalize_task_text(&t.text) == normalized
+                    || (t.source.as_deref() == Some(INSIGHT_SOURCE) && t.source_ref.as_deref() == Some(*gap))
+            })
+        })
+        .map(|(gap, (text, description))| Task {
+            // Filled in by add_task when the suggestion is accepted
+            id: String::new(),
+            text,
+            completed: false,
+            created_at: now.clone(),
+            completed_at: None,
+            description: Some(description),
+            tags: Vec::new(),
+            due_date: None,
+            source: Some(INSIGHT_SOURCE.to_string()),
+            source_ref: Some(gap.to_string()),
+        })
+        .collect()
+}
+
+// Completes accepted insight tasks whose gap the refreshed `insights` no
+// longer show; `aspects` are the ones that were part of the refresh
+pub fn complete_resolved(
+    conn: &Connection,
+    project_path: &str,
+    insights: &ProjectInsights,
+    aspects: &Option<Vec<String>>,
+) -> Result<usize, String> {
+    let Some(project) = db::get_project_by_path(conn, project_path).map_err(|e| e.to_string())? else {
+        return Ok(0);
+    };
+    let resolved: Vec<&str> = GAPS
+        .iter()
+        .filter(|(gap, aspect)| insights::wants(aspects, aspect) && open_gap(gap, insights).is_none())
+        .map(|(gap, _)| *gap)
+        .collect();
+    db::complete_tasks_by_source(conn, project.id, INSIGHT_SOURCE, &resolved).map_err(|e| e.to_string())
+}
+
+// Task candidates for the gaps insights found, for one-click adding with
+// add_task. Open tasks covering the same gap are left out
+#[tauri::command]
+pub async fn suggest_tasks_from_insights(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<Vec<Task>, String> {
+    if !fs_utils::is_existing_dir(Path::new(&project_path)) {
+        return Err("Invalid project path".to_string());
+    }
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let aspects = Some(GAPS.iter().map(|(_, aspect)| aspect.to_string()).collect());
+        let insights = insights::cached_insights(&conn, Path::new(&project_path), &aspects, None, false)?;
+        let existing = match db::get_project_by_path(&conn, &project_path).map_err(|e| e.to_string())? {
+            Some(project) => db::load_task_list(&conn, project.id, &project_path)
+                .map_err(|e| e.to_string())?
+                .map(|list| list.tasks)
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        Ok(candidates(&insights, &existing))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gaps_everywhere() -> ProjectInsights {
+        let mut insights = ProjectInsights::default();
+        insights.package_info.missing_common_files = vec!["LICENSE".to_string(), ".gitignore".to_string()];
+        insights.release_info.status = Some(ChangelogStatus::BehindTags);
+        insights.release_info.latest_tag = Some("v1.2.0".to_string());
+        insights
+    }
+
+    #[test]
+    fn test_candidates_skip_gaps_with_open_tasks() {
+        let insights = gaps_everywhere();
+        let all = candidates(&insights, &[]);
+        let texts: Vec<&str> = all.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Add a LICENSE file",
+                "Add a .gitignore",
+                "Set up continuous integration",
+                "Add automated tests",
+                "Update the changelog for v1.2.0",
+            ]
+        );
+
+        let mut by_text = all[0].clone();
+        by_text.text = "add a  license FILE".to_string();
+        by_text.source = None;
+        let mut by_gap = all[2].clone();
+        by_gap.text = "Wire up GitHub Actions".to_string();
+        let mut done = all[3].clone();
+        done.completed = true;
+        let remaining = candidates(&insights, &[by_text, by_gap, done]);
+        let gaps: Vec<&str> = remaining.iter().filter_map(|t| t.source_ref.as_deref()).collect();
+        assert_eq!(gaps, vec!["gitignore", "tests", "changelog"]);
+    }
+
+    #[test]
+    fn test_resolved_gaps_complete_their_tasks() {
+        let (_dir, pool) = db::test_pool();
+        let mut conn = pool.get().unwrap();
+        let project_id = db::upsert_project(&conn, "/p", "p", None, false).unwrap();
+        let mut tasks = candidates(&gaps_everywhere(), &[]);
+        for (index, task) in tasks.iter_mut().enumerate() {
+            task.id = index.to_string();
+        }
+        db::append_tasks(&mut conn, project_id, &tasks).unwrap();
+
+        // The LICENSE appeared; CI wasn't part of this refresh
+        let mut refreshed = gaps_everywhere();
+        refreshed.package_info.missing_common_files = vec![".gitignore".to_string()];
+        let aspects = Some(vec!["packages".to_string(), "release".to_string()]);
+        assert_eq!(complete_resolved(&conn, "/p", &refreshed, &aspects).unwrap(), 1);
+
+        let stored = db::load_task_list(&conn, project_id, "/p").unwrap().unwrap().tasks;
+        let license = stored.iter().find(|t| t.source_ref.as_deref() == Some("license")).unwrap();
+        assert!(license.completed && license.completed_at.is_some());
+        assert_eq!(stored.iter().filter(|t| t.completed).count(), 1);
+    }
+}
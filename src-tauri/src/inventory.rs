@@ -0,0 +1,188 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{self, DbPool, TechnologyRecord};
+use crate::manifest::{self, Manifest};
+
+// Toolchain named by each ecosystem's manifest (engines.node and so on)
+fn toolchain_name(ecosystem: &str) -> &'static str {
+    match ecosystem {
+        "npm" => "node",
+        "cargo" => "rust",
+        _ => "python",
+    }
+}
+
+fn manifest_records(ecosystem: &str, manifest: &Manifest, records: &mut BTreeSet<TechnologyRecord>) {
+    let mut push = |kind: &str, name: &str, version: &str, dev: bool| {
+        records.insert(TechnologyRecord {
+            kind: kind.to_string(),
+            ecosystem: ecosystem.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+            dev,
+        });
+    };
+    for (name, version) in &manifest.runtime {
+        push("dependency", name, version, false);
+    }
+    for (name, version) in &manifest.dev {
+        push("dependency", name, version, true);
+    }
+    if let Some(toolchain) = &manifest.toolchain {
+        push("toolchain", toolchain_name(ecosystem), toolchain, false);
+    }
+}
+
+// Analysis technologies plus every dependency declared by the root manifest
+// and workspace members. Members are read one by one rather than merged so
+// differing versions of the same dependency are all kept
+pub fn collect(project_path: &Path, technologies: &[String]) -> Vec<TechnologyRecord> {
+    let mut records: BTreeSet<TechnologyRecord> = technologies
+        .iter()
+        .map(|name| TechnologyRecord {
+            kind: "technology".to_string(),
+            ecosystem: String::new(),
+            name: name.clone(),
+            version: String::new(),
+            dev: false,
+        })
+        .collect();
+    for (ecosystem, _) in manifest::ECOSYSTEMS {
+        let Some((root, members)) = manifest::read_workspace_members(project_path, ecosystem) else { continue };
+        manifest_records(ecosystem, &root, &mut records);
+        for member in members.iter().filter_map(|(_, member)| member.as_ref()) {
+            manifest_records(ecosystem, member, &mut records);
+        }
+    }
+    records.into_iter().collect()
+}
+
+// Rewrites the project's inventory; called after each analysis is cached
+pub fn refresh_project(conn: &mut Connection, project_id: i64, technologies: &[String]) -> Result<(), String> {
+    let path: String = conn
+        .query_row("SELECT path FROM projects WHERE id = ?1", [project_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let records = collect(Path::new(&path), technologies);
+    db::replace_project_technologies(conn, project_id, &records).map_err(|e| e.to_string())
+}
+
+// Indexes projects analyzed before the inventory existed
+fn backfill(conn: &mut Connection) -> Result<(), String> {
+    for (project_id, path) in db::get_projects_missing_technologies(conn).map_err(|e| e.to_string())? {
+        let technologies = db::get_cached_analysis_summary(conn, project_id)
+            .ok()
+            .flatten()
+            .map(|summary| summary.technologies)
+            .unwrap_or_default();
+        if let Err(e) = refresh_project(conn, project_id, &technologies) {
+            crate::errors::report_error("inventory", format!("Failed to index technologies: {}", e), Some(&path));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ProjectUsage {
+    pub project_path: String,
+    pub project_name: String,
+    // Declared requirements, e.g. ["^17.0.2"]; empty when none are known
+    pub versions: Vec<String>,
+    // Only ever declared as a dev dependency
+    pub dev_only: bool,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TechnologyUsage {
+    pub name: String,
+    pub kind: String,
+    // None for technologies detected by analysis
+    pub ecosystem: Option<String>,
+    pub projects: Vec<ProjectUsage>,
+}
+
+fn aggregate(records: Vec<(String, String, TechnologyRecord)>) -> Vec<TechnologyUsage> {
+    let mut grouped: BTreeMap<(String, String, String), BTreeMap<String, ProjectUsage>> = BTreeMap::new();
+    for (project_path, project_name, record) in records {
+        let projects = grouped.entry((record.name, record.kind, record.ecosystem)).or_default();
+        let usage = projects.entry(project_path.clone()).or_insert_with(|| ProjectUsage {
+            project_path,
+            project_name,
+            versions: Vec::new(),
+            dev_only: true,
+        });
+        usage.dev_only &= record.dev;
+        if !record.version.is_empty() && !usage.versions.contains(&record.version) {
+            usage.versions.push(record.version);
+        }
+    }
+    let mut inventory: Vec<TechnologyUsage> = grouped
+        .into_iter()
+        .map(|((name, kind, ecosystem), projects)| TechnologyUsage {
+            name,
+            kind,
+            ecosystem: (!ecosystem.is_empty()).then_some(ecosystem),
+            projects: projects.into_values().collect(),
+        })
+        .collect();
+    // Most widely used first
+    inventory.sort_by(|a, b| b.projects.len().cmp(&a.projects.len()).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    inventory
+}
+
+// Technology -> projects using it, across every analyzed project. `filter`
+// matches a substring of the name, case-insensitively
+#[tauri::command]
+pub async fn get_technology_inventory(
+    db_pool: State<'_, Arc<DbPool>>,
+    filter: Option<String>,
+) -> Result<Vec<TechnologyUsage>, String> {
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(|e| e.to_string())?;
+        backfill(&mut conn)?;
+        let filter = filter.as_deref().map(str::trim).filter(|f| !f.is_empty());
+        let records = db::get_technology_records(&conn, filter).map_err(|e| e.to_string())?;
+        Ok(aggregate(records))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inventory_groups_projects_and_member_versions() {
+        let (dir, pool) = db::test_pool();
+        let app = dir.path().join("app");
+        let mono = dir.path().join("mono");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::create_dir_all(mono.join("packages/legacy")).unwrap();
+        std::fs::write(app.join("package.json"), r#"{"dependencies": {"react": "^18.2.0"}, "devDependencies": {"vitest": "^1.0.0"}}"#).unwrap();
+        std::fs::write(mono.join("package.json"), r#"{"workspaces": ["packages/*"], "dependencies": {"react": "^18.2.0"}}"#).unwrap();
+        std::fs::write(mono.join("packages/legacy/package.json"), r#"{"dependencies": {"react": "^17.0.2"}}"#).unwrap();
+
+        let mut conn = pool.get().unwrap();
+        for (path, name) in [(&app, "app"), (&mono, "mono")] {
+            let id = db::upsert_project(&conn, &path.to_string_lossy(), name, None, false).unwrap();
+            refresh_project(&mut conn, id, &["React".to_string()]).unwrap();
+        }
+
+        let inventory = aggregate(db::get_technology_records(&conn, Some("REACT")).unwrap());
+        let react = inventory.iter().find(|t| t.name == "react").unwrap();
+        assert_eq!(react.ecosystem.as_deref(), Some("npm"));
+        assert_eq!(react.projects[0].versions, vec!["^18.2.0"]);
+        assert_eq!(react.projects[1].versions, vec!["^17.0.2", "^18.2.0"]);
+        assert!(inventory.iter().any(|t| t.name == "React" && t.kind == "technology" && t.projects.len() == 2));
+        assert!(!inventory.iter().any(|t| t.name == "vitest"));
+
+        let vitest = aggregate(db::get_technology_records(&conn, Some("vit")).unwrap());
+        assert!(vitest[0].projects[0].dev_only);
+    }
+}
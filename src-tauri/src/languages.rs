@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{self, DbPool};
+use crate::fs_utils::{self, get_language_from_extension, walker, FileFilter};
+
+// Data and markup formats left out of the bar unless explicitly requested,
+// mirroring GitHub's "programming languages only" default. The rest come
+// from `fs_utils::classify_file`'s file name table
+const NON_CODE_LANGUAGES: &[&str] = &[
+    "JSON", "YAML", "Markdown", "TOML", "XML",
+    "TypeScript config", "JavaScript config", "Go module", "EditorConfig", "Procfile",
+];
+const FALLBACK_COLOR: &str = "#cccccc";
+// Files looked at when estimating the primary language without an analysis
+pub const PRIMARY_LANGUAGE_MAX_FILES: usize = 5000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LanguageShare {
+    pub language: String,
+    pub bytes: u64,
+    // 0-100, one decimal place
+    pub percentage: f64,
+    pub color: String,
+}
+
+// Colors follow GitHub linguist so bars look familiar; every UI surface
+// takes the color from here rather than keeping its own table
+pub fn language_color(language: &str) -> &'static str {
+    match language {
+        "Rust" => "#dea584",
+        "JavaScript" => "#f1e05a",
+        "TypeScript" => "#3178c6",
+        "Python" => "#3572a5",
+        "Java" => "#b07219",
+        "C++" => "#f34b7d",
+        "C" => "#555555",
+        "Go" => "#00add8",
+        "PHP" => "#4f5d95",
+        "Ruby" => "#701516",
+        "C#" => "#178600",
+        "Swift" => "#f05138",
+        "Kotlin" => "#a97bff",
+        "HTML" => "#e34c26",
+        "CSS" => "#563d7c",
+        "SCSS" => "#c6538c",
+        "JSON" => "#292929",
+        "XML" => "#0060ac",
+        "YAML" => "#cb171e",
+        "TOML" => "#9c4221",
+        "Markdown" => "#083fa1",
+        "Scala" => "#c22d40",
+        "Elixir" => "#6e4a7e",
+        "Clojure" => "#db5855",
+        "Dart" => "#00b4ab",
+        "Zig" => "#ec915c",
+        "Lua" => "#000080",
+        "R" => "#198ce7",
+        "Julia" => "#a270ba",
+        "Nim" => "#ffc200",
+        "V" => "#4f87c4",
+        "Shell" => "#89e051",
+        "Fish" => "#4aae47",
+        _ => FALLBACK_COLOR,
+    }
+}
+
+pub fn is_code_language(language: &str) -> bool {
+    !NON_CODE_LANGUAGES.contains(&language)
+}
+
+// Percentage-by-bytes, largest first
+pub fn breakdown(size_by_language: &HashMap<String, u64>, include_non_code: bool) -> Vec<LanguageShare> {
+    let mut sizes: Vec<(&String, u64)> = size_by_language
+        .iter()
+        .filter(|(language, bytes)| {
+            **bytes > 0 && language.as_str() != "Unknown" && (include_non_code || is_code_language(language))
+        })
+        .map(|(language, bytes)| (language, *bytes))
+        .collect();
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let total: u64 = sizes.iter().map(|(_, bytes)| bytes).sum();
+    sizes
+        .into_iter()
+        .map(|(language, bytes)| LanguageShare {
+            language: language.clone(),
+            bytes,
+            percentage: (bytes as f64 * 1000.0 / total as f64).round() / 10.0,
+            color: language_color(language).to_string(),
+        })
+        .collect()
+}
+
+// Bytes per language from file metadata only; used when no cached analysis
+// exists so the bar never costs a content read
+pub fn scan_sizes(root: &Path, filter: &FileFilter) -> HashMap<String, u64> {
+    scan_sizes_limited(root, usize::MAX, filter)
+}
+
+// Like scan_sizes but stops after `max_files` files, which is plenty to
+// find the dominant language of a large repo
+pub fn scan_sizes_limited(root: &Path, max_files: usize, filter: &FileFilter) -> HashMap<String, u64> {
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+    let files = walker(root)
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()) && filter.should_analyze(root, e.path()))
+        .take(max_files);
+    for entry in files {
+        let path = entry.path().to_string_lossy();
+        let language = get_language_from_extension(&path);
+        if language == "Unknown" {
+            continue;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        *sizes.entry(language).or_insert(0) += size;
+    }
+    sizes
+}
+
+// Breakdown from the cached analysis when there is one, otherwise from a
+// metadata scan. The code-only breakdown is stored on the project row so
+// the listing can draw bars without loading analyses
+#[tauri::command]
+pub async fn get_language_breakdown(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    include_non_code: Option<bool>,
+) -> Result<Vec<LanguageShare>, String> {
+    if !fs_utils::is_existing_dir(Path::new(&project_path)) {
+        return Err("Invalid project path".to_string());
+    }
+
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let project = db::get_project_by_path(&conn, &project_path).map_err(|e| e.to_string())?;
+        let cached = project
+            .as_ref()
+            .and_then(|p| db::get_cached_analysis(&conn, p.id).ok().flatten());
+        let sizes = match cached {
+            Some(analysis) => analysis.size_metrics.size_by_language,
+            None => scan_sizes(Path::new(&project_path), &FileFilter::current()),
+        };
+
+        let code_only = breakdown(&sizes, false);
+        if let Some(project) = &project {
+            db::save_language_breakdown(&conn, project.id, &code_only).map_err(|e| e.to_string())?;
+        }
+        Ok(if include_non_code.unwrap_or(false) { breakdown(&sizes, true) } else { code_only })
+    })
+    .await
+    .map_err(|e| format!("Language breakdown task failed: {}", e))?
+}
+
+// Largest code language, or None when no recognised code was found
+pub fn primary_language(size_by_language: &HashMap<String, u64>) -> Option<LanguageShare> {
+    breakdown(size_by_language, false).into_iter().next()
+}
+
+// Recomputes the stored primary language from the cached analysis, or from
+// a capped metadata walk when there is none
+#[tauri::command]
+pub async fn refresh_primary_language(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<Option<LanguageShare>, String> {
+    if !fs_utils::is_existing_dir(Path::new(&project_path)) {
+        return Err("Invalid project path".to_string());
+    }
+
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let project = db::get_project_by_path(&conn, &project_path)
+            .map_err(|e| e.to_string())?
+            .ok_or("Project not found")?;
+        let sizes = match db::get_cached_analysis(&conn, project.id).ok().flatten() {
+            Some(analysis) => analysis.size_metrics.size_by_language,
+            None => scan_sizes_limited(Path::new(&project_path), PRIMARY_LANGUAGE_MAX_FILES, &FileFilter::current()),
+        };
+        let primary = primary_language(&sizes);
+        db::save_primary_language(&conn, project.id, primary.as_ref()).map_err(|e| e.to_string())?;
+        Ok(primary)
+    })
+    .await
+    .map_err(|e| format!("Primary language task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown_filters_non_code_and_sorts_by_bytes() {
+        let sizes: HashMap<String, u64> = [
+            ("TypeScript", 6000),
+            ("Rust", 3000),
+            ("JSON", 5000),
+            ("Unknown", 900),
+            ("CSS", 1000),
+        ]
+        .into_iter()
+        .map(|(l, b)| (l.to_string(), b))
+        .collect();
+
+        let shares = breakdown(&sizes, false);
+        let summary: Vec<(&str, f64)> = shares.iter().map(|s| (s.language.as_str(), s.percentage)).collect();
+        assert_eq!(summary, vec![("TypeScript", 60.0), ("Rust", 30.0), ("CSS", 10.0)]);
+        assert_eq!(shares[0].color, "#3178c6");
+
+        let all = breakdown(&sizes, true);
+        assert_eq!(all[1].language, "JSON");
+        assert_eq!(all.len(), 4);
+        assert_eq!(language_color("Brainfuck"), FALLBACK_COLOR);
+    }
+
+    #[test]
+    fn test_scan_sizes_uses_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn x() {}\n").unwrap();
+        std::fs::write(dir.path().join("logo.png"), [0u8; 64]).unwrap();
+
+        let filter = FileFilter::default();
+        let sizes = scan_sizes(dir.path(), &filter);
+        assert_eq!(sizes.get("Rust"), Some(&27));
+        assert_eq!(sizes.len(), 1);
+        // Walk order is unspecified; either source file alone is under the total
+        assert!(scan_sizes_limited(dir.path(), 1, &filter)["Rust"] < 27);
+        assert_eq!(primary_language(&sizes).map(|p| (p.language, p.percentage)), Some(("Rust".to_string(), 100.0)));
+    }
+}
@@ -0,0 +1,158 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use url::Url;
+
+use crate::db::{self, DbPool};
+use crate::fs_utils::{self, canonical_path};
+
+// `repomuse://project?path=/abs/path`, registered in tauri.conf.json
+pub const DEEP_LINK_SCHEME: &str = "repomuse";
+
+// A project asked for from outside the UI, held until the frontend takes
+// it; startup arguments arrive before the webview is listening
+static PENDING_OPEN: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+// Payload of `app:open_project`
+#[derive(Debug, Serialize, Clone)]
+struct OpenProjectRequest {
+    path: String,
+}
+
+// Path named by a `repomuse://project?path=...` link. Links come from
+// other apps, so only absolute paths (or ~/...) to existing directories count
+pub fn path_from_deep_link(url: &Url) -> Option<String> {
+    if url.scheme() != DEEP_LINK_SCHEME || url.host_str() != Some("project") {
+        return None;
+    }
+    let raw = url.query_pairs().find(|(key, _)| key == "path").map(|(_, value)| value.into_owned())?;
+    let path = match raw.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()?.join(rest),
+        None => PathBuf::from(&raw),
+    };
+    (path.is_absolute() && fs_utils::is_existing_dir(&path)).then(|| canonical_path(&path))
+}
+
+// First positional argument naming an existing directory, resolved
+// against the launching process's working directory, or a deep link
+// (Windows and Linux deliver those as arguments). Flags, including
+// macOS's -psn_ process serial number, are skipped
+pub fn project_from_args(args: &[String], cwd: &Path) -> Option<String> {
+    args.iter().skip(1).filter(|arg| !arg.starts_with('-')).find_map(|arg| {
+        if arg.starts_with(&format!("{}:", DEEP_LINK_SCHEME)) {
+            return Url::parse(arg).ok().and_then(|url| path_from_deep_link(&url));
+        }
+        let path = cwd.join(arg);
+        fs_utils::is_existing_dir(&path).then(|| canonical_path(&path))
+    })
+}
+
+pub fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+// Single entry point for opening a project from outside the UI: this
+// instance's own arguments at startup, and those a second launch forwards
+pub fn handle_args(app: &AppHandle, args: &[String], cwd: &Path) {
+    if let Some(path) = project_from_args(args, cwd) {
+        request_open(app, path);
+    }
+}
+
+// Deep links the OS hands to the running app directly (macOS)
+pub fn handle_urls(app: &AppHandle, urls: &[Url]) {
+    if let Some(path) = urls.iter().find_map(path_from_deep_link) {
+        focus_main_window(app);
+        request_open(app, path);
+    }
+}
+
+fn request_open(app: &AppHandle, path: String) {
+    if let Some(pool) = app.try_state::<Arc<DbPool>>() {
+        if let Err(e) = ensure_project(&pool, &path) {
+            crate::errors::report_error("launch", format!("Could not record project: {}", e), Some(&path));
+        }
+    }
+    tracing::info!(path = %path, "Opening project from command line or link");
+    if let Ok(mut pending) = PENDING_OPEN.lock() {
+        *pending = Some(path.clone());
+    }
+    let _ = app.emit("app:open_project", &OpenProjectRequest { path });
+}
+
+// Known projects are left alone; the frontend rescans on open
+fn ensure_project(pool: &DbPool, path: &str) -> Result<(), String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    if db::get_project_by_path(&conn, path).map_err(|e| e.to_string())?.is_some() {
+        return Ok(());
+    }
+    let dir = Path::new(path);
+    let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    db::upsert_project(&conn, path, &name, None, dir.join(".git").exists()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// The frontend calls this on mount and on each `app:open_project`, so a
+// request is opened exactly once however it arrived
+#[tauri::command]
+pub fn take_pending_open_project() -> Option<String> {
+    PENDING_OPEN.lock().ok().and_then(|mut pending| pending.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_project_from_args_resolves_relative_paths_and_skips_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("project")).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+
+        let found = project_from_args(&args(&["repomuse", "--verbose", "notes.txt", "project"]), dir.path());
+        assert_eq!(found, Some(canonical_path(&dir.path().join("project"))));
+
+        let absolute = dir.path().join("project").to_string_lossy().to_string();
+        assert!(project_from_args(&args(&["repomuse", &absolute]), Path::new("/")).is_some());
+    }
+
+    #[test]
+    fn test_project_from_args_ignores_the_executable_and_missing_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(project_from_args(&args(&["."]), dir.path()), None);
+        assert_eq!(project_from_args(&args(&["repomuse", "missing"]), dir.path()), None);
+        assert_eq!(project_from_args(&[], dir.path()), None);
+    }
+
+    #[test]
+    fn test_deep_links_need_an_existing_absolute_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut link = Url::parse("repomuse://project").unwrap();
+        link.query_pairs_mut().append_pair("path", &dir.path().to_string_lossy());
+        assert_eq!(path_from_deep_link(&link), Some(canonical_path(dir.path())));
+        assert_eq!(project_from_args(&args(&["repomuse", link.as_str()]), Path::new("/")), Some(canonical_path(dir.path())));
+
+        for rejected in [
+            "repomuse://project?path=relative/dir",
+            "repomuse://project",
+            "repomuse://settings?path=/",
+            "https://project?path=/",
+        ] {
+            assert_eq!(path_from_deep_link(&Url::parse(rejected).unwrap()), None, "{}", rejected);
+        }
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        let mut link = Url::parse("repomuse://project").unwrap();
+        link.query_pairs_mut().append_pair("path", &missing);
+        assert_eq!(path_from_deep_link(&link), None);
+    }
+}
@@ -0,0 +1,168 @@
+use once_cell::sync::OnceCell;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::db::{self, DbPool};
+
+const LOG_LEVEL_KEY: &str = "log_level";
+const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::INFO;
+
+// Daily files named repomuse.YYYY-MM-DD.log; older ones are deleted
+const LOG_FILE_PREFIX: &str = "repomuse";
+const LOG_FILE_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+
+const DEFAULT_RECENT_LINES: usize = 200;
+const MAX_RECENT_LINES: usize = 5_000;
+
+static LOG_DIR: OnceCell<PathBuf> = OnceCell::new();
+static LEVEL_HANDLE: OnceCell<reload::Handle<LevelFilter, Registry>> = OnceCell::new();
+
+// Install the global subscriber: a rolling file under `app_dir/logs`, plus
+// stderr in debug builds. Runs at the default level until
+// `apply_saved_level` reads the setting
+pub fn init(app_dir: &Path) {
+    let dir = app_dir.join("logs");
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir);
+    let file_layer = match appender {
+        Ok(appender) => Some(fmt::layer().with_writer(appender).with_ansi(false)),
+        Err(e) => {
+            eprintln!("File logging disabled: {}", e);
+            None
+        }
+    };
+    let stderr_layer = cfg!(debug_assertions).then(|| fmt::layer().with_writer(std::io::stderr));
+    let has_file = file_layer.is_some();
+
+    let (level, handle) = reload::Layer::new(DEFAULT_LOG_LEVEL);
+    if tracing_subscriber::registry().with(level).with(file_layer).with(stderr_layer).try_init().is_ok() {
+        let _ = LEVEL_HANDLE.set(handle);
+        if has_file {
+            let _ = LOG_DIR.set(dir);
+        }
+    }
+}
+
+pub fn apply_saved_level(db_pool: &DbPool) {
+    let saved = db_pool
+        .get()
+        .ok()
+        .and_then(|conn| db::load_setting(&conn, LOG_LEVEL_KEY).ok().flatten())
+        .and_then(|level| parse_level(&level).ok());
+    if let Some(level) = saved {
+        let _ = set_level(level);
+    }
+}
+
+// "off", "error", "warn", "info", "debug" or "trace", any case
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level.trim().to_lowercase().parse::<LevelFilter>().map_err(|_| format!("Unknown log level: {}", level))
+}
+
+fn level_name(level: LevelFilter) -> String {
+    level.to_string().to_lowercase()
+}
+
+fn set_level(level: LevelFilter) -> Result<(), String> {
+    let handle = LEVEL_HANDLE.get().ok_or("Logging is not initialized")?;
+    handle.modify(|current| *current = level).map_err(|e| e.to_string())
+}
+
+fn log_dir() -> Result<&'static PathBuf, String> {
+    LOG_DIR.get().ok_or_else(|| "File logging is not available".to_string())
+}
+
+// Last `count` lines across the log files, oldest first. Files sort by
+// their date suffix, so the newest is read first and older ones only as needed
+fn recent_lines(dir: &Path, count: usize) -> std::io::Result<Vec<String>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX))
+        })
+        .collect();
+    files.sort();
+
+    let mut lines: Vec<String> = Vec::with_capacity(count);
+    for file in files.iter().rev() {
+        let text = std::fs::read_to_string(file).unwrap_or_default();
+        let needed = count - lines.len();
+        let mut older: Vec<String> = text.lines().rev().take(needed).map(str::to_string).collect();
+        older.reverse();
+        older.append(&mut lines);
+        lines = older;
+        if lines.len() >= count {
+            break;
+        }
+    }
+    Ok(lines)
+}
+
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, String> {
+    let handle = LEVEL_HANDLE.get().ok_or("Logging is not initialized")?;
+    handle.clone_current().map(level_name).ok_or_else(|| "Logging is not initialized".to_string())
+}
+
+// Applies immediately and from the next startup; returns the normalized name
+#[tauri::command]
+pub async fn set_log_level(db_pool: State<'_, Arc<DbPool>>, level: String) -> Result<String, String> {
+    let level = parse_level(&level)?;
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::save_setting(&conn, LOG_LEVEL_KEY, &level_name(level)).map_err(|e| e.to_string())?;
+    set_level(level)?;
+    tracing::info!(level = %level_name(level), "Log level changed");
+    Ok(level_name(level))
+}
+
+#[tauri::command]
+pub async fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, String> {
+    let count = lines.unwrap_or(DEFAULT_RECENT_LINES).clamp(1, MAX_RECENT_LINES);
+    let dir = log_dir()?.clone();
+    tokio::task::spawn_blocking(move || recent_lines(&dir, count).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn open_log_directory() -> Result<(), String> {
+    tauri_plugin_opener::open_path(log_dir()?, None::<&str>).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level_accepts_any_case() {
+        assert_eq!(parse_level(" Debug "), Ok(LevelFilter::DEBUG));
+        assert_eq!(parse_level("off"), Ok(LevelFilter::OFF));
+        assert!(parse_level("verbose").is_err());
+        assert_eq!(level_name(LevelFilter::WARN), "warn");
+    }
+
+    #[test]
+    fn test_recent_lines_spans_rotated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("repomuse.2026-01-01.log"), "a1\na2\na3\n").unwrap();
+        std::fs::write(dir.path().join("repomuse.2026-01-02.log"), "b1\nb2\n").unwrap();
+        std::fs::write(dir.path().join("other.txt"), "x\n").unwrap();
+
+        assert_eq!(recent_lines(dir.path(), 1).unwrap(), vec!["b2"]);
+        assert_eq!(recent_lines(dir.path(), 3).unwrap(), vec!["a3", "b1", "b2"]);
+        assert_eq!(recent_lines(dir.path(), 100).unwrap().len(), 5);
+    }
+}
@@ -3,23 +3,58 @@
 mod fs_utils;
 mod db;
 mod analysis;
+mod auto_refresh;
+mod backup;
+mod background;
+mod bundle;
+mod ci;
+mod containers;
+mod diagnostics;
+mod errors;
+mod focus;
+mod freshness;
+mod git;
+mod ideas;
+mod insight_tasks;
 mod projects;
 mod storage;
 mod ai;
 mod insights;
+mod inventory;
+mod languages;
+mod launch;
+mod logging;
+mod export;
+mod maintenance;
+mod onboarding;
+mod manifest;
+mod outdated;
+mod performance;
+mod providers;
+mod redact;
+mod search;
+mod security;
+mod shutdown;
+mod theme;
+mod tray;
+mod usage;
+mod window_state;
+mod workspaces;
 
-use tauri::Manager;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
 use std::sync::Arc;
+use std::path::Path;
 
 fn main() {
-    // Tune rayon global thread pool to a sensible cap
-    let threads = std::thread::available_parallelism()
-        .map(|n| n.get())
-        .unwrap_or(4)
-        .min(8);
-    let _ = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global();
-
     tauri::Builder::default()
+        // Must come first: a second launch forwards its arguments here and exits
+        // before any other plugin or the database is touched
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            launch::focus_main_window(app);
+            launch::handle_args(app, &args, Path::new(&cwd));
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
@@ -33,20 +68,79 @@ fn main() {
                     .map_err(|e| format!("Failed to create app directory: {}", e))?;
             }
             
+            // Logging first so database recovery is recorded
+            logging::init(&app_dir);
+            errors::init(app.handle());
+            
             let db_path = app_dir.join("repomuse.db");
             let db_pool = db::init_db_pool(&db_path)
                 .map_err(|e| format!("Failed to initialize database: {}", e))?;
+            logging::apply_saved_level(&db_pool);
+            
+            // Size the rayon pool and walkers from the stored performance settings
+            performance::init(&db_pool);
+            // Ignore patterns, the hidden allowlist and file filter rules apply from the first walk
+            projects::init_walk_settings(&db_pool);
+            
+            // Restore last session's window geometry (maximized on first run)
+            if let Some(window) = app.get_webview_window("main") {
+                window_state::restore(&window.as_ref().window(), &db_pool);
+            }
+            
+            // Tray menu and the minimize-to-tray setting
+            tray::init(app.handle(), &db_pool);
+            
+            // Every AI request goes through one queue, paced by the saved limits
+            let limits = db_pool
+                .get()
+                .ok()
+                .and_then(|conn| storage::stored_settings(&conn).ok())
+                .map(|settings| ai::queue::QueueLimits::from_settings(&settings))
+                .unwrap_or_else(|| ai::queue::QueueLimits::from_settings(&storage::Settings::default()));
+            let handle = app.handle().clone();
+            app.manage(ai::queue::RequestQueue::start(
+                limits,
+                Arc::new(move |status| {
+                    let _ = handle.emit("ai:queue_status", status);
+                }),
+            ));
             
             // Store database pool in app state
             app.manage(Arc::new(db_pool));
             
-            // Maximize the main window on startup (not fullscreen)
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.maximize();
+            // Automatic backups (no-op until a schedule is configured)
+            backup::spawn_scheduler(app.handle().clone());
+            
+            // Per-project re-analysis (no-op until a project opts in)
+            auto_refresh::spawn_scheduler(app.handle().clone());
+            
+            // Scheduled light/dark switching (no-op without a schedule)
+            theme::spawn_scheduler(app.handle().clone());
+            
+            // `repomuse /path/to/project` and repomuse:// links open that project
+            let args: Vec<String> = std::env::args().collect();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            launch::handle_args(app.handle(), &args, &cwd);
+            // Installers register the scheme; this covers dev and portable builds
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                tracing::warn!(error = %e, "Could not register the repomuse:// scheme");
             }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| launch::handle_urls(&handle, &event.urls()));
             
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                window_state::save(window);
+                // Keep background work running; the tray's Quit exits
+                if tray::minimize_to_tray() {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             projects::list_project_directories,
             analysis::analyze_repository,
@@ -55,31 +149,125 @@ fn main() {
             analysis::trigger_full_scan,
             analysis::cancel_analysis,
             analysis::analyze_multiple_repositories,
+            analysis::get_analysis_metrics,
             ai::generate_ideas,
             storage::save_settings,
             storage::load_settings,
             ai::load_models,
+            ai::refresh_models,
+            ai::clear_model_cache,
+            providers::get_provider_presets,
+            providers::detect_local_servers,
             ai::generate_project_summary,
             storage::save_theme_preference,
             storage::load_theme_preference,
+            theme::save_theme_settings,
+            theme::load_theme_settings,
             storage::save_project_summary,
             storage::load_project_summary,
             storage::save_root_folder,
             storage::load_root_folder,
             storage::save_task_list,
             storage::load_task_list,
+            storage::import_tasks,
+            storage::import_tasks_from_markers,
+            export::export_tasks,
+            export::export_all_tasks,
+            storage::update_project_description,
+            storage::update_project_name,
             storage::save_favorite_projects,
             storage::load_favorite_projects,
             projects::update_project_file_count,
+            projects::watch_project,
+            projects::unwatch_project,
+            projects::record_project_opened,
+            projects::get_recent_projects,
+            projects::get_most_opened_projects,
+            projects::add_ignored_project,
+            projects::remove_ignored_project,
+            projects::list_ignored_projects,
+            projects::get_stale_projects,
+            projects::rescan_project,
+            projects::bulk_project_action,
+            projects::get_ignore_patterns,
+            projects::save_ignore_patterns,
+            projects::get_file_filter_settings,
+            projects::save_file_filter_settings,
+            projects::get_hidden_allowlist,
+            projects::save_hidden_allowlist,
+            projects::list_all_projects_paged,
+            projects::get_stale_after_months,
+            projects::save_stale_after_months,
+            languages::get_language_breakdown,
+            languages::refresh_primary_language,
             insights::get_project_insights,
+            insights::get_insights_batch,
+            insights::get_project_git_summary,
+            security::get_security_scan,
+            outdated::check_outdated_dependencies,
             insights::get_git_log,
+            insights::get_git_tags,
+            insights::get_contributor_stats,
+            insights::get_contributor_aliases,
+            insights::save_contributor_aliases,
+            insights::get_ownership_summary,
             storage::clear_all_data,
             storage::get_app_data_directory,
             storage::get_database_stats,
             storage::vacuum_database,
             storage::clear_expired_cache,
-            storage::optimize_database
+            storage::optimize_database,
+            maintenance::run_maintenance,
+            performance::get_performance_info,
+            performance::save_performance_settings,
+            backup::get_backup_schedule,
+            backup::save_backup_schedule,
+            backup::backup_database,
+            backup::list_backups,
+            backup::restore_backup,
+            bundle::export_app_data,
+            bundle::import_app_data,
+            window_state::reset_window_state,
+            launch::take_pending_open_project,
+            onboarding::get_onboarding_state,
+            onboarding::skip_onboarding,
+            onboarding::suggest_root_folders,
+            diagnostics::get_diagnostics,
+            diagnostics::get_diagnostics_text,
+            errors::get_recent_errors,
+            errors::clear_recent_errors,
+            logging::get_log_level,
+            logging::set_log_level,
+            logging::get_recent_logs,
+            logging::open_log_directory,
+            analysis::reanalyze_favorites,
+            background::get_background_paused,
+            background::set_background_paused,
+            tray::get_minimize_to_tray,
+            tray::set_minimize_to_tray,
+            usage::get_usage_stats,
+            usage::reset_usage_stats,
+            auto_refresh::set_project_auto_refresh,
+            auto_refresh::list_auto_refresh_projects,
+            auto_refresh::list_project_activity,
+            ideas::set_idea_status,
+            ideas::reorder_idea,
+            ideas::get_idea_board,
+            freshness::check_summary_freshness,
+            inventory::get_technology_inventory,
+            search::search_code,
+            focus::suggest_next_focus,
+            storage::add_task,
+            storage::add_idea_to_tasks,
+            insight_tasks::suggest_tasks_from_insights,
+            export::export_project_report
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Quitting waits briefly for in-flight analyses and backups
+            if let tauri::RunEvent::ExitRequested { api, code, .. } = event {
+                shutdown::on_exit_requested(app, &api, code);
+            }
+        });
 }
\ No newline at end of file
@@ -0,0 +1,207 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{Emitter, State};
+
+use crate::analysis::{is_analysis_running, CURRENT_ANALYSIS_SCHEMA_VERSION};
+use crate::db::DbPool;
+
+// Only one maintenance run at a time, and no analysis while one runs
+static MAINTENANCE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaintenanceOptions {
+    #[serde(default = "default_true")]
+    pub clear_expired_cache: bool,
+    #[serde(default = "default_true")]
+    pub optimize: bool,
+    #[serde(default = "default_true")]
+    pub vacuum: bool,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for MaintenanceOptions {
+    fn default() -> Self {
+        Self { clear_expired_cache: true, optimize: true, vacuum: true }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaintenanceStepReport {
+    pub step: String,
+    pub duration_ms: u64,
+    pub reclaimed_bytes: i64,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaintenanceReport {
+    pub steps: Vec<MaintenanceStepReport>,
+    pub size_before_bytes: i64,
+    pub size_after_bytes: i64,
+    pub reclaimed_bytes: i64,
+    pub total_duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct MaintenanceProgress<'a> {
+    step: &'a str,
+    index: usize,
+    total: usize,
+    status: &'a str,
+    duration_ms: Option<u64>,
+    reclaimed_bytes: Option<i64>,
+}
+
+// Clears MAINTENANCE_RUNNING however the run ends
+pub struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        MAINTENANCE_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+pub fn is_maintenance_running() -> bool {
+    MAINTENANCE_RUNNING.load(Ordering::SeqCst)
+}
+
+// Taken by every maintenance entry point for as long as it touches the
+// database. The analysis check comes after taking the flag, and analyses
+// check the flag after registering, so the two can never both proceed
+pub fn begin() -> Result<RunningGuard, String> {
+    if MAINTENANCE_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Maintenance is already running".to_string());
+    }
+    let guard = RunningGuard;
+    if is_analysis_running() {
+        return Err("An analysis is in progress; try maintenance again once it finishes".to_string());
+    }
+    Ok(guard)
+}
+
+pub fn database_size_bytes(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    ).unwrap_or(0)
+}
+
+pub fn clear_expired_cache(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    // Rows from another schema version can never be read back
+    let analyses = conn.execute(
+        "DELETE FROM analysis_cache WHERE expires_at < CURRENT_TIMESTAMP OR schema_version != ?1",
+        [CURRENT_ANALYSIS_SCHEMA_VERSION],
+    )?;
+    // Insight rows are only trusted for minutes; anything a day old is dead weight
+    let insights = conn.execute("DELETE FROM insights_cache WHERE computed_at < datetime('now', '-1 day')", [])?;
+    Ok(analyses + insights)
+}
+
+pub fn optimize(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch("ANALYZE; PRAGMA optimize;")
+}
+
+// Returns the number of bytes reclaimed
+pub fn vacuum(conn: &Connection) -> Result<i64, rusqlite::Error> {
+    let before = database_size_bytes(conn);
+    conn.execute("VACUUM", [])?;
+    Ok(before - database_size_bytes(conn))
+}
+
+fn run_step(conn: &Connection, step: &str) -> Result<(i64, String), String> {
+    match step {
+        "clear_expired_cache" => {
+            let before = database_size_bytes(conn);
+            let deleted = clear_expired_cache(conn).map_err(|e| e.to_string())?;
+            Ok((before - database_size_bytes(conn), format!("Cleared {} expired cache entries", deleted)))
+        }
+        "optimize" => {
+            optimize(conn).map_err(|e| e.to_string())?;
+            Ok((0, "Updated query planner statistics".to_string()))
+        }
+        "vacuum" => {
+            let reclaimed = vacuum(conn).map_err(|e| e.to_string())?;
+            Ok((reclaimed, format!("Reclaimed {:.2} MB", reclaimed as f64 / (1024.0 * 1024.0))))
+        }
+        other => Err(format!("Unknown maintenance step: {}", other)),
+    }
+}
+
+fn run_steps(
+    conn: &Connection,
+    steps: &[&str],
+    window: &tauri::Window,
+) -> Result<MaintenanceReport, String> {
+    let started = Instant::now();
+    let size_before_bytes = database_size_bytes(conn);
+    let mut reports = Vec::with_capacity(steps.len());
+
+    for (index, step) in steps.iter().enumerate() {
+        let _ = window.emit("maintenance:progress", &MaintenanceProgress {
+            step,
+            index,
+            total: steps.len(),
+            status: "started",
+            duration_ms: None,
+            reclaimed_bytes: None,
+        });
+
+        let step_started = Instant::now();
+        let (reclaimed_bytes, detail) = run_step(conn, step)?;
+        let duration_ms = step_started.elapsed().as_millis() as u64;
+
+        let _ = window.emit("maintenance:progress", &MaintenanceProgress {
+            step,
+            index,
+            total: steps.len(),
+            status: "finished",
+            duration_ms: Some(duration_ms),
+            reclaimed_bytes: Some(reclaimed_bytes),
+        });
+
+        reports.push(MaintenanceStepReport {
+            step: step.to_string(),
+            duration_ms,
+            reclaimed_bytes,
+            detail,
+        });
+    }
+
+    let size_after_bytes = database_size_bytes(conn);
+    Ok(MaintenanceReport {
+        steps: reports,
+        size_before_bytes,
+        size_after_bytes,
+        reclaimed_bytes: size_before_bytes - size_after_bytes,
+        total_duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[tauri::command]
+pub async fn run_maintenance(
+    db_pool: State<'_, Arc<DbPool>>,
+    window: tauri::Window,
+    options: Option<MaintenanceOptions>,
+) -> Result<MaintenanceReport, String> {
+    let guard = begin()?;
+
+    let options = options.unwrap_or_default();
+    let mut steps = Vec::new();
+    if options.clear_expired_cache { steps.push("clear_expired_cache"); }
+    if options.optimize { steps.push("optimize"); }
+    if options.vacuum { steps.push("vacuum"); }
+
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let _guard = guard;
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        run_steps(&conn, &steps, &window)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
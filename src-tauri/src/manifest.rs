@@ -0,0 +1,429 @@
+use serde_json::Value as Json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value as Toml;
+
+// Ecosystem name and the manifest that marks a directory as part of it
+pub const ECOSYSTEMS: &[(&str, &str)] = &[
+    ("npm", "package.json"),
+    ("cargo", "Cargo.toml"),
+    ("python", "pyproject.toml"),
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    // File the manifest was read from, e.g. "pyproject.toml" or "requirements.txt"
+    pub file: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    // Dependency name -> declared requirement ("" when none is given)
+    pub runtime: BTreeMap<String, String>,
+    pub dev: BTreeMap<String, String>,
+    pub workspace_globs: Vec<String>,
+    // engines.node, rust-version or requires-python
+    pub toolchain: Option<String>,
+    // package.json "packageManager", e.g. "pnpm@9.1.0"
+    pub package_manager: Option<String>,
+}
+
+fn json_deps(value: &Json) -> impl Iterator<Item = (String, String)> + '_ {
+    value
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, req)| (name.clone(), req.as_str().unwrap_or("").to_string()))
+}
+
+// `name = "1.0"` or `name = { version = "1.0", ... }`; workspace-inherited
+// entries report "workspace"
+fn toml_deps(value: Option<&Toml>) -> impl Iterator<Item = (String, String)> + '_ {
+    value.and_then(Toml::as_table).into_iter().flatten().map(|(name, spec)| {
+        let req = match spec {
+            Toml::String(req) => req.clone(),
+            Toml::Table(table) if table.get("workspace").and_then(Toml::as_bool) == Some(true) => "workspace".to_string(),
+            Toml::Table(table) => toml_str(table.get("version")).unwrap_or_default(),
+            _ => String::new(),
+        };
+        (name.clone(), req)
+    })
+}
+
+fn toml_str(value: Option<&Toml>) -> Option<String> {
+    value.and_then(Toml::as_str).map(str::to_string)
+}
+
+// (name, version specifier) from a PEP 508 requirement such as
+// "requests[socks]>=2.31; python_version > '3.8'"
+pub fn pep508_requirement(requirement: &str) -> Option<(String, String)> {
+    let requirement = requirement.split(';').next().unwrap_or("").trim();
+    let name: String = requirement
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    if name.is_empty() {
+        return None;
+    }
+    let rest = requirement[name.len()..].trim_start();
+    let specifier = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map(|(_, s)| s).unwrap_or(""),
+        None => rest,
+    };
+    Some((name.to_lowercase().replace('_', "-"), specifier.trim().to_string()))
+}
+
+fn pep508_deps(value: Option<&Toml>) -> impl Iterator<Item = (String, String)> + '_ {
+    value
+        .and_then(Toml::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|r| r.as_str().and_then(pep508_requirement))
+}
+
+pub fn parse_package_json(text: &str) -> Option<Manifest> {
+    let json: Json = serde_json::from_str(text).ok()?;
+    let mut manifest = Manifest {
+        file: "package.json".to_string(),
+        name: json["name"].as_str().map(str::to_string),
+        version: json["version"].as_str().map(str::to_string),
+        description: json["description"].as_str().map(str::to_string),
+        toolchain: json["engines"]["node"].as_str().map(str::to_string),
+        package_manager: json["packageManager"].as_str().map(str::to_string),
+        ..Default::default()
+    };
+    manifest.runtime.extend(json_deps(&json["dependencies"]));
+    manifest.runtime.extend(json_deps(&json["optionalDependencies"]));
+    manifest.dev.extend(json_deps(&json["devDependencies"]));
+    // `workspaces` is either a list of globs or { packages: [...] } (yarn classic)
+    let workspaces = json["workspaces"].as_array().or_else(|| json["workspaces"]["packages"].as_array());
+    manifest.workspace_globs = workspaces
+        .into_iter()
+        .flatten()
+        .filter_map(|g| g.as_str().map(str::to_string))
+        .collect();
+    Some(manifest)
+}
+
+pub fn parse_cargo_toml(text: &str) -> Option<Manifest> {
+    let doc: Toml = toml::from_str(text).ok()?;
+    let package = doc.get("package");
+    let workspace = doc.get("workspace");
+    // `rust-version.workspace = true` inherits from [workspace.package]
+    let rust_version = toml_str(package.and_then(|p| p.get("rust-version")))
+        .or_else(|| toml_str(workspace.and_then(|w| w.get("package")).and_then(|p| p.get("rust-version"))));
+
+    let mut manifest = Manifest {
+        file: "Cargo.toml".to_string(),
+        name: toml_str(package.and_then(|p| p.get("name"))),
+        version: toml_str(package.and_then(|p| p.get("version"))),
+        description: toml_str(package.and_then(|p| p.get("description"))),
+        toolchain: rust_version,
+        ..Default::default()
+    };
+
+    let targets = doc.get("target").and_then(Toml::as_table);
+    let tables = std::iter::once(&doc).chain(targets.into_iter().flat_map(|t| t.values()));
+    for table in tables {
+        manifest.runtime.extend(toml_deps(table.get("dependencies")));
+        manifest.dev.extend(toml_deps(table.get("dev-dependencies")));
+        manifest.dev.extend(toml_deps(table.get("build-dependencies")));
+    }
+    manifest.workspace_globs = workspace
+        .and_then(|w| w.get("members"))
+        .and_then(Toml::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.as_str().map(str::to_string))
+        .collect();
+    Some(manifest)
+}
+
+// PEP 621 `[project]` plus Poetry's `[tool.poetry]` tables
+pub fn parse_pyproject(text: &str) -> Option<Manifest> {
+    let doc: Toml = toml::from_str(text).ok()?;
+    let project = doc.get("project");
+    let poetry = doc.get("tool").and_then(|t| t.get("poetry"));
+    let field = |key: &str| {
+        toml_str(project.and_then(|p| p.get(key))).or_else(|| toml_str(poetry.and_then(|p| p.get(key))))
+    };
+
+    let poetry_deps = poetry.and_then(|p| p.get("dependencies"));
+    let mut manifest = Manifest {
+        file: "pyproject.toml".to_string(),
+        name: field("name"),
+        version: field("version"),
+        description: field("description"),
+        toolchain: toml_str(project.and_then(|p| p.get("requires-python")))
+            .or_else(|| toml_str(poetry_deps.and_then(|d| d.get("python")))),
+        ..Default::default()
+    };
+
+    manifest.runtime.extend(pep508_deps(project.and_then(|p| p.get("dependencies"))));
+    for extra in project.and_then(|p| p.get("optional-dependencies")).and_then(Toml::as_table).into_iter().flat_map(|t| t.values()) {
+        manifest.dev.extend(pep508_deps(Some(extra)));
+    }
+    for group in doc.get("dependency-groups").and_then(Toml::as_table).into_iter().flat_map(|t| t.values()) {
+        manifest.dev.extend(pep508_deps(Some(group)));
+    }
+
+    manifest.runtime.extend(toml_deps(poetry_deps).filter(|(name, _)| name != "python"));
+    manifest.dev.extend(toml_deps(poetry.and_then(|p| p.get("dev-dependencies"))));
+    for group in poetry.and_then(|p| p.get("group")).and_then(Toml::as_table).into_iter().flat_map(|t| t.values()) {
+        manifest.dev.extend(toml_deps(group.get("dependencies")));
+    }
+    Some(manifest)
+}
+
+// requirements.txt style: one requirement per line; options and includes are skipped
+pub fn parse_requirements(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('-'))
+        .filter_map(pep508_requirement)
+        .collect()
+}
+
+// `packages:` list from pnpm-workspace.yaml, read with a line scan
+pub fn parse_pnpm_workspace(text: &str) -> Vec<String> {
+    let mut globs = Vec::new();
+    let mut in_packages = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with(' ') && !line.starts_with('-') && !trimmed.is_empty() {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                globs.push(item.trim().trim_matches('"').trim_matches('\'').to_string());
+            }
+        }
+    }
+    globs
+}
+
+// Manifest for `ecosystem` in `dir`. Python falls back to requirements.txt
+// (with requirements-dev.txt as dev dependencies) when there is no pyproject
+pub fn read_manifest(dir: &Path, ecosystem: &str) -> Option<Manifest> {
+    let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+    match ecosystem {
+        "npm" => parse_package_json(&read("package.json")?),
+        "cargo" => parse_cargo_toml(&read("Cargo.toml")?),
+        "python" => read("pyproject.toml").and_then(|t| parse_pyproject(&t)).or_else(|| {
+            let runtime = parse_requirements(&read("requirements.txt")?);
+            let dev = read("requirements-dev.txt").map(|t| parse_requirements(&t)).unwrap_or_default();
+            Some(Manifest { file: "requirements.txt".to_string(), runtime, dev, ..Default::default() })
+        }),
+        _ => None,
+    }
+}
+
+// Member directories of a workspace root, in glob order. Negated globs
+// (pnpm's "!packages/legacy") exclude, and nothing under node_modules counts
+pub fn workspace_member_dirs(root: &Path, ecosystem: &str, manifest: &Manifest) -> Vec<PathBuf> {
+    let mut globs = manifest.workspace_globs.clone();
+    if ecosystem == "npm" {
+        if let Ok(text) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+            globs.extend(parse_pnpm_workspace(&text));
+        }
+    }
+    let manifest_file = ECOSYSTEMS
+        .iter()
+        .find(|(name, _)| *name == ecosystem)
+        .map(|(_, file)| *file)
+        .unwrap_or("package.json");
+
+    // The root is escaped so brackets or wildcards in the project path match literally
+    let base = PathBuf::from(glob::Pattern::escape(&root.to_string_lossy()));
+    let expand = |pattern: &str| -> Vec<PathBuf> {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        glob::glob(&base.join(pattern).to_string_lossy())
+            .map(|paths| paths.filter_map(|p| p.ok()).collect())
+            .unwrap_or_default()
+    };
+    let excluded: BTreeSet<PathBuf> = globs
+        .iter()
+        .filter_map(|g| g.strip_prefix('!'))
+        .flat_map(&expand)
+        .collect();
+
+    let mut members: Vec<PathBuf> = Vec::new();
+    for pattern in globs.iter().filter(|g| !g.starts_with('!')) {
+        for dir in expand(pattern) {
+            let in_node_modules = dir
+                .strip_prefix(root)
+                .map(|rel| rel.components().any(|c| c.as_os_str() == "node_modules"))
+                .unwrap_or(true);
+            if dir.join(manifest_file).is_file() && !in_node_modules && !excluded.contains(&dir) && !members.contains(&dir) {
+                members.push(dir);
+            }
+        }
+    }
+    members
+}
+
+// Root manifest plus each member directory with its own manifest, unmerged.
+// A member whose manifest doesn't parse keeps its directory
+pub fn read_workspace_members(root: &Path, ecosystem: &str) -> Option<(Manifest, Vec<(PathBuf, Option<Manifest>)>)> {
+    let manifest = read_manifest(root, ecosystem)?;
+    let members = workspace_member_dirs(root, ecosystem, &manifest)
+        .into_iter()
+        .map(|dir| {
+            let member = read_manifest(&dir, ecosystem);
+            (dir, member)
+        })
+        .collect();
+    Some((manifest, members))
+}
+
+// Root manifest with every workspace member's dependencies merged in (root
+// entries win), plus the member directories
+pub fn read_workspace(root: &Path, ecosystem: &str) -> Option<(Manifest, Vec<PathBuf>)> {
+    let (mut manifest, members) = read_workspace_members(root, ecosystem)?;
+    let mut dirs = Vec::with_capacity(members.len());
+    for (dir, member) in members {
+        if let Some(member) = member {
+            for (name, req) in member.runtime {
+                manifest.runtime.entry(name).or_insert(req);
+            }
+            for (name, req) in member.dev {
+                manifest.dev.entry(name).or_insert(req);
+            }
+        }
+        dirs.push(dir);
+    }
+    Some((manifest, dirs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_json_workspaces_and_engines() {
+        let manifest = parse_package_json(
+            r#"{
+                "name": "mono",
+                "packageManager": "pnpm@9.1.0",
+                "engines": { "node": ">=20" },
+                "workspaces": { "packages": ["packages/*"] },
+                "dependencies": { "react": "^18.0.0", "zod": "^3.0.0" },
+                "devDependencies": { "vite": "^5.0.0" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.runtime.len(), 2);
+        assert_eq!(manifest.dev.keys().collect::<Vec<_>>(), vec!["vite"]);
+        assert_eq!(manifest.toolchain.as_deref(), Some(">=20"));
+        assert_eq!(manifest.workspace_globs, vec!["packages/*"]);
+    }
+
+    #[test]
+    fn test_parse_cargo_toml_inherits_workspace_rust_version() {
+        let manifest = parse_cargo_toml(
+            r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+rust-version = "1.74"
+
+[package]
+name = "app"
+rust-version.workspace = true
+description = """
+Multi-line
+description"""
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+tokio.workspace = true
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3"
+
+[dev-dependencies]
+tempfile = "3"
+"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.runtime.keys().collect::<Vec<_>>(), vec!["serde", "tokio", "winapi"]);
+        assert_eq!(manifest.runtime["serde"], "1");
+        assert_eq!(manifest.runtime["tokio"], "workspace");
+        assert_eq!(manifest.dev.keys().collect::<Vec<_>>(), vec!["tempfile"]);
+        assert_eq!(manifest.toolchain.as_deref(), Some("1.74"));
+        assert_eq!(manifest.description.as_deref(), Some("Multi-line\ndescription"));
+        assert_eq!(manifest.workspace_globs, vec!["crates/*"]);
+    }
+
+    #[test]
+    fn test_parse_pyproject_pep621_and_poetry() {
+        let pep621 = parse_pyproject(
+            r#"
+[project]
+name = "tool"
+requires-python = ">=3.10"
+dependencies = ["requests[socks]>=2.31", "Typing_Extensions"]
+
+[project.optional-dependencies]
+test = ["pytest>=8"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(pep621.runtime.keys().collect::<Vec<_>>(), vec!["requests", "typing-extensions"]);
+        assert_eq!(pep621.runtime["requests"], ">=2.31");
+        assert_eq!(pep621.dev.keys().collect::<Vec<_>>(), vec!["pytest"]);
+        assert_eq!(pep621.toolchain.as_deref(), Some(">=3.10"));
+
+        let poetry = parse_pyproject(
+            r#"
+[tool.poetry.dependencies]
+python = "^3.11"
+fastapi = "^0.110"
+
+[tool.poetry.group.dev.dependencies]
+ruff = "*"
+"#,
+        )
+        .unwrap();
+        assert_eq!(poetry.runtime.keys().collect::<Vec<_>>(), vec!["fastapi"]);
+        assert_eq!(poetry.dev.keys().collect::<Vec<_>>(), vec!["ruff"]);
+        assert_eq!(poetry.toolchain.as_deref(), Some("^3.11"));
+    }
+
+    #[test]
+    fn test_workspace_member_dirs_skips_node_modules_and_negations() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for member in ["packages/ui", "packages/api", "packages/legacy", "packages/ui/node_modules/dep"] {
+            fs::create_dir_all(root.join(member)).unwrap();
+            fs::write(root.join(member).join("package.json"), "{}").unwrap();
+        }
+        fs::create_dir_all(root.join("packages/notes")).unwrap();
+        fs::write(root.join("pnpm-workspace.yaml"), "packages:\n  - 'packages/**'\n  - '!packages/legacy'\n").unwrap();
+
+        let manifest = Manifest::default();
+        let mut members: Vec<String> = workspace_member_dirs(root, "npm", &manifest)
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        members.sort();
+        assert_eq!(members, vec!["packages/api", "packages/ui"]);
+        assert_eq!(parse_requirements("# pinned\nDjango==5.0\n-r base.txt\n\nnumpy  # math\n").len(), 2);
+    }
+
+    #[test]
+    fn test_workspace_member_dirs_escapes_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("app[v2]");
+        fs::create_dir_all(root.join("packages/ui")).unwrap();
+        fs::write(root.join("packages/ui/package.json"), "{}").unwrap();
+        // Unescaped, "app[v2]" would match this sibling instead
+        fs::create_dir_all(dir.path().join("appv/packages/api")).unwrap();
+        fs::write(dir.path().join("appv/packages/api/package.json"), "{}").unwrap();
+
+        let manifest = Manifest { workspace_globs: vec!["packages/*".to_string()], ..Default::default() };
+        assert_eq!(workspace_member_dirs(&root, "npm", &manifest), vec![root.join("packages/ui")]);
+    }
+}
@@ -0,0 +1,212 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{self, DbPool};
+
+const ONBOARDING_KEY: &str = "onboarding";
+
+// Folders under the home directory where people usually keep checkouts
+const CANDIDATE_ROOTS: &[&str] = &["code", "projects", "src", "dev", "Developer", "repos", "workspace"];
+// Children looked at per candidate; the count is a floor past this
+const MAX_CHILDREN_PROBED: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingState {
+    NotStarted,
+    RootSelected,
+    AiConfigured,
+    FirstAnalysisDone,
+    Complete,
+}
+
+// Steps can be done in any order (settings before a root folder, say), so
+// each is tracked and the state is the furthest point reached in order
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+struct Progress {
+    root_selected: bool,
+    ai_configured: bool,
+    first_analysis_done: bool,
+    skipped: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    RootSelected,
+    AiConfigured,
+    FirstAnalysisDone,
+}
+
+impl Progress {
+    fn state(&self) -> OnboardingState {
+        if self.skipped || (self.root_selected && self.ai_configured && self.first_analysis_done) {
+            OnboardingState::Complete
+        } else if !self.root_selected {
+            OnboardingState::NotStarted
+        } else if !self.ai_configured {
+            OnboardingState::RootSelected
+        } else if !self.first_analysis_done {
+            OnboardingState::AiConfigured
+        } else {
+            OnboardingState::FirstAnalysisDone
+        }
+    }
+
+    fn mark(&mut self, step: Step) {
+        match step {
+            Step::RootSelected => self.root_selected = true,
+            Step::AiConfigured => self.ai_configured = true,
+            Step::FirstAnalysisDone => self.first_analysis_done = true,
+        }
+    }
+}
+
+// Installs from before onboarding existed start from what's already set up
+fn infer_progress(conn: &Connection) -> Progress {
+    let exists = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, bool>(0)).unwrap_or(false);
+    Progress {
+        root_selected: exists("SELECT EXISTS(SELECT 1 FROM settings WHERE key = 'root_folder')"),
+        ai_configured: exists("SELECT EXISTS(SELECT 1 FROM settings WHERE key = 'api_settings')"),
+        first_analysis_done: exists("SELECT EXISTS(SELECT 1 FROM analysis_cache)"),
+        skipped: false,
+    }
+}
+
+fn load(conn: &Connection) -> Progress {
+    db::load_setting(conn, ONBOARDING_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(|| infer_progress(conn))
+}
+
+fn save(conn: &Connection, progress: &Progress) -> Result<(), String> {
+    let json = serde_json::to_string(progress).map_err(|e| e.to_string())?;
+    db::save_setting(conn, ONBOARDING_KEY, &json).map_err(|e| e.to_string())
+}
+
+// Record a finished step; writes only when something changes
+pub fn mark_step(conn: &Connection, step: Step) -> Result<OnboardingState, String> {
+    let mut progress = load(conn);
+    let before = progress;
+    progress.mark(step);
+    if progress != before || db::load_setting(conn, ONBOARDING_KEY).ok().flatten().is_none() {
+        save(conn, &progress)?;
+    }
+    Ok(progress.state())
+}
+
+// Called from commands whose main work already succeeded, so a failure
+// here is reported rather than returned
+pub fn mark_step_in(db_pool: &DbPool, step: Step) {
+    let result = db_pool.get().map_err(|e| e.to_string()).and_then(|conn| mark_step(&conn, step));
+    if let Err(e) = result {
+        crate::errors::report_error("onboarding", format!("Failed to record onboarding step: {}", e), None);
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RootFolderSuggestion {
+    pub path: String,
+    pub exists: bool,
+    // Immediate children containing .git
+    pub git_repos: usize,
+    // True when the folder had more children than were probed
+    pub truncated: bool,
+}
+
+// One level deep and capped, so a huge home folder stays fast
+fn probe_root(path: PathBuf) -> RootFolderSuggestion {
+    let mut suggestion = RootFolderSuggestion {
+        path: path.to_string_lossy().to_string(),
+        exists: path.is_dir(),
+        git_repos: 0,
+        truncated: false,
+    };
+    if let Ok(entries) = std::fs::read_dir(&path) {
+        for (probed, entry) in entries.flatten().enumerate() {
+            if probed >= MAX_CHILDREN_PROBED {
+                suggestion.truncated = true;
+                break;
+            }
+            if entry.file_type().is_ok_and(|t| t.is_dir()) && entry.path().join(".git").exists() {
+                suggestion.git_repos += 1;
+            }
+        }
+    }
+    suggestion
+}
+
+fn suggest_in(home: &Path) -> Vec<RootFolderSuggestion> {
+    let mut suggestions: Vec<RootFolderSuggestion> = CANDIDATE_ROOTS.iter().map(|name| probe_root(home.join(name))).collect();
+    // Existing folders with the most repositories first
+    suggestions.sort_by(|a, b| b.exists.cmp(&a.exists).then(b.git_repos.cmp(&a.git_repos)));
+    suggestions
+}
+
+#[tauri::command]
+pub async fn get_onboarding_state(db_pool: State<'_, Arc<DbPool>>) -> Result<OnboardingState, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(load(&conn).state())
+}
+
+#[tauri::command]
+pub async fn skip_onboarding(db_pool: State<'_, Arc<DbPool>>) -> Result<OnboardingState, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let mut progress = load(&conn);
+    progress.skipped = true;
+    save(&conn, &progress)?;
+    Ok(progress.state())
+}
+
+#[tauri::command]
+pub async fn suggest_root_folders() -> Result<Vec<RootFolderSuggestion>, String> {
+    let home = dirs::home_dir().ok_or("Could not find the home directory")?;
+    tokio::task::spawn_blocking(move || suggest_in(&home)).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_in_any_order_reach_complete() {
+        let (_dir, pool) = db::test_pool();
+        let conn = pool.get().unwrap();
+
+        assert_eq!(load(&conn).state(), OnboardingState::NotStarted);
+        assert_eq!(mark_step(&conn, Step::AiConfigured).unwrap(), OnboardingState::NotStarted);
+        assert_eq!(mark_step(&conn, Step::RootSelected).unwrap(), OnboardingState::AiConfigured);
+        assert_eq!(mark_step(&conn, Step::FirstAnalysisDone).unwrap(), OnboardingState::Complete);
+        assert_eq!(load(&conn).state(), OnboardingState::Complete);
+    }
+
+    #[test]
+    fn test_existing_installs_infer_their_progress() {
+        let (_dir, pool) = db::test_pool();
+        let conn = pool.get().unwrap();
+        db::save_setting(&conn, "root_folder", "/projects").unwrap();
+        assert_eq!(load(&conn).state(), OnboardingState::RootSelected);
+    }
+
+    #[test]
+    fn test_suggest_counts_git_repos_one_level_deep() {
+        let home = tempfile::tempdir().unwrap();
+        for repo in ["code/a", "code/b", "code/notes", "projects/c", "code/a/nested"] {
+            std::fs::create_dir_all(home.path().join(repo)).unwrap();
+        }
+        for repo in ["code/a", "code/b", "projects/c", "code/a/nested"] {
+            std::fs::create_dir(home.path().join(repo).join(".git")).unwrap();
+        }
+
+        let suggestions = suggest_in(home.path());
+        assert_eq!(suggestions.len(), CANDIDATE_ROOTS.len());
+        assert!(suggestions[0].path.ends_with("code"));
+        assert_eq!(suggestions[0].git_repos, 2);
+        assert_eq!(suggestions[1].git_repos, 1);
+        assert!(suggestions[2..].iter().all(|s| !s.exists));
+    }
+}
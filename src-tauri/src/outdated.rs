@@ -0,0 +1,256 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+use tokio::sync::Semaphore;
+
+use crate::db::{self, DbPool};
+use crate::manifest::{self, Manifest};
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+// At most this many registry requests in flight, each holding its slot for
+// at least REQUEST_SPACING so a large manifest can't hammer a registry
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+const REQUEST_SPACING: Duration = Duration::from_millis(250);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// crates.io rejects requests without an identifying User-Agent
+const USER_AGENT: &str = "RepoMuse (https://github.com/BeardedUnicorn/RepoMuse)";
+
+static VERSION: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)(?:\.(\d+))?(?:\.(\d+))?").unwrap());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OutdatedDependency {
+    pub name: String,
+    pub ecosystem: String,
+    pub current_req: String,
+    pub latest: String,
+    pub severity: Severity,
+}
+
+// First version number in a requirement: "^1.2" -> (1, 2, 0), ">=2.31,<3" -> (2, 31, 0)
+fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    let caps = VERSION.captures(text)?;
+    let part = |i: usize| caps.get(i).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+    Some((part(1), part(2), part(3)))
+}
+
+fn severity(current_req: &str, latest: &str) -> Option<Severity> {
+    let current = parse_version(current_req)?;
+    let latest = parse_version(latest)?;
+    if latest <= current {
+        return None;
+    }
+    Some(if latest.0 != current.0 {
+        Severity::Major
+    } else if latest.1 != current.1 {
+        Severity::Minor
+    } else {
+        Severity::Patch
+    })
+}
+
+// Declared dependencies worth checking, runtime first; workspace-inherited,
+// git and path requirements have no version to compare
+fn candidates(manifest: &Manifest, limit: usize) -> Vec<(String, String)> {
+    manifest
+        .runtime
+        .iter()
+        .chain(manifest.dev.iter())
+        .filter(|(_, req)| parse_version(req).is_some())
+        .take(limit)
+        .map(|(name, req)| (name.clone(), req.clone()))
+        .collect()
+}
+
+fn registry_url(ecosystem: &str, name: &str) -> Option<String> {
+    match ecosystem {
+        "npm" => Some(format!("https://registry.npmjs.org/{}/latest", name.replace('/', "%2F"))),
+        "cargo" => Some(format!("https://crates.io/api/v1/crates/{}", name)),
+        "python" => Some(format!("https://pypi.org/pypi/{}/json", name)),
+        _ => None,
+    }
+}
+
+fn parse_latest(ecosystem: &str, body: &serde_json::Value) -> Option<String> {
+    let latest = match ecosystem {
+        "npm" => &body["version"],
+        "cargo" => &body["crate"]["max_stable_version"],
+        "python" => &body["info"]["version"],
+        _ => return None,
+    };
+    latest.as_str().map(str::to_string)
+}
+
+async fn fetch_latest(client: &reqwest::Client, ecosystem: &str, name: &str) -> Option<String> {
+    let response = client.get(registry_url(ecosystem, name)?).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    parse_latest(ecosystem, &response.json().await.ok()?)
+}
+
+fn compare(ecosystem: &str, deps: &[(String, String)], latest: &HashMap<String, String>) -> Vec<OutdatedDependency> {
+    let mut outdated: Vec<OutdatedDependency> = deps
+        .iter()
+        .filter_map(|(name, req)| {
+            let latest = latest.get(name)?;
+            Some(OutdatedDependency {
+                name: name.clone(),
+                ecosystem: ecosystem.to_string(),
+                current_req: req.clone(),
+                latest: latest.clone(),
+                severity: severity(req, latest)?,
+            })
+        })
+        .collect();
+    outdated.sort_by_key(|d| (d.severity as u8, d.name.clone()));
+    outdated
+}
+
+// Outdated dependencies across every ecosystem using only cached registry
+// versions; never touches the network
+pub fn cached_outdated(conn: &Connection, project_path: &Path) -> Vec<OutdatedDependency> {
+    manifest::ECOSYSTEMS
+        .iter()
+        .filter_map(|(ecosystem, _)| {
+            let (manifest, _) = manifest::read_workspace(project_path, ecosystem)?;
+            let deps = candidates(&manifest, MAX_LIMIT);
+            let latest: HashMap<String, String> = deps
+                .iter()
+                .filter_map(|(name, _)| {
+                    let version = db::get_registry_version(conn, ecosystem, name).ok().flatten()?;
+                    Some((name.clone(), version))
+                })
+                .collect();
+            Some(compare(ecosystem, &deps, &latest))
+        })
+        .flatten()
+        .collect()
+}
+
+// Opt-in registry lookup for the first `limit` dependencies. Versions are
+// cached for a day; with `offline` only the cache is consulted
+#[tauri::command]
+pub async fn check_outdated_dependencies(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    ecosystem: String,
+    limit: Option<usize>,
+    offline: Option<bool>,
+) -> Result<Vec<OutdatedDependency>, String> {
+    if !manifest::ECOSYSTEMS.iter().any(|(name, _)| *name == ecosystem) {
+        return Err(format!("Unsupported ecosystem \"{}\"", ecosystem));
+    }
+    let root = Path::new(&project_path);
+    let (manifest, _) = manifest::read_workspace(root, &ecosystem)
+        .ok_or_else(|| format!("No {} manifest found in {}", ecosystem, project_path))?;
+    let deps = candidates(&manifest, limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT));
+
+    let mut latest: HashMap<String, String> = HashMap::new();
+    let mut missing = Vec::new();
+    {
+        let conn = db_pool.get().map_err(|e| e.to_string())?;
+        for (name, _) in &deps {
+            match db::get_registry_version(&conn, &ecosystem, name).ok().flatten() {
+                Some(version) => {
+                    latest.insert(name.clone(), version);
+                }
+                None => missing.push(name.clone()),
+            }
+        }
+    }
+
+    if !offline.unwrap_or(false) && !missing.is_empty() {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+        let mut tasks = tokio::task::JoinSet::new();
+        for name in missing {
+            let (client, semaphore, ecosystem) = (client.clone(), semaphore.clone(), ecosystem.clone());
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok()?;
+                let version = fetch_latest(&client, &ecosystem, &name).await;
+                tokio::time::sleep(REQUEST_SPACING).await;
+                Some((name, version?))
+            });
+        }
+
+        let mut fetched = Vec::new();
+        while let Some(result) = tasks.join_next().await {
+            if let Ok(Some(entry)) = result {
+                fetched.push(entry);
+            }
+        }
+        let conn = db_pool.get().map_err(|e| e.to_string())?;
+        for (name, version) in fetched {
+            if let Err(e) = db::save_registry_version(&conn, &ecosystem, &name, &version) {
+                crate::errors::report_error("outdated", format!("Failed to cache registry version: {}", e), Some(&name));
+            }
+            latest.insert(name, version);
+        }
+    }
+
+    Ok(compare(&ecosystem, &deps, &latest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity_compares_against_declared_requirement() {
+        assert_eq!(severity("^17.0.2", "18.3.1"), Some(Severity::Major));
+        assert_eq!(severity(">=2.31,<3", "2.32.3"), Some(Severity::Minor));
+        assert_eq!(severity("~1.4.0", "1.4.2"), Some(Severity::Patch));
+        assert_eq!(severity("1", "1.0.0"), None);
+        assert_eq!(severity("workspace", "2.0.0"), None);
+    }
+
+    #[test]
+    fn test_parse_latest_per_registry() {
+        let npm = serde_json::json!({ "name": "react", "version": "18.3.1" });
+        let crates = serde_json::json!({ "crate": { "max_version": "2.0.0-rc.1", "max_stable_version": "1.9.0" } });
+        let pypi = serde_json::json!({ "info": { "version": "2.32.3" } });
+        assert_eq!(parse_latest("npm", &npm).as_deref(), Some("18.3.1"));
+        assert_eq!(parse_latest("cargo", &crates).as_deref(), Some("1.9.0"));
+        assert_eq!(parse_latest("python", &pypi).as_deref(), Some("2.32.3"));
+        assert_eq!(
+            registry_url("npm", "@tauri-apps/api").as_deref(),
+            Some("https://registry.npmjs.org/@tauri-apps%2Fapi/latest")
+        );
+    }
+
+    #[test]
+    fn test_cached_outdated_uses_only_cached_versions() {
+        let (dir, pool) = db::test_pool();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"react": "^17.0.2", "zod": "^3.22.0"}, "devDependencies": {"vite": "^5.0.0"}}"#,
+        )
+        .unwrap();
+        let conn = pool.get().unwrap();
+        db::save_registry_version(&conn, "npm", "react", "18.3.1").unwrap();
+        db::save_registry_version(&conn, "npm", "vite", "5.4.2").unwrap();
+
+        let outdated = cached_outdated(&conn, dir.path());
+        let summary: Vec<(&str, Severity)> = outdated.iter().map(|d| (d.name.as_str(), d.severity)).collect();
+        assert_eq!(summary, vec![("react", Severity::Major), ("vite", Severity::Minor)]);
+    }
+}
@@ -0,0 +1,163 @@
+use once_cell::sync::Lazy;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::State;
+
+use crate::db::{self, DbPool};
+
+const PERFORMANCE_KEY: &str = "performance";
+
+// Effective values; walker/batch/interval changes are picked up by the next
+// walk or batch, the rayon pool size only at startup
+static CURRENT: Lazy<RwLock<PerformanceSettings>> =
+    Lazy::new(|| RwLock::new(PerformanceSettings::default()));
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct PerformanceSettings {
+    pub rayon_threads: usize,
+    pub walker_threads: usize,
+    pub max_concurrent_analyses: usize,
+    pub progress_interval_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PerformanceInfo {
+    pub settings: PerformanceSettings,
+    pub cpu_count: usize,
+    pub active_rayon_threads: usize,
+    pub restart_required: bool,
+}
+
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+}
+
+impl Default for PerformanceSettings {
+    fn default() -> Self {
+        Self {
+            rayon_threads: cpu_count().min(8),
+            walker_threads: cpu_count().min(8),
+            max_concurrent_analyses: 1,
+            progress_interval_ms: 500,
+        }
+    }
+}
+
+impl PerformanceSettings {
+    pub fn clamped(&self) -> Self {
+        Self {
+            rayon_threads: self.rayon_threads.clamp(1, 64),
+            walker_threads: self.walker_threads.clamp(1, 32),
+            max_concurrent_analyses: self.max_concurrent_analyses.clamp(1, 8),
+            progress_interval_ms: self.progress_interval_ms.clamp(100, 5_000),
+        }
+    }
+}
+
+fn load_performance_settings(conn: &Connection) -> PerformanceSettings {
+    db::load_setting(conn, PERFORMANCE_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str::<PerformanceSettings>(&json).ok())
+        .unwrap_or_default()
+        .clamped()
+}
+
+pub fn current() -> PerformanceSettings {
+    CURRENT.read().map(|s| s.clone()).unwrap_or_default()
+}
+
+pub fn walker_threads() -> usize {
+    current().walker_threads
+}
+
+pub fn max_concurrent_analyses() -> usize {
+    current().max_concurrent_analyses
+}
+
+pub fn progress_interval() -> Duration {
+    Duration::from_millis(current().progress_interval_ms)
+}
+
+// Load stored settings and size the global rayon pool; call once at startup
+// before anything touches rayon
+pub fn init(db_pool: &DbPool) {
+    let settings = db_pool
+        .get()
+        .map(|conn| load_performance_settings(&conn))
+        .unwrap_or_default();
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads(settings.rayon_threads)
+        .build_global();
+    if let Ok(mut current) = CURRENT.write() {
+        *current = settings;
+    }
+}
+
+fn performance_info() -> PerformanceInfo {
+    let settings = current();
+    let active_rayon_threads = rayon::current_num_threads();
+    PerformanceInfo {
+        restart_required: settings.rayon_threads != active_rayon_threads,
+        settings,
+        cpu_count: cpu_count(),
+        active_rayon_threads,
+    }
+}
+
+#[tauri::command]
+pub async fn get_performance_info() -> Result<PerformanceInfo, String> {
+    Ok(performance_info())
+}
+
+#[tauri::command]
+pub async fn save_performance_settings(
+    db_pool: State<'_, Arc<DbPool>>,
+    settings: PerformanceSettings,
+) -> Result<PerformanceInfo, String> {
+    let settings = settings.clamped();
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    db::save_setting(&conn, PERFORMANCE_KEY, &json).map_err(|e| e.to_string())?;
+
+    if let Ok(mut current) = CURRENT.write() {
+        *current = settings;
+    }
+    Ok(performance_info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamped_keeps_values_in_range() {
+        let wild = PerformanceSettings {
+            rayon_threads: 0,
+            walker_threads: 500,
+            max_concurrent_analyses: 0,
+            progress_interval_ms: 10,
+        };
+        assert_eq!(
+            wild.clamped(),
+            PerformanceSettings {
+                rayon_threads: 1,
+                walker_threads: 32,
+                max_concurrent_analyses: 1,
+                progress_interval_ms: 100,
+            }
+        );
+        let defaults = PerformanceSettings::default();
+        assert_eq!(defaults.clamped(), defaults);
+    }
+
+    #[test]
+    fn test_missing_fields_fall_back_to_defaults() {
+        let parsed: PerformanceSettings = serde_json::from_str(r#"{"walker_threads": 2}"#).unwrap();
+        assert_eq!(parsed.walker_threads, 2);
+        assert_eq!(parsed.progress_interval_ms, 500);
+    }
+}
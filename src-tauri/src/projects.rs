@@ -1,64 +1,148 @@
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
-use std::sync::Arc;
-use tauri::State;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Semaphore;
 
-use crate::fs_utils::{should_analyze_file, walker_parallel};
+use crate::fs_utils::{self, canonical_path, get_language_from_extension, walker_parallel_with_depth, walker_with_depth, MAX_WALK_DEPTH};
 use crate::db::{self, DbPool};
+use crate::insights;
+use crate::manifest;
+use crate::languages::{self, LanguageShare};
+use crate::workspaces;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct ProjectDirectory {
     pub name: String,
+    // Canonical path, the key every other command takes
     pub path: String,
+    // The path as reached under the listing root, which may go through a
+    // symlink; equal to `path` otherwise
+    pub display_path: String,
     pub is_git_repo: bool,
     pub file_count: usize,
+    // Bytes of analyzable files, measured with the file count
+    pub total_size_bytes: u64,
     pub description: Option<String>,
     pub is_counting: bool,
+    pub last_opened_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_analyzed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_favorite: bool,
+    pub is_archived: bool,
+    pub tags: Vec<String>,
+    // Newer of the last commit and newest file, measured with the file count
+    pub last_activity_at: Option<chrono::DateTime<chrono::Utc>>,
+    // No activity within the stale threshold setting
+    pub is_stale: bool,
+    pub language_breakdown: Option<Vec<LanguageShare>>,
+    pub primary_language: Option<String>,
+    pub primary_language_percentage: Option<f64>,
+    // Which indicator identified the project, e.g. "node", "terraform" or
+    // "git" for a repository with source files but no manifest
+    pub project_kind: Option<String>,
+    // Set for monorepo roots; each member directory can be analyzed on its own
+    pub workspace_kind: Option<String>,
+    pub workspace_members: Vec<String>,
+    // Set on workspace members expanded from a monorepo root
+    pub parent_path: Option<String>,
+    // Members of this root in the nested view; always empty in the flat one
+    pub children: Vec<ProjectDirectory>,
 }
 
+// Indicator file and the project kind it implies, most specific first so a
+// Deno or Bun project with a package.json is not reported as plain Node.
+// `*.ext` entries match any file with that extension
+const PROJECT_INDICATORS: &[(&str, &str)] = &[
+    ("deno.json", "deno"), ("deno.jsonc", "deno"),
+    ("bun.lockb", "bun"), ("bun.lock", "bun"), ("bun.toml", "bun"),
+    ("package.json", "node"),
+    // Any Cargo.toml counts, including a `[workspace]`-only root
+    ("Cargo.toml", "rust"),
+    ("go.mod", "go"),
+    ("pyproject.toml", "python"), ("setup.py", "python"), ("setup.cfg", "python"), ("requirements.txt", "python"),
+    ("pom.xml", "java"), ("build.gradle", "java"), ("build.gradle.kts", "java"),
+    ("Gemfile", "ruby"), ("*.gemspec", "ruby"),
+    ("composer.json", "php"),
+    ("project.clj", "clojure"), ("deps.edn", "clojure"),
+    // Umbrella apps have a mix.exs at the root as well as under apps/
+    ("mix.exs", "elixir"),
+    ("*.sln", "dotnet"), ("*.csproj", "dotnet"), ("*.fsproj", "dotnet"),
+    ("pubspec.yaml", "dart"),
+    ("*.tf", "terraform"),
+    ("CMakeLists.txt", "cpp"),
+    ("flake.nix", "nix"),
+    ("Makefile", "make"), ("justfile", "make"), ("Justfile", "make"),
+    ("README.md", "docs"), ("README.txt", "docs"),
+];
+// How deep the git fallback looks for a source file
+const GIT_SOURCE_MAX_DEPTH: usize = 3;
+
 fn is_project_directory(path: &Path) -> bool {
-    let project_indicators = vec![
-        "package.json", "Cargo.toml", "pom.xml", "build.gradle", "requirements.txt", "Gemfile", "go.mod",
-        "composer.json", "project.clj", "mix.exs", ".csproj", "pubspec.yaml", "CMakeLists.txt", "Makefile",
-        "README.md", "README.txt",
-    ];
-
-    for indicator in project_indicators {
-        if indicator.ends_with(".csproj") {
-            if let Ok(entries) = fs::read_dir(path) {
-                for entry in entries.flatten() {
-                    if let Some(name) = entry.file_name().to_str() {
-                        if name.ends_with(".csproj") {
-                            return true;
-                        }
-                    }
-                }
-            }
-        } else if path.join(indicator).exists() {
-            return true;
-        }
+    detect_project_kind(path).is_some()
+}
+
+// Which indicator marks `path` as a project. A git repository without any
+// indicator still counts ("git") when it holds recognisable source files
+fn detect_project_kind(path: &Path) -> Option<&'static str> {
+    let extensions: HashSet<String> = fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.path().extension().map(|x| x.to_string_lossy().to_string()))
+        .collect();
+    let matched = PROJECT_INDICATORS.iter().find(|(indicator, _)| match indicator.strip_prefix("*.") {
+        Some(extension) => extensions.contains(extension),
+        None => path.join(indicator).exists(),
+    });
+    if let Some((_, kind)) = matched {
+        return Some(kind);
     }
-    false
+
+    let has_source = || {
+        let filter = fs_utils::FileFilter::current();
+        walker_with_depth(path, Some(GIT_SOURCE_MAX_DEPTH))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+            .any(|e| {
+                let file = e.path().to_string_lossy();
+                let language = get_language_from_extension(&file);
+                filter.should_analyze(path, e.path()) && language != "Unknown" && languages::is_code_language(&language)
+            })
+    };
+    (path.join(".git").exists() && has_source()).then_some("git")
 }
 
 fn get_project_description(path: &Path) -> Option<String> {
-    if let Ok(package_json) = fs::read_to_string(path.join("package.json")) {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&package_json) {
-            if let Some(description) = json["description"].as_str() {
-                return Some(description.to_string());
-            }
-        }
-    }
-    if let Ok(cargo_toml) = fs::read_to_string(path.join("Cargo.toml")) {
-        if let Some(desc_line) = cargo_toml.lines().find(|line| line.starts_with("description")) {
-            if let Some(desc) = desc_line.split('=').nth(1) {
-                return Some(desc.trim().trim_matches('"').to_string());
-            }
-        }
+    let read = |name: &str| fs::read_to_string(path.join(name)).ok();
+    let described = read("package.json")
+        .and_then(|t| manifest::parse_package_json(&t))
+        .and_then(|m| m.description)
+        .or_else(|| cargo_description(&read("Cargo.toml")?))
+        .or_else(|| manifest::parse_pyproject(&read("pyproject.toml")?)?.description)
+        .or_else(|| {
+            let json: serde_json::Value = serde_json::from_str(&read("composer.json")?).ok()?;
+            json["description"].as_str().map(str::to_string)
+        })
+        .or_else(|| gemspec_description(&read_first_with_extension(path, "gemspec")?))
+        .or_else(|| gemfile_description(&read("Gemfile")?))
+        .or_else(|| pubspec_description(&read("pubspec.yaml")?))
+        .or_else(|| csproj_description(&read_first_with_extension(path, "csproj")?))
+        .or_else(|| go_doc_description(&read("doc.go")?));
+    if let Some(description) = described.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()) {
+        return Some(description);
     }
+
     for readme_name in &["README.md", "README.txt", "readme.md", "readme.txt"] {
         if let Ok(readme) = fs::read_to_string(path.join(readme_name)) {
+            if let Some(description) = insights::readme_description(&readme) {
+                return Some(description);
+            }
+            // A README that is only a title still names the project
             let first_line = readme.lines().next().unwrap_or("").trim();
             if !first_line.is_empty() && first_line.len() < 200 {
                 let cleaned = first_line.trim_start_matches('#').trim();
@@ -68,33 +152,337 @@ fn get_project_description(path: &Path) -> Option<String> {
             }
         }
     }
+    // Module path is the weakest signal; only used when nothing else describes the project
+    if let Some(go_mod) = read("go.mod") {
+        if let Some(module) = go_mod.lines().find_map(|line| line.trim().strip_prefix("module ")) {
+            return Some(format!("Go module: {}", module.trim().trim_matches('"')));
+        }
+    }
     None
 }
 
-fn count_project_files(path: &Path) -> usize {
-    use std::sync::atomic::{AtomicUsize, Ordering};
-    let counter = AtomicUsize::new(0);
-    walker_parallel(path).run(|| {
-        let c = &counter;
+// First file in `dir` with the extension, by name so the choice is stable
+fn read_first_with_extension(dir: &Path, extension: &str) -> Option<String> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == extension))
+        .collect();
+    paths.sort();
+    fs::read_to_string(paths.first()?).ok()
+}
+
+// `[package] description`, or `[workspace.package]` for a virtual manifest
+// or a crate that inherits it with `description.workspace = true`
+fn cargo_description(text: &str) -> Option<String> {
+    manifest::parse_cargo_toml(text)?.description.or_else(|| {
+        let doc: toml::Value = toml::from_str(text).ok()?;
+        doc.get("workspace")?.get("package")?.get("description")?.as_str().map(str::to_string)
+    })
+}
+
+// `spec.summary` (preferred) or `spec.description`, quoted or %q{...}
+fn gemspec_description(text: &str) -> Option<String> {
+    let field = |name: &str| {
+        GEMSPEC_FIELD
+            .captures_iter(text)
+            .find(|c| &c[1] == name)
+            .and_then(|c| c.get(2).or_else(|| c.get(3)).or_else(|| c.get(4)))
+            .map(|m| m.as_str().split_whitespace().collect::<Vec<_>>().join(" "))
+    };
+    field("summary").or_else(|| field("description"))
+}
+
+// Leading comment block of a Gemfile, minus magic comments
+fn gemfile_description(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .take_while(|l| l.starts_with('#'))
+        .map(|l| l.trim_start_matches('#').trim())
+        .filter(|l| !l.is_empty() && !l.contains("frozen_string_literal") && !l.starts_with("-*-"))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join(" "))
+}
+
+// Top-level `description:`, including `>` and `|` block scalars
+fn pubspec_description(text: &str) -> Option<String> {
+    let mut lines = text.lines();
+    let value = lines.find_map(|l| l.strip_prefix("description:"))?.trim();
+    if !matches!(value.chars().next(), Some('>' | '|')) {
+        return Some(value.trim_matches('"').trim_matches('\'').to_string());
+    }
+    let block: Vec<&str> = lines
+        .take_while(|l| l.trim().is_empty() || l.starts_with(' '))
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    (!block.is_empty()).then(|| block.join(" "))
+}
+
+fn csproj_description(text: &str) -> Option<String> {
+    let caps = CSPROJ_DESCRIPTION.captures(text)?;
+    Some(caps[1].split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+// Package doc comment from doc.go: the `//` block right above `package`
+fn go_doc_description(text: &str) -> Option<String> {
+    let mut comment: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("package ") {
+            break;
+        }
+        match line.strip_prefix("//") {
+            Some(rest) if !rest.starts_with("go:") => comment.push(rest.trim()),
+            _ => comment.clear(),
+        }
+    }
+    // Only the first paragraph; later ones are usage docs
+    let paragraph: Vec<&str> = comment.into_iter().skip_while(|l| l.is_empty()).take_while(|l| !l.is_empty()).collect();
+    (!paragraph.is_empty()).then(|| paragraph.join(" "))
+}
+
+// Listing reuses a stored file count younger than this
+const FILE_COUNT_MAX_AGE_MINUTES: i64 = 5;
+// Extra rows fetched for DB-backed listings so ignored ones can be dropped
+const IGNORED_HEADROOM: u32 = 50;
+// Background counts walking at once
+const MAX_CONCURRENT_COUNTS: usize = 4;
+
+static GEMSPEC_FIELD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*\w+\.(summary|description)\s*=\s*(?:"([^"]*)"|'([^']*)'|%q\{([^}]*)\})"#).unwrap()
+});
+static CSPROJ_DESCRIPTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<Description>\s*(.*?)\s*</Description>").unwrap());
+
+static COUNTS_IN_FLIGHT: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+// Watch-mode tasks by canonical project path; aborting one drops its watcher
+static PROJECT_WATCHERS: Lazy<Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize, Clone)]
+struct FilesChanged {
+    path: String,
+    changes: Vec<fs_utils::watch::FileChange>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct CountUpdate {
+    path: String,
+    count: usize,
+    total_size_bytes: u64,
+    // Entries the walk couldn't read; the count is low when this isn't zero
+    io_errors: usize,
+}
+
+// Analyzable files under a project, their total size and the newest
+// modification time among them, from one metadata-only walk
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct FileCount {
+    count: usize,
+    total_bytes: u64,
+    newest_mtime: Option<chrono::DateTime<chrono::Utc>>,
+    io_errors: usize,
+}
+
+fn count_project_files(path: &Path, filter: &fs_utils::FileFilter, symlinks: fs_utils::SymlinkPolicy) -> FileCount {
+    use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+    let stats = fs_utils::WalkStats::default();
+    let bytes = AtomicU64::new(0);
+    let newest = AtomicI64::new(i64::MIN);
+    walker_parallel_with_depth(path, Some(MAX_WALK_DEPTH), symlinks).run(|| {
+        let (stats, bytes, newest) = (&stats, &bytes, &newest);
         Box::new(move |entry_res| {
-            if let Ok(entry) = entry_res {
-                if entry.file_type().map_or(false, |ft| ft.is_file()) {
+            if let Some(entry) = stats.observe(entry_res) {
+                if entry.file_type().is_some_and(|ft| ft.is_file()) {
                     let p = entry.path();
-                    if should_analyze_file(&p.to_string_lossy()) {
-                        c.fetch_add(1, Ordering::Relaxed);
+                    if !filter.should_analyze(path, p) {
+                        stats.record_skipped();
+                    } else if let Ok(metadata) = entry.metadata().or_else(|_| fs_utils::metadata(entry.path())) {
+                        bytes.fetch_add(metadata.len(), Ordering::Relaxed);
+                        if let Ok(modified) = metadata.modified() {
+                            newest.fetch_max(chrono::DateTime::<chrono::Utc>::from(modified).timestamp(), Ordering::Relaxed);
+                        }
                     }
                 }
             }
             ignore::WalkState::Continue
         })
     });
-    counter.load(Ordering::Relaxed)
+    let walk = stats.summary();
+    if walk.io_errors > 0 {
+        tracing::warn!(path = %path.display(), io_errors = walk.io_errors, samples = ?walk.error_samples, "Unreadable entries during count");
+    }
+    FileCount {
+        count: walk.files - walk.skipped_by_filter,
+        total_bytes: bytes.load(Ordering::Relaxed),
+        newest_mtime: chrono::DateTime::from_timestamp(newest.load(Ordering::Relaxed), 0),
+        io_errors: walk.io_errors,
+    }
 }
 
-fn process_project_directory(
-    path: std::path::PathBuf,
+// Newer of the last commit (cached git_info, else `git log -1`) and the
+// newest file
+fn last_activity(
     conn: &rusqlite::Connection,
-) -> Option<ProjectDirectory> {
+    project: &db::Project,
+    newest_mtime: Option<chrono::DateTime<chrono::Utc>>,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    let path = Path::new(&project.path);
+    let commit = db::get_last_commit_date(conn, project.id)
+        .ok()
+        .flatten()
+        .or_else(|| path.join(".git").exists().then(|| insights::run_git_line(path, &["log", "-1", "--format=%cI"]))?)
+        .and_then(|date| chrono::DateTime::parse_from_rfc3339(&date).ok())
+        .map(|date| date.with_timezone(&chrono::Utc));
+    commit.max(newest_mtime)
+}
+
+// Listing flags projects idle for longer than this many months
+const STALE_AFTER_KEY: &str = "stale_after_months";
+const DEFAULT_STALE_AFTER_MONTHS: u32 = 6;
+
+fn stale_after_months(conn: &rusqlite::Connection) -> u32 {
+    db::load_setting(conn, STALE_AFTER_KEY)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_STALE_AFTER_MONTHS)
+}
+
+fn stale_cutoff(months: u32) -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now() - chrono::Duration::days(30 * months as i64)
+}
+
+fn mark_stale(projects: &mut [ProjectDirectory], cutoff: chrono::DateTime<chrono::Utc>) {
+    for project in projects {
+        project.is_stale = project.last_activity_at.is_some_and(|at| at < cutoff);
+        mark_stale(&mut project.children, cutoff);
+    }
+}
+
+// Projects the user never wants listed: exact paths (which also cover
+// everything below them) and glob patterns such as `**/tmp-*`
+#[derive(Debug, Default)]
+pub struct IgnoreList {
+    paths: Vec<String>,
+    globs: Vec<glob::Pattern>,
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+impl IgnoreList {
+    pub fn new(patterns: &[String]) -> Self {
+        let mut list = IgnoreList::default();
+        for pattern in patterns {
+            if pattern.contains(['*', '?', '[']) {
+                if let Ok(glob) = glob::Pattern::new(&normalize_path(pattern)) {
+                    list.globs.push(glob);
+                }
+            } else {
+                list.paths.push(normalize_path(pattern));
+            }
+        }
+        list
+    }
+
+    pub fn load(conn: &rusqlite::Connection) -> Self {
+        Self::new(&db::list_ignored_projects(conn).unwrap_or_default())
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        let path = normalize_path(path);
+        self.paths
+            .iter()
+            .any(|p| path == *p || path.strip_prefix(p.as_str()).is_some_and(|rest| rest.starts_with('/')))
+            || self.globs.iter().any(|g| g.matches(&path))
+    }
+}
+
+// Counts one project and stores the result; blocking
+fn count_and_store(
+    pool: &DbPool,
+    path: &str,
+    filter: &fs_utils::FileFilter,
+    symlinks: fs_utils::SymlinkPolicy,
+) -> Result<FileCount, String> {
+    let counted = count_project_files(Path::new(path), filter, symlinks);
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    if let Some(project) = db::get_project_by_path(&conn, path).map_err(|e| e.to_string())? {
+        db::update_project_file_count(&conn, project.id, counted.count as i64, counted.total_bytes as i64)
+            .map_err(|e| e.to_string())?;
+        let activity = last_activity(&conn, &project, counted.newest_mtime);
+        db::set_project_last_activity(&conn, project.id, activity).map_err(|e| e.to_string())?;
+        // An analysis keeps this current; until one runs, a capped walk of
+        // the project's own directory (members included for a root) estimates it
+        if project.primary_language.is_none() {
+            let sizes = languages::scan_sizes_limited(Path::new(path), languages::PRIMARY_LANGUAGE_MAX_FILES, filter);
+            let primary = languages::primary_language(&sizes);
+            db::save_primary_language(&conn, project.id, primary.as_ref()).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(counted)
+}
+
+// Counts each path off the listing's critical path, emitting
+// `projects:count_updated` as each one finishes. Paths already being
+// counted by an earlier listing are skipped
+fn spawn_background_counts(app: AppHandle, pool: Arc<DbPool>, paths: Vec<String>) {
+    let paths: Vec<String> = {
+        let mut in_flight = COUNTS_IN_FLIGHT.lock().unwrap();
+        paths.into_iter().filter(|p| in_flight.insert(p.clone())).collect()
+    };
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_COUNTS));
+    let filter = Arc::new(fs_utils::FileFilter::current());
+    for path in paths {
+        let (app, pool, semaphore, filter) = (app.clone(), pool.clone(), semaphore.clone(), filter.clone());
+        tauri::async_runtime::spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let counted = {
+                let path = path.clone();
+                tokio::task::spawn_blocking(move || count_and_store(&pool, &path, &filter, fs_utils::SymlinkPolicy::default())).await
+            };
+            COUNTS_IN_FLIGHT.lock().unwrap().remove(&path);
+            match counted {
+                Ok(Ok(counted)) => {
+                    let update = CountUpdate {
+                        path,
+                        count: counted.count,
+                        total_size_bytes: counted.total_bytes,
+                        io_errors: counted.io_errors,
+                    };
+                    let _ = app.emit("projects:count_updated", &update);
+                }
+                Ok(Err(e)) => crate::errors::report_error("projects", format!("Failed to count files: {}", e), Some(&path)),
+                Err(e) => crate::errors::report_error("projects", format!("File count task panicked: {}", e), Some(&path)),
+            }
+        });
+    }
+}
+
+// Everything the listing learns from the filesystem, gathered without a
+// database connection so candidates can be detected in parallel
+#[derive(Debug, Clone)]
+struct DetectedProject {
+    // As reached from the root, used for filesystem reads
+    path: std::path::PathBuf,
+    // Canonical spelling, used for the database
+    key: String,
+    name: String,
+    description: Option<String>,
+    is_git_repo: bool,
+    project_kind: Option<&'static str>,
+    modified_at: Option<chrono::DateTime<chrono::Utc>>,
+    workspace_kind: Option<String>,
+    workspace_members: Vec<String>,
+    children: Vec<DetectedProject>,
+}
+
+// A listing candidate directly under the root, None when it is skipped,
+// ignored or not a project
+fn detect_candidate(path: std::path::PathBuf, ignored: &IgnoreList) -> Option<DetectedProject> {
     let dir_name = path
         .file_name()
         .and_then(|n| n.to_str())
@@ -107,61 +495,164 @@ fn process_project_directory(
         return None;
     }
 
-    if is_project_directory(&path) {
-        let path_str = path.to_string_lossy().to_string();
-        let is_git_repo = path.join(".git").exists();
-        let description = get_project_description(&path);
-        
-        // Get or create project in database
-        let project = db::get_project_by_path(conn, &path_str).ok().flatten();
-        
-        let file_count = if let Some(p) = &project {
-            p.file_count as usize
-        } else {
-            // First time seeing this project - do a quick count
-            let count = count_project_files(&path);
-            
-            // Store in database
-            let _ = db::upsert_project(
-                conn,
-                &path_str,
-                &dir_name,
-                description.as_deref(),
-                is_git_repo,
-            );
-            
-            if let Ok(Some(proj)) = db::get_project_by_path(conn, &path_str) {
-                let _ = db::update_project_file_count(conn, proj.id, count as i64);
-            }
-            
-            count
-        };
+    // Ignored entries are skipped before anything is written to the
+    // database, whichever spelling the pattern used
+    if ignored.matches(&path.to_string_lossy()) || ignored.matches(&canonical_path(&path)) {
+        return None;
+    }
 
-        Some(ProjectDirectory {
-            name: dir_name,
-            path: path_str,
-            is_git_repo,
-            file_count,
-            description,
-            is_counting: false,
-        })
+    let kind = detect_project_kind(&path)?;
+    let mut detected = detect_project(&path, dir_name, false, ignored);
+    detected.project_kind = Some(kind);
+    Some(detected)
+}
+
+// Top-level entries get their workspace members as children. The kind is
+// left for the caller when it already has it
+fn detect_project(path: &Path, name: String, member: bool, ignored: &IgnoreList) -> DetectedProject {
+    let (workspace_kind, workspace_members, children) = if !member {
+        let workspace = workspaces::get_workspace_info(path);
+        // A directory can be a member twice (npm and Cargo); the first name wins
+        let mut seen = HashSet::new();
+        let children = workspace
+            .members
+            .iter()
+            .filter(|m| !m.path.is_empty() && seen.insert(m.path.clone()))
+            .map(|m| (path.join(&m.path), m))
+            .filter(|(dir, _)| !ignored.matches(&dir.to_string_lossy()))
+            .map(|(dir, m)| {
+                let mut child = detect_project(&dir, m.name.clone(), true, ignored);
+                child.project_kind = detect_project_kind(&dir);
+                child
+            })
+            .collect();
+        let members = workspaces::member_dirs(path, &workspace)
+            .iter()
+            .map(|d| d.to_string_lossy().to_string())
+            .collect();
+        (workspace.kind, members, children)
     } else {
-        None
+        (None, Vec::new(), Vec::new())
+    };
+
+    DetectedProject {
+        path: path.to_path_buf(),
+        key: canonical_path(path),
+        name,
+        description: get_project_description(path),
+        is_git_repo: path.join(".git").exists(),
+        project_kind: None,
+        modified_at: fs::metadata(path).and_then(|m| m.modified()).ok().map(chrono::DateTime::from),
+        workspace_kind,
+        workspace_members,
+        children,
     }
 }
 
-#[tauri::command]
-pub async fn list_project_directories(
-    db_pool: State<'_, Arc<DbPool>>,
-    root_path: String,
-) -> Result<Vec<ProjectDirectory>, String> {
-    let root = Path::new(&root_path);
-    if !root.exists() || !root.is_dir() {
-        return Err("Invalid root directory".to_string());
-    }
+fn build_project_directory(
+    path: &Path,
+    default_name: String,
+    conn: &rusqlite::Connection,
+    parent_path: Option<&str>,
+    ignored: &IgnoreList,
+) -> ProjectDirectory {
+    let mut detected = detect_project(path, default_name, parent_path.is_some(), ignored);
+    detected.project_kind = detect_project_kind(path);
+    store_project(detected, conn, parent_path)
+}
 
-    let conn = db_pool.get().map_err(|e| e.to_string())?;
+// One listing entry from a detected project: creates or refreshes its row
+// (and its members' rows, linked to it) and merges in the stored state
+fn store_project(detected: DetectedProject, conn: &rusqlite::Connection, parent_path: Option<&str>) -> ProjectDirectory {
+    let display_path = detected.path.to_string_lossy().to_string();
+    let path_str = detected.key.clone();
+    let DetectedProject { is_git_repo, description, .. } = detected;
+
+    // Rows stored under the symlinked spelling move to (or merge into) the
+    // canonical one
+    if display_path != path_str {
+        if let Err(e) = db::rename_project_path(conn, &display_path, &path_str) {
+            crate::errors::report_error("projects", format!("Failed to merge project rows: {}", e), Some(&path_str));
+        }
+    }
+    // Get or create project in database
+    let mut project = db::get_project_by_path(conn, &path_str).ok().flatten();
+    // Adding a README or running `git init` touches the directory, so the
+    // detected metadata is written back whenever it changed since the row
+    if project.as_ref().is_some_and(|p| detected.modified_at.is_some_and(|m| m > p.updated_at)) {
+        let _ = db::refresh_project_metadata(conn, &path_str, description.as_deref(), is_git_repo);
+        project = db::get_project_by_path(conn, &path_str).ok().flatten();
+    }
     
+    let last_opened_at = project.as_ref().and_then(|p| p.last_opened_at);
+    let last_analyzed_at = project.as_ref().and_then(|p| p.last_analyzed_at);
+    let is_favorite = project.as_ref().is_some_and(|p| p.is_favorite);
+    let is_archived = project.as_ref().is_some_and(|p| p.is_archived);
+    let tags = project.as_ref().map(|p| p.tags.clone()).unwrap_or_default();
+    let last_activity_at = project.as_ref().and_then(|p| p.last_activity_at);
+    let language_breakdown = project.as_ref().and_then(|p| p.language_breakdown.clone());
+    let primary_language = project.as_ref().and_then(|p| p.primary_language.clone());
+    let primary_language_percentage = project.as_ref().and_then(|p| p.primary_language_percentage);
+    let fresh = project
+        .as_ref()
+        .is_some_and(|p| db::file_count_is_fresh(conn, p.id, FILE_COUNT_MAX_AGE_MINUTES).unwrap_or(false));
+    // Never counted or stale counts are refreshed in the background; the
+    // stored count (0 for a new project) is shown until then
+    let file_count = project.as_ref().map_or(0, |p| p.file_count as usize);
+    let total_size_bytes = project.as_ref().map_or(0, |p| p.total_size_bytes as u64);
+    let is_counting = !fresh;
+    if project.is_none() {
+        let _ = db::upsert_project(conn, &path_str, &detected.name, description.as_deref(), is_git_repo);
+    }
+    if parent_path.is_some() {
+        let _ = db::set_project_parent(conn, &path_str, parent_path);
+    }
+    if project.as_ref().map(|p| p.project_kind.as_deref()) != Some(detected.project_kind) {
+        let _ = db::set_project_kind(conn, &path_str, detected.project_kind);
+    }
+
+    // Stored name and description win so manual edits stick
+    let name = project.as_ref().map(|p| p.name.clone()).unwrap_or(detected.name);
+    let description = project.and_then(|p| p.description).or(description);
+    let mut children: Vec<ProjectDirectory> = detected
+        .children
+        .into_iter()
+        .map(|child| store_project(child, conn, Some(&path_str)))
+        .collect();
+    children.sort_by_key(|c| c.name.to_lowercase());
+
+    ProjectDirectory {
+        name,
+        path: path_str,
+        display_path,
+        is_git_repo,
+        file_count,
+        total_size_bytes,
+        description,
+        is_counting,
+        last_opened_at,
+        last_analyzed_at,
+        is_favorite,
+        is_archived,
+        tags,
+        last_activity_at,
+        is_stale: false,
+        language_breakdown,
+        primary_language,
+        primary_language_percentage,
+        project_kind: detected.project_kind.map(str::to_string),
+        workspace_kind: detected.workspace_kind,
+        workspace_members: detected.workspace_members,
+        parent_path: parent_path.map(str::to_string),
+        children,
+    }
+}
+
+// Projects directly under `root`, unsorted. Detection (manifests, README,
+// workspaces) runs in parallel without a connection; the database is then
+// read and written in one transaction. File counts are left to the
+// background counter
+fn collect_projects(pool: &DbPool, root: &Path) -> Result<Vec<ProjectDirectory>, String> {
     let entries: Vec<std::path::PathBuf> = fs::read_dir(root)
         .map_err(|e| format!("Failed to read directory: {}", e))?
         .filter_map(|e| e.ok())
@@ -169,37 +660,1075 @@ pub async fn list_project_directories(
         .map(|entry| entry.path())
         .collect();
 
-    // Process in parallel but collect sequentially for database access
-    let mut projects = Vec::new();
-    for path in entries {
-        if let Some(project) = process_project_directory(path, &conn) {
-            projects.push(project);
+    let (ignored, stale_after) = {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        (IgnoreList::load(&conn), stale_after_months(&conn))
+    };
+    let detected: Vec<DetectedProject> = entries
+        .into_par_iter()
+        .filter_map(|path| detect_candidate(path, &ignored))
+        .collect();
+
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut projects: Vec<ProjectDirectory> = detected.into_iter().map(|d| store_project(d, &tx, None)).collect();
+    tx.commit().map_err(|e| e.to_string())?;
+
+    mark_stale(&mut projects, stale_cutoff(stale_after));
+    Ok(projects)
+}
+
+// Sorting and filtering for the listing; omitted fields keep the old
+// behavior (alphabetical, unfiltered apart from archived projects, flat)
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct ListingOptions {
+    // "name", "file_count", "size", "last_analyzed" or "last_opened"
+    pub sort_by: Option<String>,
+    pub descending: Option<bool>,
+    // Case-insensitive substring of the name or description
+    pub filter_text: Option<String>,
+    pub only_git: bool,
+    pub only_favorites: bool,
+    pub nested: bool,
+    // Archived projects are hidden unless asked for
+    pub include_archived: bool,
+}
+
+impl ListingOptions {
+    fn matches(&self, project: &ProjectDirectory) -> bool {
+        let text = self.filter_text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+        let text_matches = text.map(str::to_lowercase).is_none_or(|text| {
+            project.name.to_lowercase().contains(&text)
+                || project.description.as_ref().is_some_and(|d| d.to_lowercase().contains(&text))
+        });
+        text_matches
+            && (!self.only_git || project.is_git_repo)
+            && (!self.only_favorites || project.is_favorite)
+            && (self.include_archived || !project.is_archived)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectListing {
+    pub projects: Vec<ProjectDirectory>,
+    // Entries before filtering, for "12 of 118 projects"
+    pub total_count: usize,
+}
+
+fn directed(ordering: std::cmp::Ordering, descending: bool) -> std::cmp::Ordering {
+    if descending { ordering.reverse() } else { ordering }
+}
+
+// `descending` defaults to false for "name" and true for the rest (largest
+// and most recent first). Projects without the date sort last either way;
+// ties keep alphabetical order
+fn sort_projects(projects: &mut [ProjectDirectory], sort_by: &str, descending: Option<bool>) -> Result<(), String> {
+    let descending = descending.unwrap_or(sort_by != "name");
+    projects.sort_by_cached_key(|p| p.name.to_lowercase());
+    match sort_by {
+        "name" => {
+            if descending {
+                projects.reverse();
+            }
+        }
+        "file_count" => projects.sort_by(|a, b| directed(a.file_count.cmp(&b.file_count), descending)),
+        "size" => projects.sort_by(|a, b| directed(a.total_size_bytes.cmp(&b.total_size_bytes), descending)),
+        "last_opened" | "last_analyzed" => {
+            let date = |p: &ProjectDirectory| if sort_by == "last_opened" { p.last_opened_at } else { p.last_analyzed_at };
+            projects.sort_by(|a, b| match (date(a), date(b)) {
+                (Some(x), Some(y)) => directed(x.cmp(&y), descending),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            });
         }
+        other => return Err(format!("Unknown sort \"{}\"", other)),
     }
+    Ok(())
+}
+
+// Nested view keeps a root when it or any of its members match, with only
+// the matching members; the flat view filters every entry on its own
+fn filter_projects(projects: Vec<ProjectDirectory>, filter: &ListingOptions) -> Vec<ProjectDirectory> {
+    if !filter.nested {
+        return flatten_projects(projects).into_iter().filter(|p| filter.matches(p)).collect();
+    }
+    projects
+        .into_iter()
+        .filter_map(|mut root| {
+            root.children.retain(|c| filter.matches(c));
+            (filter.matches(&root) || !root.children.is_empty()).then_some(root)
+        })
+        .collect()
+}
 
-    projects.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+#[tauri::command]
+pub async fn list_project_directories(
+    app: AppHandle,
+    db_pool: State<'_, Arc<DbPool>>,
+    root_path: String,
+    options: Option<ListingOptions>,
+) -> Result<ProjectListing, String> {
+    let options = options.unwrap_or_default();
+    let root = Path::new(&root_path);
+    if !fs_utils::is_existing_dir(root) {
+        return Err("Invalid root directory".to_string());
+    }
+
+    let pool = db_pool.inner().clone();
+    let mut projects = tokio::task::spawn_blocking(move || collect_projects(&pool, Path::new(&root_path)))
+        .await
+        .map_err(|e| format!("Listing task failed: {}", e))??;
+
+    sort_projects(&mut projects, options.sort_by.as_deref().unwrap_or("name"), options.descending)?;
+    let pending: Vec<String> = projects
+        .iter()
+        .flat_map(|p| std::iter::once(p).chain(&p.children))
+        .filter(|p| p.is_counting)
+        .map(|p| p.path.clone())
+        .collect();
+    spawn_background_counts(app, db_pool.inner().clone(), pending);
+
+    let total_count = if options.nested {
+        projects.len()
+    } else {
+        projects.iter().map(|p| 1 + p.children.len()).sum()
+    };
+    // Flat view: every member follows its root
+    Ok(ProjectListing { projects: filter_projects(projects, &options), total_count })
+}
+
+fn flatten_projects(projects: Vec<ProjectDirectory>) -> Vec<ProjectDirectory> {
+    let mut flat = Vec::with_capacity(projects.len());
+    for mut project in projects {
+        let children = std::mem::take(&mut project.children);
+        flat.push(project);
+        flat.extend(children);
+    }
+    flat
+}
+
+#[tauri::command]
+pub async fn record_project_opened(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<(), String> {
+    let project_path = canonical_path(Path::new(&project_path));
+    let path = Path::new(&project_path);
+    if !path.is_dir() {
+        return Err("Invalid project path".to_string());
+    }
+
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+
+    if !db::record_project_opened(&conn, &project_path).map_err(|e| e.to_string())? {
+        // Project was never listed; register it before recording the open
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown");
+        db::upsert_project(
+            &conn,
+            &project_path,
+            name,
+            get_project_description(path).as_deref(),
+            path.join(".git").exists(),
+        ).map_err(|e| e.to_string())?;
+        db::record_project_opened(&conn, &project_path).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// Rows kept from before a pattern was added (without deleting data) are
+// still hidden; the limit applies after filtering
+fn without_ignored(
+    conn: &rusqlite::Connection,
+    limit: u32,
+    query: impl Fn(u32) -> Result<Vec<db::Project>, rusqlite::Error>,
+) -> Result<Vec<db::Project>, String> {
+    let ignored = IgnoreList::load(conn);
+    let mut projects = query(limit.saturating_add(IGNORED_HEADROOM)).map_err(|e| e.to_string())?;
+    projects.retain(|p| !ignored.matches(&p.path));
+    projects.truncate(limit as usize);
     Ok(projects)
 }
 
+#[tauri::command]
+pub async fn get_recent_projects(
+    db_pool: State<'_, Arc<DbPool>>,
+    limit: Option<u32>,
+) -> Result<Vec<db::Project>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    without_ignored(&conn, limit.unwrap_or(10), |n| db::get_recent_projects(&conn, n))
+}
+
+#[tauri::command]
+pub async fn get_most_opened_projects(
+    db_pool: State<'_, Arc<DbPool>>,
+    limit: Option<u32>,
+) -> Result<Vec<db::Project>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    without_ignored(&conn, limit.unwrap_or(10), |n| db::get_most_opened_projects(&conn, n))
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectPage {
+    pub projects: Vec<db::Project>,
+    pub has_more: bool,
+    // Pass back as `cursor` for the next page
+    pub next_cursor: Option<i64>,
+}
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 500;
+
+// Every stored project, a page at a time in id order; archived ones only
+// with `include_archived`. Ignored projects are dropped after paging, so a
+// page can come back short while has_more is set
+#[tauri::command]
+pub async fn list_all_projects_paged(
+    db_pool: State<'_, Arc<DbPool>>,
+    cursor: Option<i64>,
+    page_size: Option<u32>,
+    include_archived: Option<bool>,
+) -> Result<ProjectPage, String> {
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize;
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    // One extra row tells whether another page follows
+    let mut projects = db::get_projects_page(&conn, cursor, page_size + 1, include_archived.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+    let has_more = projects.len() > page_size;
+    projects.truncate(page_size);
+    let next_cursor = if has_more { projects.last().map(|p| p.id) } else { None };
+
+    let ignored = IgnoreList::load(&conn);
+    projects.retain(|p| !ignored.matches(&p.path));
+    Ok(ProjectPage { projects, has_more, next_cursor })
+}
+
+// With `delete_data`, stored rows for every known project the pattern
+// matches are removed too. Returns how many were removed
+#[tauri::command]
+pub async fn add_ignored_project(
+    db_pool: State<'_, Arc<DbPool>>,
+    pattern: String,
+    delete_data: Option<bool>,
+) -> Result<usize, String> {
+    let pattern = pattern.trim().to_string();
+    if pattern.is_empty() {
+        return Err("Pattern is empty".to_string());
+    }
+    if pattern.contains(['*', '?', '[']) {
+        glob::Pattern::new(&pattern).map_err(|e| format!("Invalid pattern \"{}\": {}", pattern, e))?;
+    }
+
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::add_ignored_project(&conn, &pattern).map_err(|e| e.to_string())?;
+    if !delete_data.unwrap_or(false) {
+        return Ok(0);
+    }
+    let ignored = IgnoreList::new(std::slice::from_ref(&pattern));
+    let mut removed = 0;
+    for path in db::all_project_paths(&conn).map_err(|e| e.to_string())? {
+        if ignored.matches(&path) && db::delete_project(&mut conn, &path).map_err(|e| e.to_string())? {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn remove_ignored_project(
+    db_pool: State<'_, Arc<DbPool>>,
+    pattern: String,
+) -> Result<bool, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::remove_ignored_project(&conn, &pattern).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_ignored_projects(db_pool: State<'_, Arc<DbPool>>) -> Result<Vec<String>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::list_ignored_projects(&conn).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn update_project_file_count(
     db_pool: State<'_, Arc<DbPool>>,
     project_path: String,
+    symlinks: Option<fs_utils::SymlinkPolicy>,
 ) -> Result<usize, String> {
+    let project_path = canonical_path(Path::new(&project_path));
     let path = Path::new(&project_path);
-    if !path.exists() || !path.is_dir() { 
+    if !fs_utils::is_existing_dir(path) { 
         return Err("Invalid project path".to_string()); 
     }
-    
-    let count = count_project_files(path);
-    
+    count_and_store(&db_pool, &project_path, &fs_utils::FileFilter::current(), symlinks.unwrap_or_default())
+        .map(|counted| counted.count)
+}
+
+pub fn active_watchers() -> usize {
+    PROJECT_WATCHERS
+        .lock()
+        .map(|watchers| watchers.values().filter(|task| !task.inner().is_finished()).count())
+        .unwrap_or(0)
+}
+
+// Emits `project:files_changed` with each debounced batch of changes under
+// the project until `unwatch_project`. Watching an already watched path is a no-op
+#[tauri::command]
+pub async fn watch_project(app: AppHandle, project_path: String) -> Result<(), String> {
+    let project_path = canonical_path(Path::new(&project_path));
+    if !fs_utils::is_existing_dir(Path::new(&project_path)) {
+        return Err("Invalid project path".to_string());
+    }
+    let mut watchers = PROJECT_WATCHERS.lock().map_err(|e| e.to_string())?;
+    if watchers.get(&project_path).is_some_and(|task| !task.inner().is_finished()) {
+        return Ok(());
+    }
+    let mut watcher = fs_utils::watch::watch(Path::new(&project_path), fs_utils::watch::DEFAULT_DEBOUNCE)?;
+    let path = project_path.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        // Batches queue up in the channel while background work is paused
+        while let Some(changes) = watcher.next_batch().await {
+            crate::background::wait_until_resumed().await;
+            let _ = app.emit("project:files_changed", &FilesChanged { path: path.clone(), changes });
+        }
+    });
+    watchers.insert(project_path, task);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unwatch_project(project_path: String) -> Result<(), String> {
+    let project_path = canonical_path(Path::new(&project_path));
+    if let Some(task) = PROJECT_WATCHERS.lock().map_err(|e| e.to_string())?.remove(&project_path) {
+        task.abort();
+    }
+    Ok(())
+}
+
+// Re-detects one project's description, git flag and file count and
+// returns its refreshed listing entry
+#[tauri::command]
+pub async fn rescan_project(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<ProjectDirectory, String> {
+    let project_path = canonical_path(Path::new(&project_path));
+    let path = Path::new(&project_path);
+    if !path.is_dir() || !is_project_directory(path) {
+        return Err(format!("{} is not a project directory", project_path));
+    }
+
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&project_path);
+        let description = get_project_description(path);
+        let is_git_repo = path.join(".git").exists();
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let existing = db::get_project_by_path(&conn, &project_path).map_err(|e| e.to_string())?;
+        let default_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if existing.is_some() {
+            db::refresh_project_metadata(&conn, &project_path, description.as_deref(), is_git_repo)
+        } else {
+            db::upsert_project(&conn, &project_path, &default_name, description.as_deref(), is_git_repo).map(|_| true)
+        }
+        .map_err(|e| e.to_string())?;
+        count_and_store(&pool, &project_path, &fs_utils::FileFilter::current(), fs_utils::SymlinkPolicy::default())?;
+
+        let parent_path = existing
+            .and_then(|p| p.parent_id)
+            .and_then(|id| db::get_project_path(&conn, id).ok().flatten());
+        let ignored = IgnoreList::load(&conn);
+        let mut project = build_project_directory(path, default_name, &conn, parent_path.as_deref(), &ignored);
+        mark_stale(std::slice::from_mut(&mut project), stale_cutoff(stale_after_months(&conn)));
+        Ok(project)
+    })
+    .await
+    .map_err(|e| format!("Rescan task failed: {}", e))?
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BulkAction {
+    Favorite,
+    Unfavorite,
+    Archive,
+    Unarchive,
+    AddTag { tag: String },
+    RemoveTag { tag: String },
+    Delete,
+    QueueAnalysis,
+}
+
+impl BulkAction {
+    fn is_destructive(&self) -> bool {
+        matches!(self, BulkAction::Delete)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BulkOutcome {
+    pub path: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+// Applies `action` to each stored project in one transaction. A path that
+// fails is reported and the rest still apply; only a database error aborts
+// the whole batch
+fn apply_bulk_action(conn: &mut rusqlite::Connection, paths: &[String], action: &BulkAction) -> Result<Vec<BulkOutcome>, String> {
+    let tag = match action {
+        BulkAction::AddTag { tag } | BulkAction::RemoveTag { tag } => {
+            let tag = tag.trim();
+            if tag.is_empty() {
+                return Err("Tag is empty".to_string());
+            }
+            // Stored tags are read back split on line breaks
+            if tag.contains(['\n', '\r']) {
+                return Err("Tag can't contain line breaks".to_string());
+            }
+            Some(tag.to_string())
+        }
+        _ => None,
+    };
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut outcomes = Vec::with_capacity(paths.len());
+    for path in paths {
+        let known = db::get_project_by_path(&tx, path).map_err(|e| e.to_string())?.is_some();
+        let result = if !known {
+            Err("Project not found".to_string())
+        } else {
+            let tag = tag.as_deref().unwrap_or_default();
+            match action {
+                BulkAction::Favorite | BulkAction::Unfavorite => {
+                    db::toggle_favorite(&tx, path, *action == BulkAction::Favorite).map(|_| true)
+                }
+                BulkAction::Archive | BulkAction::Unarchive => {
+                    db::set_project_archived(&tx, path, *action == BulkAction::Archive)
+                }
+                BulkAction::AddTag { .. } => db::add_project_tag(&tx, path, tag),
+                BulkAction::RemoveTag { .. } => db::remove_project_tag(&tx, path, tag).map(|_| true),
+                BulkAction::Delete => db::delete_project_rows(&tx, path),
+                // Nothing to write; the caller starts the analyses
+                BulkAction::QueueAnalysis => Ok(true),
+            }
+            .map_err(|e| e.to_string())
+            .and_then(|found| if found { Ok(()) } else { Err("Project not found".to_string()) })
+        };
+        outcomes.push(BulkOutcome {
+            path: path.clone(),
+            ok: result.is_ok(),
+            error: result.err(),
+        });
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(outcomes)
+}
+
+// One action over many projects. Destructive actions need `confirm: true`.
+// Emits a single `projects:bulk_updated` with the paths that changed so the
+// UI reloads once; queued analyses run in the background afterwards
+#[tauri::command]
+pub async fn bulk_project_action(
+    app: AppHandle,
+    db_pool: State<'_, Arc<DbPool>>,
+    paths: Vec<String>,
+    action: BulkAction,
+    confirm: Option<bool>,
+) -> Result<Vec<BulkOutcome>, String> {
+    if action.is_destructive() && !confirm.unwrap_or(false) {
+        return Err("This action cannot be undone; pass confirm: true to proceed".to_string());
+    }
+    let mut paths: Vec<String> = paths.iter().map(|p| canonical_path(Path::new(p))).collect();
+    let mut seen = HashSet::new();
+    paths.retain(|p| seen.insert(p.clone()));
+
+    let pool = db_pool.inner().clone();
+    let bulk_action = action.clone();
+    let outcomes = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(|e| e.to_string())?;
+        apply_bulk_action(&mut conn, &paths, &bulk_action)
+    })
+    .await
+    .map_err(|e| format!("Bulk action task failed: {}", e))??;
+
+    let affected: Vec<String> = outcomes.iter().filter(|o| o.ok).map(|o| o.path.clone()).collect();
+    if !affected.is_empty() {
+        let _ = app.emit("projects:bulk_updated", serde_json::json!({ "action": action, "paths": affected }));
+    }
+    if action == BulkAction::QueueAnalysis && !affected.is_empty() {
+        tauri::async_runtime::spawn(crate::analysis::queue_analyses(db_pool.inner().clone(), affected, false));
+    }
+    Ok(outcomes)
+}
+
+// Projects idle for longer than `months` (the saved threshold when omitted),
+// least recently active first
+#[tauri::command]
+pub async fn get_stale_projects(
+    db_pool: State<'_, Arc<DbPool>>,
+    months: Option<u32>,
+) -> Result<Vec<db::Project>, String> {
     let conn = db_pool.get().map_err(|e| e.to_string())?;
-    
-    // Get or create project
-    if let Ok(Some(project)) = db::get_project_by_path(&conn, &project_path) {
-        db::update_project_file_count(&conn, project.id, count as i64)
-            .map_err(|e| e.to_string())?;
+    let months = months.unwrap_or_else(|| stale_after_months(&conn));
+    let ignored = IgnoreList::load(&conn);
+    let mut projects = db::get_stale_projects(&conn, stale_cutoff(months)).map_err(|e| e.to_string())?;
+    projects.retain(|p| !ignored.matches(&p.path));
+    Ok(projects)
+}
+
+#[tauri::command]
+pub async fn get_stale_after_months(db_pool: State<'_, Arc<DbPool>>) -> Result<u32, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(stale_after_months(&conn))
+}
+
+#[tauri::command]
+pub async fn save_stale_after_months(db_pool: State<'_, Arc<DbPool>>, months: u32) -> Result<(), String> {
+    if months == 0 {
+        return Err("The stale threshold must be at least one month".to_string());
+    }
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::save_setting(&conn, STALE_AFTER_KEY, &months.to_string()).map_err(|e| e.to_string())
+}
+
+// Global file ignore patterns (gitignore syntax), stored as a JSON array and
+// mirrored into fs_utils for every walker
+const IGNORE_PATTERNS_KEY: &str = "ignore_patterns";
+// Hidden paths walked despite the leading dot; unset means the defaults
+const HIDDEN_ALLOWLIST_KEY: &str = "hidden_allowlist";
+
+fn load_pattern_list(conn: &rusqlite::Connection, key: &str) -> Option<Vec<String>> {
+    db::load_setting(conn, key)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+fn load_ignore_patterns(conn: &rusqlite::Connection) -> Vec<String> {
+    load_pattern_list(conn, IGNORE_PATTERNS_KEY).unwrap_or_default()
+}
+
+fn load_hidden_allowlist(conn: &rusqlite::Connection) -> Vec<String> {
+    load_pattern_list(conn, HIDDEN_ALLOWLIST_KEY)
+        .unwrap_or_else(|| fs_utils::DEFAULT_HIDDEN_ALLOWLIST.iter().map(|p| p.to_string()).collect())
+}
+
+// Trimmed, validated and de-duplicated, then stored as JSON
+fn save_pattern_list(conn: &rusqlite::Connection, key: &str, patterns: &[String]) -> Result<Vec<String>, String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        fs_utils::validate_ignore_pattern(pattern)?;
+        if !cleaned.iter().any(|p| p == pattern) {
+            cleaned.push(pattern.to_string());
+        }
+    }
+    let json = serde_json::to_string(&cleaned).map_err(|e| e.to_string())?;
+    db::save_setting(conn, key, &json).map_err(|e| e.to_string())?;
+    Ok(cleaned)
+}
+
+// Extra ignored extensions and directories, and extensions let back in
+const FILE_FILTER_KEY: &str = "file_filter";
+
+fn load_file_filter_settings(conn: &rusqlite::Connection) -> fs_utils::FileFilterSettings {
+    db::load_setting(conn, FILE_FILTER_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// Call once at startup so the first walk already applies saved patterns
+// and filter rules
+pub fn init_walk_settings(db_pool: &DbPool) {
+    if let Ok(conn) = db_pool.get() {
+        fs_utils::set_global_ignore_patterns(load_ignore_patterns(&conn));
+        fs_utils::set_hidden_allowlist(load_hidden_allowlist(&conn));
+        fs_utils::set_file_filter_settings(load_file_filter_settings(&conn));
+    }
+}
+
+#[tauri::command]
+pub async fn get_ignore_patterns(db_pool: State<'_, Arc<DbPool>>) -> Result<Vec<String>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(load_ignore_patterns(&conn))
+}
+
+// Replaces the whole list; walks started afterwards pick it up
+#[tauri::command]
+pub async fn save_ignore_patterns(db_pool: State<'_, Arc<DbPool>>, patterns: Vec<String>) -> Result<(), String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    fs_utils::set_global_ignore_patterns(save_pattern_list(&conn, IGNORE_PATTERNS_KEY, &patterns)?);
+    fs_utils::invalidate_walker_cache(None);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_file_filter_settings(db_pool: State<'_, Arc<DbPool>>) -> Result<fs_utils::FileFilterSettings, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(load_file_filter_settings(&conn))
+}
+
+// Runs started afterwards use the new rules; counts and analyses already
+// stored keep theirs until refreshed
+#[tauri::command]
+pub async fn save_file_filter_settings(
+    db_pool: State<'_, Arc<DbPool>>,
+    settings: fs_utils::FileFilterSettings,
+) -> Result<(), String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    db::save_setting(&conn, FILE_FILTER_KEY, &json).map_err(|e| e.to_string())?;
+    fs_utils::set_file_filter_settings(settings);
+    fs_utils::invalidate_walker_cache(None);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_hidden_allowlist(db_pool: State<'_, Arc<DbPool>>) -> Result<Vec<String>, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(load_hidden_allowlist(&conn))
+}
+
+#[tauri::command]
+pub async fn save_hidden_allowlist(db_pool: State<'_, Arc<DbPool>>, patterns: Vec<String>) -> Result<(), String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    fs_utils::set_hidden_allowlist(save_pattern_list(&conn, HIDDEN_ALLOWLIST_KEY, &patterns)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process_project_directory(
+        path: std::path::PathBuf,
+        conn: &rusqlite::Connection,
+        ignored: &IgnoreList,
+    ) -> Option<ProjectDirectory> {
+        detect_candidate(path, ignored).map(|detected| store_project(detected, conn, None))
+    }
+
+    #[test]
+    fn test_is_project_directory_detects_newer_indicators() {
+        for indicator in ["flake.nix", "deno.json", "deno.jsonc", "bun.toml", "justfile", "Justfile"] {
+            let dir = tempfile::tempdir().unwrap();
+            fs::write(dir.path().join(indicator), "").unwrap();
+            assert!(is_project_directory(dir.path()), "{} not detected", indicator);
+        }
+
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        assert!(is_project_directory(workspace.path()));
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(!is_project_directory(empty.path()));
+    }
+
+    #[test]
+    fn test_detect_project_kind_per_layout() {
+        let kind_of = |files: &[&str]| {
+            let dir = tempfile::tempdir().unwrap();
+            for file in files {
+                let path = dir.path().join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, "").unwrap();
+            }
+            detect_project_kind(dir.path())
+        };
+
+        assert_eq!(kind_of(&["pubspec.yaml", "lib/main.dart"]), Some("dart"));
+        assert_eq!(kind_of(&["pyproject.toml"]), Some("python"));
+        assert_eq!(kind_of(&["setup.py"]), Some("python"));
+        assert_eq!(kind_of(&["setup.cfg"]), Some("python"));
+        assert_eq!(kind_of(&["mix.exs", "apps/web/mix.exs"]), Some("elixir"));
+        assert_eq!(kind_of(&["Acme.sln", "src/Acme/Program.cs"]), Some("dotnet"));
+        assert_eq!(kind_of(&["main.tf", "variables.tf"]), Some("terraform"));
+        assert_eq!(kind_of(&["deno.json", "package.json"]), Some("deno"));
+        assert_eq!(kind_of(&["bun.lockb", "package.json"]), Some("bun"));
+        assert_eq!(kind_of(&["package.json", "README.md"]), Some("node"));
+        assert_eq!(kind_of(&["README.md"]), Some("docs"));
+
+        // A bare git repo counts only when it holds source files
+        assert_eq!(kind_of(&[".git/HEAD", "scripts/deploy.sh"]), Some("git"));
+        assert_eq!(kind_of(&[".git/HEAD", "notes/todo.txt"]), None);
+        assert_eq!(kind_of(&["scripts/deploy.sh"]), None);
+    }
+
+    #[test]
+    fn test_get_project_description_reads_other_ecosystems() {
+        let py = tempfile::tempdir().unwrap();
+        fs::write(
+            py.path().join("pyproject.toml"),
+            "[tool.black]\ndescription = \"not this\"\n\n[project]\nname = \"demo\"\ndescription = \"A tidy CLI\"\n",
+        ).unwrap();
+        assert_eq!(get_project_description(py.path()).as_deref(), Some("A tidy CLI"));
+
+        let php = tempfile::tempdir().unwrap();
+        fs::write(php.path().join("composer.json"), r#"{"name": "acme/demo", "description": "PHP toolkit"}"#).unwrap();
+        assert_eq!(get_project_description(php.path()).as_deref(), Some("PHP toolkit"));
+
+        let go = tempfile::tempdir().unwrap();
+        fs::write(go.path().join("go.mod"), "module github.com/acme/demo\n\ngo 1.22\n").unwrap();
+        assert_eq!(get_project_description(go.path()).as_deref(), Some("Go module: github.com/acme/demo"));
+
+        // A README beats the bare module path
+        fs::write(go.path().join("README.md"), "# Demo service\n").unwrap();
+        assert_eq!(get_project_description(go.path()).as_deref(), Some("Demo service"));
+
+        // Its first paragraph beats the title
+        fs::write(go.path().join("README.md"), "# Demo service\n\nServes demos over HTTP.\n").unwrap();
+        assert_eq!(get_project_description(go.path()).as_deref(), Some("Serves demos over HTTP."));
+    }
+
+    #[test]
+    fn test_get_project_description_per_manifest_type() {
+        let described = |files: &[(&str, &str)]| {
+            let dir = tempfile::tempdir().unwrap();
+            for (name, content) in files {
+                fs::write(dir.path().join(name), content).unwrap();
+            }
+            get_project_description(dir.path())
+        };
+
+        let cargo = "[package]\nname = \"demo\"\ndescription = \"\"\"\nFast\nparser\"\"\"\n";
+        assert_eq!(described(&[("Cargo.toml", cargo)]).as_deref(), Some("Fast\nparser"));
+        let inline = "package = { name = \"demo\", description = \"Inline crate\" }\n";
+        assert_eq!(described(&[("Cargo.toml", inline)]).as_deref(), Some("Inline crate"));
+        let virtual_manifest = "[workspace]\nmembers = []\n\n[workspace.package]\ndescription = \"Shared\"\n";
+        assert_eq!(described(&[("Cargo.toml", virtual_manifest)]).as_deref(), Some("Shared"));
+
+        let gemspec = "Gem::Specification.new do |spec|\n  spec.name = \"demo\"\n  spec.description = %q{Longer text}\n  spec.summary = 'Ruby helpers'\nend\n";
+        assert_eq!(described(&[("demo.gemspec", gemspec)]).as_deref(), Some("Ruby helpers"));
+        let gemfile = "# frozen_string_literal: true\n# Rails app for the bakery\nsource \"https://rubygems.org\"\n";
+        assert_eq!(described(&[("Gemfile", gemfile)]).as_deref(), Some("Rails app for the bakery"));
+
+        let pubspec = "name: demo\ndescription: >\n  A Flutter app\n  for notes.\nversion: 1.0.0\n";
+        assert_eq!(described(&[("pubspec.yaml", pubspec)]).as_deref(), Some("A Flutter app for notes."));
+        let csproj = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <Description>\n      .NET tools\n    </Description>\n  </PropertyGroup>\n</Project>\n";
+        assert_eq!(described(&[("Demo.csproj", csproj)]).as_deref(), Some(".NET tools"));
+
+        let doc_go = "// Copyright 2024 Acme\n\n// Package demo serves demos\n// over HTTP.\n//\n// Usage: demo serve\npackage demo\n";
+        let go = [("go.mod", "module github.com/acme/demo\n"), ("doc.go", doc_go), ("README.md", "# Demo\n")];
+        assert_eq!(described(&go).as_deref(), Some("Package demo serves demos over HTTP."));
+    }
+
+    #[test]
+    fn test_workspace_members_become_child_entries() {
+        let (dir, pool) = db::test_pool();
+        let root = dir.path().join("mono");
+        fs::create_dir_all(root.join("packages/api/src")).unwrap();
+        fs::write(root.join("package.json"), r#"{"name": "mono", "workspaces": ["packages/*"]}"#).unwrap();
+        fs::write(root.join("packages/api/package.json"), r#"{"name": "@mono/api"}"#).unwrap();
+        fs::write(root.join("packages/api/src/index.ts"), "export {};\n").unwrap();
+        let conn = pool.get().unwrap();
+
+        let project = process_project_directory(root.clone(), &conn, &IgnoreList::default()).unwrap();
+        let member_path = root.join("packages/api").to_string_lossy().to_string();
+        assert_eq!(project.children.len(), 1);
+        assert_eq!(project.children[0].name, "@mono/api");
+        assert!(project.children[0].is_counting);
+        assert_eq!(project.children[0].file_count, 0);
+        assert_eq!(count_and_store(&pool, &member_path, &fs_utils::FileFilter::default(), Default::default()).map(|c| c.count), Ok(2));
+        let counted = process_project_directory(root.clone(), &conn, &IgnoreList::default()).unwrap();
+        assert!(!counted.children[0].is_counting);
+        assert_eq!(counted.children[0].file_count, 2);
+        assert_eq!(counted.children[0].primary_language.as_deref(), Some("TypeScript"));
+        assert_eq!(counted.primary_language, None);
+        assert_eq!(project.children[0].parent_path.as_deref(), Some(project.path.as_str()));
+
+        let root_id = db::get_project_by_path(&conn, &project.path).unwrap().unwrap().id;
+        let member = db::get_project_by_path(&conn, &member_path).unwrap().unwrap();
+        assert_eq!(member.parent_id, Some(root_id));
+
+        let flat = flatten_projects(vec![project]);
+        let paths: Vec<&str> = flat.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec![root.to_string_lossy().as_ref(), member_path.as_str()]);
+        assert!(flat[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_ignore_list_paths_and_globs() {
+        let ignored = IgnoreList::new(&["/code/scratch".to_string(), "**/tmp-*".to_string()]);
+        assert!(ignored.matches("/code/scratch"));
+        assert!(ignored.matches("/code/scratch/packages/ui"));
+        assert!(!ignored.matches("/code/scratchpad"));
+        assert!(ignored.matches("/code/tmp-rust-book"));
+        assert!(ignored.matches("C:\\code\\tmp-clone"));
+        assert!(!ignored.matches("/code/repomuse"));
+    }
+
+    #[test]
+    fn test_ignored_projects_are_not_stored() {
+        let (dir, pool) = db::test_pool();
+        let project = dir.path().join("tmp-tutorial");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"tutorial\"\n").unwrap();
+        let mut conn = pool.get().unwrap();
+        let path = project.to_string_lossy().to_string();
+
+        db::add_ignored_project(&conn, "**/tmp-*").unwrap();
+        assert!(process_project_directory(project.clone(), &conn, &IgnoreList::load(&conn)).is_none());
+        assert!(db::get_project_by_path(&conn, &path).unwrap().is_none());
+
+        assert!(db::remove_ignored_project(&conn, "**/tmp-*").unwrap());
+        assert!(process_project_directory(project.clone(), &conn, &IgnoreList::load(&conn)).is_some());
+        assert!(db::delete_project(&mut conn, &path).unwrap());
+        assert!(db::get_project_by_path(&conn, &path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_listing_refreshes_metadata_after_directory_changes() {
+        let (dir, pool) = db::test_pool();
+        let project = dir.path().join("tool");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"tool\"\n").unwrap();
+        let conn = pool.get().unwrap();
+        let listed = process_project_directory(project.clone(), &conn, &IgnoreList::default()).unwrap();
+        assert!(!listed.is_git_repo);
+
+        fs::create_dir(project.join(".git")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"tool\"\ndescription = \"A tool\"\n").unwrap();
+        conn.execute("UPDATE projects SET updated_at = datetime('now', '-1 hour')", []).unwrap();
+        let listed = process_project_directory(project.clone(), &conn, &IgnoreList::default()).unwrap();
+        assert!(listed.is_git_repo);
+        let stored = db::get_project_by_path(&conn, &listed.path).unwrap().unwrap();
+        assert!(stored.is_git_repo);
+        assert_eq!(stored.description.as_deref(), Some("A tool"));
+    }
+
+    #[test]
+    fn test_collect_projects_stays_fast_on_large_tree() {
+        // 60 projects of 200 files each: listing must not walk them
+        let (dir, pool) = db::test_pool();
+        let root = dir.path().join("code");
+        for p in 0..60 {
+            let project = root.join(format!("project-{:02}", p));
+            for d in 0..10 {
+                let src = project.join(format!("src/mod{}", d));
+                fs::create_dir_all(&src).unwrap();
+                for f in 0..20 {
+                    fs::write(src.join(format!("file{}.rs", f)), "pub fn f() {}\n").unwrap();
+                }
+            }
+            fs::write(project.join("Cargo.toml"), format!("[package]\nname = \"p{}\"\ndescription = \"Project {}\"\n", p, p)).unwrap();
+        }
+        fs::create_dir_all(root.join("notes")).unwrap();
+
+        let started = std::time::Instant::now();
+        let projects = collect_projects(&pool, &root).unwrap();
+        let first = started.elapsed();
+        assert_eq!(projects.len(), 60);
+        assert!(projects.iter().all(|p| p.is_counting && p.file_count == 0));
+        assert!(projects.iter().any(|p| p.description.as_deref() == Some("Project 7")));
+        assert!(projects.iter().all(|p| p.project_kind.as_deref() == Some("rust")));
+        let stored = db::get_project_by_path(&pool.get().unwrap(), &projects[0].path).unwrap().unwrap();
+        assert_eq!(stored.project_kind.as_deref(), Some("rust"));
+
+        let started = std::time::Instant::now();
+        assert_eq!(collect_projects(&pool, &root).unwrap().len(), 60);
+        let second = started.elapsed();
+        // Generous bounds; a regression to per-project walks or one
+        // connection round trip per statement blows well past them
+        assert!(first < std::time::Duration::from_secs(5), "first listing took {:?}", first);
+        assert!(second < std::time::Duration::from_secs(5), "second listing took {:?}", second);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_project_is_stored_once_under_canonical_path() {
+        let (dir, pool) = db::test_pool();
+        let real_root = dir.path().join("volume/code");
+        let project = real_root.join("app");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        fs::write(project.join("src/main.rs"), "fn main() {}\n").unwrap();
+        // A link back to the project root must not send the count around in circles
+        std::os::unix::fs::symlink(&project, project.join("src/loop")).unwrap();
+        let linked_root = dir.path().join("code");
+        std::os::unix::fs::symlink(&real_root, &linked_root).unwrap();
+
+        // A row from before paths were canonical moves to the canonical key
+        let display = linked_root.join("app").to_string_lossy().to_string();
+        db::upsert_project(&pool.get().unwrap(), &display, "My app", None, false).unwrap();
+
+        let via_link = collect_projects(&pool, &linked_root).unwrap();
+        let direct = collect_projects(&pool, &real_root).unwrap();
+        let canonical = canonical_path(&project);
+        assert_eq!(via_link[0].path, canonical);
+        assert_eq!(via_link[0].display_path, display);
+        assert_eq!(via_link[0].name, "My app");
+        assert_eq!(direct[0].path, canonical);
+        assert_eq!(direct[0].display_path, canonical);
+
+        let conn = pool.get().unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM projects", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(count_and_store(&pool, &canonical, &fs_utils::FileFilter::default(), Default::default()).map(|c| c.count), Ok(2));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_row_merges_into_existing_canonical_row() {
+        let (dir, pool) = db::test_pool();
+        let real_root = dir.path().join("volume/code");
+        let project = real_root.join("app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        let linked_root = dir.path().join("code");
+        std::os::unix::fs::symlink(&real_root, &linked_root).unwrap();
+
+        // Both spellings were stored, each with data of its own
+        let canonical = canonical_path(&project);
+        let display = linked_root.join("app").to_string_lossy().to_string();
+        let conn = pool.get().unwrap();
+        let canonical_id = db::upsert_project(&conn, &canonical, "app", None, false).unwrap();
+        let linked_id = db::upsert_project(&conn, &display, "app", None, false).unwrap();
+        db::toggle_favorite(&conn, &display, true).unwrap();
+        for (path, tag) in [(&display, "work"), (&display, "rust"), (&canonical, "rust")] {
+            db::add_project_tag(&conn, path, tag).unwrap();
+        }
+        conn.execute("INSERT INTO tasks (id, project_id, text) VALUES ('t1', ?1, 'Write docs')", [linked_id]).unwrap();
+        drop(conn);
+
+        let listed = collect_projects(&pool, &linked_root).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].path, canonical);
+        assert!(listed[0].is_favorite);
+
+        let conn = pool.get().unwrap();
+        let paths: Vec<String> = conn
+            .prepare("SELECT path FROM projects")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(paths, vec![canonical.clone()]);
+        let stored = db::get_project_by_path(&conn, &canonical).unwrap().unwrap();
+        assert_eq!(stored.id, canonical_id);
+        let mut tags = stored.tags.clone();
+        tags.sort();
+        assert_eq!(tags, vec!["rust", "work"]);
+        let task_owner: i64 = conn.query_row("SELECT project_id FROM tasks WHERE id = 't1'", [], |row| row.get(0)).unwrap();
+        assert_eq!(task_owner, canonical_id);
+    }
+
+    #[test]
+    fn test_sort_and_filter_listing() {
+        let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2026, 1, d).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let project = |name: &str, files: usize, opened: Option<u32>, git: bool| ProjectDirectory {
+            name: name.to_string(),
+            path: format!("/code/{}", name),
+            file_count: files,
+            last_opened_at: opened.map(day),
+            is_git_repo: git,
+            ..Default::default()
+        };
+        let mut projects = vec![
+            project("beta", 40, None, true),
+            project("Alpha", 10, Some(2), false),
+            project("gamma", 40, Some(5), true),
+        ];
+        projects[0].description = Some("Tutorial clone".to_string());
+        projects[2].total_size_bytes = 5000;
+        let names = |projects: &[ProjectDirectory]| projects.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+
+        sort_projects(&mut projects, "file_count", None).unwrap();
+        assert_eq!(names(&projects), vec!["beta", "gamma", "Alpha"]);
+        sort_projects(&mut projects, "size", None).unwrap();
+        assert_eq!(names(&projects), vec!["gamma", "Alpha", "beta"]);
+        sort_projects(&mut projects, "last_opened", Some(false)).unwrap();
+        assert_eq!(names(&projects), vec!["Alpha", "gamma", "beta"]);
+        sort_projects(&mut projects, "name", None).unwrap();
+        assert_eq!(names(&projects), vec!["Alpha", "beta", "gamma"]);
+        assert!(sort_projects(&mut projects, "stars", None).is_err());
+
+        let options = ListingOptions { filter_text: Some(" TUTORIAL ".to_string()), ..Default::default() };
+        assert_eq!(names(&filter_projects(projects.clone(), &options)), vec!["beta"]);
+        let options = ListingOptions { only_git: true, ..Default::default() };
+        assert_eq!(names(&filter_projects(projects.clone(), &options)), vec!["beta", "gamma"]);
+
+        projects[1].is_archived = true;
+        assert_eq!(names(&filter_projects(projects.clone(), &ListingOptions::default())), vec!["Alpha", "gamma"]);
+        let options = ListingOptions { include_archived: true, ..Default::default() };
+        assert_eq!(names(&filter_projects(projects, &options)), vec!["Alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn test_last_activity_comes_from_newest_file() {
+        let (dir, pool) = db::test_pool();
+        let project = dir.path().join("old-tool");
+        fs::create_dir_all(&project).unwrap();
+        let long_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(400 * 24 * 60 * 60);
+        for name in ["main.py", "util.py"] {
+            fs::write(project.join(name), "print(1)\n").unwrap();
+        }
+        fs::File::options().write(true).open(project.join("main.py")).unwrap().set_modified(long_ago).unwrap();
+        fs::File::options().write(true).open(project.join("util.py")).unwrap().set_modified(long_ago - std::time::Duration::from_secs(60)).unwrap();
+        let path = project.to_string_lossy().to_string();
+        db::upsert_project(&pool.get().unwrap(), &path, "old-tool", None, false).unwrap();
+
+        let counted = count_and_store(&pool, &path, &fs_utils::FileFilter::default(), Default::default()).unwrap();
+        assert_eq!((counted.count, counted.total_bytes), (2, 18));
+        let conn = pool.get().unwrap();
+        let stored = db::get_project_by_path(&conn, &path).unwrap().unwrap();
+        let expected = chrono::DateTime::<chrono::Utc>::from(long_ago).timestamp();
+        assert_eq!(stored.last_activity_at.map(|at| at.timestamp()), Some(expected));
+
+        let stale = db::get_stale_projects(&conn, stale_cutoff(6)).unwrap();
+        assert_eq!(stale.len(), 1);
+        assert!(db::get_stale_projects(&conn, stale_cutoff(24)).unwrap().is_empty());
+
+        let mut listed = vec![ProjectDirectory { last_activity_at: stored.last_activity_at, ..Default::default() }];
+        mark_stale(&mut listed, stale_cutoff(DEFAULT_STALE_AFTER_MONTHS));
+        assert!(listed[0].is_stale);
+    }
+
+    #[test]
+    fn test_bulk_action_reports_each_path() {
+        let (_dir, pool) = db::test_pool();
+        let mut conn = pool.get().unwrap();
+        for path in ["/code/a", "/code/b"] {
+            db::upsert_project(&conn, path, "p", None, false).unwrap();
+        }
+        let paths = vec!["/code/a".to_string(), "/code/b".to_string(), "/code/missing".to_string()];
+
+        let tag = BulkAction::AddTag { tag: " client ".into() };
+        let outcomes = apply_bulk_action(&mut conn, &paths, &tag).unwrap();
+        let ok: Vec<bool> = outcomes.iter().map(|o| o.ok).collect();
+        assert_eq!(ok, vec![true, true, false]);
+        assert_eq!(outcomes[2].error.as_deref(), Some("Project not found"));
+        apply_bulk_action(&mut conn, &paths[..1], &BulkAction::Archive).unwrap();
+        let a = db::get_project_by_path(&conn, "/code/a").unwrap().unwrap();
+        assert!(a.is_archived);
+        assert_eq!(a.tags, vec!["client"]);
+
+        apply_bulk_action(&mut conn, &paths, &BulkAction::Delete).unwrap();
+        assert!(db::get_project_by_path(&conn, "/code/b").unwrap().is_none());
+        let empty = BulkAction::RemoveTag { tag: " ".into() };
+        assert!(apply_bulk_action(&mut conn, &paths, &empty).is_err());
+        let multiline = BulkAction::AddTag { tag: "client\nwork".into() };
+        assert!(apply_bulk_action(&mut conn, &paths, &multiline).is_err());
+    }
+
+    #[test]
+    fn test_repomuseignore_excludes_files_from_count_and_languages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("legacy")).unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("legacy/old.py"), "print('hi')\n").unwrap();
+        fs::write(root.join(fs_utils::REPOMUSE_IGNORE_FILE), "legacy/\n").unwrap();
+
+        let filter = fs_utils::FileFilter::default();
+        assert_eq!(count_project_files(root, &filter, Default::default()).count, 1);
+        let sizes = languages::scan_sizes(root, &filter);
+        assert_eq!(sizes.keys().collect::<Vec<_>>(), vec!["Rust"]);
     }
-    
-    Ok(count)
 }
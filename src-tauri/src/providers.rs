@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::ai::{parse_models_response, ModelInfo};
+use crate::storage::Settings;
+
+// Each probe gets this long; all probes run concurrently so detection stays under ~2s
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderPreset {
+    pub id: String,
+    pub name: String,
+    pub api_url: String,
+    pub requires_api_key: bool,
+    pub is_local: bool,
+    // Ready to pass to save_settings once an API key (if required) is filled in
+    pub settings: Settings,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocalServer {
+    pub preset_id: String,
+    pub name: String,
+    pub api_url: String,
+    pub running: bool,
+    pub models: Vec<ModelInfo>,
+}
+
+struct PresetSpec {
+    id: &'static str,
+    name: &'static str,
+    api_url: &'static str,
+    default_model: &'static str,
+    requires_api_key: bool,
+    // Endpoint probed by detect_local_servers; None for hosted providers
+    probe_url: Option<&'static str>,
+}
+
+const PRESETS: &[PresetSpec] = &[
+    PresetSpec {
+        id: "openai",
+        name: "OpenAI",
+        api_url: "https://api.openai.com/v1/chat/completions",
+        default_model: "gpt-4o-mini",
+        requires_api_key: true,
+        probe_url: None,
+    },
+    PresetSpec {
+        id: "openrouter",
+        name: "OpenRouter",
+        api_url: "https://openrouter.ai/api/v1/chat/completions",
+        default_model: "openai/gpt-4o-mini",
+        requires_api_key: true,
+        probe_url: None,
+    },
+    PresetSpec {
+        id: "ollama",
+        name: "Ollama",
+        api_url: "http://localhost:11434/v1/chat/completions",
+        default_model: "llama3",
+        requires_api_key: false,
+        probe_url: Some("http://localhost:11434/api/tags"),
+    },
+    PresetSpec {
+        id: "lmstudio",
+        name: "LM Studio",
+        api_url: "http://localhost:1234/v1/chat/completions",
+        default_model: "",
+        requires_api_key: false,
+        probe_url: Some("http://localhost:1234/v1/models"),
+    },
+    PresetSpec {
+        id: "llamacpp",
+        name: "llama.cpp server",
+        api_url: "http://localhost:8080/v1/chat/completions",
+        default_model: "",
+        requires_api_key: false,
+        probe_url: Some("http://localhost:8080/v1/models"),
+    },
+];
+
+fn preset_from_spec(spec: &PresetSpec) -> ProviderPreset {
+    ProviderPreset {
+        id: spec.id.to_string(),
+        name: spec.name.to_string(),
+        api_url: spec.api_url.to_string(),
+        requires_api_key: spec.requires_api_key,
+        is_local: spec.probe_url.is_some(),
+        settings: Settings {
+            api_url: spec.api_url.to_string(),
+            model: spec.default_model.to_string(),
+            api_key: String::new(),
+            ..Settings::default()
+        },
+    }
+}
+
+async fn probe_server(client: &reqwest::Client, spec: &PresetSpec, probe_url: &str) -> LocalServer {
+    let models = match client.get(probe_url).send().await {
+        Ok(response) if response.status().is_success() => {
+            let text = response.text().await.unwrap_or_default();
+            Some(parse_models_response(&text).unwrap_or_default())
+        }
+        _ => None,
+    };
+
+    LocalServer {
+        preset_id: spec.id.to_string(),
+        name: spec.name.to_string(),
+        api_url: spec.api_url.to_string(),
+        running: models.is_some(),
+        models: models.unwrap_or_default(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_provider_presets() -> Result<Vec<ProviderPreset>, String> {
+    Ok(PRESETS.iter().map(preset_from_spec).collect())
+}
+
+#[tauri::command]
+pub async fn detect_local_servers() -> Result<Vec<LocalServer>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .connect_timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let probes = PRESETS.iter().filter_map(|spec| {
+        let client = client.clone();
+        spec.probe_url.map(|url| async move { probe_server(&client, spec, url).await })
+    });
+    let handles: Vec<_> = probes.map(tokio::spawn).collect();
+
+    let mut servers = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(server) = handle.await {
+            servers.push(server);
+        }
+    }
+    Ok(servers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_produce_complete_settings() {
+        for spec in PRESETS {
+            let preset = preset_from_spec(spec);
+            assert_eq!(preset.settings.api_url, spec.api_url);
+            assert_eq!(preset.is_local, !spec.requires_api_key);
+            assert!(preset.settings.max_tokens_ideas > 0);
+        }
+    }
+
+    #[test]
+    fn test_parse_models_response_handles_openai_and_ollama_shapes() {
+        let openai = r#"{"data":[{"id":"gpt-4o-mini","name":null,"description":null}]}"#;
+        let ollama = r#"{"models":[{"name":"llama3:8b","details":{"parameter_size":"8B"}}]}"#;
+
+        assert_eq!(parse_models_response(openai).unwrap()[0].id, "gpt-4o-mini");
+        let models = parse_models_response(ollama).unwrap();
+        assert_eq!(models[0].id, "llama3:8b");
+        assert_eq!(models[0].description.as_deref(), Some("8B"));
+    }
+}
@@ -0,0 +1,317 @@
+use regex::{Regex, RegexBuilder};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::analysis::FileInfo;
+use crate::db::{self, DbPool};
+use crate::fs_utils::{self, classify_file, walker_with_options, FileFilter, WalkOptions, MAX_WALK_DEPTH};
+
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 1000;
+// Per file, so one noisy file can't crowd out the rest
+const MAX_MATCHES_PER_FILE: usize = 5;
+// Bytes of context kept either side of a match
+const SNIPPET_CONTEXT: usize = 60;
+// Full-file mode reads at most this much of each file
+const MAX_FULL_FILE_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    // Empty searches every analyzed project
+    pub project_paths: Vec<String>,
+    // Language names as analysis reports them, e.g. "Rust"; empty for all
+    pub languages: Vec<String>,
+    pub limit: Option<usize>,
+    // Read the real files of a single project instead of the sampled
+    // content in its cached analysis. Slower, but complete
+    pub full_files: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSource {
+    Path,
+    // Only the stored prefix of the file was searched
+    SampledContent,
+    File,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SearchMatch {
+    pub project_path: String,
+    pub project_name: String,
+    // Relative to the project
+    pub path: String,
+    // 1-based; None for path matches
+    pub line: Option<usize>,
+    // The snippet split around the match so it can be highlighted
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+    pub source: MatchSource,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+    // More matches existed past the limit
+    pub truncated: bool,
+    // True unless `full_files` was used; content outside the sampled
+    // prefixes was not searched
+    pub sampled_only: bool,
+    pub projects_searched: usize,
+}
+
+fn build_matcher(query: &str, options: &SearchOptions) -> Result<Regex, String> {
+    let query = query.trim();
+    if query.chars().count() < 2 {
+        return Err("Search for at least 2 characters".to_string());
+    }
+    let escaped = regex::escape(query);
+    let pattern = if options.whole_word { format!(r"\b{}\b", escaped) } else { escaped };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+// (before, matched, after) with up to SNIPPET_CONTEXT bytes either side
+fn snippet(line: &str, start: usize, end: usize) -> (String, String, String) {
+    let from = floor_char_boundary(line, start.saturating_sub(SNIPPET_CONTEXT));
+    let to = ceil_char_boundary(line, (end + SNIPPET_CONTEXT).min(line.len()));
+    let mut before = line[from..start].trim_start().to_string();
+    let mut after = line[end..to].trim_end().to_string();
+    if from > 0 {
+        before.insert(0, '…');
+    }
+    if to < line.len() {
+        after.push('…');
+    }
+    (before, line[start..end].to_string(), after)
+}
+
+struct Collector<'a> {
+    matcher: &'a Regex,
+    limit: usize,
+    matches: Vec<SearchMatch>,
+    truncated: bool,
+}
+
+impl Collector<'_> {
+    fn full(&self) -> bool {
+        self.truncated
+    }
+
+    fn push(&mut self, found: SearchMatch) {
+        if self.matches.len() >= self.limit {
+            self.truncated = true;
+        } else {
+            self.matches.push(found);
+        }
+    }
+
+    // Path first, then up to MAX_MATCHES_PER_FILE lines of `content`
+    fn search_file(&mut self, project: (&str, &str), relative: &str, content: &str, source: MatchSource) {
+        let (project_path, project_name) = project;
+        let found = |line: Option<usize>, (before, matched, after): (String, String, String), source| SearchMatch {
+            project_path: project_path.to_string(),
+            project_name: project_name.to_string(),
+            path: relative.to_string(),
+            line,
+            before,
+            matched,
+            after,
+            source,
+        };
+        if let Some(m) = self.matcher.find(relative) {
+            let parts = (relative[..m.start()].to_string(), m.as_str().to_string(), relative[m.end()..].to_string());
+            self.push(found(None, parts, MatchSource::Path));
+        }
+        let mut in_file = 0;
+        for (index, line) in content.lines().enumerate() {
+            if in_file >= MAX_MATCHES_PER_FILE || self.full() {
+                break;
+            }
+            if let Some(m) = self.matcher.find(line) {
+                in_file += 1;
+                self.push(found(Some(index + 1), snippet(line, m.start(), m.end()), source));
+            }
+        }
+    }
+}
+
+fn relative_path<'a>(file_path: &'a str, project_path: &str) -> &'a str {
+    file_path.strip_prefix(project_path).unwrap_or(file_path).trim_start_matches(['/', '\\'])
+}
+
+fn language_allowed(options: &SearchOptions, language: &str) -> bool {
+    options.languages.is_empty() || options.languages.iter().any(|l| l.eq_ignore_ascii_case(language))
+}
+
+fn search_sampled_files(collector: &mut Collector, options: &SearchOptions, project: (&str, &str), files: &[FileInfo]) {
+    for file in files {
+        if collector.full() {
+            return;
+        }
+        if language_allowed(options, &file.language) {
+            let relative = relative_path(&file.path, project.0);
+            collector.search_file(project, relative, &file.content, MatchSource::SampledContent);
+        }
+    }
+}
+
+fn search_sampled(conn: &Connection, query: &str, options: &SearchOptions) -> Result<SearchResults, String> {
+    let matcher = build_matcher(query, options)?;
+    let limit = options.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let mut collector = Collector { matcher: &matcher, limit, matches: Vec::new(), truncated: false };
+    let projects: Vec<(i64, String, String)> = db::get_analyzed_projects(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|(_, path, _)| options.project_paths.is_empty() || options.project_paths.contains(path))
+        .collect();
+
+    let mut projects_searched = 0;
+    for (project_id, path, name) in &projects {
+        if collector.full() {
+            break;
+        }
+        let Some(analysis) = db::get_cached_analysis(conn, *project_id).ok().flatten() else { continue };
+        projects_searched += 1;
+        search_sampled_files(&mut collector, options, (path, name), &analysis.files);
+    }
+    Ok(SearchResults { matches: collector.matches, truncated: collector.truncated, sampled_only: true, projects_searched })
+}
+
+// Walks the project like an analysis does and reads each file up to
+// MAX_FULL_FILE_BYTES
+fn search_full_files(project_path: &str, project_name: &str, query: &str, options: &SearchOptions) -> Result<SearchResults, String> {
+    let root = Path::new(project_path);
+    if !fs_utils::is_existing_dir(root) {
+        return Err(format!("Project folder not found: {}", project_path));
+    }
+    let matcher = build_matcher(query, options)?;
+    let limit = options.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let mut collector = Collector { matcher: &matcher, limit, matches: Vec::new(), truncated: false };
+    let filter = FileFilter::current();
+
+    let walk = walker_with_options(root, WalkOptions { max_depth: Some(MAX_WALK_DEPTH), ..Default::default() });
+    for entry in walk.flatten() {
+        if collector.full() {
+            break;
+        }
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path().to_string_lossy().to_string();
+        if !filter.should_analyze(root, entry.path()) || !language_allowed(options, &classify_file(&path).language) {
+            continue;
+        }
+        // Unreadable files are skipped; there's no partial result to report
+        let Ok((content, _)) = fs_utils::read_text_prefix_limited(&path, MAX_FULL_FILE_BYTES) else { continue };
+        collector.search_file((project_path, project_name), relative_path(&path, project_path), &content, MatchSource::File);
+    }
+    Ok(SearchResults { matches: collector.matches, truncated: collector.truncated, sampled_only: false, projects_searched: 1 })
+}
+
+// Searches file paths and sampled contents across analyzed projects, most
+// recently analyzed first. With `full_files`, searches one project on disk
+#[tauri::command]
+pub async fn search_code(
+    db_pool: State<'_, Arc<DbPool>>,
+    query: String,
+    options: Option<SearchOptions>,
+) -> Result<SearchResults, String> {
+    let options = options.unwrap_or_default();
+    let pool = db_pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        if !options.full_files {
+            return search_sampled(&conn, &query, &options);
+        }
+        let [project_path] = options.project_paths.as_slice() else {
+            return Err("Full-file search needs exactly one project".to_string());
+        };
+        let name = db::get_project_by_path(&conn, project_path)
+            .map_err(|e| e.to_string())?
+            .map(|project| project.name)
+            .unwrap_or_else(|| project_path.clone());
+        drop(conn);
+        search_full_files(project_path, &name, &query, &options)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> FileInfo {
+        FileInfo {
+            path: path.to_string(),
+            content: content.to_string(),
+            language: classify_file(path).language,
+            size: content.len() as u64,
+            encoding: Default::default(),
+            role: Default::default(),
+            prefix_hash: 0,
+        }
+    }
+
+    fn run(query: &str, options: &SearchOptions, files: &[FileInfo]) -> Vec<SearchMatch> {
+        let matcher = build_matcher(query, options).unwrap();
+        let mut collector = Collector { matcher: &matcher, limit: 50, matches: Vec::new(), truncated: false };
+        search_sampled_files(&mut collector, options, ("/p", "p"), files);
+        collector.matches
+    }
+
+    #[test]
+    fn test_matches_paths_and_lines_with_options() {
+        let files = [
+            file("/p/src/parse_config.rs", "fn main() {}\npub fn parse_config() {}\n"),
+            file("/p/web/app.ts", "const parseConfigLater = 1;\n"),
+        ];
+        let found = run("parse_config", &SearchOptions::default(), &files);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].source, found[0].path.as_str()), (MatchSource::Path, "src/parse_config.rs"));
+        assert_eq!((found[1].line, found[1].before.as_str(), found[1].matched.as_str()), (Some(2), "pub fn ", "parse_config"));
+
+        let whole = SearchOptions { whole_word: true, ..Default::default() };
+        assert!(run("parseConfig", &whole, &files).is_empty());
+        assert_eq!(run("parseConfig", &SearchOptions::default(), &files).len(), 1);
+        let sensitive = SearchOptions { case_sensitive: true, ..Default::default() };
+        assert!(run("PARSE_CONFIG", &sensitive, &files).is_empty());
+        let typescript_only = SearchOptions { languages: vec!["typescript".to_string()], ..Default::default() };
+        assert!(run("parse_config", &typescript_only, &files).is_empty());
+    }
+
+    #[test]
+    fn test_snippet_trims_long_lines_on_char_boundaries() {
+        let line = format!("{}needle{}", "é".repeat(100), "x".repeat(100));
+        let start = line.find("needle").unwrap();
+        let (before, matched, after) = snippet(&line, start, start + 6);
+        assert!(before.starts_with('…') && after.ends_with('…'));
+        assert_eq!(matched, "needle");
+        assert!(before.len() <= SNIPPET_CONTEXT + '…'.len_utf8());
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, ExitRequestApi, Manager};
+
+use crate::db::DbPool;
+use crate::{analysis, backup};
+
+// How long quitting waits for cancelled analyses and a running backup
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Set on the first exit request; new analyses and backups refuse to start
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+// Set once cleanup is done so the exit it triggers goes through
+static READY_TO_EXIT: AtomicBool = AtomicBool::new(false);
+
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+// Called for every `RunEvent::ExitRequested`. The first request is held
+// while in-flight work winds down, then re-issued with the same code
+pub fn on_exit_requested(app: &AppHandle, api: &ExitRequestApi, code: Option<i32>) {
+    if READY_TO_EXIT.load(Ordering::SeqCst) {
+        return;
+    }
+    api.prevent_exit();
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let _ = app.emit("app:shutting_down", ());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        finish_work(&app).await;
+        READY_TO_EXIT.store(true, Ordering::SeqCst);
+        app.exit(code.unwrap_or(0));
+    });
+}
+
+async fn finish_work(app: &AppHandle) {
+    let started = Instant::now();
+    let cancelled = analysis::cancel_all();
+    tracing::info!(cancelled, "Shutting down");
+
+    // Cancelled analyses skip their cache write, so this is usually quick
+    while analysis::is_analysis_running() || backup::is_backup_running() {
+        if started.elapsed() >= SHUTDOWN_GRACE {
+            tracing::warn!(
+                analyses = analysis::running_analyses(),
+                backup = backup::is_backup_running(),
+                "Exiting with work still in progress"
+            );
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    // Fold the WAL into the main file so the next launch opens a clean database
+    if let Some(db_pool) = app.try_state::<Arc<DbPool>>() {
+        let pool = db_pool.inner().clone();
+        let checkpoint = tokio::task::spawn_blocking(move || checkpoint(&pool));
+        match tokio::time::timeout(SHUTDOWN_GRACE, checkpoint).await {
+            Ok(Ok(Ok(()))) => {}
+            Ok(Ok(Err(e))) => tracing::warn!(error = %e, "WAL checkpoint on exit failed"),
+            _ => tracing::warn!("WAL checkpoint on exit did not finish"),
+        }
+    }
+    tracing::info!(elapsed_ms = started.elapsed().as_millis() as u64, "Shutdown complete");
+}
+
+fn checkpoint(pool: &DbPool) -> Result<(), String> {
+    let conn = pool.get_timeout(SHUTDOWN_GRACE).map_err(|e| e.to_string())?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())).map_err(|e| e.to_string())
+}
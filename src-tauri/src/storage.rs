@@ -1,7 +1,25 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use crate::ai::queue::{QueueLimits, RequestQueue};
+use crate::analysis::RepoAnalysis;
 use crate::db::{self, DbPool};
+use crate::fs_utils::canonical_path;
+use crate::freshness::SummaryFingerprint;
+use crate::maintenance;
+use crate::onboarding;
+
+static CHECKLIST_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*[-*+]\s+\[([ xX])\]\s+(.+?)\s*$").unwrap());
+static DUE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\(due:\s*(\d{4}-\d{2}-\d{2})\)").unwrap());
+static COMPLETED_NOTE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\(completed\s*([^)]*)\)").unwrap());
+static MARKER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(TODO|FIXME)\b(?:\([^)]*\))?:?\s*(.*)$").unwrap());
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Settings {
@@ -24,6 +42,14 @@ pub struct Settings {
     pub max_tokens_summary: u32,
     #[serde(default = "default_use_stop_ideas")]
     pub use_stop_ideas: bool,
+    // None means "redact unless the API URL is local"
+    #[serde(default)]
+    pub redact_previews: Option<bool>,
+    // Limits of the shared AI request queue; 0 requests per minute is no limit
+    #[serde(default)]
+    pub ai_requests_per_minute: u32,
+    #[serde(default = "default_ai_max_concurrent_requests")]
+    pub ai_max_concurrent_requests: u32,
 }
 
 fn default_temperature_ideas() -> f32 { 0.6 }
@@ -34,6 +60,121 @@ fn default_temperature_summary() -> f32 { 0.4 }
 fn default_presence_penalty_summary() -> f32 { 0.1 }
 fn default_max_tokens_summary() -> u32 { 1200 }
 fn default_use_stop_ideas() -> bool { true }
+fn default_ai_max_concurrent_requests() -> u32 { 2 }
+
+pub const MAX_AI_CONCURRENT_REQUESTS: u32 = 16;
+
+// Bump when stored settings JSON needs a migration step below
+pub const SETTINGS_VERSION: u64 = 1;
+
+// Old key -> current key, applied when upgrading from version 0
+const SETTINGS_V0_RENAMES: &[(&str, &str)] = &[
+    ("max_tokens", "max_tokens_ideas"),
+    ("temperature", "temperature_ideas"),
+    ("frequency_penalty", "frequency_penalty_ideas"),
+    ("presence_penalty", "presence_penalty_ideas"),
+];
+
+// Bring stored settings JSON up to SETTINGS_VERSION; JSON without a
+// `version` field is version 0. Unparseable input is returned untouched.
+pub fn migrate_settings_json(raw: &str) -> String {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return raw.to_string();
+    };
+    let Some(obj) = value.as_object_mut() else {
+        return raw.to_string();
+    };
+
+    let mut version = obj.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version >= SETTINGS_VERSION {
+        return raw.to_string();
+    }
+
+    while version < SETTINGS_VERSION {
+        if version == 0 {
+            for (old, new) in SETTINGS_V0_RENAMES {
+                if let Some(old_value) = obj.remove(*old) {
+                    obj.entry(*new).or_insert(old_value);
+                }
+            }
+        }
+        version += 1;
+    }
+
+    obj.insert("version".to_string(), serde_json::json!(SETTINGS_VERSION));
+    value.to_string()
+}
+
+fn settings_to_json(settings: &Settings) -> Result<String, serde_json::Error> {
+    let mut value = serde_json::to_value(settings)?;
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("version".to_string(), serde_json::json!(SETTINGS_VERSION));
+    }
+    serde_json::to_string(&value)
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            api_url: "http://localhost:11434/v1/chat/completions".to_string(),
+            model: "llama2".to_string(),
+            api_key: "".to_string(),
+            temperature_ideas: default_temperature_ideas(),
+            frequency_penalty_ideas: default_frequency_penalty_ideas(),
+            presence_penalty_ideas: default_presence_penalty_ideas(),
+            max_tokens_ideas: default_max_tokens_ideas(),
+            temperature_summary: default_temperature_summary(),
+            presence_penalty_summary: default_presence_penalty_summary(),
+            max_tokens_summary: default_max_tokens_summary(),
+            use_stop_ideas: default_use_stop_ideas(),
+            redact_previews: None,
+            ai_requests_per_minute: 0,
+            ai_max_concurrent_requests: default_ai_max_concurrent_requests(),
+        }
+    }
+}
+
+impl Settings {
+    // Collect every problem so the UI can show them together
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        match url::Url::parse(&self.api_url) {
+            Ok(parsed) if !matches!(parsed.scheme(), "http" | "https") => {
+                errors.push(format!("Invalid API URL: unsupported scheme '{}'", parsed.scheme()));
+            }
+            Ok(_) => {}
+            Err(e) => errors.push(format!("Invalid API URL: {}", e)),
+        }
+        for (name, value) in [
+            ("temperature_ideas", self.temperature_ideas),
+            ("temperature_summary", self.temperature_summary),
+        ] {
+            if !(0.0..=2.0).contains(&value) {
+                errors.push(format!("{} must be between 0.0 and 2.0 (got {})", name, value));
+            }
+        }
+        if self.max_tokens_ideas == 0 {
+            errors.push("max_tokens_ideas must be greater than 0".to_string());
+        }
+        if self.max_tokens_summary == 0 {
+            errors.push("max_tokens_summary must be greater than 0".to_string());
+        }
+        if !(1..=MAX_AI_CONCURRENT_REQUESTS).contains(&self.ai_max_concurrent_requests) {
+            errors.push(format!(
+                "ai_max_concurrent_requests must be between 1 and {} (got {})",
+                MAX_AI_CONCURRENT_REQUESTS, self.ai_max_concurrent_requests
+            ));
+        }
+
+        errors
+    }
+
+    pub fn should_redact_previews(&self) -> bool {
+        self.redact_previews
+            .unwrap_or_else(|| !crate::redact::is_local_api_url(&self.api_url))
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ThemePreference {
@@ -48,6 +189,18 @@ pub struct Task {
     pub completed: bool,
     pub created_at: String,
     pub completed_at: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+    // "insight" for tasks suggested from project insights; None otherwise
+    #[serde(default)]
+    pub source: Option<String>,
+    // The insight gap the task closes, e.g. "license"
+    #[serde(default)]
+    pub source_ref: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -70,6 +223,9 @@ pub struct ProjectSummary {
     pub generated_at: String,
     pub technologies: Vec<String>,
     pub key_features: Vec<String>,
+    // State of the code at generation time, for staleness checks
+    #[serde(default)]
+    pub fingerprint: Option<SummaryFingerprint>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -82,6 +238,52 @@ pub struct DatabaseStats {
     pub total_summaries: i64,
     pub database_size_bytes: i64,
     pub database_size_mb: f64,
+    pub wal_size_bytes: u64,
+    pub shm_size_bytes: u64,
+    // Everything under `backups/` and `logs/` in the app data directory
+    pub backups_size_bytes: u64,
+    pub logs_size_bytes: u64,
+    // The database file with its side files, backups and logs
+    pub total_storage_bytes: i64,
+    pub table_row_counts: Vec<db::TableRowCount>,
+    pub largest_projects: Vec<db::ProjectStorage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SettingsChanged {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+// Broadcast a saved setting to every window; callers strip secrets from `value`
+pub fn emit_settings_changed<R: Runtime>(app: &AppHandle<R>, key: &str, value: serde_json::Value) {
+    let _ = app.emit("settings:changed", SettingsChanged { key: key.to_string(), value });
+}
+
+// API settings as broadcast to the frontend: everything except the key itself
+fn public_settings_value(settings: &Settings) -> serde_json::Value {
+    let mut value = serde_json::to_value(settings).unwrap_or(serde_json::Value::Null);
+    if let Some(obj) = value.as_object_mut() {
+        obj.remove("api_key");
+    }
+    value
+}
+
+fn file_size_bytes(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+// Files directly inside `dir`; 0 when it doesn't exist
+fn dir_size_bytes(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok()?.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
 }
 
 #[tauri::command]
@@ -137,6 +339,20 @@ pub async fn get_database_stats(
     
     let database_size_mb = database_size_bytes as f64 / (1024.0 * 1024.0);
     
+    let table_row_counts = db::table_row_counts(&conn).map_err(|e| e.to_string())?;
+    let largest_projects = db::largest_projects_by_storage(&conn, 10).map_err(|e| e.to_string())?;
+
+    // Side files, backups and logs live in the app data directory alongside the database
+    let db_path = db::database_path(&conn);
+    let side_file = |suffix: &str| db_path.as_ref().map_or(0, |p| file_size_bytes(Path::new(&format!("{}{}", p.display(), suffix))));
+    let wal_size_bytes = side_file("-wal");
+    let shm_size_bytes = side_file("-shm");
+    let data_dir = db_path.as_ref().and_then(|p| p.parent());
+    let backups_size_bytes = data_dir.map_or(0, |dir| dir_size_bytes(&dir.join("backups")));
+    let logs_size_bytes = data_dir.map_or(0, |dir| dir_size_bytes(&dir.join("logs")));
+    // The main file as it sits on disk; pages not yet checkpointed are in the WAL
+    let database_file_bytes = db_path.as_deref().map_or(0, file_size_bytes);
+    
     Ok(DatabaseStats {
         total_projects,
         total_files,
@@ -146,6 +362,13 @@ pub async fn get_database_stats(
         total_summaries,
         database_size_bytes,
         database_size_mb,
+        wal_size_bytes,
+        shm_size_bytes,
+        backups_size_bytes,
+        logs_size_bytes,
+        total_storage_bytes: (database_file_bytes + wal_size_bytes + shm_size_bytes + backups_size_bytes + logs_size_bytes) as i64,
+        table_row_counts,
+        largest_projects,
     })
 }
 
@@ -153,26 +376,9 @@ pub async fn get_database_stats(
 pub async fn vacuum_database(
     db_pool: State<'_, Arc<DbPool>>,
 ) -> Result<String, String> {
+    let _guard = maintenance::begin()?;
     let conn = db_pool.get().map_err(|e| e.to_string())?;
-    
-    // Get size before vacuum
-    let size_before: i64 = conn.query_row(
-        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
-        [],
-        |row| row.get(0),
-    ).unwrap_or(0);
-    
-    // Run VACUUM
-    conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
-    
-    // Get size after vacuum
-    let size_after: i64 = conn.query_row(
-        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
-        [],
-        |row| row.get(0),
-    ).unwrap_or(0);
-    
-    let reclaimed = size_before - size_after;
+    let reclaimed = maintenance::vacuum(&conn).map_err(|e| e.to_string())?;
     let reclaimed_mb = reclaimed as f64 / (1024.0 * 1024.0);
     
     Ok(format!("Vacuum complete. Reclaimed {:.2} MB", reclaimed_mb))
@@ -182,12 +388,9 @@ pub async fn vacuum_database(
 pub async fn clear_expired_cache(
     db_pool: State<'_, Arc<DbPool>>,
 ) -> Result<String, String> {
+    let _guard = maintenance::begin()?;
     let conn = db_pool.get().map_err(|e| e.to_string())?;
-    
-    let deleted = conn.execute(
-        "DELETE FROM analysis_cache WHERE expires_at < CURRENT_TIMESTAMP",
-        [],
-    ).map_err(|e| e.to_string())?;
+    let deleted = maintenance::clear_expired_cache(&conn).map_err(|e| e.to_string())?;
     
     Ok(format!("Cleared {} expired cache entries", deleted))
 }
@@ -196,28 +399,30 @@ pub async fn clear_expired_cache(
 pub async fn optimize_database(
     db_pool: State<'_, Arc<DbPool>>,
 ) -> Result<String, String> {
+    let _guard = maintenance::begin()?;
     let conn = db_pool.get().map_err(|e| e.to_string())?;
     
-    // Run ANALYZE to update statistics
-    conn.execute("ANALYZE", []).map_err(|e| e.to_string())?;
-    
-    // Clear expired cache
-    let deleted: usize = conn.execute(
-        "DELETE FROM analysis_cache WHERE expires_at < CURRENT_TIMESTAMP",
-        [],
-    ).map_err(|e| e.to_string())?;
+    // Update statistics, then clear expired cache
+    maintenance::optimize(&conn).map_err(|e| e.to_string())?;
+    let deleted = maintenance::clear_expired_cache(&conn).map_err(|e| e.to_string())?;
     
     Ok(format!("Optimization complete. Cleared {} expired cache entries", deleted))
 }
 
+// Legacy shim: updates only the mode of the stored ThemeSettings
 #[tauri::command]
 pub async fn save_theme_preference(
+    app: AppHandle,
     db_pool: State<'_, Arc<DbPool>>,
     theme: String,
 ) -> Result<(), String> {
     let conn = db_pool.get().map_err(|e| e.to_string())?;
-    db::save_setting(&conn, "theme_preference", &theme)
-        .map_err(|e| e.to_string())
+    let mut settings = crate::theme::load(&conn);
+    settings.mode = serde_json::from_value(serde_json::Value::String(theme.clone()))
+        .map_err(|_| format!("Unknown theme \"{}\"", theme))?;
+    crate::theme::store(&conn, &settings)?;
+    emit_settings_changed(&app, "theme_preference", serde_json::Value::String(theme));
+    Ok(())
 }
 
 #[tauri::command]
@@ -231,13 +436,27 @@ pub async fn load_theme_preference(
 
 #[tauri::command]
 pub async fn save_settings(
+    app: AppHandle,
     db_pool: State<'_, Arc<DbPool>>,
     settings: Settings,
-) -> Result<(), String> {
-    let conn = db_pool.get().map_err(|e| e.to_string())?;
-    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+) -> Result<(), Vec<String>> {
+    let errors = settings.validate();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let conn = db_pool.get().map_err(|e| vec![e.to_string()])?;
+    let json = settings_to_json(&settings).map_err(|e| vec![e.to_string()])?;
     db::save_setting(&conn, "api_settings", &json)
-        .map_err(|e| e.to_string())
+        .map_err(|e| vec![e.to_string()])?;
+    if !settings.model.trim().is_empty() {
+        onboarding::mark_step_in(&db_pool, onboarding::Step::AiConfigured);
+    }
+    if let Some(queue) = app.try_state::<RequestQueue>() {
+        queue.set_limits(QueueLimits::from_settings(&settings));
+    }
+    emit_settings_changed(&app, "api_settings", public_settings_value(&settings));
+    Ok(())
 }
 
 #[tauri::command]
@@ -245,43 +464,49 @@ pub async fn load_settings(
     db_pool: State<'_, Arc<DbPool>>,
 ) -> Result<Settings, String> {
     let conn = db_pool.get().map_err(|e| e.to_string())?;
-    
-    if let Some(json) = db::load_setting(&conn, "api_settings").map_err(|e| e.to_string())? {
-        // Backward-compatible: provide defaults for any missing fields
-        let settings: Settings = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    stored_settings(&conn)
+}
+
+// Saved API settings, or the defaults before anything was saved
+pub fn stored_settings(conn: &rusqlite::Connection) -> Result<Settings, String> {
+    if let Some(json) = db::load_setting(conn, "api_settings").map_err(|e| e.to_string())? {
+        // Rename keys from older versions, then let serde(default) fill the rest
+        let migrated = migrate_settings_json(&json);
+        if migrated != json {
+            let _ = db::save_setting(conn, "api_settings", &migrated);
+        }
+        let settings: Settings = serde_json::from_str(&migrated).map_err(|e| e.to_string())?;
         // Fields with serde(default) are already filled; just return
         Ok(settings)
     } else {
-        Ok(Settings {
-            api_url: "http://localhost:11434/v1/chat/completions".to_string(),
-            model: "llama2".to_string(),
-            api_key: "".to_string(),
-            temperature_ideas: default_temperature_ideas(),
-            frequency_penalty_ideas: default_frequency_penalty_ideas(),
-            presence_penalty_ideas: default_presence_penalty_ideas(),
-            max_tokens_ideas: default_max_tokens_ideas(),
-            temperature_summary: default_temperature_summary(),
-            presence_penalty_summary: default_presence_penalty_summary(),
-            max_tokens_summary: default_max_tokens_summary(),
-            use_stop_ideas: default_use_stop_ideas(),
-        })
+        Ok(Settings::default())
     }
 }
 
+// Summaries and tasks can be saved for a project that was never listed, so
+// its row is created on demand. An existing row is left untouched
+pub fn get_or_create_project_id(conn: &rusqlite::Connection, project_path: &str) -> Result<i64, String> {
+    if let Some(project) = db::get_project_by_path(conn, project_path).map_err(|e| e.to_string())? {
+        return Ok(project.id);
+    }
+    let path = Path::new(project_path);
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Unknown");
+    db::upsert_project(conn, project_path, name, None, path.join(".git").exists())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn save_project_summary(
     db_pool: State<'_, Arc<DbPool>>,
     summary: ProjectSummary,
 ) -> Result<(), String> {
+    let project_path = canonical_path(Path::new(&summary.project_path));
     let conn = db_pool.get().map_err(|e| e.to_string())?;
-    
-    // Get or create project
-    let project_path = summary.project_path.clone();
-    let project = db::get_project_by_path(&conn, &project_path)
-        .map_err(|e| e.to_string())?
-        .ok_or("Project not found")?;
-    
-    db::save_summary(&conn, project.id, &summary)
+    let project_id = get_or_create_project_id(&conn, &project_path)?;
+    db::save_summary(&conn, project_id, &summary)
         .map_err(|e| e.to_string())
 }
 
@@ -290,6 +515,7 @@ pub async fn load_project_summary(
     db_pool: State<'_, Arc<DbPool>>,
     project_path: String,
 ) -> Result<Option<ProjectSummary>, String> {
+    let project_path = canonical_path(Path::new(&project_path));
     let conn = db_pool.get().map_err(|e| e.to_string())?;
     
     if let Some(project) = db::get_project_by_path(&conn, &project_path).map_err(|e| e.to_string())? {
@@ -302,12 +528,16 @@ pub async fn load_project_summary(
 
 #[tauri::command]
 pub async fn save_root_folder(
+    app: AppHandle,
     db_pool: State<'_, Arc<DbPool>>,
     root_folder: String,
 ) -> Result<(), String> {
     let conn = db_pool.get().map_err(|e| e.to_string())?;
     db::save_setting(&conn, "root_folder", &root_folder)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    onboarding::mark_step_in(&db_pool, onboarding::Step::RootSelected);
+    emit_settings_changed(&app, "root_folder", serde_json::Value::String(root_folder));
+    Ok(())
 }
 
 #[tauri::command]
@@ -335,14 +565,10 @@ pub async fn save_task_list(
     db_pool: State<'_, Arc<DbPool>>,
     task_list: TaskList,
 ) -> Result<(), String> {
-    let conn = db_pool.get().map_err(|e| e.to_string())?;
-    
-    // Get or create project
-    let project = db::get_project_by_path(&conn, &task_list.project_path)
-        .map_err(|e| e.to_string())?
-        .ok_or("Project not found")?;
-    
-    db::save_task_list(&conn, project.id, &task_list.tasks)
+    let project_path = canonical_path(Path::new(&task_list.project_path));
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    let project_id = get_or_create_project_id(&conn, &project_path)?;
+    db::save_task_list(&mut conn, project_id, &task_list.tasks)
         .map_err(|e| e.to_string())
 }
 
@@ -351,6 +577,7 @@ pub async fn load_task_list(
     db_pool: State<'_, Arc<DbPool>>,
     project_path: String,
 ) -> Result<Option<TaskList>, String> {
+    let project_path = canonical_path(Path::new(&project_path));
     let conn = db_pool.get().map_err(|e| e.to_string())?;
     
     if let Some(project) = db::get_project_by_path(&conn, &project_path).map_err(|e| e.to_string())? {
@@ -361,6 +588,319 @@ pub async fn load_task_list(
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskImportResult {
+    pub created: usize,
+    pub skipped_duplicates: usize,
+}
+
+fn new_task_id(index: usize) -> String {
+    let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    format!("{:x}{:x}", nanos, index)
+}
+
+pub fn normalize_task_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// Drop the backslash before escaped punctuation, as written by the Markdown export
+fn unescape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek().is_some_and(|next| next.is_ascii_punctuation()) {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+// Parse `- [ ] text (due: YYYY-MM-DD) #tag` lines; anything else is ignored.
+// A checked task keeps the date from its `(completed YYYY-MM-DD)` note, or
+// none; the import time says nothing about when it was done
+fn parse_markdown_tasks(markdown: &str) -> Vec<Task> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tasks = Vec::new();
+
+    for line in markdown.lines() {
+        let Some(caps) = CHECKLIST_REGEX.captures(line) else { continue };
+        let completed = &caps[1] != " ";
+        let mut rest = caps[2].to_string();
+
+        let due_date = DUE_REGEX.captures(&rest).map(|c| c[1].to_string());
+        let completed_at = COMPLETED_NOTE_REGEX
+            .captures(&rest)
+            .and_then(|c| chrono::NaiveDate::parse_from_str(c[1].trim(), "%Y-%m-%d").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|at| at.and_utc().to_rfc3339())
+            .filter(|_| completed);
+        rest = DUE_REGEX.replace_all(&rest, "").to_string();
+        rest = COMPLETED_NOTE_REGEX.replace_all(&rest, "").to_string();
+
+        // Tags are trailing `#tag` tokens
+        let mut words: Vec<&str> = rest.split_whitespace().collect();
+        let mut tags = Vec::new();
+        while let Some(last) = words.last() {
+            if last.len() > 1 && last.starts_with('#') {
+                tags.insert(0, unescape_markdown(last.trim_start_matches('#')));
+                words.pop();
+            } else {
+                break;
+            }
+        }
+
+        let text = unescape_markdown(&words.join(" "));
+        if text.is_empty() {
+            continue;
+        }
+
+        tasks.push(Task {
+            id: new_task_id(tasks.len()),
+            text,
+            completed,
+            created_at: now.clone(),
+            completed_at,
+            description: None,
+            tags,
+            due_date,
+            source: None,
+            source_ref: None,
+        });
+    }
+
+    tasks
+}
+
+// Collect TODO/FIXME markers from the sampled file contents of an analysis
+fn extract_marker_tasks(analysis: &RepoAnalysis, project_path: &str) -> Vec<Task> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tasks = Vec::new();
+
+    for file in &analysis.files {
+        let relative = file
+            .path
+            .strip_prefix(project_path)
+            .unwrap_or(&file.path)
+            .trim_start_matches(['/', '\\']);
+        for (index, line) in file.content.lines().enumerate() {
+            let Some(caps) = MARKER_REGEX.captures(line) else { continue };
+            let note = caps[2].trim().trim_end_matches("*/").trim_end_matches("-->").trim();
+            let text = if note.is_empty() {
+                format!("{} in {}", &caps[1], relative)
+            } else {
+                format!("{}: {}", &caps[1], note)
+            };
+            tasks.push(Task {
+                id: new_task_id(tasks.len()),
+                text,
+                completed: false,
+                created_at: now.clone(),
+                completed_at: None,
+                description: Some(format!("{}:{}", relative, index + 1)),
+                tags: vec![caps[1].to_lowercase()],
+                due_date: None,
+                source: None,
+                source_ref: None,
+            });
+        }
+    }
+
+    tasks
+}
+
+// Normalized text plus location. A marker's "file:line" location compares by
+// file alone, so a TODO that moved after an edit above it isn't imported again
+fn dedupe_key(task: &Task) -> (String, Option<String>) {
+    let location = task.description.as_deref().map(|d| match d.rsplit_once(':') {
+        Some((file, line)) if !d.contains('\n') && !line.is_empty() && line.bytes().all(|b| b.is_ascii_digit()) => {
+            file.to_string()
+        }
+        _ => d.to_string(),
+    });
+    (normalize_task_text(&task.text), location)
+}
+
+// Append candidates, skipping ones whose text (and location) already exist
+fn append_new_tasks(
+    conn: &mut rusqlite::Connection,
+    project_id: i64,
+    project_path: &str,
+    candidates: Vec<Task>,
+) -> Result<TaskImportResult, String> {
+    let existing = db::load_task_list(conn, project_id, project_path)
+        .map_err(|e| e.to_string())?
+        .map(|list| list.tasks)
+        .unwrap_or_default();
+    let mut seen: std::collections::HashSet<(String, Option<String>)> = existing.iter().map(dedupe_key).collect();
+
+    let total = candidates.len();
+    let new_tasks: Vec<Task> = candidates.into_iter().filter(|t| seen.insert(dedupe_key(t))).collect();
+
+    db::append_tasks(conn, project_id, &new_tasks).map_err(|e| e.to_string())?;
+
+    Ok(TaskImportResult {
+        created: new_tasks.len(),
+        skipped_duplicates: total - new_tasks.len(),
+    })
+}
+
+// Adds one task unless one with the same text exists; returns the stored
+// task either way, and whether it was added
+fn add_single_task(
+    conn: &mut rusqlite::Connection,
+    project_id: i64,
+    project_path: &str,
+    task: Task,
+) -> Result<(Task, bool), String> {
+    let normalized = normalize_task_text(&task.text);
+    if append_new_tasks(conn, project_id, project_path, vec![task.clone()])?.created == 1 {
+        return Ok((task, true));
+    }
+    db::load_task_list(conn, project_id, project_path)
+        .map_err(|e| e.to_string())?
+        .and_then(|list| list.tasks.into_iter().find(|t| normalize_task_text(&t.text) == normalized))
+        .map(|task| (task, false))
+        .ok_or_else(|| "Failed to add the task".to_string())
+}
+
+// Outcome of "add to tasks" on an idea; `created` is false when a task with
+// the same text already existed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdeaTask {
+    pub task_id: String,
+    pub created: bool,
+}
+
+// "Add to tasks" on an idea; points at the existing task when one has the
+// same text
+pub fn create_task_from_idea(
+    conn: &mut rusqlite::Connection,
+    project_id: i64,
+    project_path: &str,
+    idea_text: &str,
+) -> Result<IdeaTask, String> {
+    let task = Task {
+        id: new_task_id(0),
+        text: idea_text.trim().to_string(),
+        completed: false,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        completed_at: None,
+        description: None,
+        tags: Vec::new(),
+        due_date: None,
+        source: None,
+        source_ref: None,
+    };
+    let (task, created) = add_single_task(conn, project_id, project_path, task)?;
+    Ok(IdeaTask { task_id: task.id, created })
+}
+
+#[tauri::command]
+pub async fn add_idea_to_tasks(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    idea_text: String,
+) -> Result<IdeaTask, String> {
+    if idea_text.trim().is_empty() {
+        return Err("Task text is empty".to_string());
+    }
+    let project_path = canonical_path(Path::new(&project_path));
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    let project_id = get_or_create_project_id(&conn, &project_path)?;
+    create_task_from_idea(&mut conn, project_id, &project_path, &idea_text)
+}
+
+// Adds one task, e.g. an accepted insight suggestion. An empty id gets a
+// fresh one; a task with the same text is returned instead of a duplicate
+#[tauri::command]
+pub async fn add_task(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    mut task: Task,
+) -> Result<Task, String> {
+    task.text = task.text.trim().to_string();
+    if task.text.is_empty() {
+        return Err("Task text is empty".to_string());
+    }
+    if task.id.is_empty() {
+        task.id = new_task_id(0);
+    }
+    let project_path = canonical_path(Path::new(&project_path));
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    let project_id = get_or_create_project_id(&conn, &project_path)?;
+    add_single_task(&mut conn, project_id, &project_path, task).map(|(task, _)| task)
+}
+
+#[tauri::command]
+pub async fn import_tasks(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    markdown: String,
+) -> Result<TaskImportResult, String> {
+    let project_path = canonical_path(Path::new(&project_path));
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+
+    let project = db::get_project_by_path(&conn, &project_path)
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+
+    append_new_tasks(&mut conn, project.id, &project_path, parse_markdown_tasks(&markdown))
+}
+
+#[tauri::command]
+pub async fn import_tasks_from_markers(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+) -> Result<TaskImportResult, String> {
+    let project_path = canonical_path(Path::new(&project_path));
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+
+    let project = db::get_project_by_path(&conn, &project_path)
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+
+    let analysis = db::get_cached_analysis(&conn, project.id)
+        .map_err(|e| e.to_string())?
+        .ok_or("No analysis available; analyze the project first")?;
+
+    append_new_tasks(&mut conn, project.id, &project_path, extract_marker_tasks(&analysis, &project_path))
+}
+
+#[tauri::command]
+pub async fn update_project_description(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    description: String,
+) -> Result<(), String> {
+    let project_path = canonical_path(Path::new(&project_path));
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    // An empty description clears the override rather than storing ""
+    let description = Some(description.trim()).filter(|d| !d.is_empty());
+    if !db::update_project_description(&conn, &project_path, description).map_err(|e| e.to_string())? {
+        return Err("Project not found".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn update_project_name(
+    db_pool: State<'_, Arc<DbPool>>,
+    project_path: String,
+    name: String,
+) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Project name cannot be empty".to_string());
+    }
+    let project_path = canonical_path(Path::new(&project_path));
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    if !db::update_project_name(&conn, &project_path, name).map_err(|e| e.to_string())? {
+        return Err("Project not found".to_string());
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn save_favorite_projects(
     db_pool: State<'_, Arc<DbPool>>,
@@ -390,23 +930,150 @@ pub async fn load_favorite_projects(
         .map_err(|e| e.to_string())
 }
 
+// Both flags default to false, wiping everything as before
 #[tauri::command]
 pub async fn clear_all_data(
     db_pool: State<'_, Arc<DbPool>>,
+    preserve_settings: Option<bool>,
+    preserve_favorites: Option<bool>,
 ) -> Result<(), String> {
-    let conn = db_pool.get().map_err(|e| e.to_string())?;
-    
-    // Clear all data but keep schema
-    conn.execute("DELETE FROM tasks", []).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM summaries", []).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM analysis_cache", []).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM files", []).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM git_info", []).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM projects", []).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM settings", []).map_err(|e| e.to_string())?;
-    
-    // Run vacuum to reclaim space
-    conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
-    
-    Ok(())
+    let mut conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::clear_all_data(
+        &mut conn,
+        preserve_settings.unwrap_or(false),
+        preserve_favorites.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown_tasks_reads_due_dates_and_tags() {
+        let md = "# Tasks\n\n- [ ] Write docs (due: 2026-03-01) #docs #release\n- [x] Ship beta (completed 2026-02-03)\nnot a task\n* [X] Tag only #done";
+        let tasks = parse_markdown_tasks(md);
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].text, "Write docs");
+        assert_eq!(tasks[0].due_date.as_deref(), Some("2026-03-01"));
+        assert_eq!(tasks[0].tags, vec!["docs", "release"]);
+        assert!(!tasks[0].completed);
+        assert_eq!(tasks[1].text, "Ship beta");
+        assert!(tasks[1].completed);
+        assert_eq!(tasks[1].completed_at.as_deref(), Some("2026-02-03T00:00:00+00:00"));
+        assert_eq!(tasks[2].text, "Tag only");
+        assert_eq!(tasks[2].tags, vec!["done"]);
+        assert_eq!(tasks[2].completed_at, None);
+
+        let escaped = parse_markdown_tasks("- [ ] \\# Fix \\[link\\](x) \\*now\\* #good-first-issue");
+        assert_eq!(escaped[0].text, "# Fix [link](x) *now*");
+        assert_eq!(escaped[0].tags, vec!["good-first-issue"]);
+    }
+
+    #[test]
+    fn test_get_or_create_project_id_registers_unlisted_project() {
+        let (dir, pool) = db::test_pool();
+        let project = dir.path().join("fresh-app");
+        std::fs::create_dir_all(project.join(".git")).unwrap();
+        let conn = pool.get().unwrap();
+        let path = project.to_string_lossy().to_string();
+
+        let id = get_or_create_project_id(&conn, &path).unwrap();
+        let stored = db::get_project_by_path(&conn, &path).unwrap().unwrap();
+        assert_eq!(stored.id, id);
+        assert_eq!(stored.name, "fresh-app");
+        assert!(stored.is_git_repo);
+
+        db::update_project_name(&conn, &path, "Renamed").unwrap();
+        assert_eq!(get_or_create_project_id(&conn, &path), Ok(id));
+        assert_eq!(db::get_project_by_path(&conn, &path).unwrap().unwrap().name, "Renamed");
+    }
+
+    #[test]
+    fn test_append_new_tasks_skips_duplicates_in_one_transaction() {
+        let (_dir, pool) = db::test_pool();
+        let mut conn = pool.get().unwrap();
+        let project_id = db::upsert_project(&conn, "/code/app", "app", None, false).unwrap();
+
+        db::save_task_list(&mut conn, project_id, &parse_markdown_tasks("- [ ] Write docs\n- [ ] Ship")).unwrap();
+        let result = append_new_tasks(&mut conn, project_id, "/code/app", parse_markdown_tasks("- [ ] write  DOCS\n- [ ] Add CI")).unwrap();
+        assert_eq!((result.created, result.skipped_duplicates), (1, 1));
+
+        let marker = |line: usize| Task {
+            description: Some(format!("src/main.rs:{}", line)),
+            ..parse_markdown_tasks("- [ ] TODO: retry on timeout").remove(0)
+        };
+        assert_eq!(append_new_tasks(&mut conn, project_id, "/code/app", vec![marker(10)]).unwrap().created, 1);
+        let moved = append_new_tasks(&mut conn, project_id, "/code/app", vec![marker(14)]).unwrap();
+        assert_eq!((moved.created, moved.skipped_duplicates), (0, 1));
+
+        db::save_task_list(&mut conn, project_id, &parse_markdown_tasks("- [x] Only task")).unwrap();
+        let tasks = db::load_task_list(&conn, project_id, "/code/app").unwrap().unwrap().tasks;
+        assert_eq!(tasks.len(), 1);
+        assert!(tasks[0].completed);
+    }
+
+    #[test]
+    fn test_settings_changed_event_reaches_listeners_without_api_key() {
+        use std::sync::mpsc;
+        use tauri::Listener;
+
+        let app = tauri::test::mock_app();
+        let (tx, rx) = mpsc::channel();
+        app.handle().listen("settings:changed", move |event| {
+            let _ = tx.send(event.payload().to_string());
+        });
+
+        let settings = Settings { api_key: "sk-proj-abcdefghijklmnop1234567890".to_string(), ..Settings::default() };
+        emit_settings_changed(app.handle(), "api_settings", public_settings_value(&settings));
+
+        let payload = rx.recv_timeout(std::time::Duration::from_secs(2)).expect("event should be delivered");
+        let changed: SettingsChanged = serde_json::from_str(&payload).unwrap();
+        assert_eq!(changed.key, "api_settings");
+        assert_eq!(changed.value["api_url"], settings.api_url);
+        assert!(changed.value.get("api_key").is_none());
+    }
+
+    #[test]
+    fn test_migrate_settings_json_renames_version_zero_keys() {
+        let old = r#"{"api_url":"http://localhost:11434/v1/chat/completions","model":"llama2","api_key":"","max_tokens":900,"temperature":0.9}"#;
+        let migrated = migrate_settings_json(old);
+        let value: serde_json::Value = serde_json::from_str(&migrated).unwrap();
+        assert_eq!(value["version"], SETTINGS_VERSION);
+        assert!(value.get("max_tokens").is_none());
+
+        let settings: Settings = serde_json::from_str(&migrated).unwrap();
+        assert_eq!(settings.max_tokens_ideas, 900);
+        assert!((settings.temperature_ideas - 0.9).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_migrate_settings_json_keeps_current_versions_untouched() {
+        let current = settings_to_json(&Settings::default()).unwrap();
+        assert_eq!(migrate_settings_json(&current), current);
+        assert_eq!(migrate_settings_json("not json"), "not json");
+    }
+
+    #[test]
+    fn test_settings_validate_reports_all_problems() {
+        assert!(Settings::default().validate().is_empty());
+
+        let settings = Settings {
+            api_url: "not a url".to_string(),
+            temperature_ideas: 2.5,
+            max_tokens_summary: 0,
+            ..Settings::default()
+        };
+        let errors = settings.validate();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].starts_with("Invalid API URL:"));
+        assert!(errors[1].starts_with("temperature_ideas"));
+        assert!(errors[2].starts_with("max_tokens_summary"));
+    }
+
+    #[test]
+    fn test_normalize_task_text_ignores_case_and_spacing() {
+        assert_eq!(normalize_task_text("  Write   Docs "), normalize_task_text("write docs"));
+    }
 }
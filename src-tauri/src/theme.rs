@@ -0,0 +1,227 @@
+use chrono::{Local, NaiveTime};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::db::{self, DbPool};
+use crate::storage::emit_settings_changed;
+
+const THEME_SETTINGS_KEY: &str = "theme_settings";
+// Pre-ThemeSettings value: a bare "light" / "dark" / "system" string
+const LEGACY_THEME_KEY: &str = "theme_preference";
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+static HEX_COLOR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#(?:[0-9a-fA-F]{3}|[0-9a-fA-F]{6})$").unwrap());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UiDensity {
+    Compact,
+    #[default]
+    Comfortable,
+    Spacious,
+}
+
+// Dark mode between `dark_start` and `dark_end` (HH:MM, local time); the
+// window may wrap past midnight
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ThemeSchedule {
+    pub dark_start: String,
+    pub dark_end: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ThemeSettings {
+    pub mode: ThemeMode,
+    pub accent_color: Option<String>,
+    pub density: UiDensity,
+    pub schedule: Option<ThemeSchedule>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ThemeSwitch {
+    pub mode: ThemeMode,
+}
+
+fn parse_hhmm(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+impl ThemeSettings {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(color) = &self.accent_color {
+            if !HEX_COLOR.is_match(color) {
+                errors.push(format!("Accent color must be a hex value like #2563eb, got \"{}\"", color));
+            }
+        }
+        if let Some(schedule) = &self.schedule {
+            for (label, value) in [("start", &schedule.dark_start), ("end", &schedule.dark_end)] {
+                if parse_hhmm(value).is_none() {
+                    errors.push(format!("Schedule {} must be HH:MM, got \"{}\"", label, value));
+                }
+            }
+        }
+        errors
+    }
+
+    // Light or dark as dictated by the schedule at `now`; None without a (valid) schedule
+    pub fn scheduled_mode(&self, now: NaiveTime) -> Option<ThemeMode> {
+        let schedule = self.schedule.as_ref()?;
+        let start = parse_hhmm(&schedule.dark_start)?;
+        let end = parse_hhmm(&schedule.dark_end)?;
+        let dark = if start <= end {
+            now >= start && now < end
+        } else {
+            now >= start || now < end
+        };
+        Some(if dark { ThemeMode::Dark } else { ThemeMode::Light })
+    }
+}
+
+fn legacy_mode(value: &str) -> ThemeMode {
+    match value {
+        "light" => ThemeMode::Light,
+        "dark" => ThemeMode::Dark,
+        _ => ThemeMode::System,
+    }
+}
+
+// Stored settings, falling back to the legacy bare-string preference
+pub fn load(conn: &Connection) -> ThemeSettings {
+    if let Some(settings) = db::load_setting(conn, THEME_SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+    {
+        return settings;
+    }
+
+    match db::load_setting(conn, LEGACY_THEME_KEY).ok().flatten() {
+        Some(legacy) => ThemeSettings { mode: legacy_mode(&legacy), ..Default::default() },
+        None => ThemeSettings::default(),
+    }
+}
+
+pub fn store(conn: &Connection, settings: &ThemeSettings) -> Result<(), String> {
+    let errors = settings.validate();
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    let json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    db::save_setting(conn, THEME_SETTINGS_KEY, &json).map_err(|e| e.to_string())?;
+    // Keep the legacy key in step for anything still reading it
+    let mode = serde_json::to_value(settings.mode)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| "system".to_string());
+    db::save_setting(conn, LEGACY_THEME_KEY, &mode).map_err(|e| e.to_string())
+}
+
+// Emits `theme:switch` whenever the schedule crosses a boundary
+pub fn spawn_scheduler<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut last: Option<ThemeMode> = None;
+        loop {
+            let settings = app
+                .try_state::<Arc<DbPool>>()
+                .and_then(|pool| pool.get().ok().map(|conn| load(&conn)));
+            let now = Local::now().time();
+            let scheduled = settings.and_then(|s| s.scheduled_mode(now));
+
+            if let Some(mode) = scheduled {
+                if last.is_some() && last != Some(mode) {
+                    let _ = app.emit("theme:switch", ThemeSwitch { mode });
+                }
+            }
+            last = scheduled;
+            tokio::time::sleep(SCHEDULE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn load_theme_settings(
+    db_pool: State<'_, Arc<DbPool>>,
+) -> Result<ThemeSettings, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    Ok(load(&conn))
+}
+
+#[tauri::command]
+pub async fn save_theme_settings(
+    app: AppHandle,
+    db_pool: State<'_, Arc<DbPool>>,
+    settings: ThemeSettings,
+) -> Result<(), String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    store(&conn, &settings)?;
+    emit_settings_changed(
+        &app,
+        THEME_SETTINGS_KEY,
+        serde_json::to_value(&settings).unwrap_or(serde_json::Value::Null),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hhmm: &str) -> NaiveTime {
+        parse_hhmm(hhmm).unwrap()
+    }
+
+    #[test]
+    fn test_scheduled_mode_wraps_past_midnight() {
+        let settings = ThemeSettings {
+            schedule: Some(ThemeSchedule { dark_start: "20:00".into(), dark_end: "07:30".into() }),
+            ..Default::default()
+        };
+        assert_eq!(settings.scheduled_mode(at("23:15")), Some(ThemeMode::Dark));
+        assert_eq!(settings.scheduled_mode(at("06:00")), Some(ThemeMode::Dark));
+        assert_eq!(settings.scheduled_mode(at("07:30")), Some(ThemeMode::Light));
+        assert_eq!(settings.scheduled_mode(at("12:00")), Some(ThemeMode::Light));
+        assert_eq!(ThemeSettings::default().scheduled_mode(at("23:00")), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_accent_and_times() {
+        let settings = ThemeSettings {
+            accent_color: Some("blue".into()),
+            schedule: Some(ThemeSchedule { dark_start: "25:00".into(), dark_end: "07:00".into() }),
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().len(), 2);
+        let ok = ThemeSettings { accent_color: Some("#2563EB".into()), ..Default::default() };
+        assert!(ok.validate().is_empty());
+    }
+
+    #[test]
+    fn test_load_migrates_legacy_preference() {
+        let (_dir, pool) = db::test_pool();
+        let conn = pool.get().unwrap();
+        db::save_setting(&conn, LEGACY_THEME_KEY, "dark").unwrap();
+        assert_eq!(load(&conn).mode, ThemeMode::Dark);
+
+        let settings = ThemeSettings { mode: ThemeMode::Light, density: UiDensity::Compact, ..Default::default() };
+        store(&conn, &settings).unwrap();
+        assert_eq!(load(&conn), settings);
+        assert_eq!(db::load_setting(&conn, LEGACY_THEME_KEY).unwrap().as_deref(), Some("light"));
+    }
+}
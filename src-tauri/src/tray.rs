@@ -0,0 +1,169 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, State, Wry};
+
+use crate::db::{self, DbPool};
+use crate::{analysis, background, launch, window_state};
+
+const TRAY_ID: &str = "main";
+const MINIMIZE_TO_TRAY_KEY: &str = "minimize_to_tray";
+
+// How often the tooltip picks up analyses starting and finishing
+const STATUS_INTERVAL: Duration = Duration::from_secs(2);
+
+static MINIMIZE_TO_TRAY: AtomicBool = AtomicBool::new(false);
+// False when the platform has no tray (e.g. Linux without appindicator);
+// closing then always exits, or the window would be unreachable
+static TRAY_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+// Kept in app state so the label can follow the pause state
+struct TrayMenu {
+    pause: MenuItem<Wry>,
+}
+
+pub fn init(app: &AppHandle, db_pool: &DbPool) {
+    let enabled = db_pool
+        .get()
+        .ok()
+        .and_then(|conn| db::load_setting(&conn, MINIMIZE_TO_TRAY_KEY).ok().flatten())
+        .is_some_and(|value| value == "true");
+    MINIMIZE_TO_TRAY.store(enabled, Ordering::Relaxed);
+
+    match build(app) {
+        Ok(()) => {
+            TRAY_AVAILABLE.store(true, Ordering::Relaxed);
+            spawn_status_updates(app.clone());
+        }
+        Err(e) => tracing::warn!(error = %e, "System tray unavailable"),
+    }
+}
+
+fn build(app: &AppHandle) -> tauri::Result<()> {
+    let open = MenuItem::with_id(app, "open", "Open RepoMuse", true, None::<&str>)?;
+    let reanalyze = MenuItem::with_id(app, "reanalyze_favorites", "Re-analyze Favorites Now", true, None::<&str>)?;
+    let pause = MenuItem::with_id(app, "toggle_pause", pause_label(background::is_paused()), true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit RepoMuse", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&open, &reanalyze, &pause, &separator, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(status_line(background::is_paused(), analysis::running_analyses()))
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| handle_menu(app, event.id.as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                launch::focus_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    app.manage(TrayMenu { pause });
+    Ok(())
+}
+
+// Each item goes through the same function as its Tauri command
+fn handle_menu(app: &AppHandle, id: &str) {
+    match id {
+        "open" => launch::focus_main_window(app),
+        "reanalyze_favorites" => {
+            let Some(db_pool) = app.try_state::<Arc<DbPool>>() else { return };
+            let db_pool = db_pool.inner().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = analysis::queue_favorites(db_pool).await {
+                    crate::errors::report_actionable_error("tray", format!("Re-analyzing favorites failed: {}", e), None);
+                }
+            });
+        }
+        "toggle_pause" => background::set_paused(app, !background::is_paused()),
+        "quit" => quit(app),
+        _ => {}
+    }
+}
+
+// Exit even with minimize-to-tray on; the window's close event doesn't
+// fire here, so its geometry is saved first
+pub fn quit(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        window_state::save(&window.as_ref().window());
+    }
+    app.exit(0);
+}
+
+pub fn minimize_to_tray() -> bool {
+    MINIMIZE_TO_TRAY.load(Ordering::Relaxed) && TRAY_AVAILABLE.load(Ordering::Relaxed)
+}
+
+fn pause_label(paused: bool) -> &'static str {
+    if paused { "Resume Background Work" } else { "Pause Background Work" }
+}
+
+// One-line tooltip, e.g. "RepoMuse: analyzing 2 projects"
+fn status_line(paused: bool, running: usize) -> String {
+    let projects = if running == 1 { "project" } else { "projects" };
+    let status = match (paused, running) {
+        (true, 0) => "background work paused".to_string(),
+        (true, n) => format!("paused, finishing {} {}", n, projects),
+        (false, 0) => "idle".to_string(),
+        (false, n) => format!("analyzing {} {}", n, projects),
+    };
+    format!("RepoMuse: {}", status)
+}
+
+pub fn refresh(app: &AppHandle) {
+    let paused = background::is_paused();
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.pause.set_text(pause_label(paused));
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(status_line(paused, analysis::running_analyses())));
+    }
+}
+
+fn spawn_status_updates(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut shown = String::new();
+        loop {
+            tokio::time::sleep(STATUS_INTERVAL).await;
+            let status = status_line(background::is_paused(), analysis::running_analyses());
+            if status != shown {
+                if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                    let _ = tray.set_tooltip(Some(&status));
+                }
+                shown = status;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_minimize_to_tray() -> Result<bool, String> {
+    Ok(MINIMIZE_TO_TRAY.load(Ordering::Relaxed))
+}
+
+#[tauri::command]
+pub async fn set_minimize_to_tray(db_pool: State<'_, Arc<DbPool>>, enabled: bool) -> Result<bool, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::save_setting(&conn, MINIMIZE_TO_TRAY_KEY, if enabled { "true" } else { "false" }).map_err(|e| e.to_string())?;
+    MINIMIZE_TO_TRAY.store(enabled, Ordering::Relaxed);
+    Ok(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        assert_eq!(status_line(false, 0), "RepoMuse: idle");
+        assert_eq!(status_line(false, 1), "RepoMuse: analyzing 1 project");
+        assert_eq!(status_line(false, 2), "RepoMuse: analyzing 2 projects");
+        assert_eq!(status_line(true, 0), "RepoMuse: background work paused");
+        assert_eq!(status_line(true, 3), "RepoMuse: paused, finishing 3 projects");
+    }
+}
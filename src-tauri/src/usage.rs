@@ -0,0 +1,294 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::DbPool;
+
+const DEFAULT_BUCKETS: u32 = 30;
+const MAX_BUCKETS: u32 = 366;
+const BUSIEST_PROJECTS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+    Analysis,
+    Ideas,
+    Summary,
+}
+
+impl UsageKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            UsageKind::Analysis => "analysis",
+            UsageKind::Ideas => "ideas",
+            UsageKind::Summary => "summary",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageEvent<'a> {
+    pub project_path: Option<&'a str>,
+    pub duration_ms: Option<u64>,
+    pub cache_hit: Option<bool>,
+    // Only when the endpoint reports usage
+    pub tokens: Option<u64>,
+}
+
+fn insert(conn: &Connection, kind: UsageKind, event: &UsageEvent) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO usage_events (kind, project_path, duration_ms, cache_hit, tokens) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![kind.as_str(), event.project_path, event.duration_ms.map(|ms| ms as i64), event.cache_hit, event.tokens.map(|t| t as i64)],
+    )?;
+    Ok(())
+}
+
+// Best effort: a failed write is reported, never returned to the caller
+pub fn record(db_pool: &DbPool, kind: UsageKind, event: UsageEvent) {
+    let result = db_pool.get().map_err(|e| e.to_string()).and_then(|conn| insert(&conn, kind, &event).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        crate::errors::report_error("usage", format!("Failed to record usage: {}", e), event.project_path);
+    }
+}
+
+// `usage.total_tokens` from an OpenAI-compatible response
+pub fn response_tokens(response: &serde_json::Value) -> Option<u64> {
+    response["usage"]["total_tokens"].as_u64()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePeriod {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl UsagePeriod {
+    // Start of the bucket containing `date`; weeks start on Monday
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            UsagePeriod::Day => date,
+            UsagePeriod::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            UsagePeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            UsagePeriod::Day => start + Duration::days(1),
+            UsagePeriod::Week => start + Duration::weeks(1),
+            UsagePeriod::Month => start.checked_add_months(Months::new(1)).unwrap_or(start),
+        }
+    }
+
+    fn back(self, start: NaiveDate, count: u32) -> NaiveDate {
+        match self {
+            UsagePeriod::Day => start - Duration::days(count as i64),
+            UsagePeriod::Week => start - Duration::weeks(count as i64),
+            UsagePeriod::Month => start.checked_sub_months(Months::new(count)).unwrap_or(start),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct UsageCounts {
+    pub analyses: i64,
+    pub analysis_ms: i64,
+    pub cache_hits: i64,
+    pub ideas: i64,
+    pub summaries: i64,
+    // None when no request in range reported token usage
+    pub tokens: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UsageBucket {
+    // First day of the bucket, YYYY-MM-DD
+    pub start: String,
+    #[serde(flatten)]
+    pub counts: UsageCounts,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectUsage {
+    pub project_path: String,
+    #[serde(flatten)]
+    pub counts: UsageCounts,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UsageStats {
+    pub period: UsagePeriod,
+    pub since: String,
+    pub totals: UsageCounts,
+    // Share of analyses answered from cache, 0-1; None without analyses
+    pub cache_hit_rate: Option<f64>,
+    // Oldest first, including empty buckets
+    pub buckets: Vec<UsageBucket>,
+    pub busiest_projects: Vec<ProjectUsage>,
+}
+
+const COUNT_COLUMNS: &str = "
+    COALESCE(SUM(kind = 'analysis'), 0),
+    COALESCE(SUM(CASE WHEN kind = 'analysis' THEN duration_ms END), 0),
+    COALESCE(SUM(kind = 'analysis' AND cache_hit), 0),
+    COALESCE(SUM(kind = 'ideas'), 0),
+    COALESCE(SUM(kind = 'summary'), 0),
+    SUM(tokens)";
+
+fn counts_from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<UsageCounts> {
+    Ok(UsageCounts {
+        analyses: row.get(offset)?,
+        analysis_ms: row.get(offset + 1)?,
+        cache_hits: row.get(offset + 2)?,
+        ideas: row.get(offset + 3)?,
+        summaries: row.get(offset + 4)?,
+        tokens: row.get(offset + 5)?,
+    })
+}
+
+fn usage_stats(conn: &Connection, period: UsagePeriod, buckets: u32, now: DateTime<Utc>) -> Result<UsageStats, rusqlite::Error> {
+    let buckets = buckets.clamp(1, MAX_BUCKETS);
+    let current = period.bucket_start(now.date_naive());
+    let first = period.back(current, buckets - 1);
+    let since = first.format("%Y-%m-%d 00:00:00").to_string();
+
+    let totals = conn.query_row(
+        &format!("SELECT {} FROM usage_events WHERE created_at >= ?1", COUNT_COLUMNS),
+        [&since],
+        |row| counts_from_row(row, 0),
+    )?;
+
+    // Per day, then folded into weeks or months here so every period
+    // shares one query
+    let mut by_day = conn.prepare(&format!(
+        "SELECT date(created_at), {} FROM usage_events WHERE created_at >= ?1 GROUP BY date(created_at)",
+        COUNT_COLUMNS
+    ))?;
+    let mut bucketed: HashMap<NaiveDate, UsageCounts> = HashMap::new();
+    for row in by_day.query_map([&since], |row| Ok((row.get::<_, String>(0)?, counts_from_row(row, 1)?)))? {
+        let (day, counts) = row?;
+        let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") else { continue };
+        let bucket = bucketed.entry(period.bucket_start(day)).or_default();
+        bucket.analyses += counts.analyses;
+        bucket.analysis_ms += counts.analysis_ms;
+        bucket.cache_hits += counts.cache_hits;
+        bucket.ideas += counts.ideas;
+        bucket.summaries += counts.summaries;
+        bucket.tokens = match (bucket.tokens, counts.tokens) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+        };
+    }
+    let mut series = Vec::with_capacity(buckets as usize);
+    let mut start = first;
+    while start <= current {
+        series.push(UsageBucket { start: start.to_string(), counts: bucketed.remove(&start).unwrap_or_default() });
+        start = period.next(start);
+    }
+
+    let mut busiest = conn.prepare(&format!(
+        "SELECT project_path, {} FROM usage_events
+         WHERE created_at >= ?1 AND project_path IS NOT NULL
+         GROUP BY project_path
+         ORDER BY COUNT(*) DESC, project_path
+         LIMIT ?2",
+        COUNT_COLUMNS
+    ))?;
+    let busiest_projects = busiest
+        .query_map(params![since, BUSIEST_PROJECTS as i64], |row| {
+            Ok(ProjectUsage { project_path: row.get(0)?, counts: counts_from_row(row, 1)? })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(UsageStats {
+        period,
+        since,
+        cache_hit_rate: (totals.analyses > 0).then(|| totals.cache_hits as f64 / totals.analyses as f64),
+        totals,
+        buckets: series,
+        busiest_projects,
+    })
+}
+
+// Computed from the local usage_events table only
+#[tauri::command]
+pub async fn get_usage_stats(
+    db_pool: State<'_, Arc<DbPool>>,
+    period: Option<UsagePeriod>,
+    buckets: Option<u32>,
+) -> Result<UsageStats, String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    usage_stats(&conn, period.unwrap_or_default(), buckets.unwrap_or(DEFAULT_BUCKETS), Utc::now()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reset_usage_stats(db_pool: State<'_, Arc<DbPool>>) -> Result<(), String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM usage_events", []).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(conn: &Connection, kind: UsageKind, path: &str, at: &str, duration_ms: u64, cache_hit: bool, tokens: Option<u64>) {
+        insert(conn, kind, &UsageEvent { project_path: Some(path), duration_ms: Some(duration_ms), cache_hit: Some(cache_hit), tokens }).unwrap();
+        conn.execute("UPDATE usage_events SET created_at = ?1 WHERE id = last_insert_rowid()", [at]).unwrap();
+    }
+
+    fn seeded() -> (tempfile::TempDir, DbPool) {
+        let (dir, pool) = crate::db::test_pool();
+        let conn = pool.get().unwrap();
+        seed(&conn, UsageKind::Analysis, "/p/a", "2026-03-02 10:00:00", 1_000, false, None);
+        seed(&conn, UsageKind::Analysis, "/p/a", "2026-03-03 10:00:00", 0, true, None);
+        seed(&conn, UsageKind::Ideas, "/p/a", "2026-03-10 09:00:00", 4_000, false, Some(1_200));
+        seed(&conn, UsageKind::Summary, "/p/b", "2026-03-10 11:00:00", 3_000, false, None);
+        // Outside every window below
+        seed(&conn, UsageKind::Analysis, "/p/old", "2025-01-01 00:00:00", 9_000, false, None);
+        drop(conn);
+        (dir, pool)
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-03-11T12:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_totals_hit_rate_and_busiest_projects() {
+        let (_dir, pool) = seeded();
+        let stats = usage_stats(&pool.get().unwrap(), UsagePeriod::Day, 30, now()).unwrap();
+
+        assert_eq!(stats.totals, UsageCounts { analyses: 2, analysis_ms: 1_000, cache_hits: 1, ideas: 1, summaries: 1, tokens: Some(1_200) });
+        assert_eq!(stats.cache_hit_rate, Some(0.5));
+        assert_eq!(stats.buckets.len(), 30);
+        assert_eq!(stats.buckets.last().unwrap().start, "2026-03-11");
+        let paths: Vec<&str> = stats.busiest_projects.iter().map(|p| p.project_path.as_str()).collect();
+        assert_eq!(paths, vec!["/p/a", "/p/b"]);
+        assert_eq!(stats.busiest_projects[0].counts.ideas, 1);
+    }
+
+    #[test]
+    fn test_week_and_month_buckets() {
+        let (_dir, pool) = seeded();
+        let conn = pool.get().unwrap();
+
+        let weeks = usage_stats(&conn, UsagePeriod::Week, 2, now()).unwrap();
+        let starts: Vec<&str> = weeks.buckets.iter().map(|b| b.start.as_str()).collect();
+        assert_eq!(starts, vec!["2026-03-02", "2026-03-09"]);
+        assert_eq!(weeks.buckets[0].counts.analyses, 2);
+        assert_eq!(weeks.buckets[1].counts.ideas, 1);
+        assert_eq!(weeks.buckets[0].counts.tokens, None);
+
+        let months = usage_stats(&conn, UsagePeriod::Month, 3, now()).unwrap();
+        let starts: Vec<&str> = months.buckets.iter().map(|b| b.start.as_str()).collect();
+        assert_eq!(starts, vec!["2026-01-01", "2026-02-01", "2026-03-01"]);
+        assert_eq!(months.buckets[2].counts.summaries, 1);
+        assert_eq!(months.cache_hit_rate, Some(0.5));
+    }
+}
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Runtime, State, Window};
+
+use crate::db::{self, DbPool};
+
+const WINDOW_STATE_KEY: &str = "window_state";
+
+// Geometry is stored in physical pixels and always describes the restored
+// (non-maximized) window so un-maximizing after launch lands somewhere sane
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    pub monitor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && (x as i64) < self.x as i64 + self.width as i64
+            && (y as i64) < self.y as i64 + self.height as i64
+    }
+}
+
+// A connected monitor as `restore` sees it; the name matches `WindowState::monitor`
+#[derive(Debug, Clone, PartialEq)]
+struct Monitor {
+    name: Option<String>,
+    rect: Rect,
+}
+
+// Center `saved` on `target`, shrunk to fit
+fn fit_onto(saved: Rect, target: Rect) -> Rect {
+    let width = saved.width.min(target.width);
+    let height = saved.height.min(target.height);
+    Rect {
+        x: target.x + ((target.width - width) / 2) as i32,
+        y: target.y + ((target.height - height) / 2) as i32,
+        width,
+        height,
+    }
+}
+
+// Keep the window on the monitor it was saved on. If that monitor is still
+// connected the window goes back onto it; if it is gone the saved coordinates
+// belong to an old layout, so the window moves onto the primary screen and is
+// shrunk to fit. States saved without a monitor name only need to land on
+// some connected monitor
+fn clamp_to_monitors(state: &WindowState, monitors: &[Monitor], primary: Option<Rect>) -> Rect {
+    let saved = Rect { x: state.x, y: state.y, width: state.width, height: state.height };
+    match &state.monitor {
+        Some(name) => {
+            if let Some(monitor) = monitors.iter().find(|m| m.name.as_deref() == Some(name.as_str())) {
+                return if monitor.rect.contains(state.x, state.y) { saved } else { fit_onto(saved, monitor.rect) };
+            }
+        }
+        None => {
+            if monitors.iter().any(|m| m.rect.contains(state.x, state.y)) {
+                return saved;
+            }
+        }
+    }
+
+    match primary.or_else(|| monitors.first().map(|m| m.rect)) {
+        Some(target) => fit_onto(saved, target),
+        None => saved,
+    }
+}
+
+fn monitor_info(monitor: &tauri::Monitor) -> Monitor {
+    Monitor {
+        name: monitor.name().cloned(),
+        rect: Rect {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        },
+    }
+}
+
+fn load_window_state(db_pool: &DbPool) -> Option<WindowState> {
+    let conn = db_pool.get().ok()?;
+    let json = db::load_setting(&conn, WINDOW_STATE_KEY).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+// Restore the saved geometry; first run (nothing saved) keeps the old maximize behavior
+pub fn restore<R: Runtime>(window: &Window<R>, db_pool: &DbPool) {
+    let Some(state) = load_window_state(db_pool) else {
+        let _ = window.maximize();
+        return;
+    };
+
+    let monitors: Vec<Monitor> = window
+        .available_monitors()
+        .map(|ms| ms.iter().map(monitor_info).collect())
+        .unwrap_or_default();
+    let primary = window.primary_monitor().ok().flatten().map(|m| monitor_info(&m).rect);
+    let rect = clamp_to_monitors(&state, &monitors, primary);
+
+    let _ = window.set_size(PhysicalSize::new(rect.width, rect.height));
+    let _ = window.set_position(PhysicalPosition::new(rect.x, rect.y));
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+// Persist the current geometry; called when the window is about to close
+pub fn save<R: Runtime>(window: &Window<R>) {
+    let Some(db_pool) = window.try_state::<Arc<DbPool>>() else { return };
+    let maximized = window.is_maximized().unwrap_or(false);
+
+    let state = match (maximized, load_window_state(&db_pool)) {
+        // Maximized geometry is the monitor's; keep the last restored geometry instead
+        (true, Some(previous)) => WindowState { maximized: true, ..previous },
+        _ => {
+            let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+                return;
+            };
+            WindowState {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+                monitor: window
+                    .current_monitor()
+                    .ok()
+                    .flatten()
+                    .and_then(|m| m.name().cloned()),
+            }
+        }
+    };
+
+    if let (Ok(conn), Ok(json)) = (db_pool.get(), serde_json::to_string(&state)) {
+        if let Err(e) = db::save_setting(&conn, WINDOW_STATE_KEY, &json) {
+            crate::errors::report_error("window_state", format!("Failed to save window state: {}", e), None);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn reset_window_state(
+    db_pool: State<'_, Arc<DbPool>>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    let conn = db_pool.get().map_err(|e| e.to_string())?;
+    db::delete_setting(&conn, WINDOW_STATE_KEY).map_err(|e| e.to_string())?;
+    window.maximize().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(x: i32, y: i32, width: u32, height: u32) -> WindowState {
+        WindowState { x, y, width, height, maximized: false, monitor: None }
+    }
+
+    fn monitor(name: &str, x: i32, y: i32, width: u32, height: u32) -> Monitor {
+        Monitor { name: Some(name.to_string()), rect: Rect { x, y, width, height } }
+    }
+
+    #[test]
+    fn test_clamp_keeps_position_on_connected_monitor() {
+        let monitors = [monitor("left", 0, 0, 1920, 1080), monitor("right", 1920, 0, 2560, 1440)];
+        let rect = clamp_to_monitors(&state(2000, 100, 1600, 900), &monitors, Some(monitors[0].rect));
+        assert_eq!(rect, Rect { x: 2000, y: 100, width: 1600, height: 900 });
+    }
+
+    #[test]
+    fn test_clamp_moves_window_from_disconnected_monitor_to_primary() {
+        let primary = monitor("main", 0, 0, 1920, 1080);
+        let rect = clamp_to_monitors(&state(3000, 200, 2400, 900), &[primary.clone()], Some(primary.rect));
+        assert_eq!(rect, Rect { x: 0, y: 90, width: 1920, height: 900 });
+    }
+
+    #[test]
+    fn test_clamp_uses_the_saved_monitor_name() {
+        let monitors = [monitor("main", 0, 0, 1920, 1080), monitor("side", 1920, 0, 1920, 1080)];
+        let on = |name: &str, x: i32| WindowState { monitor: Some(name.to_string()), ..state(x, 100, 800, 600) };
+
+        // Saved monitor still connected but the window drifted off it
+        let rect = clamp_to_monitors(&on("side", 100), &monitors, Some(monitors[0].rect));
+        assert_eq!(rect, Rect { x: 2480, y: 240, width: 800, height: 600 });
+
+        // Saved monitor gone; its coordinates now land on another screen
+        let rect = clamp_to_monitors(&on("projector", 2000), &monitors, Some(monitors[0].rect));
+        assert_eq!(rect, Rect { x: 560, y: 240, width: 800, height: 600 });
+    }
+}
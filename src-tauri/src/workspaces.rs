@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fs_utils::walker_with_depth;
+use crate::manifest;
+
+// How deep to look for nested go.mod files when there is no go.work
+const GO_MODULE_MAX_DEPTH: usize = 3;
+// How deep to look for Nx project.json files under an nx.json root
+const NX_PROJECT_MAX_DEPTH: usize = 4;
+// Task runners layered over a workspace, keyed by their config file
+const WORKSPACE_TOOLS: &[(&str, &str)] = &[
+    ("nx.json", "nx"),
+    ("turbo.json", "turborepo"),
+    ("lerna.json", "lerna"),
+];
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct WorkspaceMember {
+    // Package or module name; the directory name when the manifest has none
+    pub name: String,
+    // Relative to the workspace root, forward slashes
+    pub path: String,
+    pub version: Option<String>,
+    // "npm", "cargo", "go" or "nx"
+    pub ecosystem: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct WorkspaceInfo {
+    // "npm", "yarn", "pnpm", "cargo", "go" or "nx"; the first one found when a
+    // repo has several (members from all of them are listed)
+    pub kind: Option<String>,
+    pub tools: Vec<String>,
+    pub members: Vec<WorkspaceMember>,
+}
+
+fn relative(root: &Path, dir: &Path) -> String {
+    dir.strip_prefix(root).unwrap_or(dir).to_string_lossy().replace('\\', "/")
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+// pnpm and yarn share package.json `workspaces` with npm, so the package
+// manager is read from `packageManager` first and the lockfile second
+fn npm_flavor(root: &Path, manifest: &manifest::Manifest) -> &'static str {
+    let declared = manifest.package_manager.as_deref().unwrap_or("");
+    if declared.starts_with("pnpm") || root.join("pnpm-workspace.yaml").exists() || root.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if declared.starts_with("yarn") || root.join("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    }
+}
+
+// `use` directives from go.work, both the single-line and block forms
+pub fn parse_go_work(text: &str) -> Vec<String> {
+    let mut dirs = Vec::new();
+    let mut in_block = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                dirs.push(line.trim_matches('"').to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use ").or_else(|| line.strip_prefix("use(")) {
+            match rest.trim().trim_start_matches('(') {
+                "" => in_block = true,
+                dir => dirs.push(dir.trim_matches('"').to_string()),
+            }
+        }
+    }
+    dirs
+}
+
+fn go_module_name(dir: &Path) -> Option<String> {
+    let text = fs::read_to_string(dir.join("go.mod")).ok()?;
+    text.lines()
+        .find_map(|l| l.trim().strip_prefix("module "))
+        .map(|m| m.trim().trim_matches('"').to_string())
+}
+
+// Module directories from go.work, or every go.mod below a root module.
+// A lone root go.mod is a single-module repo and yields nothing
+fn go_module_dirs(root: &Path) -> Vec<PathBuf> {
+    if let Ok(text) = fs::read_to_string(root.join("go.work")) {
+        return parse_go_work(&text)
+            .iter()
+            .map(|d| root.join(d.trim_start_matches("./")))
+            .filter(|d| d.join("go.mod").is_file())
+            .collect();
+    }
+    if !root.join("go.mod").is_file() {
+        return Vec::new();
+    }
+    let mut dirs: Vec<PathBuf> = walker_with_depth(root, Some(GO_MODULE_MAX_DEPTH + 1))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == "go.mod" && e.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|e| e.path().parent().map(Path::to_path_buf))
+        .filter(|d| {
+            d.as_path() != root
+                && !d
+                    .strip_prefix(root)
+                    .map(|rel| rel.components().any(|c| c.as_os_str() == "vendor" || c.as_os_str() == "testdata"))
+                    .unwrap_or(true)
+        })
+        .collect();
+    dirs.sort();
+    if dirs.is_empty() {
+        return dirs;
+    }
+    dirs.insert(0, root.to_path_buf());
+    dirs
+}
+
+// Nx projects declared with project.json, which need not be package
+// manager workspaces at all
+fn nx_project_dirs(root: &Path) -> Vec<(PathBuf, Option<String>)> {
+    if !root.join("nx.json").is_file() {
+        return Vec::new();
+    }
+    let mut dirs: Vec<(PathBuf, Option<String>)> = walker_with_depth(root, Some(NX_PROJECT_MAX_DEPTH + 1))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == "project.json" && e.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|e| {
+            let dir = e.path().parent()?.to_path_buf();
+            let name = fs::read_to_string(e.path())
+                .ok()
+                .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+                .and_then(|json| json["name"].as_str().map(str::to_string));
+            (dir != root).then_some((dir, name))
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+pub fn get_workspace_info(root: &Path) -> WorkspaceInfo {
+    let mut info = WorkspaceInfo {
+        tools: WORKSPACE_TOOLS
+            .iter()
+            .filter(|(file, _)| root.join(file).is_file())
+            .map(|(_, tool)| tool.to_string())
+            .collect(),
+        ..Default::default()
+    };
+
+    for ecosystem in ["npm", "cargo"] {
+        let Some((root_manifest, members)) = manifest::read_workspace_members(root, ecosystem) else { continue };
+        if members.is_empty() {
+            continue;
+        }
+        if info.kind.is_none() {
+            let kind = if ecosystem == "npm" { npm_flavor(root, &root_manifest) } else { "cargo" };
+            info.kind = Some(kind.to_string());
+        }
+        for (dir, member) in members {
+            let member = member.unwrap_or_default();
+            info.members.push(WorkspaceMember {
+                name: member.name.unwrap_or_else(|| dir_name(&dir)),
+                path: relative(root, &dir),
+                version: member.version,
+                ecosystem: ecosystem.to_string(),
+            });
+        }
+    }
+
+    let go_dirs = go_module_dirs(root);
+    if !go_dirs.is_empty() {
+        info.kind.get_or_insert_with(|| "go".to_string());
+        for dir in go_dirs {
+            info.members.push(WorkspaceMember {
+                name: go_module_name(&dir).unwrap_or_else(|| dir_name(&dir)),
+                path: relative(root, &dir),
+                version: None,
+                ecosystem: "go".to_string(),
+            });
+        }
+    }
+
+    for (dir, name) in nx_project_dirs(root) {
+        let path = relative(root, &dir);
+        if info.members.iter().any(|m| m.path == path) {
+            continue;
+        }
+        info.kind.get_or_insert_with(|| "nx".to_string());
+        info.members.push(WorkspaceMember {
+            name: name.unwrap_or_else(|| dir_name(&dir)),
+            path,
+            version: None,
+            ecosystem: "nx".to_string(),
+        });
+    }
+    info
+}
+
+// Absolute member directories, for callers that treat each member as its
+// own project. The root itself is left out even when it is a member
+pub fn member_dirs(root: &Path, info: &WorkspaceInfo) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for member in info.members.iter().filter(|m| !m.path.is_empty()) {
+        let dir = root.join(&member.path);
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_go_work_single_and_block_use() {
+        let dirs = parse_go_work("go 1.22\n\nuse (\n\t./api // service\n\t\"./cli\"\n)\nuse ./tools\n");
+        assert_eq!(dirs, vec!["./api", "./cli", "./tools"]);
+    }
+
+    #[test]
+    fn test_pnpm_workspace_members_skip_node_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("package.json"), r#"{"name": "mono", "private": true}"#).unwrap();
+        std::fs::write(root.join("pnpm-workspace.yaml"), "packages:\n  - \"packages/*\"\n  - \"!packages/legacy\"\n").unwrap();
+        std::fs::write(root.join("turbo.json"), "{}").unwrap();
+        for (dir, manifest) in [
+            ("packages/ui", r#"{"name": "@mono/ui", "version": "1.2.0"}"#),
+            ("packages/legacy", r#"{"name": "@mono/legacy"}"#),
+            ("packages/ui/node_modules/react", r#"{"name": "react"}"#),
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            std::fs::write(root.join(dir).join("package.json"), manifest).unwrap();
+        }
+        std::fs::create_dir_all(root.join("packages/docs")).unwrap();
+
+        let info = get_workspace_info(root);
+        assert_eq!(info.kind.as_deref(), Some("pnpm"));
+        assert_eq!(info.tools, vec!["turborepo"]);
+        assert_eq!(
+            info.members,
+            vec![WorkspaceMember {
+                name: "@mono/ui".into(),
+                path: "packages/ui".into(),
+                version: Some("1.2.0".into()),
+                ecosystem: "npm".into(),
+            }]
+        );
+        assert_eq!(member_dirs(root, &info), vec![root.join("packages/ui")]);
+    }
+
+    #[test]
+    fn test_nx_projects_without_package_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("nx.json"), "{}").unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name": "acme"}"#).unwrap();
+        std::fs::create_dir_all(root.join("apps/web")).unwrap();
+        std::fs::write(root.join("apps/web/project.json"), r#"{"name": "web-app"}"#).unwrap();
+        std::fs::create_dir_all(root.join("libs/shared/ui")).unwrap();
+        std::fs::write(root.join("libs/shared/ui/project.json"), "{}").unwrap();
+
+        let info = get_workspace_info(root);
+        assert_eq!(info.kind.as_deref(), Some("nx"));
+        assert_eq!(info.tools, vec!["nx"]);
+        let members: Vec<(&str, &str)> = info.members.iter().map(|m| (m.name.as_str(), m.path.as_str())).collect();
+        assert_eq!(members, vec![("web-app", "apps/web"), ("ui", "libs/shared/ui")]);
+    }
+
+    #[test]
+    fn test_cargo_and_go_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        std::fs::create_dir_all(root.join("crates/core")).unwrap();
+        std::fs::write(root.join("crates/core/Cargo.toml"), "[package]\nname = \"core\"\nversion = \"0.3.1\"\n").unwrap();
+        std::fs::write(root.join("go.mod"), "module example.com/mono\n").unwrap();
+        std::fs::create_dir_all(root.join("tools/gen")).unwrap();
+        std::fs::write(root.join("tools/gen/go.mod"), "module example.com/mono/tools/gen\n").unwrap();
+
+        let info = get_workspace_info(root);
+        assert_eq!(info.kind.as_deref(), Some("cargo"));
+        let members: Vec<(&str, &str, &str)> =
+            info.members.iter().map(|m| (m.ecosystem.as_str(), m.name.as_str(), m.path.as_str())).collect();
+        assert_eq!(
+            members,
+            vec![
+                ("cargo", "core", "crates/core"),
+                ("go", "example.com/mono", ""),
+                ("go", "example.com/mono/tools/gen", "tools/gen"),
+            ]
+        );
+    }
+}
@@ -4,14 +4,16 @@ import FolderSelector from './components/FolderSelector';
 import Settings from './components/Settings';
 import ProjectList from './components/ProjectList';
 import ProjectAnalyzer from './components/ProjectAnalyzer';
-import { Settings as SettingsType, ProjectDirectory } from './types';
+import ErrorNotifier from './components/ErrorNotifier';
+import { listen } from '@tauri-apps/api/event';
+import { Settings as SettingsType, ProjectDirectory, SettingsChanged } from './types';
 import { loadSettings } from './utils/storage';
-import { loadRootFolder, saveRootFolder } from './utils/api';
+import { loadRootFolder, saveRootFolder, recordProjectOpened, rescanProject, takePendingOpenProject } from './utils/api';
 import './index.css';
 import Button from './components/ui/Button';
 import HeaderNav from './components/ui/HeaderNav';
 import ToastProvider from './components/ui/ToastProvider';
-import { basename } from './utils/format';
+import { basename, dirname } from './utils/format';
 import { ThemeProvider } from './contexts/ThemeContext';
 import ThemeToggle from './components/ui/ThemeToggle';
 
@@ -22,12 +24,58 @@ const App: React.FC = () => {
   const [settings, setSettings] = useState<SettingsType | null>(null);
   const [rootPath, setRootPath] = useState<string>('');
   const [selectedProject, setSelectedProject] = useState<ProjectDirectory | null>(null);
+  const [shuttingDown, setShuttingDown] = useState(false);
 
   useEffect(() => {
     loadSettings().then(setSettings);
     loadSavedRootFolder();
   }, []);
 
+  // Keep in sync with settings saved elsewhere; api_key is never broadcast
+  useEffect(() => {
+    const unlisten = listen<SettingsChanged>('settings:changed', (event) => {
+      const { key, value } = event.payload;
+      if (key === 'api_settings') {
+        setSettings((prev) => ({ ...(value as SettingsType), api_key: prev?.api_key ?? '' }));
+      } else if (key === 'root_folder') {
+        setRootPath(value as string);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Projects named on the command line, including by a second launch
+  useEffect(() => {
+    const openPending = async () => {
+      const path = await takePendingOpenProject();
+      if (!path) return;
+      try {
+        const project = await rescanProject(path);
+        // Without a saved root (first run), list the project's siblings
+        setRootPath((current) => current || dirname(project.path));
+        setCurrentView('workspace');
+        handleProjectSelect(project);
+      } catch (error) {
+        console.error('Error opening project from command line:', error);
+      }
+    };
+    openPending();
+    const unlisten = listen('app:open_project', openPending);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Quitting waits a few seconds for running analyses and backups
+  useEffect(() => {
+    const unlisten = listen('app:shutting_down', () => setShuttingDown(true));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const loadSavedRootFolder = async () => {
     try {
       const savedRoot = await loadRootFolder();
@@ -53,6 +101,9 @@ const App: React.FC = () => {
 
   const handleProjectSelect = (project: ProjectDirectory) => {
     setSelectedProject(project);
+    recordProjectOpened(project.path).catch((error) => {
+      console.error('Error recording project open:', error);
+    });
   };
 
   const selectNewFolder = async () => {
@@ -73,7 +124,13 @@ const App: React.FC = () => {
   return (
     <ThemeProvider>
       <ToastProvider>
+        <ErrorNotifier />
         <div className="h-screen bg-background flex flex-col">
+          {shuttingDown && (
+            <div className="fixed inset-0 z-50 flex items-center justify-center bg-background/80">
+              <p className="text-sm text-foreground-secondary">Finishing up...</p>
+            </div>
+          )}
           {/* Navigation */}
           <HeaderNav
             title="RepoMuse"
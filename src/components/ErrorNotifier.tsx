@@ -0,0 +1,23 @@
+import React, { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { AppError } from '../types';
+import { useToast } from './ui/ToastProvider';
+
+// Toasts actionable background failures; the full list is under Settings > Problems
+const ErrorNotifier: React.FC = () => {
+  const { toast } = useToast();
+
+  useEffect(() => {
+    const unlisten = listen<AppError>('app:error', (event) => {
+      const { message, context } = event.payload;
+      toast({ title: message, description: context ?? undefined, variant: 'error', duration: 6000 });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [toast]);
+
+  return null;
+};
+
+export default ErrorNotifier;
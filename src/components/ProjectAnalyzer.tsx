@@ -1,6 +1,6 @@
 import React, { useState, useEffect, useRef, useCallback } from 'react';
-import { ProjectDirectory, RepoAnalysis, Settings, ProjectSummary, ProjectInsights, GitLog, TaskList } from '../types';
-import { analyzeRepository, analyzeRepositoryFresh, analyzeRepositoryLazy, triggerFullScan, cancelAnalysis, generateIdeaList, generateProjectSummary, saveProjectSummary, loadProjectSummary, getProjectInsights, getGitLog, loadTaskList } from '../utils/api';
+import { ProjectDirectory, RepoAnalysis, Settings, ProjectSummary, SummaryFreshness, ProjectInsights, GitLog, ContributorStats, TaskList } from '../types';
+import { analyzeRepository, analyzeRepositoryFresh, analyzeRepositoryLazy, triggerFullScan, cancelAnalysis, generateIdeaList, generateProjectSummary, saveProjectSummary, loadProjectSummary, checkSummaryFreshness, getProjectInsights, getGitLog, getContributorStats, loadTaskList, addIdeaToTasks as addIdeaToTasksApi } from '../utils/api';
 import Spinner from './ui/Spinner';
 import Alert from './ui/Alert';
 import Card from './ui/Card';
@@ -13,7 +13,7 @@ import { FileText, Lightbulb, TrendingUp, GitBranch, Plus, Focus, HardDrive, Fil
 import MarkdownRenderer from './MarkdownRenderer';
 import ProjectInsightsComponent from './ProjectInsights';
 import ProjectHeader from './ProjectHeader';
-import TaskListComponent, { createTaskFromIdea } from './TaskList';
+import TaskListComponent from './TaskList';
 import { useToast } from './ui/ToastProvider';
 import { basename } from '../utils/format';
 import { listen } from '@tauri-apps/api/event';
@@ -39,7 +39,12 @@ interface ProgressUpdate {
   bytes_processed: number;
   total_bytes: number | null;
   skipped_filtered?: number;
+  // Keyed by "ignored_directory" / "ignored_extension"
+  skipped_by_reason?: Record<string, number> | null;
   dirs_seen?: number;
+  // Unreadable entries, e.g. directories without permission; counts are low when nonzero
+  io_errors?: number | null;
+  error_message?: string | null;
 }
 
 // Helper function to format file size
@@ -77,6 +82,7 @@ const ProgressBar: React.FC<{ progress: ProgressUpdate }> = React.memo(({ progre
       case 'lazy-streaming': return 'bg-green-500';
       case 'processing': return 'bg-primary';
       case 'cancelled': return 'bg-orange-500';
+      case 'error': return 'bg-red-500';
       case 'complete': return 'bg-success';
       default: return 'bg-gray-500';
     }
@@ -90,6 +96,7 @@ const ProgressBar: React.FC<{ progress: ProgressUpdate }> = React.memo(({ progre
       case 'lazy-streaming': return '⚡ Quick scan...';
       case 'processing': return '🔨 Processing files...';
       case 'cancelled': return '⏹️ Cancelled';
+      case 'error': return '❌ Failed';
       case 'complete': return '✅ Complete';
       default: return phase;
     }
@@ -175,6 +182,14 @@ const ProgressBar: React.FC<{ progress: ProgressUpdate }> = React.memo(({ progre
             <span>Scanning directory structure...</span>
           </div>
         )}
+        {progress.phase === 'error' && progress.error_message && (
+          <div className="text-sm text-error">{progress.error_message}</div>
+        )}
+        {!!progress.io_errors && (
+          <div className="text-sm text-error">
+            ⚠️ {progress.io_errors.toLocaleString()} unreadable {progress.io_errors === 1 ? 'entry' : 'entries'}; file counts may be incomplete
+          </div>
+        )}
 
         {/* Controls */}
         {!progress.is_complete && progress.phase !== 'cancelled' && (
@@ -193,8 +208,10 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
   const [analysis, setAnalysis] = useState<RepoAnalysis | null>(null);
   const [ideas, setIdeas] = useState<string[]>([]);
   const [summary, setSummary] = useState<ProjectSummary | null>(null);
+  const [summaryFreshness, setSummaryFreshness] = useState<SummaryFreshness | null>(null);
   const [insights, setInsights] = useState<ProjectInsights | null>(null);
   const [gitLog, setGitLog] = useState<GitLog | null>(null);
+  const [contributors, setContributors] = useState<ContributorStats[]>([]);
   const [taskList, setTaskList] = useState<TaskList | null>(null);
   const [isAnalyzing, setIsAnalyzing] = useState(false);
   const [isGeneratingIdeas, setIsGeneratingIdeas] = useState(false);
@@ -290,8 +307,10 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
       setProgress(null);
       setIdeas([]);
       setSummary(null);
+      setSummaryFreshness(null);
       setInsights(null);
       setGitLog(null);
+      setContributors([]);
       setTaskList(null);
       setAnalysisError('');
       setIdeasError('');
@@ -435,6 +454,7 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
         analysis,
         settings,
         focus_area: focusArea || undefined,
+        project_path: selectedProject?.path,
       });
       setIdeas(generatedIdeas);
       // Store the focus area that was used during generation
@@ -455,20 +475,21 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
       const loadedSummary = await loadProjectSummary(selectedProject.path);
       if (loadedSummary) {
         setSummary(loadedSummary);
+        setSummaryFreshness(await checkSummaryFreshness(selectedProject.path));
       }
     } catch (err) {
       console.error('Error loading summary:', err);
     }
   };
 
-  const loadInsights = async () => {
+  const loadInsights = async (forceRefresh = false) => {
     if (!selectedProject) return;
     
     setIsLoadingInsights(true);
     setInsightsError('');
     
     try {
-      const projectInsights = await getProjectInsights(selectedProject.path);
+      const projectInsights = await getProjectInsights(selectedProject.path, undefined, forceRefresh);
       setInsights(projectInsights);
     } catch (err) {
       setInsightsError(err as string);
@@ -486,6 +507,10 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
     try {
       const log = await getGitLog(selectedProject.path);
       setGitLog(log);
+      // Contributors are a nice-to-have; a failure here shouldn't hide the log
+      getContributorStats(selectedProject.path)
+        .then(setContributors)
+        .catch((error) => console.error('Error loading contributors:', error));
     } catch (err) {
       setGitLogError(err as string);
     } finally {
@@ -518,6 +543,7 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
       setSummary(generatedSummary);
       // Save the summary for future use
       await saveProjectSummary(generatedSummary);
+      setSummaryFreshness(null);
       toast({ title: 'Summary generated', variant: 'success' });
     } catch (err) {
       setSummaryError(err as string);
@@ -531,15 +557,15 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
     if (!selectedProject) return;
     
     try {
-      const result = await createTaskFromIdea(selectedProject.path, idea);
+      const result = await addIdeaToTasksApi(selectedProject.path, idea);
       
-      if (result.success) {
+      if (result.created) {
         toast({ title: 'Task added', description: 'Idea has been added to your task list', variant: 'success' });
         // Trigger task list refresh
         setTaskUpdateTrigger(prev => prev + 1);
         // Reload tasks to update the badge count
         loadTasks();
-      } else if (result.isDuplicate) {
+      } else {
         toast({ 
           title: 'Task already exists', 
           description: 'This idea has already been added to your task list', 
@@ -627,12 +653,21 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
                 <p className="text-xs text-foreground-tertiary">
                   Generated: {new Date(summary.generated_at).toLocaleString()}
                 </p>
+                {summaryFreshness?.state === 'stale' && (
+                  <p className="text-xs text-error mt-1">
+                    Possibly outdated: {summaryFreshness.drivers.join(', ')}
+                  </p>
+                )}
               </div>
             </div>
           )}
         </div>
       ),
-      badge: summary ? <Badge variant="green" className="ml-2">✓</Badge> : undefined
+      badge: summary
+        ? summaryFreshness?.state === 'stale'
+          ? <Badge variant="red" className="ml-2">Outdated</Badge>
+          : <Badge variant="green" className="ml-2">✓</Badge>
+        : undefined
     },
     {
       id: 'ideas',
@@ -797,13 +832,25 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
             <>
               <Alert variant="error" title="Insights Error">{insightsError}</Alert>
               <div className="mt-4">
-                <Button onClick={loadInsights}>Retry Analysis</Button>
+                <Button onClick={() => loadInsights(true)}>Retry Analysis</Button>
               </div>
             </>
           )}
 
           {insights && (
-            <ProjectInsightsComponent insights={insights} />
+            <>
+              <div className="flex items-center justify-end gap-2 mb-3 text-xs text-foreground-tertiary">
+                {insights.computed_at && (
+                  <span>
+                    {insights.from_cache ? 'Cached' : 'Computed'} {new Date(insights.computed_at).toLocaleTimeString()}
+                  </span>
+                )}
+                <Button size="sm" variant="ghost" onClick={() => loadInsights(true)} loading={isLoadingInsights}>
+                  Refresh
+                </Button>
+              </div>
+              <ProjectInsightsComponent insights={insights} projectPath={selectedProject?.path} />
+            </>
           )}
 
           {!insights && !isLoadingInsights && !insightsError && (
@@ -848,7 +895,11 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
             <div className="space-y-6">
               {/* Git Summary */}
               <div className="grid grid-cols-1 md:grid-cols-3 gap-4">
-                <StatTile label="Total Commits" value={gitLog.total_commits.toLocaleString()} color="blue" />
+                <StatTile
+                  label={gitLog.is_shallow ? 'Total Commits (shallow clone)' : 'Total Commits'}
+                  value={gitLog.total_commits.toLocaleString()}
+                  color="blue"
+                />
                 <StatTile label="Recent Commits" value={gitLog.commits.length} color="green" />
                 <StatTile label="Branches" value={gitLog.branches.length} color="purple" />
               </div>
@@ -858,7 +909,35 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
                 <div className="flex items-center space-x-2 text-sm">
                   <GitBranch className="h-4 w-4 text-foreground-secondary" />
                   <span className="text-foreground-secondary">Current branch:</span>
-                  <Badge variant="blue">{gitLog.current_branch}</Badge>
+                  {gitLog.current_branch_url ? (
+                    <a href={gitLog.current_branch_url} target="_blank" rel="noopener noreferrer">
+                      <Badge variant="blue">{gitLog.current_branch}</Badge>
+                    </a>
+                  ) : (
+                    <Badge variant="blue">{gitLog.current_branch}</Badge>
+                  )}
+                </div>
+              )}
+
+              {/* Contributors */}
+              {contributors.length > 0 && (
+                <div>
+                  <h3 className="text-md font-semibold text-foreground mb-4">Contributors</h3>
+                  <div className="space-y-2">
+                    {contributors.slice(0, 10).map((contributor) => (
+                      <div key={contributor.email} className="flex items-center justify-between text-sm">
+                        <div className="min-w-0">
+                          <span className="font-medium text-foreground">{contributor.name}</span>
+                          <span className="ml-2 text-foreground-tertiary">{contributor.email}</span>
+                        </div>
+                        <div className="flex items-center space-x-3 text-xs text-foreground-secondary ml-4">
+                          <span>{contributor.commits.toLocaleString()} commits</span>
+                          <span className="text-success">+{contributor.lines_added.toLocaleString()}</span>
+                          <span className="text-error">-{contributor.lines_removed.toLocaleString()}</span>
+                        </div>
+                      </div>
+                    ))}
+                  </div>
                 </div>
               )}
 
@@ -877,9 +956,20 @@ const ProjectAnalyzer: React.FC<ProjectAnalyzerProps> = ({ selectedProject, sett
                             <span>{new Date(commit.date).toLocaleString()}</span>
                           </div>
                         </div>
-                        <code className="text-xs bg-background-tertiary px-2 py-1 rounded font-mono text-foreground-secondary ml-4">
-                          {commit.hash.substring(0, 7)}
-                        </code>
+                        {commit.url ? (
+                          <a
+                            href={commit.url}
+                            target="_blank"
+                            rel="noopener noreferrer"
+                            className="text-xs bg-background-tertiary px-2 py-1 rounded font-mono text-primary hover:text-primary-hover ml-4"
+                          >
+                            {commit.hash.substring(0, 7)}
+                          </a>
+                        ) : (
+                          <code className="text-xs bg-background-tertiary px-2 py-1 rounded font-mono text-foreground-secondary ml-4">
+                            {commit.hash.substring(0, 7)}
+                          </code>
+                        )}
                       </div>
                     </div>
                   ))}
@@ -1,11 +1,16 @@
-import React from 'react';
+import React, { useEffect, useState } from 'react';
 import Card from './ui/Card';
 import Badge from './ui/Badge';
-import { ProjectInsights } from '../types';
+import Button from './ui/Button';
+import LanguageBar from './ui/LanguageBar';
+import { ChangelogStatus, CIWorkflow, LanguageShare, OutdatedDependency, ProjectInsights } from '../types';
+import { checkOutdatedDependencies, getLanguageBreakdown } from '../utils/api';
 import { ExternalLink } from 'lucide-react';
 
 type Props = {
   insights: ProjectInsights;
+  // Enables the opt-in registry check for outdated dependencies
+  projectPath?: string;
 };
 
 const Section: React.FC<{ title: string; children: React.ReactNode }> = ({ title, children }) => (
@@ -22,40 +27,119 @@ const Row: React.FC<{ label: string; value: React.ReactNode }> = ({ label, value
   </div>
 );
 
-// Helper function to convert git URL to web URL
-const getWebUrl = (gitUrl: string): string | null => {
-  let url = gitUrl;
-  
-  // Remove .git extension if present
-  if (url.endsWith('.git')) {
-    url = url.slice(0, -4);
+// "build, test, release (on tags)"
+const describeWorkflow = (workflow: CIWorkflow): string => {
+  const purposes: string[] = [];
+  if (workflow.runs_build) purposes.push('build');
+  if (workflow.runs_tests) purposes.push('test');
+  if (workflow.runs_lint) purposes.push('lint');
+  if (workflow.runs_security_scan) purposes.push('security');
+  if (workflow.builds_release) {
+    purposes.push(workflow.triggers.includes('tags') ? 'release (on tags)' : 'release');
   }
-  
-  // Convert SSH URLs to HTTPS
-  // git@github.com:user/repo.git -> https://github.com/user/repo
-  const sshMatch = url.match(/^git@([^:]+):(.+)$/);
-  if (sshMatch) {
-    return `https://${sshMatch[1]}/${sshMatch[2]}`;
-  }
-  
-  // If it's already an HTTPS URL, return it
-  if (url.startsWith('http://') || url.startsWith('https://')) {
-    return url;
-  }
-  
-  return null;
+  return purposes.length > 0 ? purposes.join(', ') : workflow.jobs.join(', ');
+};
+
+const CHANGELOG_STATUS: Record<ChangelogStatus, { label: string; variant: 'green' | 'red' | 'blue' | 'purple' }> = {
+  in_sync: { label: 'In sync with tags', variant: 'green' },
+  behind_tags: { label: 'Changelog behind tags', variant: 'red' },
+  ahead_of_tags: { label: 'Untagged release', variant: 'blue' },
+  tags_without_changelog: { label: 'Tags without changelog', variant: 'purple' },
 };
 
-const ProjectInsightsComponent: React.FC<Props> = ({ insights }) => {
-  const { git_status, readme_info, ci_info, package_info, testing_info } = insights;
+const ProjectInsightsComponent: React.FC<Props> = ({ insights, projectPath }) => {
+  const { git_status, readme_info, ci_info, package_info, testing_info, dependency_report, container_info } = insights;
+  const [outdated, setOutdated] = useState<OutdatedDependency[] | null>(null);
+  const [checkingOutdated, setCheckingOutdated] = useState(false);
+  const [outdatedError, setOutdatedError] = useState<string | null>(null);
+  const [languages, setLanguages] = useState<LanguageShare[]>([]);
+
+  useEffect(() => {
+    if (!projectPath) return;
+    let cancelled = false;
+    getLanguageBreakdown(projectPath)
+      .then((breakdown) => {
+        if (!cancelled) setLanguages(breakdown);
+      })
+      .catch(() => {
+        if (!cancelled) setLanguages([]);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [projectPath]);
+
+  const handleCheckOutdated = async () => {
+    if (!projectPath) return;
+    setCheckingOutdated(true);
+    setOutdatedError(null);
+    try {
+      const results = await Promise.all(
+        dependency_report.ecosystems.map((eco) => checkOutdatedDependencies(projectPath, eco.ecosystem))
+      );
+      setOutdated(results.flat());
+    } catch (error) {
+      setOutdatedError(String(error));
+    } finally {
+      setCheckingOutdated(false);
+    }
+  };
 
   return (
     <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+      {languages.length > 0 && (
+        <div className="md:col-span-2">
+          <Section title="Languages">
+            <LanguageBar languages={languages} showLegend />
+          </Section>
+        </div>
+      )}
       <Section title="Git">
         <div className="space-y-2">
           <Row label="Repository" value={git_status.is_git_repo ? <Badge variant="green">Yes</Badge> : <Badge variant="gray">No</Badge>} />
           {git_status.current_branch && <Row label="Branch" value={git_status.current_branch} />}
+          {git_status.is_detached && <Row label="Branch" value={<Badge variant="purple">Detached HEAD</Badge>} />}
           {typeof git_status.commit_count === 'number' && <Row label="Commits" value={git_status.commit_count} />}
+          {git_status.latest_version && (
+            <Row
+              label="Latest Tag"
+              value={typeof git_status.commits_since_latest_version === 'number'
+                ? `${git_status.latest_version}, ${git_status.commits_since_latest_version} commits since`
+                : git_status.latest_version}
+            />
+          )}
+          {git_status.last_commit_date && <Row label="Last Commit" value={new Date(git_status.last_commit_date).toLocaleString()} />}
+          {git_status.is_git_repo && (
+            <Row
+              label="Working Tree"
+              value={git_status.has_uncommitted_changes
+                ? `${git_status.staged_files.length} staged · ${git_status.unstaged_files.length} modified · ${git_status.untracked_files.length} untracked`
+                : <Badge variant="green">Clean</Badge>}
+            />
+          )}
+          {git_status.stash_count > 0 && (
+            <Row label="Stashes" value={<span title={git_status.stashes.join('\n')}>{git_status.stash_count}</span>} />
+          )}
+          {git_status.submodules.length > 0 && (
+            <Row
+              label="Submodules"
+              value={
+                <div className="flex flex-wrap justify-end gap-2 text-xs">
+                  {git_status.submodules.map((m) => (
+                    <Badge key={m.path} variant={m.initialized ? 'gray' : 'red'}>
+                      {m.path}{m.initialized ? '' : ' (not initialized)'}
+                    </Badge>
+                  ))}
+                </div>
+              }
+            />
+          )}
+          {git_status.worktrees.length > 0 && (
+            <Row
+              label="Worktrees"
+              value={git_status.worktrees.map((w) => `${w.branch ?? 'detached'} → ${w.path}`).join(', ')}
+            />
+          )}
           
           {/* Display remotes */}
           {git_status.remotes && git_status.remotes.length > 0 && (
@@ -63,11 +147,16 @@ const ProjectInsightsComponent: React.FC<Props> = ({ insights }) => {
               <h4 className="text-xs font-medium text-foreground-secondary mb-2">Remotes</h4>
               <div className="space-y-2">
                 {git_status.remotes.map((remote, index) => {
-                  const webUrl = getWebUrl(remote.url);
+                  const webUrl = remote.web_url;
                   return (
                     <div key={index} className="text-xs">
                       <div className="flex items-start justify-between">
-                        <span className="font-medium text-foreground">{remote.name}</span>
+                        <span className="font-medium text-foreground">
+                          {remote.name}
+                          {remote.owner && remote.repo && (
+                            <span className="ml-2 font-normal text-foreground-secondary">{remote.owner}/{remote.repo}</span>
+                          )}
+                        </span>
                         {webUrl && (
                           <a 
                             href={webUrl} 
@@ -94,6 +183,23 @@ const ProjectInsightsComponent: React.FC<Props> = ({ insights }) => {
       <Section title="README">
         <Row label="Exists" value={readme_info.exists ? <Badge variant="green">Yes</Badge> : <Badge variant="red">No</Badge>} />
         {readme_info.path && <Row label="Path" value={<span className="truncate max-w-[220px] inline-block align-bottom" title={readme_info.path}>{readme_info.path}</span>} />}
+        {readme_info.description && <p className="text-xs text-foreground-secondary mt-1 line-clamp-3">{readme_info.description}</p>}
+        {readme_info.quality && (
+          <>
+            <Row
+              label="Completeness"
+              value={
+                <Badge variant={readme_info.quality.score >= 70 ? 'green' : readme_info.quality.score >= 40 ? 'purple' : 'red'}>
+                  {readme_info.quality.score}/100
+                </Badge>
+              }
+            />
+            {readme_info.is_default && <Row label="Template" value={<Badge variant="red">Unmodified</Badge>} />}
+            {readme_info.quality.missing_sections.length > 0 && (
+              <Row label="Missing" value={readme_info.quality.missing_sections.join(', ')} />
+            )}
+          </>
+        )}
       </Section>
 
       <Section title="CI">
@@ -105,6 +211,19 @@ const ProjectInsightsComponent: React.FC<Props> = ({ insights }) => {
             ))}
           </div>
         )}
+        {ci_info.workflows.map((workflow) => (
+          <Row
+            key={workflow.file}
+            label={workflow.name || workflow.file}
+            value={workflow.parsed ? describeWorkflow(workflow) : <Badge variant="gray">Not parsed</Badge>}
+          />
+        ))}
+        {ci_info.ci_matrix && ci_info.ci_matrix.os_targets.length > 0 && (
+          <Row label="Matrix Platforms" value={ci_info.ci_matrix.os_targets.join(', ')} />
+        )}
+        {ci_info.ci_matrix && ci_info.ci_matrix.language_versions.length > 0 && (
+          <Row label="Matrix Versions" value={ci_info.ci_matrix.language_versions.join(', ')} />
+        )}
       </Section>
 
       <Section title="Packages">
@@ -114,7 +233,55 @@ const ProjectInsightsComponent: React.FC<Props> = ({ insights }) => {
           {package_info.has_requirements_txt && <Badge variant="gray">requirements.txt</Badge>}
           {package_info.has_gemfile && <Badge variant="gray">Gemfile</Badge>}
           {package_info.has_go_mod && <Badge variant="gray">go.mod</Badge>}
+          {package_info.has_changelog && <Badge variant="gray">CHANGELOG</Badge>}
         </div>
+        {dependency_report.ecosystems.map((eco) => (
+          <Row
+            key={eco.ecosystem}
+            label={eco.manifest}
+            value={`${eco.runtime_count} deps, ${eco.dev_count} dev${eco.workspace_members.length > 0 ? `, ${eco.workspace_members.length} workspace members` : ''}`}
+          />
+        ))}
+        {dependency_report.package_manager && <Row label="Package Manager" value={dependency_report.package_manager} />}
+        {dependency_report.node_version && <Row label="Node" value={dependency_report.node_version} />}
+        {dependency_report.python_version && <Row label="Python" value={dependency_report.python_version} />}
+        {dependency_report.rust_version && <Row label="Rust" value={dependency_report.rust_version} />}
+        {(dependency_report.missing_lockfiles.length > 0 || dependency_report.lockfiles.some((l) => l.older_than_manifest)) && (
+          <div className="mt-2 flex flex-wrap gap-2 text-xs">
+            {dependency_report.missing_lockfiles.map((eco) => (
+              <Badge key={eco} variant="red">No {eco} lockfile</Badge>
+            ))}
+            {dependency_report.lockfiles.filter((l) => l.older_than_manifest).map((l) => (
+              <Badge key={l.file} variant="red">{l.file} older than manifest</Badge>
+            ))}
+          </div>
+        )}
+        {projectPath && dependency_report.ecosystems.length > 0 && (
+          <div className="mt-3">
+            <Button variant="secondary" size="sm" loading={checkingOutdated} onClick={handleCheckOutdated}>
+              Check for updates
+            </Button>
+            {outdatedError && <div className="mt-1 text-xs text-error">{outdatedError}</div>}
+            {outdated && outdated.length === 0 && (
+              <div className="mt-1 text-xs text-foreground-secondary">All checked dependencies are current.</div>
+            )}
+            {outdated && outdated.length > 0 && (
+              <div className="mt-2 space-y-1 text-xs">
+                {outdated.map((dep) => (
+                  <div key={`${dep.ecosystem}:${dep.name}`} className="flex justify-between gap-2">
+                    <span className="truncate" title={dep.name}>{dep.name}</span>
+                    <span className="flex items-center gap-2 text-foreground-secondary">
+                      {dep.current_req} → {dep.latest}
+                      <Badge variant={dep.severity === 'major' ? 'red' : dep.severity === 'minor' ? 'purple' : 'gray'}>
+                        {dep.severity}
+                      </Badge>
+                    </span>
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+        )}
         {package_info.missing_common_files.length > 0 && (
           <div className="mt-2">
             <div className="text-xs text-foreground-secondary mb-1">Missing common files:</div>
@@ -129,10 +296,18 @@ const ProjectInsightsComponent: React.FC<Props> = ({ insights }) => {
 
       <Section title="Testing">
         <Row label="Has Framework" value={testing_info.has_testing_framework ? <Badge variant="green">Yes</Badge> : <Badge variant="red">No</Badge>} />
-        <Row label="Test Files" value={testing_info.has_test_files ? testing_info.test_file_count : 0} />
-        {typeof testing_info.source_to_test_ratio === 'number' && (
-          <Row label="Source/Test Ratio" value={testing_info.source_to_test_ratio?.toFixed(2)} />
+        <Row
+          label="Test Files"
+          value={`${testing_info.has_test_files ? testing_info.test_file_count : 0}${testing_info.scan_truncated ? '+' : ''}`}
+        />
+        {testing_info.rust_test_fn_count > 0 && <Row label="Rust Tests" value={testing_info.rust_test_fn_count} />}
+        {typeof testing_info.test_to_source_ratio === 'number' && (
+          <Row label="Tests per Source File" value={testing_info.test_to_source_ratio?.toFixed(2)} />
+        )}
+        {testing_info.coverage_tools.length > 0 && (
+          <Row label="Coverage" value={testing_info.coverage_tools.join(', ')} />
         )}
+        {testing_info.has_benchmarks && <Row label="Benchmarks" value="Yes" />}
         {testing_info.testing_frameworks.length > 0 && (
           <div className="mt-1 flex flex-wrap gap-2 text-xs">
             {testing_info.testing_frameworks.map((f) => (
@@ -141,6 +316,124 @@ const ProjectInsightsComponent: React.FC<Props> = ({ insights }) => {
           </div>
         )}
       </Section>
+
+      {(container_info.dockerfiles.length > 0 || container_info.compose_files.length > 0 || container_info.has_devcontainer) && (
+        <Section title="Containers">
+          {container_info.dockerfiles.map((d) => (
+            <Row
+              key={d.path}
+              label={d.path}
+              value={[d.base_images.join(' → '), d.exposed_ports.length > 0 ? `ports ${d.exposed_ports.join(', ')}` : '']
+                .filter(Boolean)
+                .join(' · ')}
+            />
+          ))}
+          {container_info.compose_files.map((c) => (
+            <Row key={c.path} label={c.path} value={c.services.map((s) => s.name).join(', ') || 'no services'} />
+          ))}
+          <div className="mt-1 flex flex-wrap gap-2 text-xs">
+            {container_info.has_database_service && <Badge variant="blue">Database service</Badge>}
+            {container_info.has_devcontainer && <Badge variant="gray">Dev Container</Badge>}
+            {container_info.dockerfiles.length > 0 && !container_info.has_dockerignore && <Badge variant="red">No .dockerignore</Badge>}
+          </div>
+        </Section>
+      )}
+
+      <Section title="Formatting">
+        <Row label="Prettier" value={insights.formatter_info.prettier_config ?? <Badge variant="gray">None</Badge>} />
+        <Row label="ESLint" value={insights.formatter_info.eslint_config ?? <Badge variant="gray">None</Badge>} />
+        <Row label="rustfmt" value={insights.formatter_info.has_rustfmt ? <Badge variant="green">Yes</Badge> : <Badge variant="gray">No</Badge>} />
+        <Row label="EditorConfig" value={insights.formatter_info.has_editorconfig ? <Badge variant="green">Yes</Badge> : <Badge variant="gray">No</Badge>} />
+      </Section>
+
+      <Section title="Community">
+        <div className="flex flex-wrap gap-2 text-xs">
+          {insights.community_info.contributing && <Badge variant="gray">CONTRIBUTING</Badge>}
+          {insights.community_info.code_of_conduct && <Badge variant="gray">Code of Conduct</Badge>}
+          {insights.community_info.security_policy && <Badge variant="gray">SECURITY</Badge>}
+          {insights.community_info.issue_templates.length > 0 && <Badge variant="gray">Issue templates</Badge>}
+          {insights.community_info.pull_request_template && <Badge variant="gray">PR template</Badge>}
+          {insights.community_info.funding && <Badge variant="gray">FUNDING</Badge>}
+        </div>
+        {insights.community_info.codeowners && (
+          <Row
+            label="CODEOWNERS"
+            value={`${insights.community_info.codeowners_rules.length} rule${insights.community_info.codeowners_rules.length === 1 ? '' : 's'}`}
+          />
+        )}
+        {insights.community_info.missing_files.length > 0 && (
+          <Row label="Missing" value={insights.community_info.missing_files.join(', ')} />
+        )}
+      </Section>
+
+      {insights.workspace_info.kind && (
+        <Section title="Workspace">
+          <Row
+            label="Layout"
+            value={
+              <div className="flex flex-wrap justify-end gap-2 text-xs">
+                <Badge variant="purple">{insights.workspace_info.kind}</Badge>
+                {insights.workspace_info.tools.map((tool) => (
+                  <Badge key={tool} variant="gray">{tool}</Badge>
+                ))}
+              </div>
+            }
+          />
+          {insights.workspace_info.members.map((m) => (
+            <Row key={`${m.ecosystem}:${m.path}`} label={m.path || '.'} value={`${m.name}${m.version ? ` @ ${m.version}` : ''}`} />
+          ))}
+        </Section>
+      )}
+
+      <Section title="Releases">
+        <Row label="Changelog" value={insights.release_info.changelog_file ?? <Badge variant="gray">None</Badge>} />
+        {insights.release_info.latest_changelog_version && (
+          <Row
+            label="Latest Entry"
+            value={`${insights.release_info.latest_changelog_version}${insights.release_info.latest_changelog_date ? ` (${insights.release_info.latest_changelog_date})` : ''}`}
+          />
+        )}
+        {insights.release_info.latest_tag && <Row label="Latest Tag" value={insights.release_info.latest_tag} />}
+        {insights.release_info.status && (
+          <Row label="Status" value={<Badge variant={CHANGELOG_STATUS[insights.release_info.status].variant}>{CHANGELOG_STATUS[insights.release_info.status].label}</Badge>} />
+        )}
+        {insights.release_info.has_unreleased_section && <Row label="Unreleased" value={<Badge variant="blue">Pending changes</Badge>} />}
+      </Section>
+
+      <Section title="Developer Experience">
+        <Row
+          label="Commit Hooks"
+          value={
+            insights.hooks_info.tools.length > 0
+              ? insights.hooks_info.tools.join(', ')
+              : insights.hooks_info.native_hooks.length > 0
+                ? `Local only (${insights.hooks_info.native_hooks.join(', ')})`
+                : <Badge variant="gray">None</Badge>
+          }
+        />
+        {insights.hooks_info.commit_convention && (
+          <Row
+            label="Conventional Commits"
+            value={
+              <Badge variant={insights.hooks_info.commit_convention.conventional_percentage >= 80 ? 'green' : insights.hooks_info.commit_convention.conventional_percentage >= 40 ? 'blue' : 'gray'}>
+                {insights.hooks_info.commit_convention.conventional_percentage}% of {insights.hooks_info.commit_convention.sampled}
+              </Badge>
+            }
+          />
+        )}
+        {insights.hooks_info.commit_convention && insights.hooks_info.commit_convention.dominant_types.length > 0 && (
+          <Row label="Commit Types" value={insights.hooks_info.commit_convention.dominant_types.join(', ')} />
+        )}
+      </Section>
+
+      <Section title="Security">
+        <Row
+          label="Possible Secrets"
+          value={insights.security_finding_count > 0
+            ? <Badge variant="red">{insights.security_finding_count}</Badge>
+            : <Badge variant="green">None found</Badge>}
+        />
+      </Section>
     </div>
   );
 };
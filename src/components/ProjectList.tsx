@@ -1,13 +1,19 @@
 import React, { useState, useEffect, useMemo } from 'react';
-import { invoke } from '@tauri-apps/api/core';
-import { ProjectDirectory } from '../types';
-import { listProjectDirectories, loadFavoriteProjects, saveFavoriteProjects } from '../utils/api';
+import { listen } from '@tauri-apps/api/event';
+import { ProjectCountUpdate, ProjectDirectory, ProjectsBulkUpdate } from '../types';
+import { addIgnoredProject, listProjectDirectories, rescanProject, loadFavoriteProjects, saveFavoriteProjects } from '../utils/api';
 import Spinner from './ui/Spinner';
 import Alert from './ui/Alert';
 import EmptyState from './ui/EmptyState';
-import { Folder, GitBranch, FileText, Search, X, Star } from 'lucide-react';
+import {
+  Folder, GitBranch, FileText, Search, X, Star, EyeOff, RefreshCw,
+  Box, Cloud, Code2, Coffee, Cog, Droplet, Gem, Hexagon, Smartphone, Snowflake, LucideIcon,
+} from 'lucide-react';
 import SidebarListItem from './ui/SidebarListItem';
+import LanguageBar from './ui/LanguageBar';
+import Badge from './ui/Badge';
 import { basename } from '../utils/format';
+import { formatBytes } from '../utils/db-utils';
 
 interface ProjectListProps {
   rootPath: string;
@@ -15,6 +21,23 @@ interface ProjectListProps {
   onProjectSelect: (project: ProjectDirectory) => void;
 }
 
+// Ecosystem icons for `project_kind`; kinds without one show nothing
+const KIND_ICONS: Record<string, LucideIcon> = {
+  node: Hexagon,
+  deno: Hexagon,
+  bun: Hexagon,
+  rust: Cog,
+  java: Coffee,
+  ruby: Gem,
+  elixir: Droplet,
+  dotnet: Box,
+  dart: Smartphone,
+  terraform: Cloud,
+  nix: Snowflake,
+  python: Code2,
+  go: Code2,
+};
+
 const ProjectList: React.FC<ProjectListProps> = ({ rootPath, selectedProject, onProjectSelect }) => {
   const [projects, setProjects] = useState<ProjectDirectory[]>([]);
   const [favorites, setFavorites] = useState<Set<string>>(new Set());
@@ -44,20 +67,40 @@ const ProjectList: React.FC<ProjectListProps> = ({ rootPath, selectedProject, on
     initializeData();
   }, [rootPath]);
 
+  // Counts for new or stale projects arrive after the listing
+  useEffect(() => {
+    const unlistenPromise = listen<ProjectCountUpdate>('projects:count_updated', (event) => {
+      const { path, count, total_size_bytes } = event.payload;
+      setProjects(prevProjects =>
+        prevProjects.map(p =>
+          p.path === path ? { ...p, file_count: count, total_size_bytes, is_counting: false } : p
+        )
+      );
+    });
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  // Bulk actions report every changed path at once; reload a single time
+  useEffect(() => {
+    const unlistenPromise = listen<ProjectsBulkUpdate>('projects:bulk_updated', () => {
+      if (rootPath) {
+        loadProjects();
+      }
+    });
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [rootPath]);
+
   const loadProjects = async () => {
     setIsLoading(true);
     setError('');
     
     try {
-      const projectList = await listProjectDirectories(rootPath);
-      setProjects(projectList);
-      
-      // Asynchronously update file counts for projects that need it
-      projectList.forEach(async (project) => {
-        if (project.is_counting) {
-          updateFileCount(project.path);
-        }
-      });
+      const listing = await listProjectDirectories(rootPath);
+      setProjects(listing.projects);
     } catch (err) {
       setError(err as string);
     } finally {
@@ -101,20 +144,28 @@ const ProjectList: React.FC<ProjectListProps> = ({ rootPath, selectedProject, on
     }
   };
 
-  const updateFileCount = async (projectPath: string) => {
+  const ignoreProject = async (projectPath: string, event: React.MouseEvent) => {
+    event.stopPropagation();
     try {
-      const count = await invoke<number>('update_project_file_count', { projectPath });
-      
-      // Update the project in state with the new count
-      setProjects(prevProjects => 
-        prevProjects.map(p => 
-          p.path === projectPath 
-            ? { ...p, file_count: count, is_counting: false }
-            : p
-        )
+      await addIgnoredProject(projectPath);
+      // Members of an ignored root are ignored with it
+      setProjects(prevProjects =>
+        prevProjects.filter(p => p.path !== projectPath && p.parent_path !== projectPath)
       );
     } catch (err) {
-      console.error(`Failed to update file count for ${projectPath}:`, err);
+      console.error(`Failed to ignore ${projectPath}:`, err);
+    }
+  };
+
+  const rescan = async (projectPath: string, event: React.MouseEvent) => {
+    event.stopPropagation();
+    try {
+      const refreshed = await rescanProject(projectPath);
+      setProjects(prevProjects =>
+        prevProjects.map(p => (p.path === projectPath ? { ...refreshed, children: p.children, display_path: p.display_path } : p))
+      );
+    } catch (err) {
+      console.error(`Failed to rescan ${projectPath}:`, err);
     }
   };
 
@@ -140,7 +191,19 @@ const ProjectList: React.FC<ProjectListProps> = ({ rootPath, selectedProject, on
     const nonFavoriteProjects = filtered.filter(p => !favorites.has(p.path))
       .sort((a, b) => a.name.toLowerCase().localeCompare(b.name.toLowerCase()));
     
-    return [...favoriteProjects, ...nonFavoriteProjects];
+    // Workspace members follow their root; a member whose root was filtered
+    // out is shown on its own
+    const visible = new Set(filtered.map(p => p.path));
+    const isRoot = (p: ProjectDirectory) => !p.parent_path || !visible.has(p.parent_path);
+    const byName = (a: ProjectDirectory, b: ProjectDirectory) =>
+      a.name.toLowerCase().localeCompare(b.name.toLowerCase());
+
+    return [...favoriteProjects, ...nonFavoriteProjects]
+      .filter(isRoot)
+      .flatMap(root => [
+        root,
+        ...filtered.filter(p => p.parent_path === root.path).sort(byName),
+      ]);
   }, [projects, favorites, searchQuery]);
 
   const clearSearch = () => {
@@ -239,9 +302,11 @@ const ProjectList: React.FC<ProjectListProps> = ({ rootPath, selectedProject, on
           <div className="divide-y divide-border">
             {sortedAndFilteredProjects.map((project, index) => {
               const isFavorite = favorites.has(project.path);
+              // Members sit in their root's group, so the divider follows the root
+              const groupOf = (p: ProjectDirectory) => p.parent_path ?? p.path;
               const isFirstNonFavorite = index > 0 && 
-                favorites.has(sortedAndFilteredProjects[index - 1].path) && 
-                !isFavorite;
+                favorites.has(groupOf(sortedAndFilteredProjects[index - 1])) && 
+                !favorites.has(groupOf(project));
               
               return (
                 <React.Fragment key={project.path}>
@@ -252,24 +317,52 @@ const ProjectList: React.FC<ProjectListProps> = ({ rootPath, selectedProject, on
                       </span>
                     </div>
                   )}
-                  <div className="relative group">
+                  <div
+                    className="relative group"
+                    title={project.display_path !== project.path ? `${project.display_path} → ${project.path}` : project.path}
+                  >
                     <SidebarListItem
                       selected={selectedProject === project.path}
                       onClick={() => onProjectSelect(project)}
-                      title={project.name}
+                      title={project.parent_path ? `↳ ${project.name}` : project.name}
                       subtitle={project.description}
                       left={
                         <div className="flex items-center gap-1">
                           {isFavorite && <Star className="h-3 w-3 text-yellow-500 fill-yellow-500" />}
                           {project.is_git_repo && <GitBranch className="h-3 w-3 text-foreground-tertiary" />}
+                          {project.project_kind && KIND_ICONS[project.project_kind] && (
+                            <span title={project.project_kind}>
+                              {React.createElement(KIND_ICONS[project.project_kind], {
+                                className: 'h-3 w-3 text-foreground-tertiary',
+                              })}
+                            </span>
+                          )}
                         </div>
                       }
                       meta={
-                        <span className="flex items-center">
-                          <FileText className="h-3 w-3 mr-1" />
-                          {project.file_count} files
-                          {project.is_counting && (
-                            <span className="ml-1 text-primary animate-pulse">(counting...)</span>
+                        <span className="flex flex-col gap-1">
+                          <span className="flex items-center">
+                            <FileText className="h-3 w-3 mr-1" />
+                            {project.file_count} files
+                            {project.total_size_bytes > 0 && ` · ${formatBytes(project.total_size_bytes)}`}
+                            {project.is_counting && (
+                              <span className="ml-1 text-primary animate-pulse">(counting...)</span>
+                            )}
+                            {project.is_stale && project.last_activity_at && (
+                              <span
+                                className="ml-2 text-foreground-tertiary"
+                                title={`Last activity ${new Date(project.last_activity_at).toLocaleDateString()}`}
+                              >
+                                · stale
+                              </span>
+                            )}
+                          </span>
+                          {project.display_path !== project.path && (
+                            <span className="truncate text-foreground-tertiary">via {project.display_path}</span>
+                          )}
+                          {project.primary_language && <Badge>{project.primary_language}</Badge>}
+                          {project.language_breakdown && (
+                            <LanguageBar languages={project.language_breakdown} className="w-32" />
                           )}
                         </span>
                       }
@@ -285,6 +378,20 @@ const ProjectList: React.FC<ProjectListProps> = ({ rootPath, selectedProject, on
                     >
                       <Star className={`h-4 w-4 ${isFavorite ? 'fill-current' : ''}`} />
                     </button>
+                    <button
+                      onClick={(e) => ignoreProject(project.path, e)}
+                      className="absolute right-12 top-1/2 transform -translate-y-1/2 p-1.5 rounded-md transition-all text-foreground-tertiary opacity-0 group-hover:opacity-100 hover:text-foreground hover:bg-background-tertiary"
+                      title="Hide from project list"
+                    >
+                      <EyeOff className="h-4 w-4" />
+                    </button>
+                    <button
+                      onClick={(e) => rescan(project.path, e)}
+                      className="absolute right-20 top-1/2 transform -translate-y-1/2 p-1.5 rounded-md transition-all text-foreground-tertiary opacity-0 group-hover:opacity-100 hover:text-foreground hover:bg-background-tertiary"
+                      title="Rescan project"
+                    >
+                      <RefreshCw className="h-4 w-4" />
+                    </button>
                   </div>
                 </React.Fragment>
               );
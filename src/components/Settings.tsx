@@ -1,16 +1,30 @@
 import React, { useState, useEffect } from 'react';
-import { Settings as SettingsType, ModelInfo } from '../types';
+import { Settings as SettingsType, FileFilterSettings, ModelInfo, ProviderPreset, LocalServer, PerformanceInfo, PerformanceSettings, ThemeSettings, LogLevel, AppError } from '../types';
 import { saveSettings } from '../utils/storage';
-import { loadModels } from '../utils/api';
+import { loadModels, refreshModels, getProviderPresets, detectLocalServers, resetWindowState, getPerformanceInfo, savePerformanceSettings, listIgnoredProjects, addIgnoredProject, removeIgnoredProject, getStaleAfterMonths, saveStaleAfterMonths, getIgnorePatterns, saveIgnorePatterns, getHiddenAllowlist, saveHiddenAllowlist, getFileFilterSettings, saveFileFilterSettings, getLogLevel, setLogLevel, getRecentLogs, openLogDirectory, getDiagnosticsText, getRecentErrors, clearRecentErrors, getBackgroundPaused, setBackgroundPaused, getMinimizeToTray, setMinimizeToTray, reanalyzeFavorites } from '../utils/api';
 import { 
   openAppDataDirectory, 
   getDatabaseStats, 
   vacuumDatabase, 
   clearExpiredCache, 
   optimizeDatabase,
+  runMaintenance,
+  getBackupSchedule,
+  saveBackupSchedule,
+  backupDatabase,
+  listBackups,
+  restoreBackup,
+  exportAppData,
+  importAppData,
   formatBytes,
-  DatabaseStats 
+  DatabaseStats,
+  MaintenanceProgress,
+  BackupSchedule,
+  BackupInfo,
+  TransferProgress
 } from '../utils/db-utils';
+import { open as openDialog, save as saveDialog } from '@tauri-apps/plugin-dialog';
+import { listen } from '@tauri-apps/api/event';
 import Button from './ui/Button';
 import TextField from './ui/TextField';
 import Select from './ui/Select';
@@ -20,7 +34,18 @@ import Card from './ui/Card';
 import StatTile from './ui/StatTile';
 import { isThinkingModel } from '../utils/models';
 import { useToast } from './ui/ToastProvider';
-import { Database, HardDrive, Zap, Trash2, FolderOpen, RefreshCw } from 'lucide-react';
+import { useTheme } from '../contexts/ThemeContext';
+import { Database, HardDrive, Zap, Trash2, FolderOpen, RefreshCw, Archive, Cpu, Palette, FileText, Moon, AlertTriangle } from 'lucide-react';
+
+// Mirrors redact::is_local_api_url on the backend
+const isLocalApiUrl = (apiUrl: string): boolean => {
+  try {
+    const host = new URL(apiUrl).hostname.replace(/^\[|\]$/g, '').toLowerCase();
+    return host === 'localhost' || host === '0.0.0.0' || host === '::1' || host.startsWith('127.');
+  } catch {
+    return false;
+  }
+};
 
 interface SettingsProps {
   settings: SettingsType;
@@ -36,12 +61,165 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
   const [dbStats, setDbStats] = useState<DatabaseStats | null>(null);
   const [isLoadingStats, setIsLoadingStats] = useState(false);
   const [isPerformingMaintenance, setIsPerformingMaintenance] = useState(false);
+  const [maintenanceStep, setMaintenanceStep] = useState<string | null>(null);
+  const [presets, setPresets] = useState<ProviderPreset[]>([]);
+  const [localServers, setLocalServers] = useState<LocalServer[]>([]);
+  const [backupSchedule, setBackupSchedule] = useState<BackupSchedule>({ frequency: 'off', retention: 7 });
+  const [backups, setBackups] = useState<BackupInfo[]>([]);
+  const [performanceInfo, setPerformanceInfo] = useState<PerformanceInfo | null>(null);
+  const [logLevel, setLogLevelState] = useState<LogLevel>('info');
+  const [backgroundPaused, setBackgroundPausedState] = useState(false);
+  const [minimizeToTray, setMinimizeToTrayState] = useState(false);
+  const [recentErrors, setRecentErrors] = useState<AppError[]>([]);
+  const [transferStatus, setTransferStatus] = useState<string | null>(null);
+  const [ignoredProjects, setIgnoredProjects] = useState<string[]>([]);
+  const [newIgnorePattern, setNewIgnorePattern] = useState('');
+  const [staleAfterMonths, setStaleAfterMonths] = useState(6);
+  const [filePatterns, setFilePatterns] = useState<string[]>([]);
+  const [newFilePattern, setNewFilePattern] = useState('');
+  const [hiddenAllowlist, setHiddenAllowlist] = useState<string[]>([]);
+  const [newHiddenPattern, setNewHiddenPattern] = useState('');
+  const [fileFilter, setFileFilter] = useState<FileFilterSettings>({
+    extra_ignored_extensions: [],
+    extra_ignored_dirs: [],
+    allowed_extensions: [],
+  });
   const { toast } = useToast();
+  const { themePreference, themeSettings, saveThemeSettings } = useTheme();
 
   useEffect(() => {
     loadDatabaseStats();
+    loadBackups();
+    getPerformanceInfo().then(setPerformanceInfo).catch((error) => {
+      console.error('Error loading performance info:', error);
+    });
+    getLogLevel().then(setLogLevelState).catch((error) => {
+      console.error('Error loading log level:', error);
+    });
+    getBackgroundPaused().then(setBackgroundPausedState).catch((error) => {
+      console.error('Error loading background pause state:', error);
+    });
+    getMinimizeToTray().then(setMinimizeToTrayState).catch((error) => {
+      console.error('Error loading minimize-to-tray setting:', error);
+    });
+    loadRecentErrors();
+    listIgnoredProjects().then(setIgnoredProjects).catch((error) => {
+      console.error('Error loading ignored projects:', error);
+    });
+    getStaleAfterMonths().then(setStaleAfterMonths).catch((error) => {
+      console.error('Error loading stale threshold:', error);
+    });
+    getIgnorePatterns().then(setFilePatterns).catch((error) => {
+      console.error('Error loading file ignore patterns:', error);
+    });
+    getHiddenAllowlist().then(setHiddenAllowlist).catch((error) => {
+      console.error('Error loading hidden allowlist:', error);
+    });
+    getFileFilterSettings().then(setFileFilter).catch((error) => {
+      console.error('Error loading file filter settings:', error);
+    });
+    getBackupSchedule().then(setBackupSchedule).catch((error) => {
+      console.error('Error loading backup schedule:', error);
+    });
+    // Scheduled backups run in the background; refresh the list when one lands
+    const unlistenPromise = listen<BackupInfo>('backup:completed', () => {
+      loadBackups();
+    });
+    getProviderPresets().then(setPresets).catch((error) => {
+      console.error('Error loading provider presets:', error);
+    });
+    // Detection runs in the background and never blocks the form
+    detectLocalServers().then(setLocalServers).catch((error) => {
+      console.error('Error detecting local servers:', error);
+    });
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
   }, []);
 
+  const handleAddIgnorePattern = async () => {
+    const pattern = newIgnorePattern.trim();
+    if (!pattern) return;
+    try {
+      await addIgnoredProject(pattern);
+      setIgnoredProjects(await listIgnoredProjects());
+      setNewIgnorePattern('');
+    } catch (error) {
+      toast({ title: 'Failed to add ignore pattern', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleStaleAfterMonthsChange = async (months: number) => {
+    setStaleAfterMonths(months);
+    try {
+      await saveStaleAfterMonths(months);
+    } catch (error) {
+      toast({ title: 'Failed to save stale threshold', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleRemoveIgnorePattern = async (pattern: string) => {
+    try {
+      await removeIgnoredProject(pattern);
+      setIgnoredProjects((prev) => prev.filter((p) => p !== pattern));
+    } catch (error) {
+      toast({ title: 'Failed to remove ignore pattern', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleSaveFilePatterns = async (patterns: string[]) => {
+    try {
+      await saveIgnorePatterns(patterns);
+      setFilePatterns(patterns);
+      setNewFilePattern('');
+    } catch (error) {
+      toast({ title: 'Failed to save file patterns', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleSaveHiddenAllowlist = async (patterns: string[]) => {
+    try {
+      await saveHiddenAllowlist(patterns);
+      setHiddenAllowlist(patterns);
+      setNewHiddenPattern('');
+    } catch (error) {
+      toast({ title: 'Failed to save hidden file patterns', description: String(error), variant: 'error' });
+    }
+  };
+
+  // Lists are edited as comma-separated text and saved when the field loses focus
+  const handleFileFilterChange = (name: keyof FileFilterSettings, value: string) => {
+    setFileFilter((prev) => ({ ...prev, [name]: value.split(',').map((v) => v.trim()) }));
+  };
+
+  const handleSaveFileFilter = async () => {
+    const cleaned: FileFilterSettings = {
+      extra_ignored_extensions: fileFilter.extra_ignored_extensions.filter(Boolean),
+      extra_ignored_dirs: fileFilter.extra_ignored_dirs.filter(Boolean),
+      allowed_extensions: fileFilter.allowed_extensions.filter(Boolean),
+    };
+    try {
+      await saveFileFilterSettings(cleaned);
+      setFileFilter(cleaned);
+    } catch (error) {
+      toast({ title: 'Failed to save file filter', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleApplyPreset = (preset: ProviderPreset) => {
+    const server = localServers.find((s) => s.preset_id === preset.id && s.running);
+    const model = preset.settings.model || server?.models[0]?.id || formData.model;
+    setFormData({
+      ...preset.settings,
+      model,
+      api_key: preset.requires_api_key ? formData.api_key : '',
+    });
+    if (server && server.models.length > 0) {
+      setModels(server.models);
+      setModelsLoaded(true);
+    }
+  };
+
   const loadDatabaseStats = async () => {
     setIsLoadingStats(true);
     try {
@@ -64,7 +242,9 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
       toast({ title: 'Settings saved', variant: 'success' });
     } catch (error) {
       console.error('Error saving settings:', error);
-      toast({ title: 'Failed to save settings', description: String(error), variant: 'error' });
+      // Validation failures arrive as a list of messages
+      const description = Array.isArray(error) ? error.join('\n') : String(error);
+      toast({ title: 'Failed to save settings', description, variant: 'error' });
     } finally {
       setIsSaving(false);
     }
@@ -78,13 +258,15 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
     'temperature_summary',
     'presence_penalty_summary',
     'max_tokens_summary',
+    'ai_requests_per_minute',
+    'ai_max_concurrent_requests',
   ]);
 
   const handleChange = (e: React.ChangeEvent<HTMLInputElement | HTMLSelectElement>) => {
     const { name, value, type } = e.target;
     let newValue: any = value;
     if (numberFields.has(name)) {
-      newValue = type === 'number' ? (name.includes('tokens') ? parseInt(value || '0', 10) : parseFloat(value || '0')) : value;
+      newValue = type === 'number' ? (name.includes('tokens') || name.startsWith('ai_') ? parseInt(value || '0', 10) : parseFloat(value || '0')) : value;
       if (Number.isNaN(newValue)) newValue = 0;
     }
     setFormData({
@@ -101,7 +283,9 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
 
     setIsLoadingModels(true);
     try {
-      const availableModels = await loadModels(formData.api_url, formData.api_key);
+      // A second click means the user wants a fresh list, not the cached one
+      const load = modelsLoaded ? refreshModels : loadModels;
+      const availableModels = await load(formData.api_url, formData.api_key);
       setModels(availableModels);
       setModelsLoaded(true);
       toast({ title: `Loaded ${availableModels.length} models`, variant: 'success' });
@@ -123,6 +307,137 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
     }
   };
 
+  const handleResetWindowState = async () => {
+    try {
+      await resetWindowState();
+      toast({ title: 'Window layout reset', variant: 'success' });
+    } catch (error) {
+      console.error('Error resetting window state:', error);
+      toast({ title: 'Failed to reset window layout', variant: 'error' });
+    }
+  };
+
+  const handleThemeSettingsChange = async (changes: Partial<ThemeSettings>) => {
+    try {
+      await saveThemeSettings({ ...themeSettings, mode: themePreference, ...changes });
+    } catch (error) {
+      console.error('Error saving theme settings:', error);
+      toast({ title: 'Failed to save appearance', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handlePerformanceChange = (name: keyof PerformanceSettings, value: string) => {
+    if (!performanceInfo) return;
+    setPerformanceInfo({
+      ...performanceInfo,
+      settings: { ...performanceInfo.settings, [name]: parseInt(value || '0', 10) || 0 },
+    });
+  };
+
+  const handleSavePerformance = async () => {
+    if (!performanceInfo) return;
+    try {
+      // The backend clamps out-of-range values; show what was actually applied
+      const info = await savePerformanceSettings(performanceInfo.settings);
+      setPerformanceInfo(info);
+      toast({
+        title: 'Performance settings saved',
+        description: info.restart_required ? 'Restart RepoMuse to apply the new thread pool size' : undefined,
+        variant: 'success',
+      });
+    } catch (error) {
+      console.error('Error saving performance settings:', error);
+      toast({ title: 'Failed to save performance settings', description: String(error), variant: 'error' });
+    }
+  };
+
+  // The tray can pause too; follow it while this page is open
+  useEffect(() => {
+    const unlisten = listen<boolean>('background:paused_changed', (event) => setBackgroundPausedState(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleBackgroundPausedChange = async (paused: boolean) => {
+    try {
+      setBackgroundPausedState(await setBackgroundPaused(paused));
+    } catch (error) {
+      toast({ title: 'Failed to change background work', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleMinimizeToTrayChange = async (enabled: boolean) => {
+    try {
+      setMinimizeToTrayState(await setMinimizeToTray(enabled));
+    } catch (error) {
+      toast({ title: 'Failed to save tray setting', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleReanalyzeFavorites = async () => {
+    try {
+      const count = await reanalyzeFavorites();
+      toast({ title: 'Re-analyzing favorites', description: `${count} project${count === 1 ? '' : 's'} queued`, variant: 'success' });
+    } catch (error) {
+      toast({ title: 'Failed to queue favorites', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleLogLevelChange = async (level: LogLevel) => {
+    try {
+      setLogLevelState(await setLogLevel(level));
+    } catch (error) {
+      console.error('Error saving log level:', error);
+      toast({ title: 'Failed to change log level', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleOpenLogDirectory = async () => {
+    try {
+      await openLogDirectory();
+    } catch (error) {
+      toast({ title: 'Failed to open log folder', description: String(error), variant: 'error' });
+    }
+  };
+
+  // For pasting into bug reports
+  const handleCopyRecentLogs = async () => {
+    try {
+      const lines = await getRecentLogs(500);
+      await navigator.clipboard.writeText(lines.join('\n'));
+      toast({ title: 'Recent logs copied', description: `${lines.length} lines`, variant: 'success' });
+    } catch (error) {
+      toast({ title: 'Failed to copy logs', description: String(error), variant: 'error' });
+    }
+  };
+
+  const loadRecentErrors = async () => {
+    try {
+      setRecentErrors(await getRecentErrors(50));
+    } catch (error) {
+      console.error('Error loading recent problems:', error);
+    }
+  };
+
+  const handleClearRecentErrors = async () => {
+    try {
+      await clearRecentErrors();
+      setRecentErrors([]);
+    } catch (error) {
+      toast({ title: 'Failed to clear problems', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleCopyDiagnostics = async () => {
+    try {
+      await navigator.clipboard.writeText(await getDiagnosticsText());
+      toast({ title: 'Diagnostics copied', variant: 'success' });
+    } catch (error) {
+      toast({ title: 'Failed to collect diagnostics', description: String(error), variant: 'error' });
+    }
+  };
+
   const handleVacuumDatabase = async () => {
     setIsPerformingMaintenance(true);
     try {
@@ -165,6 +480,123 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
     }
   };
 
+  const loadBackups = async () => {
+    try {
+      setBackups(await listBackups());
+    } catch (error) {
+      console.error('Error loading backups:', error);
+    }
+  };
+
+  const handleBackupScheduleChange = async (schedule: BackupSchedule) => {
+    setBackupSchedule(schedule);
+    try {
+      await saveBackupSchedule(schedule);
+    } catch (error) {
+      console.error('Error saving backup schedule:', error);
+      toast({ title: 'Failed to save backup schedule', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleBackupNow = async () => {
+    setIsPerformingMaintenance(true);
+    try {
+      const backup = await backupDatabase();
+      toast({ title: 'Backup created', description: `${backup.filename} (${formatBytes(backup.size_bytes)})`, variant: 'success' });
+      await loadBackups();
+    } catch (error) {
+      console.error('Error creating backup:', error);
+      toast({ title: 'Backup failed', description: String(error), variant: 'error' });
+    } finally {
+      setIsPerformingMaintenance(false);
+    }
+  };
+
+  const handleRestoreBackup = async (filename: string) => {
+    if (!confirm(`Restore ${filename}? This replaces all current data.`)) return;
+    setIsPerformingMaintenance(true);
+    try {
+      await restoreBackup(filename);
+      toast({ title: 'Backup restored', description: filename, variant: 'success' });
+      await loadDatabaseStats();
+    } catch (error) {
+      console.error('Error restoring backup:', error);
+      toast({ title: 'Restore failed', description: String(error), variant: 'error' });
+    } finally {
+      setIsPerformingMaintenance(false);
+    }
+  };
+
+  // Export and import report `app_data:progress` while they run
+  const withTransferProgress = async <T,>(run: () => Promise<T>): Promise<T> => {
+    setIsPerformingMaintenance(true);
+    const unlisten = await listen<TransferProgress>('app_data:progress', (event) => {
+      const { step, bytes_done, bytes_total } = event.payload;
+      setTransferStatus(`${step}: ${formatBytes(bytes_done)} of ${formatBytes(bytes_total)}`);
+    });
+    try {
+      return await run();
+    } finally {
+      unlisten();
+      setTransferStatus(null);
+      setIsPerformingMaintenance(false);
+    }
+  };
+
+  const handleExportAppData = async () => {
+    const target = await saveDialog({
+      defaultPath: `repomuse-data-${new Date().toISOString().slice(0, 10)}.zip`,
+      filters: [{ name: 'Zip archive', extensions: ['zip'] }],
+    });
+    if (!target) return;
+    const includeSecrets = confirm('Include your API key in the export? Choose Cancel to leave it out.');
+    try {
+      const report = await withTransferProgress(() => exportAppData(target, includeSecrets));
+      toast({ title: 'Data exported', description: `${report.path} (${formatBytes(report.size_bytes)})`, variant: 'success' });
+    } catch (error) {
+      toast({ title: 'Export failed', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleImportAppData = async () => {
+    const source = await openDialog({ multiple: false, filters: [{ name: 'Zip archive', extensions: ['zip'] }] });
+    if (!source || typeof source !== 'string') return;
+    if (!confirm('Import this bundle? All current data is replaced; a copy is kept in the app data folder.')) return;
+    try {
+      const report = await withTransferProgress(() => importAppData(source));
+      toast({ title: 'Data imported', description: `Previous data moved to ${report.previous_data_dir}`, variant: 'success' });
+      await loadDatabaseStats();
+      await loadBackups();
+    } catch (error) {
+      toast({ title: 'Import failed', description: String(error), variant: 'error' });
+    }
+  };
+
+  const handleRunMaintenance = async () => {
+    setIsPerformingMaintenance(true);
+    const unlisten = await listen<MaintenanceProgress>('maintenance:progress', (event) => {
+      const { step, index, total, status } = event.payload;
+      setMaintenanceStep(status === 'started' ? `${step.replace(/_/g, ' ')} (${index + 1}/${total})` : null);
+    });
+    try {
+      const report = await runMaintenance();
+      const seconds = (report.total_duration_ms / 1000).toFixed(1);
+      toast({
+        title: 'Maintenance complete',
+        description: `Reclaimed ${formatBytes(Math.max(report.reclaimed_bytes, 0))} in ${seconds}s`,
+        variant: 'success',
+      });
+      await loadDatabaseStats();
+    } catch (error) {
+      console.error('Error running maintenance:', error);
+      toast({ title: 'Maintenance failed', description: String(error), variant: 'error' });
+    } finally {
+      unlisten();
+      setMaintenanceStep(null);
+      setIsPerformingMaintenance(false);
+    }
+  };
+
   return (
     <div className="max-w-4xl mx-auto space-y-8">
       {/* API Settings */}
@@ -198,6 +630,51 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
               />
             </FormRow>
 
+            <div className="flex items-center space-x-2">
+              <input
+                type="checkbox"
+                id="redact_previews"
+                name="redact_previews"
+                checked={formData.redact_previews ?? !isLocalApiUrl(formData.api_url)}
+                onChange={(e) => setFormData({ ...formData, redact_previews: e.target.checked })}
+                className="h-4 w-4 rounded border-border text-primary focus:ring-primary"
+              />
+              <label htmlFor="redact_previews" className="text-sm text-foreground">
+                Redact secrets from file previews sent to the API
+              </label>
+            </div>
+
+            <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+              <FormRow>
+                <TextField
+                  label="Requests per Minute"
+                  type="number"
+                  step="1"
+                  min={0}
+                  id="ai_requests_per_minute"
+                  name="ai_requests_per_minute"
+                  value={formData.ai_requests_per_minute ?? 0}
+                  onChange={handleChange}
+                  placeholder="0"
+                  helpText="Pace AI requests to stay under provider rate limits; 0 for no limit"
+                />
+              </FormRow>
+              <FormRow>
+                <TextField
+                  label="Concurrent Requests"
+                  type="number"
+                  step="1"
+                  min={1}
+                  max={16}
+                  id="ai_max_concurrent_requests"
+                  name="ai_max_concurrent_requests"
+                  value={formData.ai_max_concurrent_requests ?? 2}
+                  onChange={handleChange}
+                  placeholder="2"
+                />
+              </FormRow>
+            </div>
+
             <FormRow>
               <div className="flex items-center justify-between mb-2">
                 <label htmlFor="model" className="block text-sm font-medium text-foreground">
@@ -211,7 +688,7 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
                   disabled={isLoadingModels || !formData.api_url}
                   loading={isLoadingModels}
                 >
-                  Load Models
+                  {modelsLoaded ? 'Refresh Models' : 'Load Models'}
                 </Button>
               </div>
 
@@ -434,11 +911,43 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
                   <div className="text-sm text-info/80 space-y-1">
                     <p>Total content indexed: {formatBytes(dbStats.total_size_bytes)}</p>
                     <p>Database file size: {formatBytes(dbStats.database_size_bytes)}</p>
+                    <p>Write-ahead log: {formatBytes(dbStats.wal_size_bytes + dbStats.shm_size_bytes)}</p>
+                    <p>Backups: {formatBytes(dbStats.backups_size_bytes)}</p>
+                    <p>Logs: {formatBytes(dbStats.logs_size_bytes)}</p>
+                    <p>Total disk usage: {formatBytes(dbStats.total_storage_bytes)}</p>
                     <p>Compression ratio: {((dbStats.total_size_bytes / Math.max(1, dbStats.database_size_bytes)) * 100).toFixed(1)}%</p>
                   </div>
                 </div>
               </div>
             </Card>
+
+            <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+              <Card className="p-4">
+                <h4 className="text-sm font-medium text-foreground-secondary mb-2">Rows per Table</h4>
+                <div className="text-sm text-foreground space-y-1">
+                  {dbStats.table_row_counts.map((t) => (
+                    <div key={t.table} className="flex justify-between">
+                      <span>{t.table}</span>
+                      <span>{t.rows.toLocaleString()}</span>
+                    </div>
+                  ))}
+                </div>
+              </Card>
+              <Card className="p-4">
+                <h4 className="text-sm font-medium text-foreground-secondary mb-2">Largest Projects</h4>
+                <div className="text-sm text-foreground space-y-1">
+                  {dbStats.largest_projects.length === 0 && (
+                    <p className="text-foreground-secondary">No stored project data yet</p>
+                  )}
+                  {dbStats.largest_projects.map((p) => (
+                    <div key={p.project_id} className="flex justify-between" title={p.path}>
+                      <span className="truncate mr-2">{p.name}</span>
+                      <span>{formatBytes(p.total_bytes)}</span>
+                    </div>
+                  ))}
+                </div>
+              </Card>
+            </div>
           </div>
         ) : (
           <div className="text-center py-8 text-foreground-secondary">
@@ -455,6 +964,25 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
         </div>
 
         <div className="space-y-4">
+          <div className="flex items-center justify-between p-4 bg-background-tertiary rounded-md">
+            <div>
+              <h4 className="font-medium text-foreground">Run All Maintenance</h4>
+              <p className="text-sm text-foreground-secondary mt-1">
+                {maintenanceStep
+                  ? `Running ${maintenanceStep}...`
+                  : 'Clear expired cache, optimize, and vacuum in one pass'}
+              </p>
+            </div>
+            <Button
+              variant="secondary"
+              onClick={handleRunMaintenance}
+              disabled={isPerformingMaintenance}
+            >
+              <RefreshCw className="h-4 w-4 mr-2" />
+              Run
+            </Button>
+          </div>
+
           <div className="flex items-center justify-between p-4 bg-background-tertiary rounded-md">
             <div>
               <h4 className="font-medium text-foreground">Clear Expired Cache</h4>
@@ -521,31 +1049,509 @@ const Settings: React.FC<SettingsProps> = ({ settings, onSettingsUpdated }) => {
               Open
             </Button>
           </div>
+
+          <div className="flex items-center justify-between p-4 bg-background-tertiary rounded-md">
+            <div>
+              <h4 className="font-medium text-foreground">Reset Window Layout</h4>
+              <p className="text-sm text-foreground-secondary mt-1">
+                Forget the saved window size and position and maximize the window
+              </p>
+            </div>
+            <Button
+              variant="secondary"
+              onClick={handleResetWindowState}
+            >
+              <RefreshCw className="h-4 w-4 mr-2" />
+              Reset
+            </Button>
+          </div>
         </div>
       </div>
 
-      {/* API Configuration Examples */}
-      <div className="bg-background-tertiary rounded-md p-6">
-        <h3 className="text-lg font-medium text-foreground mb-4">Popular API Configurations</h3>
-        <div className="space-y-3 text-sm text-foreground-secondary">
-          <div>
-            <strong className="text-foreground">Ollama (Local):</strong><br />
-            URL: http://localhost:11434/v1/chat/completions<br />
-            Models: llama2, codellama, deepseek-r1, etc.<br />
-            API Key: (leave empty)
+      {/* Appearance */}
+      <div className="bg-background-secondary rounded-lg shadow-md p-8 border border-border">
+        <div className="flex items-center space-x-2 mb-6">
+          <Palette className="h-5 w-5 text-foreground-secondary" />
+          <h2 className="text-lg font-semibold text-foreground">Appearance</h2>
+        </div>
+
+        <FormRow>
+          <div className="flex items-end space-x-2">
+            <TextField
+              label="Accent Color"
+              id="accent_color"
+              type="color"
+              value={themeSettings.accent_color || '#2563eb'}
+              onChange={(e) => handleThemeSettingsChange({ accent_color: e.target.value })}
+            />
+            {themeSettings.accent_color && (
+              <Button variant="ghost" size="sm" onClick={() => handleThemeSettingsChange({ accent_color: null })}>
+                Reset
+              </Button>
+            )}
+          </div>
+          <Select
+            label="Density"
+            id="density"
+            value={themeSettings.density}
+            onChange={(e) => handleThemeSettingsChange({ density: e.target.value as ThemeSettings['density'] })}
+          >
+            <option value="compact">Compact</option>
+            <option value="comfortable">Comfortable</option>
+            <option value="spacious">Spacious</option>
+          </Select>
+        </FormRow>
+
+        <div className="flex items-center space-x-2 mt-6">
+          <input
+            type="checkbox"
+            id="theme_schedule"
+            checked={Boolean(themeSettings.schedule)}
+            onChange={(e) =>
+              handleThemeSettingsChange({
+                schedule: e.target.checked ? { dark_start: '20:00', dark_end: '07:00' } : null,
+              })
+            }
+            className="h-4 w-4 rounded border-border text-primary focus:ring-primary"
+          />
+          <label htmlFor="theme_schedule" className="text-sm text-foreground">
+            Switch to dark mode on a schedule
+          </label>
+        </div>
+        {themeSettings.schedule && (
+          <FormRow>
+            <TextField
+              label="Dark From"
+              id="dark_start"
+              type="time"
+              value={themeSettings.schedule.dark_start}
+              onChange={(e) => handleThemeSettingsChange({ schedule: { ...themeSettings.schedule!, dark_start: e.target.value } })}
+            />
+            <TextField
+              label="Dark Until"
+              id="dark_end"
+              type="time"
+              value={themeSettings.schedule.dark_end}
+              onChange={(e) => handleThemeSettingsChange({ schedule: { ...themeSettings.schedule!, dark_end: e.target.value } })}
+            />
+          </FormRow>
+        )}
+      </div>
+
+      {/* Performance */}
+      {performanceInfo && (
+        <div className="bg-background-secondary rounded-lg shadow-md p-8 border border-border">
+          <div className="flex items-center justify-between mb-6">
+            <div className="flex items-center space-x-2">
+              <Cpu className="h-5 w-5 text-foreground-secondary" />
+              <h2 className="text-lg font-semibold text-foreground">Performance</h2>
+            </div>
+            <Button variant="secondary" onClick={handleSavePerformance}>
+              Save
+            </Button>
+          </div>
+
+          <FormRow>
+            <TextField
+              label="Analysis Threads"
+              id="rayon_threads"
+              type="number"
+              min="1"
+              value={performanceInfo.settings.rayon_threads}
+              onChange={(e) => handlePerformanceChange('rayon_threads', e.target.value)}
+              helpText={`${performanceInfo.cpu_count} CPUs detected · ${performanceInfo.active_rayon_threads} active${performanceInfo.restart_required ? ' · restart required' : ''}`}
+            />
+            <TextField
+              label="Walker Threads"
+              id="walker_threads"
+              type="number"
+              min="1"
+              value={performanceInfo.settings.walker_threads}
+              onChange={(e) => handlePerformanceChange('walker_threads', e.target.value)}
+            />
+          </FormRow>
+          <FormRow>
+            <TextField
+              label="Concurrent Batch Analyses"
+              id="max_concurrent_analyses"
+              type="number"
+              min="1"
+              value={performanceInfo.settings.max_concurrent_analyses}
+              onChange={(e) => handlePerformanceChange('max_concurrent_analyses', e.target.value)}
+            />
+            <TextField
+              label="Progress Interval (ms)"
+              id="progress_interval_ms"
+              type="number"
+              min="100"
+              step="100"
+              value={performanceInfo.settings.progress_interval_ms}
+              onChange={(e) => handlePerformanceChange('progress_interval_ms', e.target.value)}
+            />
+          </FormRow>
+        </div>
+      )}
+
+      {/* Background work */}
+      <div className="bg-background-secondary rounded-lg shadow-md p-8 border border-border">
+        <div className="flex items-center justify-between mb-6">
+          <div className="flex items-center space-x-2">
+            <Moon className="h-5 w-5 text-foreground-secondary" />
+            <h2 className="text-lg font-semibold text-foreground">Background Work</h2>
+          </div>
+          <Button variant="secondary" onClick={handleReanalyzeFavorites}>
+            <RefreshCw className="h-4 w-4 mr-2" />
+            Re-analyze Favorites Now
+          </Button>
+        </div>
+
+        <div className="space-y-3">
+          <div className="flex items-center space-x-2">
+            <input
+              type="checkbox"
+              id="background_paused"
+              checked={backgroundPaused}
+              onChange={(e) => handleBackgroundPausedChange(e.target.checked)}
+              className="h-4 w-4 rounded border-border text-primary focus:ring-primary"
+            />
+            <label htmlFor="background_paused" className="text-sm text-foreground">
+              Pause scheduled backups, watchers and batch analyses until resumed
+            </label>
+          </div>
+          <div className="flex items-center space-x-2">
+            <input
+              type="checkbox"
+              id="minimize_to_tray"
+              checked={minimizeToTray}
+              onChange={(e) => handleMinimizeToTrayChange(e.target.checked)}
+              className="h-4 w-4 rounded border-border text-primary focus:ring-primary"
+            />
+            <label htmlFor="minimize_to_tray" className="text-sm text-foreground">
+              Keep running in the system tray when the window is closed
+            </label>
+          </div>
+        </div>
+      </div>
+
+      {/* Problems */}
+      <div className="bg-background-secondary rounded-lg shadow-md p-8 border border-border">
+        <div className="flex items-center justify-between mb-6">
+          <div className="flex items-center space-x-2">
+            <AlertTriangle className="h-5 w-5 text-foreground-secondary" />
+            <h2 className="text-lg font-semibold text-foreground">Problems</h2>
+          </div>
+          <div className="flex space-x-2">
+            <Button variant="secondary" onClick={loadRecentErrors}>
+              <RefreshCw className="h-4 w-4 mr-2" />
+              Refresh
+            </Button>
+            <Button variant="secondary" onClick={handleClearRecentErrors} disabled={recentErrors.length === 0}>
+              Clear
+            </Button>
+          </div>
+        </div>
+
+        {recentErrors.length === 0 ? (
+          <p className="text-sm text-foreground-secondary">No problems this session</p>
+        ) : (
+          <div className="space-y-2 max-h-80 overflow-y-auto">
+            {recentErrors.map((error, index) => (
+              <div key={`${error.timestamp}-${index}`} className="p-3 bg-background-tertiary rounded-md">
+                <p className={`text-sm ${error.actionable ? 'text-error' : 'text-foreground'}`}>{error.message}</p>
+                <p className="text-xs text-foreground-secondary mt-1">
+                  {error.source_module}
+                  {error.context && ` · ${error.context}`}
+                  {` · ${new Date(error.timestamp).toLocaleString()}`}
+                </p>
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+
+      {/* Logging */}
+      <div className="bg-background-secondary rounded-lg shadow-md p-8 border border-border">
+        <div className="flex items-center justify-between mb-6">
+          <div className="flex items-center space-x-2">
+            <FileText className="h-5 w-5 text-foreground-secondary" />
+            <h2 className="text-lg font-semibold text-foreground">Logging</h2>
+          </div>
+          <div className="flex space-x-2">
+            <Button variant="secondary" onClick={handleCopyDiagnostics}>
+              Copy Diagnostics
+            </Button>
+            <Button variant="secondary" onClick={handleCopyRecentLogs}>
+              Copy Recent Logs
+            </Button>
+            <Button variant="secondary" onClick={handleOpenLogDirectory}>
+              <FolderOpen className="h-4 w-4 mr-2" />
+              Open Log Folder
+            </Button>
           </div>
-          <div>
-            <strong className="text-foreground">OpenAI:</strong><br />
-            URL: https://api.openai.com/v1/chat/completions<br />
-            Models: gpt-3.5-turbo, gpt-4, o1-mini, o1-preview<br />
-            API Key: Your OpenAI API key
+        </div>
+
+        <Select
+          label="Log Level"
+          id="log_level"
+          value={logLevel}
+          onChange={(e) => handleLogLevelChange(e.target.value as LogLevel)}
+        >
+          <option value="error">Error</option>
+          <option value="warn">Warning</option>
+          <option value="info">Info</option>
+          <option value="debug">Debug</option>
+          <option value="trace">Trace</option>
+          <option value="off">Off</option>
+        </Select>
+      </div>
+
+      {/* Backups */}
+      <div className="bg-background-secondary rounded-lg shadow-md p-8 border border-border">
+        <div className="flex items-center justify-between mb-6">
+          <div className="flex items-center space-x-2">
+            <Archive className="h-5 w-5 text-foreground-secondary" />
+            <h2 className="text-lg font-semibold text-foreground">Backups</h2>
+          </div>
+          <div className="flex space-x-2">
+            <Button variant="secondary" onClick={handleExportAppData} disabled={isPerformingMaintenance}>
+              Export All Data
+            </Button>
+            <Button variant="secondary" onClick={handleImportAppData} disabled={isPerformingMaintenance}>
+              Import Data
+            </Button>
+            <Button
+              variant="secondary"
+              onClick={handleBackupNow}
+              disabled={isPerformingMaintenance}
+            >
+              <Archive className="h-4 w-4 mr-2" />
+              Back Up Now
+            </Button>
+          </div>
+        </div>
+        {transferStatus && <p className="text-sm text-foreground-secondary mb-4">{transferStatus}</p>}
+
+        <FormRow>
+          <Select
+            label="Automatic Backups"
+            id="backup_frequency"
+            value={backupSchedule.frequency}
+            onChange={(e) => handleBackupScheduleChange({ ...backupSchedule, frequency: e.target.value as BackupSchedule['frequency'] })}
+          >
+            <option value="off">Off</option>
+            <option value="daily">Daily</option>
+            <option value="weekly">Weekly</option>
+          </Select>
+          <TextField
+            label="Backups to Keep"
+            id="backup_retention"
+            type="number"
+            min="1"
+            value={backupSchedule.retention}
+            onChange={(e) => handleBackupScheduleChange({ ...backupSchedule, retention: Math.max(1, parseInt(e.target.value || '1', 10)) })}
+          />
+        </FormRow>
+
+        <div className="space-y-2 mt-6">
+          {backups.length === 0 ? (
+            <p className="text-sm text-foreground-secondary">No backups yet</p>
+          ) : (
+            backups.map((backup) => (
+              <div key={backup.filename} className="flex items-center justify-between p-4 bg-background-tertiary rounded-md">
+                <div>
+                  <h4 className="font-medium text-foreground">{backup.filename}</h4>
+                  <p className="text-sm text-foreground-secondary mt-1">
+                    {formatBytes(backup.size_bytes)}
+                    {backup.created_at && ` · ${new Date(backup.created_at).toLocaleString()}`}
+                  </p>
+                </div>
+                <Button
+                  variant="secondary"
+                  onClick={() => handleRestoreBackup(backup.filename)}
+                  disabled={isPerformingMaintenance}
+                >
+                  Restore
+                </Button>
+              </div>
+            ))
+          )}
+        </div>
+      </div>
+
+      {/* Ignored Projects */}
+      <div className="bg-background-tertiary rounded-md p-6">
+        <h3 className="text-lg font-medium text-foreground mb-2">Ignored Projects</h3>
+        <p className="text-sm text-foreground-secondary mb-4">
+          Paths or glob patterns (such as <code>**/tmp-*</code>) left out of project lists
+        </p>
+        <div className="flex items-end gap-2 mb-4">
+          <TextField
+            label="Path or pattern"
+            id="ignore_pattern"
+            value={newIgnorePattern}
+            placeholder="/path/to/project or **/tmp-*"
+            onChange={(e) => setNewIgnorePattern(e.target.value)}
+          />
+          <Button variant="secondary" onClick={handleAddIgnorePattern} disabled={!newIgnorePattern.trim()}>
+            Add
+          </Button>
+        </div>
+        <div className="mb-4">
+          <TextField
+            label="Stale After (months)"
+            id="stale_after_months"
+            type="number"
+            min="1"
+            value={staleAfterMonths}
+            helpText="Projects with no commits or file changes for this long are marked stale"
+            onChange={(e) => handleStaleAfterMonthsChange(Math.max(1, parseInt(e.target.value || '1', 10)))}
+          />
+        </div>
+        {ignoredProjects.length === 0 ? (
+          <p className="text-sm text-foreground-secondary">Nothing ignored</p>
+        ) : (
+          <div className="space-y-2">
+            {ignoredProjects.map((pattern) => (
+              <div key={pattern} className="flex items-center justify-between text-sm">
+                <code className="text-foreground break-all">{pattern}</code>
+                <Button variant="secondary" size="sm" onClick={() => handleRemoveIgnorePattern(pattern)}>
+                  Remove
+                </Button>
+              </div>
+            ))}
           </div>
-          <div>
-            <strong className="text-foreground">DeepSeek:</strong><br />
-            URL: https://api.deepseek.com/v1/chat/completions<br />
-            Models: deepseek-chat, deepseek-r1 (thinking)<br />
-            API Key: Your DeepSeek API key
+        )}
+      </div>
+
+      {/* Ignored Files */}
+      <div className="bg-background-tertiary rounded-md p-6">
+        <h3 className="text-lg font-medium text-foreground mb-2">Ignored Files</h3>
+        <p className="text-sm text-foreground-secondary mb-4">
+          Gitignore-style patterns left out of analysis, file counts and insights in every project.
+          A <code>.repomuseignore</code> file at a project root adds patterns for that project only.
+        </p>
+        <div className="flex items-end gap-2 mb-4">
+          <TextField
+            label="Pattern"
+            id="file_ignore_pattern"
+            value={newFilePattern}
+            placeholder="**/fixtures/** or *.snap"
+            onChange={(e) => setNewFilePattern(e.target.value)}
+          />
+          <Button
+            variant="secondary"
+            onClick={() => handleSaveFilePatterns([...filePatterns, newFilePattern.trim()])}
+            disabled={!newFilePattern.trim()}
+          >
+            Add
+          </Button>
+        </div>
+        {filePatterns.length === 0 ? (
+          <p className="text-sm text-foreground-secondary mb-4">No extra patterns</p>
+        ) : (
+          <div className="space-y-2">
+            {filePatterns.map((pattern) => (
+              <div key={pattern} className="flex items-center justify-between text-sm">
+                <code className="text-foreground break-all">{pattern}</code>
+                <Button
+                  variant="secondary"
+                  size="sm"
+                  onClick={() => handleSaveFilePatterns(filePatterns.filter((p) => p !== pattern))}
+                >
+                  Remove
+                </Button>
+              </div>
+            ))}
           </div>
+        )}
+        <div className="grid grid-cols-1 md:grid-cols-3 gap-4 mt-6">
+          <TextField
+            label="Ignored Extensions"
+            id="extra_ignored_extensions"
+            value={fileFilter.extra_ignored_extensions.join(', ')}
+            placeholder="snap, lock"
+            onChange={(e) => handleFileFilterChange('extra_ignored_extensions', e.target.value)}
+            onBlur={handleSaveFileFilter}
+          />
+          <TextField
+            label="Ignored Folder Names"
+            id="extra_ignored_dirs"
+            value={fileFilter.extra_ignored_dirs.join(', ')}
+            placeholder="fixtures, generated"
+            onChange={(e) => handleFileFilterChange('extra_ignored_dirs', e.target.value)}
+            onBlur={handleSaveFileFilter}
+          />
+          <TextField
+            label="Always Analyze Extensions"
+            id="allowed_extensions"
+            value={fileFilter.allowed_extensions.join(', ')}
+            placeholder="svg"
+            helpText="Overrides the built-in binary list"
+            onChange={(e) => handleFileFilterChange('allowed_extensions', e.target.value)}
+            onBlur={handleSaveFileFilter}
+          />
+        </div>
+        <h4 className="text-sm font-medium text-foreground mt-6 mb-2">Included Hidden Files</h4>
+        <p className="text-sm text-foreground-secondary mb-4">
+          Dotfiles and folders are skipped except these. <code>.git</code> is always skipped.
+        </p>
+        <div className="flex items-end gap-2 mb-4">
+          <TextField
+            label="Pattern"
+            id="hidden_allow_pattern"
+            value={newHiddenPattern}
+            placeholder=".github/ or /.*rc"
+            onChange={(e) => setNewHiddenPattern(e.target.value)}
+          />
+          <Button
+            variant="secondary"
+            onClick={() => handleSaveHiddenAllowlist([...hiddenAllowlist, newHiddenPattern.trim()])}
+            disabled={!newHiddenPattern.trim()}
+          >
+            Add
+          </Button>
+        </div>
+        <div className="space-y-2">
+          {hiddenAllowlist.map((pattern) => (
+            <div key={pattern} className="flex items-center justify-between text-sm">
+              <code className="text-foreground break-all">{pattern}</code>
+              <Button
+                variant="secondary"
+                size="sm"
+                onClick={() => handleSaveHiddenAllowlist(hiddenAllowlist.filter((p) => p !== pattern))}
+              >
+                Remove
+              </Button>
+            </div>
+          ))}
+        </div>
+      </div>
+
+      {/* Provider Presets */}
+      <div className="bg-background-tertiary rounded-md p-6">
+        <h3 className="text-lg font-medium text-foreground mb-4">Provider Presets</h3>
+        <div className="space-y-3 text-sm text-foreground-secondary">
+          {presets.map((preset) => {
+            const server = localServers.find((s) => s.preset_id === preset.id);
+            return (
+              <div key={preset.id} className="flex items-center justify-between">
+                <div>
+                  <strong className="text-foreground">{preset.name}</strong>
+                  {preset.is_local && server?.running && (
+                    <span className="ml-2 text-success">
+                      Running · {server.models.length} model{server.models.length === 1 ? '' : 's'}
+                    </span>
+                  )}
+                  <br />
+                  URL: {preset.api_url}<br />
+                  API Key: {preset.requires_api_key ? 'Required' : '(leave empty)'}
+                </div>
+                <Button variant="secondary" size="sm" onClick={() => handleApplyPreset(preset)}>
+                  Use
+                </Button>
+              </div>
+            );
+          })}
         </div>
       </div>
     </div>
@@ -220,41 +220,3 @@ const TaskListComponent: React.FC<TaskListProps> = ({ projectPath }) => {
 
 export default TaskListComponent;
 
-// Export a function to add a task from outside the component
-export const createTaskFromIdea = async (projectPath: string, ideaText: string): Promise<{ success: boolean; isDuplicate?: boolean }> => {
-  try {
-    const existingTaskList = await loadTaskList(projectPath);
-    const tasks = existingTaskList?.tasks || [];
-    
-    // Check for duplicate task (case-insensitive comparison)
-    const normalizedIdeaText = ideaText.trim().toLowerCase();
-    const isDuplicate = tasks.some(task => 
-      task.text.toLowerCase() === normalizedIdeaText
-    );
-    
-    if (isDuplicate) {
-      return { success: false, isDuplicate: true };
-    }
-    
-    const newTask: Task = {
-      id: Math.random().toString(36).substring(2) + Date.now().toString(36),
-      text: ideaText.trim(),
-      completed: false,
-      created_at: new Date().toISOString(),
-    };
-    
-    const updatedTasks = [...tasks, newTask];
-    
-    const taskList: TaskListType = {
-      project_path: projectPath,
-      tasks: updatedTasks,
-      updated_at: new Date().toISOString(),
-    };
-    
-    await saveTaskList(taskList);
-    return { success: true };
-  } catch (error) {
-    console.error('Error adding task from idea:', error);
-    throw error;
-  }
-};
\ No newline at end of file
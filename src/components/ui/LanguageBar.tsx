@@ -0,0 +1,39 @@
+import React from 'react';
+import { LanguageShare } from '../../types';
+
+type LanguageBarProps = {
+  languages: LanguageShare[];
+  // Show a legend with percentages under the bar
+  showLegend?: boolean;
+  className?: string;
+};
+
+// Colors come from the backend so every surface renders the same palette
+const LanguageBar: React.FC<LanguageBarProps> = ({ languages, showLegend = false, className = '' }) => {
+  if (languages.length === 0) return null;
+  return (
+    <div className={className}>
+      <div className="flex h-2 w-full overflow-hidden rounded-full bg-background-tertiary">
+        {languages.map((lang) => (
+          <span
+            key={lang.language}
+            style={{ width: `${lang.percentage}%`, backgroundColor: lang.color }}
+            title={`${lang.language} ${lang.percentage}%`}
+          />
+        ))}
+      </div>
+      {showLegend && (
+        <div className="flex flex-wrap gap-x-3 gap-y-1 mt-2 text-xs text-foreground-secondary">
+          {languages.map((lang) => (
+            <span key={lang.language} className="flex items-center gap-1">
+              <span className="inline-block h-2 w-2 rounded-full" style={{ backgroundColor: lang.color }} />
+              {lang.language} {lang.percentage}%
+            </span>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default LanguageBar;
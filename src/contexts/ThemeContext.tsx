@@ -1,5 +1,7 @@
 import React, { createContext, useContext, useEffect, useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { SettingsChanged, ThemeSettings } from '../types';
 
 type Theme = 'light' | 'dark';
 type ThemePreference = 'light' | 'dark' | 'system';
@@ -10,6 +12,8 @@ interface ThemeContextType {
   toggleTheme: () => void;
   setTheme: (theme: Theme) => void;
   setThemePreference: (preference: ThemePreference) => void;
+  themeSettings: ThemeSettings;
+  saveThemeSettings: (settings: ThemeSettings) => Promise<void>;
 }
 
 const ThemeContext = createContext<ThemeContextType | undefined>(undefined);
@@ -26,6 +30,31 @@ interface ThemeProviderProps {
   children: React.ReactNode;
 }
 
+const DEFAULT_THEME_SETTINGS: ThemeSettings = { mode: 'system', density: 'comfortable' };
+
+// Mirrors ThemeSettings::scheduled_mode on the backend
+const getScheduledTheme = (settings: ThemeSettings): Theme | null => {
+  if (!settings.schedule) return null;
+  const toMinutes = (hhmm: string) => {
+    const [h, m] = hhmm.split(':').map(Number);
+    return h * 60 + m;
+  };
+  const now = new Date();
+  const current = now.getHours() * 60 + now.getMinutes();
+  const start = toMinutes(settings.schedule.dark_start);
+  const end = toMinutes(settings.schedule.dark_end);
+  const dark = start <= end ? current >= start && current < end : current >= start || current < end;
+  return dark ? 'dark' : 'light';
+};
+
+// "#2563eb" / "#26e" -> "37 99 235", the format the CSS color variables use
+const hexToRgbChannels = (hex: string): string | null => {
+  let digits = hex.replace('#', '');
+  if (digits.length === 3) digits = digits.split('').map((c) => c + c).join('');
+  if (!/^[0-9a-fA-F]{6}$/.test(digits)) return null;
+  return [0, 2, 4].map((i) => parseInt(digits.slice(i, i + 2), 16)).join(' ');
+};
+
 // Get system theme preference
 const getSystemTheme = (): Theme => {
   if (window.matchMedia && window.matchMedia('(prefers-color-scheme: dark)').matches) {
@@ -38,6 +67,52 @@ export const ThemeProvider: React.FC<ThemeProviderProps> = ({ children }) => {
   const [themePreference, setThemePreferenceState] = useState<ThemePreference>('system');
   const [theme, setThemeState] = useState<Theme>(() => getSystemTheme());
   const [isInitialized, setIsInitialized] = useState(false);
+  const [themeSettings, setThemeSettings] = useState<ThemeSettings>(DEFAULT_THEME_SETTINGS);
+
+  // Accent, density and schedule live in ThemeSettings; the mode still flows
+  // through themePreference below
+  useEffect(() => {
+    invoke<ThemeSettings>('load_theme_settings')
+      .then((settings) => {
+        setThemeSettings(settings);
+        const scheduled = getScheduledTheme(settings);
+        if (scheduled) setThemeState(scheduled);
+      })
+      .catch((error) => console.error('Error loading theme settings:', error));
+
+    const unlistenSwitch = listen<{ mode: Theme }>('theme:switch', (event) => {
+      setThemeState(event.payload.mode);
+    });
+    const unlistenChanged = listen<SettingsChanged>('settings:changed', (event) => {
+      if (event.payload.key === 'theme_settings') {
+        setThemeSettings(event.payload.value as ThemeSettings);
+      }
+    });
+    return () => {
+      unlistenSwitch.then((fn) => fn());
+      unlistenChanged.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const root = document.documentElement;
+    const channels = themeSettings.accent_color ? hexToRgbChannels(themeSettings.accent_color) : null;
+    if (channels) {
+      root.style.setProperty('--primary', channels);
+      root.style.setProperty('--primary-hover', channels);
+    } else {
+      root.style.removeProperty('--primary');
+      root.style.removeProperty('--primary-hover');
+    }
+    root.dataset.density = themeSettings.density;
+  }, [themeSettings]);
+
+  const saveThemeSettings = useCallback(async (settings: ThemeSettings) => {
+    await invoke('save_theme_settings', { settings });
+    setThemeSettings(settings);
+    const scheduled = getScheduledTheme(settings);
+    if (scheduled) setThemeState(scheduled);
+  }, []);
 
   // Load theme preference from Tauri storage on mount
   useEffect(() => {
@@ -85,6 +160,19 @@ export const ThemeProvider: React.FC<ThemeProviderProps> = ({ children }) => {
     loadThemePreference();
   }, []);
 
+  // Follow theme changes saved from other windows
+  useEffect(() => {
+    const unlisten = listen<SettingsChanged>('settings:changed', (event) => {
+      if (event.payload.key !== 'theme_preference') return;
+      const preference = event.payload.value as ThemePreference;
+      setThemePreferenceState(preference);
+      setThemeState(preference === 'system' ? getSystemTheme() : preference);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for system theme changes
   useEffect(() => {
     if (!window.matchMedia) return;
@@ -178,7 +266,9 @@ export const ThemeProvider: React.FC<ThemeProviderProps> = ({ children }) => {
       themePreference, 
       toggleTheme, 
       setTheme, 
-      setThemePreference 
+      setThemePreference,
+      themeSettings,
+      saveThemeSettings
     }}>
       {children}
     </ThemeContext.Provider>
@@ -12,6 +12,34 @@ export interface Settings {
   presence_penalty_summary: number;
   max_tokens_summary: number;
   use_stop_ideas: boolean;
+  // null/undefined: redact file previews unless the API URL is local
+  redact_previews?: boolean | null;
+  // Shared AI request queue limits; 0 requests per minute is no limit
+  ai_requests_per_minute?: number;
+  ai_max_concurrent_requests?: number;
+}
+
+export interface QueuedAIRequest {
+  id: number;
+  kind: 'ideas' | 'summary' | 'models' | string;
+  project_path: string | null;
+  // Running requests plus those waiting in front of this one
+  ahead: number;
+  eta_seconds: number;
+}
+
+// Payload of the `ai:queue_status` event
+export interface AIQueueStatus {
+  running: number;
+  waiting: QueuedAIRequest[];
+  max_per_minute: number;
+  max_concurrent: number;
+}
+
+// Payload of the `settings:changed` event; secrets such as api_key are omitted
+export interface SettingsChanged {
+  key: 'api_settings' | 'theme_preference' | 'root_folder' | string;
+  value: unknown;
 }
 
 export interface ModelInfo {
@@ -20,13 +48,35 @@ export interface ModelInfo {
   description?: string;
 }
 
+export interface ProviderPreset {
+  id: string;
+  name: string;
+  api_url: string;
+  requires_api_key: boolean;
+  is_local: boolean;
+  settings: Settings;
+}
+
+export interface LocalServer {
+  preset_id: string;
+  name: string;
+  api_url: string;
+  running: boolean;
+  models: ModelInfo[];
+}
+
 export interface FileInfo {
   path: string;
   content: string;
   language: string;
   size: number;
+  // "utf-8", "utf-16le", "utf-16be" or "latin-1"; missing on older analyses
+  encoding?: string;
+  role?: FileRole;
 }
 
+export type FileRole = 'source' | 'config' | 'manifest' | 'lockfile' | 'ci' | 'docs' | 'test';
+
 export interface FileSizeInfo {
   path: string;
   size_bytes: number;
@@ -64,19 +114,349 @@ export interface RepoAnalysis {
   scan_progress?: ScanProgress;
 }
 
+// Cached analysis minus file contents, for dashboard cards
+export interface AnalysisMetricsSummary {
+  technologies: string[];
+  metrics: Record<string, number>;
+  size_metrics: SizeMetrics;
+  generated_at?: string | null;
+  from_cache?: boolean | null;
+  is_lazy_scan?: boolean | null;
+}
+
+export interface BatchResult {
+  analyses: RepoAnalysis[];
+  // [folder_path, error] pairs
+  errors: [string, string][];
+}
+
 export interface IdeaRequest {
   analysis: RepoAnalysis;
   settings: Settings;
   focus_area?: string;
+  project_path?: string;
 }
 
 export interface ProjectDirectory {
   name: string;
+  // Canonical path; pass this to commands
   path: string;
+  // As reached under the root folder, possibly through a symlink
+  display_path: string;
   is_git_repo: boolean;
   file_count: number;
+  // Bytes of analyzable files, measured with the file count
+  total_size_bytes: number;
   description?: string;
   is_counting: boolean;
+  last_opened_at?: string | null;
+  last_analyzed_at?: string | null;
+  is_favorite: boolean;
+  is_archived: boolean;
+  tags: string[];
+  // Newer of the last commit and newest file
+  last_activity_at?: string | null;
+  is_stale: boolean;
+  language_breakdown?: LanguageShare[] | null;
+  // Largest code language by bytes; percentage is 0-100
+  primary_language?: string | null;
+  primary_language_percentage?: number | null;
+  // Ecosystem the project was detected by, e.g. "rust", "terraform", or
+  // "git" for a repository with source files but no manifest
+  project_kind?: string | null;
+  workspace_kind?: string | null;
+  workspace_members: string[];
+  // Set on workspace members listed under their monorepo root
+  parent_path?: string | null;
+  children: ProjectDirectory[];
+}
+
+export type BulkAction =
+  | { type: 'favorite' }
+  | { type: 'unfavorite' }
+  | { type: 'archive' }
+  | { type: 'unarchive' }
+  | { type: 'add_tag'; tag: string }
+  | { type: 'remove_tag'; tag: string }
+  | { type: 'delete' }
+  | { type: 'queue_analysis' };
+
+export interface BulkOutcome {
+  path: string;
+  ok: boolean;
+  error?: string | null;
+}
+
+// Payload of `projects:bulk_updated`; `paths` are the ones that changed
+export interface ProjectsBulkUpdate {
+  action: BulkAction;
+  paths: string[];
+}
+
+// Payload of `projects:count_updated`
+export interface ProjectCountUpdate {
+  path: string;
+  count: number;
+  total_size_bytes: number;
+  // Entries the walk couldn't read; the count is low when nonzero
+  io_errors: number;
+}
+
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export interface DatabaseHealth {
+  path: string | null;
+  size_bytes: number;
+  wal_size_bytes: number;
+  user_version: number;
+  integrity_ok: boolean;
+}
+
+// A cache table: its row count and the bytes of its payload column
+export interface CacheStats {
+  name: string;
+  entries: number;
+  size_bytes: number;
+}
+
+export interface EndpointStatus {
+  host: string;
+  kind: string;
+  // null when the request got no response at all
+  status: number | null;
+  ok: boolean;
+  at: string;
+}
+
+// Next onboarding step to show; steps done out of order count once reached
+export type OnboardingState = 'not_started' | 'root_selected' | 'ai_configured' | 'first_analysis_done' | 'complete';
+
+export interface RootFolderSuggestion {
+  path: string;
+  exists: boolean;
+  // Immediate children with a .git folder
+  git_repos: number;
+  // More children than were checked; git_repos is a lower bound
+  truncated: boolean;
+}
+
+export type UsagePeriod = 'day' | 'week' | 'month';
+
+export interface UsageCounts {
+  analyses: number;
+  analysis_ms: number;
+  cache_hits: number;
+  ideas: number;
+  summaries: number;
+  // null when no request in range reported token usage
+  tokens: number | null;
+}
+
+export interface UsageStats {
+  period: UsagePeriod;
+  since: string;
+  totals: UsageCounts;
+  cache_hit_rate: number | null;
+  // Oldest first; `start` is the bucket's first day (YYYY-MM-DD)
+  buckets: (UsageCounts & { start: string })[];
+  busiest_projects: (UsageCounts & { project_path: string })[];
+}
+
+export interface SearchOptions {
+  case_sensitive?: boolean;
+  whole_word?: boolean;
+  // Empty or omitted searches every analyzed project
+  project_paths?: string[];
+  // Language names as analysis reports them, e.g. 'Rust'
+  languages?: string[];
+  limit?: number;
+  // Read one project's files from disk instead of the sampled contents
+  full_files?: boolean;
+}
+
+export interface SearchMatch {
+  project_path: string;
+  project_name: string;
+  path: string;
+  // null for matches in the file path
+  line: number | null;
+  // Snippet split around the match for highlighting
+  before: string;
+  matched: string;
+  after: string;
+  source: 'path' | 'sampled_content' | 'file';
+}
+
+export interface SearchResults {
+  matches: SearchMatch[];
+  truncated: boolean;
+  // When true, label results "sampled content only": only file prefixes were searched
+  sampled_only: boolean;
+  projects_searched: number;
+}
+
+// One technology or dependency and the projects that use it
+export interface TechnologyUsage {
+  name: string;
+  kind: 'technology' | 'dependency' | 'toolchain';
+  // null for technologies detected by analysis
+  ecosystem: 'npm' | 'cargo' | 'python' | null;
+  projects: {
+    project_path: string;
+    project_name: string;
+    // Declared requirements, one per distinct workspace member version
+    versions: string[];
+    dev_only: boolean;
+  }[];
+}
+
+export type IdeaStatus = 'new' | 'shortlisted' | 'in_progress' | 'done' | 'rejected';
+
+// A generated idea kept on the project's board
+export interface BoardIdea {
+  id: number;
+  text: string;
+  status: IdeaStatus;
+  // Order within the status column, from 0
+  position: number;
+  // Set once the idea was turned into a task
+  task_id: string | null;
+  created_at: string;
+  updated_at: string | null;
+  // Focus area of the generation that produced it
+  focus_area: string | null;
+}
+
+// A focus area worth trying next, highest score first
+export interface FocusSuggestion {
+  focus_area: string;
+  score: number;
+  reasons: string[];
+}
+
+export interface IdeaBoard {
+  project_path: string;
+  // Every status in workflow order, including empty ones
+  columns: { status: IdeaStatus; ideas: BoardIdea[] }[];
+}
+
+// A project re-analyzed in the background every `interval_hours`
+export interface AutoRefreshStatus {
+  id: number;
+  path: string;
+  name: string;
+  interval_hours: number;
+  last_analyzed_at: string | null;
+  // Consecutive failures; each doubles the wait before the next attempt
+  failures: number;
+  retry_at: string | null;
+  next_run_at: string;
+}
+
+// One background run on a project, such as an auto-refresh
+export interface ActivityEntry {
+  kind: string;
+  // For auto-refresh: skipped, revalidated (cache kept), rescanned or failed
+  outcome: string;
+  detail: string | null;
+  created_at: string;
+}
+
+// A background failure no command returned; newest first from getRecentErrors
+export interface AppError {
+  timestamp: string;
+  source_module: string;
+  message: string;
+  context: string | null;
+  actionable: boolean;
+}
+
+export interface Diagnostics {
+  app_version: string;
+  os: string;
+  arch: string;
+  // null when the database didn't answer in time; see `unavailable`
+  database: DatabaseHealth | null;
+  pool: { max_size: number; connections: number; idle_connections: number };
+  caches: CacheStats[];
+  running_analyses: number;
+  active_watchers: number;
+  rayon_threads: number;
+  walker_threads: number;
+  background_paused: boolean;
+  last_ai_response: EndpointStatus | null;
+  unavailable: string[];
+}
+
+export type FileChange =
+  | { kind: 'created'; path: string }
+  | { kind: 'modified'; path: string }
+  | { kind: 'removed'; path: string }
+  | { kind: 'renamed'; from: string; to: string };
+
+// Payload of `project:files_changed`, one debounced batch per event
+export interface ProjectFilesChanged {
+  path: string;
+  changes: FileChange[];
+}
+
+export interface LanguageShare {
+  language: string;
+  bytes: number;
+  percentage: number;
+  color: string;
+}
+
+export interface Project {
+  id: number;
+  path: string;
+  name: string;
+  description?: string | null;
+  is_git_repo: boolean;
+  is_favorite: boolean;
+  last_analyzed_at?: string | null;
+  file_count: number;
+  total_size_bytes: number;
+  created_at: string;
+  updated_at: string;
+  last_opened_at?: string | null;
+  opened_count: number;
+  language_breakdown?: LanguageShare[] | null;
+  parent_id?: number | null;
+  last_activity_at?: string | null;
+  primary_language?: string | null;
+  primary_language_percentage?: number | null;
+  project_kind?: string | null;
+  is_archived: boolean;
+  tags: string[];
+}
+
+export interface ProjectPage {
+  projects: Project[];
+  has_more: boolean;
+  // Pass as `cursor` to fetch the next page
+  next_cursor?: number | null;
+}
+
+export type ProjectSortBy = 'name' | 'file_count' | 'size' | 'last_analyzed' | 'last_opened';
+
+// Omitted fields keep the defaults: alphabetical, unfiltered, flat
+export interface ProjectListingOptions {
+  sort_by?: ProjectSortBy;
+  // Defaults to false for name and true (largest / most recent first) otherwise
+  descending?: boolean;
+  filter_text?: string;
+  only_git?: boolean;
+  only_favorites?: boolean;
+  nested?: boolean;
+  // Archived projects are left out unless this is set
+  include_archived?: boolean;
+}
+
+export interface ProjectListing {
+  projects: ProjectDirectory[];
+  // Entries before filtering
+  total_count: number;
 }
 
 export interface ProjectSummary {
@@ -85,6 +465,25 @@ export interface ProjectSummary {
   generated_at: string;
   technologies: string[];
   key_features: string[];
+  // State of the code when generated; older summaries have none
+  fingerprint?: SummaryFingerprint | null;
+}
+
+export interface SummaryFingerprint {
+  file_count: number;
+  total_lines: number;
+  technologies: string[];
+  primary_language: string | null;
+  head_commit: string | null;
+}
+
+export interface SummaryFreshness {
+  state: 'fresh' | 'stale' | 'unknown';
+  // 0 (unchanged) to 1 (rewritten)
+  score: number;
+  // e.g. "34 files added", biggest change first
+  drivers: string[];
+  summary_generated_at: string | null;
 }
 
 export interface SummaryRequest {
@@ -93,19 +492,71 @@ export interface SummaryRequest {
   project_path?: string;
 }
 
+export type RemotePlatform = 'github' | 'gitlab' | 'bitbucket' | 'gitea' | 'self-hosted';
+
 export interface GitRemote {
   name: string;
   url: string;
+  web_url?: string | null;
+  platform?: RemotePlatform | null;
+  owner?: string | null;
+  repo?: string | null;
 }
 
 export interface GitStatus {
   is_git_repo: boolean;
   has_uncommitted_changes: boolean;
   uncommitted_files: string[];
+  staged_files: string[];
+  unstaged_files: string[];
+  untracked_files: string[];
   current_branch?: string;
+  is_detached: boolean;
   last_commit_date?: string;
   commit_count?: number;
   remotes: GitRemote[];
+  latest_version?: string | null;
+  commits_since_latest_version?: number | null;
+  stash_count: number;
+  stashes: string[];
+  submodules: GitSubmodule[];
+  worktrees: GitWorktree[];
+}
+
+export interface GitSubmodule {
+  name: string;
+  path: string;
+  url?: string | null;
+  initialized: boolean;
+}
+
+export interface GitWorktree {
+  path: string;
+  branch?: string | null;
+  is_detached: boolean;
+}
+
+export interface GitTag {
+  name: string;
+  target: string;
+  date?: string | null;
+  is_annotated: boolean;
+}
+
+export interface GitTagPage {
+  tags: GitTag[];
+  total: number;
+}
+
+export interface ReadmeQuality {
+  sections: string[];
+  missing_sections: string[];
+  badge_count: number;
+  length: number;
+  has_code_blocks: boolean;
+  has_images: boolean;
+  score: number;
+  reasons: string[];
 }
 
 export interface ReadmeInfo {
@@ -113,12 +564,35 @@ export interface ReadmeInfo {
   is_default: boolean;
   path?: string;
   content_preview?: string;
+  description?: string | null;
+  quality?: ReadmeQuality | null;
+}
+
+export interface CIWorkflow {
+  file: string;
+  name?: string | null;
+  triggers: string[];
+  jobs: string[];
+  runs_tests: boolean;
+  runs_lint: boolean;
+  runs_build: boolean;
+  builds_release: boolean;
+  runs_security_scan: boolean;
+  matrix?: CIMatrix | null;
+  parsed: boolean;
+}
+
+export interface CIMatrix {
+  os_targets: string[];
+  language_versions: string[];
 }
 
 export interface CIInfo {
   has_ci: boolean;
   ci_platforms: string[];
   ci_files: string[];
+  workflows: CIWorkflow[];
+  ci_matrix?: CIMatrix | null;
 }
 
 export interface PackageInfo {
@@ -127,6 +601,7 @@ export interface PackageInfo {
   has_requirements_txt: boolean;
   has_gemfile: boolean;
   has_go_mod: boolean;
+  has_changelog: boolean;
   missing_common_files: string[];
 }
 
@@ -136,15 +611,171 @@ export interface TestingInfo {
   has_test_files: boolean;
   test_file_count: number;
   test_file_patterns: string[];
-  source_to_test_ratio?: number;
+  // Test files per source file; higher is better
+  test_to_source_ratio?: number;
+  rust_test_fn_count: number;
+  coverage_tools: string[];
+  has_benchmarks: boolean;
+  scan_truncated: boolean;
+  // Unreadable entries; counts are lower bounds when nonzero
+  walk_errors?: number;
 }
 
+export type InsightAspect = 'git' | 'readme' | 'ci' | 'packages' | 'testing' | 'security' | 'containers' | 'formatting' | 'hooks' | 'release' | 'community' | 'workspace';
+
 export interface ProjectInsights {
   git_status: GitStatus;
   readme_info: ReadmeInfo;
   ci_info: CIInfo;
   package_info: PackageInfo;
   testing_info: TestingInfo;
+  dependency_report: DependencyReport;
+  container_info: ContainerInfo;
+  formatter_info: FormatterInfo;
+  hooks_info: HooksInfo;
+  release_info: ReleaseInfo;
+  community_info: CommunityInfo;
+  workspace_info: WorkspaceInfo;
+  security_finding_count: number;
+  from_cache: boolean;
+  computed_at?: string | null;
+}
+
+export interface FormatterInfo {
+  has_prettier: boolean;
+  prettier_config?: string | null;
+  has_eslint: boolean;
+  eslint_config?: string | null;
+  has_rustfmt: boolean;
+  has_editorconfig: boolean;
+}
+
+export interface CodeOwnersRule {
+  pattern: string;
+  owners: string[];
+}
+
+export interface CommunityInfo {
+  contributing?: string | null;
+  code_of_conduct?: string | null;
+  security_policy?: string | null;
+  issue_templates: string[];
+  pull_request_template?: string | null;
+  funding?: string | null;
+  codeowners?: string | null;
+  codeowners_rules: CodeOwnersRule[];
+  missing_files: string[];
+}
+
+export interface WorkspaceMember {
+  name: string;
+  path: string;
+  version?: string | null;
+  ecosystem: 'npm' | 'cargo' | 'go';
+}
+
+export interface WorkspaceInfo {
+  kind?: 'npm' | 'yarn' | 'pnpm' | 'cargo' | 'go' | null;
+  tools: string[];
+  members: WorkspaceMember[];
+}
+
+export type ChangelogStatus = 'in_sync' | 'behind_tags' | 'ahead_of_tags' | 'tags_without_changelog';
+
+export interface ReleaseInfo {
+  changelog_file?: string | null;
+  has_changelog: boolean;
+  latest_changelog_version?: string | null;
+  latest_changelog_date?: string | null;
+  has_unreleased_section: boolean;
+  latest_tag?: string | null;
+  status?: ChangelogStatus | null;
+}
+
+export interface CommitConvention {
+  sampled: number;
+  conventional_percentage: number;
+  dominant_types: string[];
+}
+
+export interface HooksInfo {
+  tools: string[];
+  native_hooks: string[];
+  commit_convention?: CommitConvention | null;
+}
+
+export interface DockerfileInfo {
+  path: string;
+  base_images: string[];
+  stages: string[];
+  exposed_ports: string[];
+}
+
+export interface ComposeService {
+  name: string;
+  image?: string | null;
+  builds_locally: boolean;
+}
+
+export interface ComposeFile {
+  path: string;
+  services: ComposeService[];
+}
+
+export interface ContainerInfo {
+  dockerfiles: DockerfileInfo[];
+  compose_files: ComposeFile[];
+  has_dockerignore: boolean;
+  has_devcontainer: boolean;
+  has_database_service: boolean;
+}
+
+export interface EcosystemDependencies {
+  ecosystem: string;
+  manifest: string;
+  runtime_count: number;
+  dev_count: number;
+  workspace_members: string[];
+}
+
+export interface LockfileStatus {
+  file: string;
+  ecosystem: string;
+  older_than_manifest: boolean;
+}
+
+export interface DependencyReport {
+  ecosystems: EcosystemDependencies[];
+  lockfiles: LockfileStatus[];
+  missing_lockfiles: string[];
+  package_manager?: string | null;
+  node_version?: string | null;
+  python_version?: string | null;
+  rust_version?: string | null;
+}
+
+export type OutdatedSeverity = 'major' | 'minor' | 'patch';
+
+export interface OutdatedDependency {
+  name: string;
+  ecosystem: string;
+  current_req: string;
+  latest: string;
+  severity: OutdatedSeverity;
+}
+
+export interface SecurityFinding {
+  path: string;
+  line?: number | null;
+  rule_id: string;
+  excerpt: string;
+  fingerprint: string;
+}
+
+export interface SecurityScan {
+  findings: SecurityFinding[];
+  files_scanned: number;
+  suppressed: number;
 }
 
 export interface GitCommit {
@@ -152,6 +783,70 @@ export interface GitCommit {
   author: string;
   date: string;
   message: string;
+  url?: string | null;
+}
+
+export interface ContributorStats {
+  name: string;
+  email: string;
+  commits: number;
+  first_commit?: string | null;
+  last_commit?: string | null;
+  lines_added: number;
+  lines_removed: number;
+}
+
+export interface AuthorShare {
+  name: string;
+  email: string;
+  lines: number;
+  percentage: number;
+}
+
+export interface FileOwnership {
+  path: string;
+  lines: number;
+  owners: AuthorShare[];
+}
+
+export interface DirectoryOwnership {
+  directory: string;
+  lines: number;
+  owners: AuthorShare[];
+}
+
+export interface OwnershipSummary {
+  head: string;
+  sampled_files: string[];
+  skipped_files: string[];
+  files: FileOwnership[];
+  directories: DirectoryOwnership[];
+  authors: AuthorShare[];
+  // Fewest authors owning 80% of sampled lines
+  bus_factor: number;
+  total_lines: number;
+  from_cache: boolean;
+}
+
+export interface GitSummary {
+  is_git_repo: boolean;
+  current_branch?: string | null;
+  commit_count?: number | null;
+  last_commit_date?: string | null;
+  last_commit_message?: string | null;
+  remotes: GitRemote[];
+  has_uncommitted_changes: boolean;
+}
+
+export interface InsightsBatch {
+  insights: Record<string, ProjectInsights>;
+  errors: Record<string, string>;
+}
+
+export interface InsightsProgress {
+  completed: number;
+  total: number;
+  project_path: string;
 }
 
 export interface GitLog {
@@ -159,6 +854,8 @@ export interface GitLog {
   total_commits: number;
   branches: string[];
   current_branch?: string;
+  is_shallow: boolean;
+  current_branch_url?: string | null;
 }
 
 export interface Task {
@@ -167,6 +864,23 @@ export interface Task {
   completed: boolean;
   created_at: string;
   completed_at?: string;
+  description?: string;
+  tags?: string[];
+  due_date?: string;
+  // 'insight' for tasks suggested from project insights
+  source?: string;
+  // The gap an insight task closes, e.g. 'license'
+  source_ref?: string;
+}
+
+export interface TaskImportResult {
+  created: number;
+  skipped_duplicates: number;
+}
+
+export interface IdeaTask {
+  task_id: string;
+  created: boolean;
 }
 
 export interface TaskList {
@@ -174,3 +888,59 @@ export interface TaskList {
   tasks: Task[];
   updated_at: string;
 }
+
+export type ExportFormat = 'markdown' | 'json';
+
+// Project report sections; omitted ones default to included
+export interface ReportSections {
+  summary?: boolean;
+  metrics?: boolean;
+  languages?: boolean;
+  insights?: boolean;
+  tasks?: boolean;
+  ideas?: boolean;
+}
+
+// Additions to the built-in file filter; extensions without the dot
+export interface FileFilterSettings {
+  extra_ignored_extensions: string[];
+  extra_ignored_dirs: string[];
+  // Built-in ignored extensions to analyze anyway, e.g. "svg"
+  allowed_extensions: string[];
+}
+
+// Opt-in symlink following; links resolving outside the project are
+// skipped unless `allow_outside_root` is set
+export interface SymlinkPolicy {
+  follow: boolean;
+  allow_outside_root?: boolean;
+}
+
+export interface PerformanceSettings {
+  rayon_threads: number;
+  walker_threads: number;
+  max_concurrent_analyses: number;
+  progress_interval_ms: number;
+}
+
+export interface PerformanceInfo {
+  settings: PerformanceSettings;
+  cpu_count: number;
+  active_rayon_threads: number;
+  restart_required: boolean;
+}
+
+export type ThemeMode = 'light' | 'dark' | 'system';
+export type UiDensity = 'compact' | 'comfortable' | 'spacious';
+
+export interface ThemeSchedule {
+  dark_start: string; // HH:MM
+  dark_end: string;
+}
+
+export interface ThemeSettings {
+  mode: ThemeMode;
+  accent_color?: string | null;
+  density: UiDensity;
+  schedule?: ThemeSchedule | null;
+}
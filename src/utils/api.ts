@@ -1,16 +1,136 @@
 import { invoke } from '@tauri-apps/api/core';
-import { RepoAnalysis, IdeaRequest, ModelInfo, ProjectDirectory, ProjectSummary, SummaryRequest, ProjectInsights, GitLog, TaskList } from '../types';
+import { RepoAnalysis, AnalysisMetricsSummary, LanguageShare, GitTagPage, SecurityScan, OutdatedDependency, ContributorStats, OwnershipSummary, InsightAspect, InsightsBatch, BatchResult, BulkAction, BulkOutcome, FileFilterSettings, SymlinkPolicy, IdeaRequest, ModelInfo, ProjectDirectory, ProjectListing, ProjectListingOptions, ProjectSummary, SummaryRequest, ProjectInsights, GitLog, GitSummary, TaskList, Task, ReportSections, ExportFormat, TaskImportResult, IdeaTask, Project, ProjectPage, ProviderPreset, LocalServer, PerformanceSettings, PerformanceInfo, LogLevel, Diagnostics, AppError, OnboardingState, RootFolderSuggestion, UsagePeriod, UsageStats, AutoRefreshStatus, ActivityEntry, IdeaStatus, BoardIdea, IdeaBoard, SummaryFreshness, TechnologyUsage, SearchOptions, SearchResults, FocusSuggestion } from '../types';
 
-export async function listProjectDirectories(rootPath: string): Promise<ProjectDirectory[]> {
-  return await invoke('list_project_directories', { rootPath });
+export async function listProjectDirectories(
+  rootPath: string,
+  options?: ProjectListingOptions
+): Promise<ProjectListing> {
+  return await invoke('list_project_directories', { rootPath, options });
 }
 
-export async function analyzeRepository(folderPath: string): Promise<RepoAnalysis> {
-  return await invoke('analyze_repository', { folderPath });
+// Re-detects description, git status and file count for one project
+export async function rescanProject(projectPath: string): Promise<ProjectDirectory> {
+  return await invoke('rescan_project', { projectPath });
 }
 
-export async function analyzeRepositoryFresh(folderPath: string): Promise<RepoAnalysis> {
-  return await invoke('analyze_repository_fresh', { folderPath });
+// `confirm` must be true for delete
+export async function bulkProjectAction(paths: string[], action: BulkAction, confirm = false): Promise<BulkOutcome[]> {
+  return await invoke('bulk_project_action', { paths, action, confirm });
+}
+
+export async function recordProjectOpened(projectPath: string): Promise<void> {
+  return await invoke('record_project_opened', { projectPath });
+}
+
+export async function updateProjectDescription(projectPath: string, description: string): Promise<void> {
+  return await invoke('update_project_description', { projectPath, description });
+}
+
+export async function updateProjectName(projectPath: string, name: string): Promise<void> {
+  return await invoke('update_project_name', { projectPath, name });
+}
+
+export async function getRecentProjects(limit?: number): Promise<Project[]> {
+  return await invoke('get_recent_projects', { limit });
+}
+
+export async function getMostOpenedProjects(limit?: number): Promise<Project[]> {
+  return await invoke('get_most_opened_projects', { limit });
+}
+
+export async function listAllProjectsPaged(cursor?: number | null, pageSize?: number, includeArchived = false): Promise<ProjectPage> {
+  return await invoke('list_all_projects_paged', { cursor, pageSize, includeArchived });
+}
+
+// `pattern` is a project path or a glob such as `**/tmp-*`; with `deleteData`
+// stored data for matching projects is removed and the count returned
+export async function addIgnoredProject(pattern: string, deleteData = false): Promise<number> {
+  return await invoke('add_ignored_project', { pattern, deleteData });
+}
+
+export async function removeIgnoredProject(pattern: string): Promise<boolean> {
+  return await invoke('remove_ignored_project', { pattern });
+}
+
+export async function listIgnoredProjects(): Promise<string[]> {
+  return await invoke('list_ignored_projects');
+}
+
+// Projects idle for longer than `months` (the saved threshold when omitted)
+export async function getStaleProjects(months?: number): Promise<Project[]> {
+  return await invoke('get_stale_projects', { months });
+}
+
+export async function getStaleAfterMonths(): Promise<number> {
+  return await invoke('get_stale_after_months');
+}
+
+export async function saveStaleAfterMonths(months: number): Promise<void> {
+  return await invoke('save_stale_after_months', { months });
+}
+
+// File patterns (gitignore syntax) excluded from every walk, on top of each
+// project's .repomuseignore
+export async function getIgnorePatterns(): Promise<string[]> {
+  return await invoke('get_ignore_patterns');
+}
+
+export async function saveIgnorePatterns(patterns: string[]): Promise<void> {
+  return await invoke('save_ignore_patterns', { patterns });
+}
+
+export async function getFileFilterSettings(): Promise<FileFilterSettings> {
+  return await invoke('get_file_filter_settings');
+}
+
+export async function saveFileFilterSettings(settings: FileFilterSettings): Promise<void> {
+  return await invoke('save_file_filter_settings', { settings });
+}
+
+// Hidden paths (CI config, tool rc files) analyzed despite the leading dot
+export async function getHiddenAllowlist(): Promise<string[]> {
+  return await invoke('get_hidden_allowlist');
+}
+
+export async function saveHiddenAllowlist(patterns: string[]): Promise<void> {
+  return await invoke('save_hidden_allowlist', { patterns });
+}
+
+// `includeHidden` walks every dotfile, not just CI and tool config;
+// `symlinks` opts into following symlinked directories
+export async function analyzeRepository(
+  folderPath: string,
+  includeSubmodules = false,
+  includeHidden = false,
+  symlinks?: SymlinkPolicy
+): Promise<RepoAnalysis> {
+  return await invoke('analyze_repository', { folderPath, includeSubmodules, includeHidden, symlinks });
+}
+
+export async function analyzeRepositoryFresh(
+  folderPath: string,
+  includeSubmodules = false,
+  includeHidden = false,
+  symlinks?: SymlinkPolicy
+): Promise<RepoAnalysis> {
+  return await invoke('analyze_repository_fresh', { folderPath, includeSubmodules, includeHidden, symlinks });
+}
+
+export async function updateProjectFileCount(projectPath: string, symlinks?: SymlinkPolicy): Promise<number> {
+  return await invoke('update_project_file_count', { projectPath, symlinks });
+}
+
+// Starts watch mode; batches arrive as `project:files_changed` events
+export async function watchProject(projectPath: string): Promise<void> {
+  return await invoke('watch_project', { projectPath });
+}
+
+export async function unwatchProject(projectPath: string): Promise<void> {
+  return await invoke('unwatch_project', { projectPath });
+}
+
+export async function getAnalysisMetrics(folderPath: string): Promise<AnalysisMetricsSummary> {
+  return await invoke('get_analysis_metrics', { folderPath });
 }
 
 export async function analyzeRepositoryLazy(folderPath: string): Promise<RepoAnalysis> {
@@ -21,6 +141,14 @@ export async function triggerFullScan(folderPath: string): Promise<RepoAnalysis>
   return await invoke('trigger_full_scan', { folderPath });
 }
 
+export async function analyzeMultipleRepositories(
+  folderPaths: string[],
+  stopOnError = false,
+  priority: 'recent' | 'most_opened' = 'recent'
+): Promise<BatchResult> {
+  return await invoke('analyze_multiple_repositories', { folderPaths, stopOnError, priority });
+}
+
 export async function cancelAnalysis(folderPath: string): Promise<void> {
   return await invoke('cancel_analysis', { folderPath });
 }
@@ -29,10 +157,28 @@ export async function generateIdeaList(request: IdeaRequest): Promise<string[]>
   return await invoke('generate_ideas', { request });
 }
 
+// Cached per API URL for a minute
 export async function loadModels(apiUrl: string, apiKey: string): Promise<ModelInfo[]> {
   return await invoke('load_models', { apiUrl, apiKey });
 }
 
+// Bypasses the model cache
+export async function refreshModels(apiUrl: string, apiKey: string): Promise<ModelInfo[]> {
+  return await invoke('refresh_models', { apiUrl, apiKey });
+}
+
+export async function clearModelCache(): Promise<void> {
+  return await invoke('clear_model_cache');
+}
+
+export async function getProviderPresets(): Promise<ProviderPreset[]> {
+  return await invoke('get_provider_presets');
+}
+
+export async function detectLocalServers(): Promise<LocalServer[]> {
+  return await invoke('detect_local_servers');
+}
+
 export async function generateProjectSummary(request: SummaryRequest): Promise<ProjectSummary> {
   return await invoke('generate_project_summary', { request });
 }
@@ -49,18 +195,213 @@ export async function saveRootFolder(rootFolder: string): Promise<void> {
   return await invoke('save_root_folder', { rootFolder });
 }
 
+// Fresh background analyses of all favorites; resolves to how many were queued
+export async function reanalyzeFavorites(): Promise<number> {
+  return await invoke('reanalyze_favorites');
+}
+
+export async function getBackgroundPaused(): Promise<boolean> {
+  return await invoke('get_background_paused');
+}
+
+// Also toggled from the tray; changes arrive as `background:paused_changed`
+export async function setBackgroundPaused(paused: boolean): Promise<boolean> {
+  return await invoke('set_background_paused', { paused });
+}
+
+export async function getMinimizeToTray(): Promise<boolean> {
+  return await invoke('get_minimize_to_tray');
+}
+
+export async function setMinimizeToTray(enabled: boolean): Promise<boolean> {
+  return await invoke('set_minimize_to_tray', { enabled });
+}
+
+export async function getLogLevel(): Promise<LogLevel> {
+  return await invoke('get_log_level');
+}
+
+// Applies immediately and persists; returns the normalized level
+export async function setLogLevel(level: LogLevel): Promise<LogLevel> {
+  return await invoke('set_log_level', { level });
+}
+
+// Last `lines` lines across the rotated log files, oldest first
+export async function getRecentLogs(lines?: number): Promise<string[]> {
+  return await invoke('get_recent_logs', { lines });
+}
+
+export async function openLogDirectory(): Promise<void> {
+  return await invoke('open_log_directory');
+}
+
+export async function getDiagnostics(): Promise<Diagnostics> {
+  return await invoke('get_diagnostics');
+}
+
+// Same report as plain text, free of keys and project contents
+export async function getDiagnosticsText(): Promise<string> {
+  return await invoke('get_diagnostics_text');
+}
+
+// Background failures, newest first; actionable ones also arrive as `app:error`
+export async function getRecentErrors(limit?: number): Promise<AppError[]> {
+  return await invoke('get_recent_errors', { limit });
+}
+
+export async function clearRecentErrors(): Promise<void> {
+  return await invoke('clear_recent_errors');
+}
+
+// Advanced by saving a root folder, saving settings with a model and the first analysis
+export async function getOnboardingState(): Promise<OnboardingState> {
+  return await invoke('get_onboarding_state');
+}
+
+export async function skipOnboarding(): Promise<OnboardingState> {
+  return await invoke('skip_onboarding');
+}
+
+// Common code folders under home, existing ones with the most repositories first
+export async function suggestRootFolders(): Promise<RootFolderSuggestion[]> {
+  return await invoke('suggest_root_folders');
+}
+
+// Local usage over the last `buckets` days, weeks or months
+export async function getUsageStats(period?: UsagePeriod, buckets?: number): Promise<UsageStats> {
+  return await invoke('get_usage_stats', { period, buckets });
+}
+
+export async function resetUsageStats(): Promise<void> {
+  return await invoke('reset_usage_stats');
+}
+
+// Re-analyze a project every `hours` (at most 720); null turns it off.
+// Listen for `analysis:updated` to pick up the results
+export async function setProjectAutoRefresh(projectPath: string, hours: number | null): Promise<void> {
+  return await invoke('set_project_auto_refresh', { projectPath, hours });
+}
+
+export async function listAutoRefreshProjects(): Promise<AutoRefreshStatus[]> {
+  return await invoke('list_auto_refresh_projects');
+}
+
+export async function listProjectActivity(projectPath: string): Promise<ActivityEntry[]> {
+  return await invoke('list_project_activity', { projectPath });
+}
+
+// Ideas from generateIdeaList are saved to the board when a project path is given
+export async function getIdeaBoard(projectPath: string): Promise<IdeaBoard> {
+  return await invoke('get_idea_board', { projectPath });
+}
+
+// Moves to the end of the column; `createTask` with 'in_progress' also adds a task
+export async function setIdeaStatus(id: number, status: IdeaStatus, createTask?: boolean): Promise<BoardIdea> {
+  return await invoke('set_idea_status', { id, status, createTask });
+}
+
+// Ranked from past focus areas, the idea board and the cached analysis; no AI call
+export async function suggestNextFocus(projectPath: string): Promise<FocusSuggestion[]> {
+  return await invoke('suggest_next_focus', { projectPath });
+}
+
+export async function reorderIdea(id: number, status: IdeaStatus, position: number): Promise<void> {
+  return await invoke('reorder_idea', { id, status, position });
+}
+
+// Which projects use what, e.g. getTechnologyInventory('react'); most used first
+export async function getTechnologyInventory(filter?: string): Promise<TechnologyUsage[]> {
+  return await invoke('get_technology_inventory', { filter });
+}
+
+// Paths and sampled contents across analyzed projects, most recently analyzed first
+export async function searchCode(query: string, options?: SearchOptions): Promise<SearchResults> {
+  return await invoke('search_code', { query, options });
+}
+
+// Compares the saved summary against the cached analysis and git HEAD
+export async function checkSummaryFreshness(projectPath: string): Promise<SummaryFreshness> {
+  return await invoke('check_summary_freshness', { projectPath });
+}
+
+// Project path from `repomuse /path/to/project`, at most once per request
+export async function takePendingOpenProject(): Promise<string | null> {
+  return await invoke('take_pending_open_project');
+}
+
 export async function loadRootFolder(): Promise<string | null> {
   return await invoke('load_root_folder');
 }
 
-export async function getProjectInsights(projectPath: string): Promise<ProjectInsights> {
-  return await invoke('get_project_insights', { projectPath });
+export async function getProjectInsights(
+  projectPath: string,
+  aspects?: InsightAspect[],
+  forceRefresh?: boolean,
+  testingDepth?: number
+): Promise<ProjectInsights> {
+  return await invoke('get_project_insights', { projectPath, aspects, forceRefresh, testingDepth });
+}
+
+// Listen for `insights:progress` ({ completed, total, project_path }) to track it
+export async function getInsightsBatch(projectPaths: string[], aspects?: InsightAspect[]): Promise<InsightsBatch> {
+  return await invoke('get_insights_batch', { projectPaths, aspects });
+}
+
+// Branch, last commit and remotes without a full insights run; cached for 15 minutes
+export async function getProjectGitSummary(projectPath: string): Promise<GitSummary> {
+  return await invoke('get_project_git_summary', { projectPath });
 }
 
 export async function getGitLog(projectPath: string): Promise<GitLog> {
   return await invoke('get_git_log', { projectPath });
 }
 
+export async function getGitTags(projectPath: string, limit?: number, offset?: number): Promise<GitTagPage> {
+  return await invoke('get_git_tags', { projectPath, limit, offset });
+}
+
+export async function checkOutdatedDependencies(
+  projectPath: string,
+  ecosystem: string,
+  limit?: number,
+  offline?: boolean
+): Promise<OutdatedDependency[]> {
+  return await invoke('check_outdated_dependencies', { projectPath, ecosystem, limit, offline });
+}
+
+export async function getLanguageBreakdown(projectPath: string, includeNonCode?: boolean): Promise<LanguageShare[]> {
+  return await invoke('get_language_breakdown', { projectPath, includeNonCode });
+}
+
+// Recomputes the project's stored primary language; null when no code was found
+export async function refreshPrimaryLanguage(projectPath: string): Promise<LanguageShare | null> {
+  return await invoke('refresh_primary_language', { projectPath });
+}
+
+export async function getSecurityScan(projectPath: string): Promise<SecurityScan> {
+  return await invoke('get_security_scan', { projectPath });
+}
+
+export async function getContributorStats(projectPath: string, since?: string): Promise<ContributorStats[]> {
+  return await invoke('get_contributor_stats', { projectPath, since });
+}
+
+export async function getContributorAliases(projectPath: string): Promise<Record<string, string>> {
+  return await invoke('get_contributor_aliases', { projectPath });
+}
+
+export async function saveContributorAliases(projectPath: string, aliases: Record<string, string>): Promise<void> {
+  return await invoke('save_contributor_aliases', { projectPath, aliases });
+}
+
+export async function getOwnershipSummary(
+  projectPath: string,
+  topNFiles?: number,
+  symlinks?: SymlinkPolicy
+): Promise<OwnershipSummary> {
+  return await invoke('get_ownership_summary', { projectPath, topNFiles, symlinks });
+}
+
 // Task list functions
 export async function loadTaskList(projectPath: string): Promise<TaskList | null> {
   return await invoke('load_task_list', { projectPath });
@@ -70,6 +411,41 @@ export async function saveTaskList(taskList: TaskList): Promise<void> {
   return await invoke('save_task_list', { taskList });
 }
 
+export async function importTasks(projectPath: string, markdown: string): Promise<TaskImportResult> {
+  return await invoke('import_tasks', { projectPath, markdown });
+}
+
+export async function importTasksFromMarkers(projectPath: string): Promise<TaskImportResult> {
+  return await invoke('import_tasks_from_markers', { projectPath });
+}
+
+export async function addTask(projectPath: string, task: Task): Promise<Task> {
+  return await invoke('add_task', { projectPath, task });
+}
+
+// "Add to tasks" on an idea; `created` is false when the task already existed
+export async function addIdeaToTasks(projectPath: string, ideaText: string): Promise<IdeaTask> {
+  return await invoke('add_idea_to_tasks', { projectPath, ideaText });
+}
+
+// Task candidates for gaps like a missing LICENSE or CI; accept with addTask
+export async function suggestTasksFromInsights(projectPath: string): Promise<Task[]> {
+  return await invoke('suggest_tasks_from_insights', { projectPath });
+}
+
+export async function exportTasks(projectPath: string, format: ExportFormat, outputPath?: string): Promise<string> {
+  return await invoke('export_tasks', { projectPath, format, outputPath });
+}
+
+export async function exportAllTasks(format: ExportFormat, outputPath?: string): Promise<string> {
+  return await invoke('export_all_tasks', { format, outputPath });
+}
+
+// Markdown review of one project; written to outputPath when given
+export async function exportProjectReport(projectPath: string, outputPath?: string, sections?: ReportSections): Promise<string> {
+  return await invoke('export_project_report', { projectPath, outputPath, sections });
+}
+
 // Favorites functions
 export async function saveFavoriteProjects(favorites: string[]): Promise<void> {
   console.log('[API] Saving favorites:', favorites);
@@ -93,3 +469,15 @@ export async function loadFavoriteProjects(): Promise<string[]> {
     return [];
   }
 }
+
+export async function resetWindowState(): Promise<void> {
+  return await invoke('reset_window_state');
+}
+
+export async function getPerformanceInfo(): Promise<PerformanceInfo> {
+  return await invoke('get_performance_info');
+}
+
+export async function savePerformanceSettings(settings: PerformanceSettings): Promise<PerformanceInfo> {
+  return await invoke('save_performance_settings', { settings });
+}
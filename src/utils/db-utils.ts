@@ -10,6 +10,99 @@ export interface DatabaseStats {
   total_summaries: number;
   database_size_bytes: number;
   database_size_mb: number;
+  wal_size_bytes: number;
+  shm_size_bytes: number;
+  backups_size_bytes: number;
+  logs_size_bytes: number;
+  total_storage_bytes: number;
+  table_row_counts: TableRowCount[];
+  largest_projects: ProjectStorage[];
+}
+
+export interface TableRowCount {
+  table: string;
+  rows: number;
+}
+
+export interface ProjectStorage {
+  project_id: number;
+  name: string;
+  path: string;
+  file_rows: number;
+  file_rows_bytes: number;
+  analysis_bytes: number;
+  total_bytes: number;
+}
+
+export interface MaintenanceOptions {
+  clear_expired_cache?: boolean;
+  optimize?: boolean;
+  vacuum?: boolean;
+}
+
+export interface MaintenanceStepReport {
+  step: string;
+  duration_ms: number;
+  reclaimed_bytes: number;
+  detail: string;
+}
+
+export interface MaintenanceReport {
+  steps: MaintenanceStepReport[];
+  size_before_bytes: number;
+  size_after_bytes: number;
+  reclaimed_bytes: number;
+  total_duration_ms: number;
+}
+
+export interface MaintenanceProgress {
+  step: string;
+  index: number;
+  total: number;
+  status: 'started' | 'finished';
+  duration_ms?: number | null;
+  reclaimed_bytes?: number | null;
+}
+
+export type BackupFrequency = 'off' | 'daily' | 'weekly';
+
+export interface BackupSchedule {
+  frequency: BackupFrequency;
+  retention: number;
+}
+
+export interface BackupInfo {
+  filename: string;
+  size_bytes: number;
+  created_at?: string | null;
+}
+
+export interface BundleManifest {
+  format_version: number;
+  app_version: string;
+  analysis_schema_version: number;
+  created_at: string;
+  includes_secrets: boolean;
+  entries: { name: string; size_bytes: number }[];
+}
+
+export interface ExportReport {
+  path: string;
+  size_bytes: number;
+  manifest: BundleManifest;
+}
+
+export interface ImportReport {
+  manifest: BundleManifest;
+  previous_data_dir: string;
+}
+
+// Payload of `app_data:progress`; bytes count within the current step
+export interface TransferProgress {
+  operation: 'export' | 'import';
+  step: string;
+  bytes_done: number;
+  bytes_total: number;
 }
 
 export async function getAppDataDirectory(): Promise<string> {
@@ -37,8 +130,44 @@ export async function optimizeDatabase(): Promise<string> {
   return await invoke('optimize_database');
 }
 
-export async function clearAllData(): Promise<void> {
-  return await invoke('clear_all_data');
+export async function runMaintenance(options?: MaintenanceOptions): Promise<MaintenanceReport> {
+  return await invoke('run_maintenance', { options });
+}
+
+export async function getBackupSchedule(): Promise<BackupSchedule> {
+  return await invoke('get_backup_schedule');
+}
+
+export async function saveBackupSchedule(schedule: BackupSchedule): Promise<void> {
+  return await invoke('save_backup_schedule', { schedule });
+}
+
+export async function backupDatabase(): Promise<BackupInfo> {
+  return await invoke('backup_database');
+}
+
+export async function listBackups(): Promise<BackupInfo[]> {
+  return await invoke('list_backups');
+}
+
+export async function restoreBackup(filename: string): Promise<void> {
+  return await invoke('restore_backup', { filename });
+}
+
+// Database, caches and settings in one zip; the API key only when asked
+export async function exportAppData(outputZip: string, includeSecrets = false): Promise<ExportReport> {
+  return await invoke('export_app_data', { outputZip, includeSecrets });
+}
+
+// Replaces all data; the current data is moved to a pre-import folder first
+export async function importAppData(inputZip: string): Promise<ImportReport> {
+  return await invoke('import_app_data', { inputZip });
+}
+
+export async function clearAllData(
+  options: { preserveSettings?: boolean; preserveFavorites?: boolean } = {}
+): Promise<void> {
+  return await invoke('clear_all_data', { ...options });
 }
 
 // Helper function to format bytes
@@ -3,3 +3,8 @@ export const basename = (p: string): string => {
   const parts = p.split(/[/\\]/);
   return parts[parts.length - 1] || p;
 };
+
+export const dirname = (p: string): string => {
+  const index = Math.max(p.lastIndexOf('/'), p.lastIndexOf('\\'));
+  return index > 0 ? p.slice(0, index) : p;
+};